@@ -0,0 +1,31 @@
+// Headless entry point: see osmilog::cli.
+use osmilog::cli::{self, CliError, CliOptions};
+
+fn main() {
+    let opts = match CliOptions::parse(std::env::args().skip(1)) {
+        Ok(Some(opts)) => opts,
+        Ok(None) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Err(e) => {
+            eprintln!("osmilog-cli: {e}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    match cli::run(&opts) {
+        Ok(table) => print!("{table}"),
+        Err(CliError::VectorsFailed { report, .. }) => {
+            print!("{report}");
+            std::process::exit(1);
+        }
+        Err(e @ CliError::Usage(_)) => {
+            eprintln!("osmilog-cli: {e}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+        Err(e) => {
+            eprintln!("osmilog-cli: {e}");
+            std::process::exit(1);
+        }
+    }
+}
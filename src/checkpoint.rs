@@ -0,0 +1,309 @@
+// Named checkpoints of a running machine: every sequential part's latched
+// state - registers, counters, shift-register stages, flip-flops, clock
+// phases - and every RAM's contents, through all subcircuit instances. Taken
+// between ticks and put back later in the session, or saved to a sidecar file
+// next to the project to resume a long run another day. Headless; the GUI
+// keeps them on Document (see gui::checkpoints).
+//
+// A part is named by its grid position on its canvas, and a subcircuit's
+// inner parts by theirs within the circuit it instantiates, so a checkpoint
+// doesn't hang on the CompKeys of one build. A part that has since moved, or
+// changed kind, is skipped on restore. Inputs aren't part of a checkpoint:
+// a restored machine resumes under the inputs as they are now.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::gui::geometry::GridPos;
+use crate::sim::circuit::{Circuit, SettleError};
+use crate::sim::component::{CompKey, Logic, LogicSeq, SeqState};
+
+pub const CHECKPOINT_FILE_EXT: &str = "osmstate";
+// Bumped on breaking changes to the sidecar format.
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+// Where each part of a live circuit sits on its canvas and, for a subcircuit
+// instance, where the parts of its inner circuit sit on theirs. Built by the
+// caller, which knows the records a circuit came from.
+#[derive(Debug, Default)]
+pub struct Layout {
+    pub parts: HashMap<CompKey, (GridPos, Layout)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub name: String,
+    // The run's tick count when it was taken.
+    pub tick: usize,
+    // Sorted top-down, then left-to-right.
+    pub parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Part {
+    pub at: GridPos,
+    pub state: PartState,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PartState {
+    // A sequential part's latch, and for a RAM its nonzero words by address.
+    Seq {
+        latch: SeqState,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        words: Vec<(usize, u64)>,
+    },
+    // The stateful parts inside a subcircuit instance.
+    Sub(Vec<Part>),
+}
+
+// A sidecar file: the checkpoints taken on one circuit of a project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointFile {
+    pub version: u32,
+    pub circuit: String,
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+impl CheckpointFile {
+    pub fn new(circuit: String, checkpoints: Vec<Checkpoint>) -> Self {
+        Self {
+            version: CHECKPOINT_VERSION,
+            circuit,
+            checkpoints,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        let file: Self = serde_json::from_str(s).map_err(|e| e.to_string())?;
+        if file.version != CHECKPOINT_VERSION {
+            return Err(format!(
+                "unsupported checkpoint file version {} (expected {CHECKPOINT_VERSION})",
+                file.version
+            ));
+        }
+        Ok(file)
+    }
+}
+
+impl Checkpoint {
+    pub fn take(name: String, tick: usize, circuit: &Circuit, layout: &Layout) -> Self {
+        Self {
+            name,
+            tick,
+            parts: take_parts(circuit, layout),
+        }
+    }
+
+    // Puts back every part found where the checkpoint left it, then settles;
+    // restoring is not a clock edge (see Circuit::restore_sequential).
+    // Returns how many of its parts weren't found.
+    pub fn restore(&self, circuit: &mut Circuit, layout: &Layout) -> Result<usize, SettleError> {
+        let mut skipped = 0;
+        restore_parts(&self.parts, circuit, layout, &mut skipped)?;
+        Ok(skipped)
+    }
+}
+
+fn take_parts(circuit: &Circuit, layout: &Layout) -> Vec<Part> {
+    let mut parts: Vec<Part> = layout
+        .parts
+        .iter()
+        .filter_map(|(key, (at, inner))| {
+            let state = match &circuit.components.get(key)?.logic {
+                Logic::Comb(_) => return None,
+                Logic::Seq(seq) => PartState::Seq {
+                    latch: seq.snapshot(),
+                    words: match seq {
+                        LogicSeq::Ram(ram) => (ram.contents().data.borrow().iter().copied())
+                            .enumerate()
+                            .filter(|&(_, word)| word != 0)
+                            .collect(),
+                        _ => Vec::new(),
+                    },
+                },
+                Logic::Sub(sub) => {
+                    let parts = take_parts(&sub.inner, inner);
+                    if parts.is_empty() {
+                        return None;
+                    }
+                    PartState::Sub(parts)
+                }
+            };
+            Some(Part { at: *at, state })
+        })
+        .collect();
+    parts.sort_by_key(|part| (part.at.y, part.at.x));
+    parts
+}
+
+fn restore_parts(
+    parts: &[Part],
+    circuit: &mut Circuit,
+    layout: &Layout,
+    skipped: &mut usize,
+) -> Result<(), SettleError> {
+    let mut latches = Vec::new();
+    for part in parts {
+        let found = layout.parts.iter().find(|(_, (at, _))| *at == part.at);
+        let Some((&key, (_, inner))) = found else {
+            *skipped += 1;
+            continue;
+        };
+        let Some(comp) = circuit.components.get_mut(&key) else {
+            *skipped += 1;
+            continue;
+        };
+        match (&part.state, &mut comp.logic) {
+            (PartState::Seq { latch, words }, Logic::Seq(seq))
+                if std::mem::discriminant(latch) == std::mem::discriminant(&seq.snapshot()) =>
+            {
+                if let LogicSeq::Ram(ram) = seq {
+                    let contents = ram.contents();
+                    contents.data.borrow_mut().fill(0);
+                    for &(index, word) in words {
+                        contents.set_word(index, word);
+                    }
+                }
+                latches.push((key, latch));
+            }
+            (PartState::Sub(parts), Logic::Sub(sub)) => {
+                // An inner settle error surfaces again on this circuit's own
+                // settle, through the same nets.
+                let _ = restore_parts(parts, &mut sub.inner, inner, skipped);
+            }
+            _ => *skipped += 1,
+        }
+    }
+    circuit.restore_sequential(&latches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::component::{ClockEdge, Component, OverflowAction, PinId, Ram, ReadBehavior};
+    use crate::sim::value::Value;
+
+    // A 4-bit counter counting up every tick, and the Output reading it.
+    // Returns (circuit, counter, output).
+    fn counting(c: &mut Circuit) -> (CompKey, CompKey) {
+        let load = c.add_component(Component::constant(0, 1));
+        let count = c.add_component(Component::constant(1, 1));
+        let counter = c.add_component(Component::counter(
+            4,
+            15,
+            OverflowAction::Wrap,
+            ClockEdge::Rising,
+        ));
+        let out = c.add_component(Component::output());
+        c.link(load, PinId::output(0), counter, PinId::input(1));
+        c.link(count, PinId::output(0), counter, PinId::input(2));
+        c.link(counter, PinId::output(0), out, PinId::input(0));
+        (counter, out)
+    }
+
+    // A subcircuit instance holding a counter, and beside it a RAM that
+    // stores the instance's count at address 1 on every tick.
+    fn machine() -> (Circuit, Layout, CompKey, CompKey) {
+        let mut inner = Circuit::new();
+        let (counter, inner_out) = counting(&mut inner);
+        inner.settle().unwrap();
+
+        let mut c = Circuit::new();
+        let sub = c.add_component(Component::subcircuit(inner, vec![], vec![inner_out]));
+        let addr = c.add_component(Component::constant(1, 2));
+        let ram = c.add_component(Component::ram(Ram::new(4, 2, ReadBehavior::ReadAfterWrite)));
+        let out = c.add_component(Component::output());
+        c.link(
+            addr,
+            PinId::output(0),
+            ram,
+            PinId::input(Ram::ADDR_PIN as u8),
+        );
+        c.link(
+            sub,
+            PinId::output(0),
+            ram,
+            PinId::input(Ram::DATA_IN_PIN as u8),
+        );
+        c.link(sub, PinId::output(0), out, PinId::input(0));
+        c.settle().unwrap();
+
+        let inner_layout = Layout {
+            parts: HashMap::from([(counter, (GridPos::new(4, 0), Layout::default()))]),
+        };
+        let layout = Layout {
+            parts: HashMap::from([
+                (sub, (GridPos::new(0, 0), inner_layout)),
+                (ram, (GridPos::new(0, 5), Layout::default())),
+            ]),
+        };
+        (c, layout, ram, out)
+    }
+
+    fn ram_word(c: &Circuit, ram: CompKey, index: usize) -> u64 {
+        match &c.components[&ram].logic {
+            Logic::Seq(LogicSeq::Ram(ram)) => ram.contents().word(index),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_restore_puts_back_state_inside_subcircuits_and_ram() {
+        let (mut c, layout, ram, out) = machine();
+        for _ in 0..3 {
+            c.tick_clock().unwrap();
+        }
+        assert_eq!(c.read_output(out), Value::new(3, 4));
+        let checkpoint = Checkpoint::take("three".to_string(), 3, &c, &layout);
+        assert_eq!(checkpoint.parts.len(), 2);
+        let words = match &checkpoint.parts[1].state {
+            PartState::Seq { words, .. } => words.clone(),
+            other => panic!("expected the RAM, got {other:?}"),
+        };
+        assert_eq!(words, vec![(1, 2)]);
+
+        for _ in 0..4 {
+            c.tick_clock().unwrap();
+        }
+        assert_eq!(c.read_output(out), Value::new(7, 4));
+        assert_eq!(checkpoint.restore(&mut c, &layout), Ok(0));
+        assert_eq!(c.read_output(out), Value::new(3, 4));
+        assert_eq!(ram_word(&c, ram, 1), 2);
+
+        // The machine runs on from there.
+        c.tick_clock().unwrap();
+        assert_eq!(c.read_output(out), Value::new(4, 4));
+        assert_eq!(ram_word(&c, ram, 1), 3);
+    }
+
+    #[test]
+    fn test_file_round_trips_and_moved_parts_are_skipped() {
+        let (mut c, layout, _, out) = machine();
+        c.tick_clock().unwrap();
+        let checkpoint = Checkpoint::take("one".to_string(), 1, &c, &layout);
+        let file = CheckpointFile::new("Main".to_string(), vec![checkpoint]);
+        let loaded = CheckpointFile::from_json(&file.to_json().unwrap()).unwrap();
+        assert_eq!(loaded, file);
+
+        c.tick_clock().unwrap();
+        c.tick_clock().unwrap();
+        let mut moved = loaded.checkpoints[0].clone();
+        moved.parts[0].at = GridPos::new(9, 9);
+        assert_eq!(moved.restore(&mut c, &layout), Ok(1));
+        assert_eq!(c.read_output(out), Value::new(3, 4));
+        assert_eq!(loaded.checkpoints[0].restore(&mut c, &layout), Ok(0));
+        assert_eq!(c.read_output(out), Value::new(1, 4));
+
+        let newer = file.to_json().unwrap().replace(
+            &format!("\"version\": {CHECKPOINT_VERSION}"),
+            "\"version\": 99",
+        );
+        assert!(CheckpointFile::from_json(&newer).is_err());
+    }
+}
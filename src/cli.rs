@@ -0,0 +1,617 @@
+// The headless simulator behind the `osmilog-cli` binary: loads a project
+// file, builds one of its circuits (see project::build_circuit), drives its
+// Inputs and the clock, and prints every Output and Probe as a table with one
+// row per clock tick - or, given test vectors, checks the circuit against them
+// (see sim::test_vector), or exports the project as Verilog (see verilog.rs)
+// or a circuit's bill of materials (see stats.rs). Nothing here touches egui.
+//
+// Inputs and Outputs go by their labels. An unlabelled one is named the way a
+// subcircuit numbers its pins: `in0`, `in1`, ... and `out0`, `out1`, ...
+// top-down by grid position. Probes go by their names.
+
+use std::path::PathBuf;
+
+use crate::io::{LoadError, ProjectFile};
+use crate::project::build_circuit;
+use crate::sim::circuit::{Circuit, SettleError};
+use crate::sim::command::Command;
+use crate::sim::component::{CompKey, ComponentSpec};
+use crate::sim::test_vector::{parse_value, Port, Step, TestVectors, VectorError};
+use crate::sim::value::{format_value, Radix, Value};
+use crate::stats::Bom;
+use crate::verilog;
+
+pub const USAGE: &str = "\
+usage: osmilog-cli <project.osm> [options]
+
+options:
+  --circuit <name>     simulate this circuit (default: the one saved as active)
+  --set <port>=<value> drive an Input before the first tick; repeatable
+  --stimulus <file>    one step per line of <port>=<value> pairs; each line is
+                       applied, then the clock ticks once
+  --ticks <n>          clock ticks to run after the stimulus (default 0)
+  --timed <units>      simulate with component delays, running <units> of time
+                       per tick
+  --vectors <file>     check the circuit against a test-vector file instead of
+                       printing a table; exits 1 if any row fails
+  --verilog            print the whole project as structural Verilog instead of
+                       simulating
+  --bom                print the circuit's parts, counted through its
+                       subcircuits, as CSV instead of simulating
+  --estimate           with --bom, add transistor and NAND2-equivalent columns
+
+Inputs and Outputs go by their labels. Unlabelled ones are in0, in1, ... and
+out0, out1, ..., numbered top-down as a subcircuit's pins are; Probes go by
+their names. Values are decimal, 0x hex or 0b binary.";
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CliOptions {
+    pub project: PathBuf,
+    pub circuit: Option<String>,
+    pub sets: Vec<(String, u64)>,
+    pub stimulus: Option<PathBuf>,
+    pub ticks: usize,
+    pub timed: Option<u32>,
+    pub vectors: Option<PathBuf>,
+    pub verilog: bool,
+    pub bom: bool,
+    pub estimate: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    Usage(String),
+    Io { path: PathBuf, msg: String },
+    Load(LoadError),
+    UnknownCircuit(String),
+    UnknownPort(String),
+    BadValue { port: String, text: String },
+    Stimulus { line: usize, msg: String },
+    Settle(SettleError),
+    Vectors(VectorError),
+    // The vectors ran but some rows failed; `report` is the full PASS/FAIL
+    // listing.
+    VectorsFailed { report: String, failed: usize },
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Usage(msg) => write!(f, "{msg}"),
+            CliError::Io { path, msg } => write!(f, "{}: {msg}", path.display()),
+            CliError::Load(e) => write!(f, "{e}"),
+            CliError::UnknownCircuit(name) => write!(f, "project has no circuit named {name:?}"),
+            CliError::UnknownPort(name) => write!(f, "circuit has no input named {name:?}"),
+            CliError::BadValue { port, text } => {
+                write!(f, "{text:?} is not a value that fits input {port}")
+            }
+            CliError::Stimulus { line, msg } => write!(f, "stimulus line {line}: {msg}"),
+            CliError::Settle(e) => write!(f, "{e}"),
+            CliError::Vectors(e) => write!(f, "test vectors: {e}"),
+            CliError::VectorsFailed { failed, .. } => write!(f, "{failed} test vector rows failed"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<SettleError> for CliError {
+    fn from(e: SettleError) -> Self {
+        CliError::Settle(e)
+    }
+}
+
+impl CliOptions {
+    // Parses the arguments after the program name. None means help was asked
+    // for.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, CliError> {
+        let mut args = args.into_iter();
+        let mut opts = CliOptions::default();
+        let mut project = None;
+        while let Some(arg) = args.next() {
+            let mut value = |flag: &str| {
+                args.next()
+                    .ok_or_else(|| CliError::Usage(format!("{flag} needs a value")))
+            };
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--circuit" => opts.circuit = Some(value("--circuit")?),
+                "--set" => opts.sets.push(parse_assignment(&value("--set")?)?),
+                "--stimulus" => opts.stimulus = Some(value("--stimulus")?.into()),
+                "--ticks" => opts.ticks = parse_number("--ticks", &value("--ticks")?)?,
+                "--timed" => opts.timed = Some(parse_number("--timed", &value("--timed")?)?),
+                "--vectors" => opts.vectors = Some(value("--vectors")?.into()),
+                "--verilog" => opts.verilog = true,
+                "--bom" => opts.bom = true,
+                "--estimate" => opts.estimate = true,
+                flag if flag.starts_with('-') => {
+                    return Err(CliError::Usage(format!("unknown option {flag}")))
+                }
+                path if project.is_none() => project = Some(PathBuf::from(path)),
+                extra => return Err(CliError::Usage(format!("unexpected argument {extra}"))),
+            }
+        }
+        opts.project = project.ok_or_else(|| CliError::Usage("no project file given".into()))?;
+        Ok(Some(opts))
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, text: &str) -> Result<T, CliError> {
+    text.parse()
+        .map_err(|_| CliError::Usage(format!("{flag} expects a number, got {text:?}")))
+}
+
+fn parse_assignment(text: &str) -> Result<(String, u64), CliError> {
+    let (port, value) = text
+        .split_once('=')
+        .ok_or_else(|| CliError::Usage(format!("expected <port>=<value>, got {text:?}")))?;
+    let bits = parse_value(value).ok_or_else(|| CliError::BadValue {
+        port: port.to_string(),
+        text: value.to_string(),
+    })?;
+    Ok((port.to_string(), bits))
+}
+
+// A stimulus file: one step per non-blank line, `#` to end of line a comment.
+pub fn parse_stimulus(text: &str) -> Result<Vec<Vec<(String, u64)>>, CliError> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line = line.split('#').next().unwrap_or("").trim();
+            (!line.is_empty()).then_some((i + 1, line))
+        })
+        .map(|(line_no, line)| {
+            line.split_whitespace()
+                .map(parse_assignment)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| CliError::Stimulus {
+                    line: line_no,
+                    msg: e.to_string(),
+                })
+        })
+        .collect()
+}
+
+fn read_file(path: &PathBuf) -> Result<String, CliError> {
+    std::fs::read_to_string(path).map_err(|e| CliError::Io {
+        path: path.clone(),
+        msg: e.to_string(),
+    })
+}
+
+// Everything the binary does short of printing: returns the output table.
+pub fn run(opts: &CliOptions) -> Result<String, CliError> {
+    let file = ProjectFile::from_json(&read_file(&opts.project)?).map_err(CliError::Load)?;
+    file.validate().map_err(CliError::Load)?;
+    if opts.verilog {
+        return Ok(verilog::export(&file));
+    }
+    if opts.bom {
+        return Ok(Bom::of(&file, circuit_index(&file, opts)?).to_csv(opts.estimate));
+    }
+    let stimulus = match &opts.stimulus {
+        Some(path) => parse_stimulus(&read_file(path)?)?,
+        None => Vec::new(),
+    };
+    match &opts.vectors {
+        Some(path) => {
+            let vectors = TestVectors::parse(&read_file(path)?).map_err(CliError::Vectors)?;
+            check_vectors(&file, opts, &vectors)
+        }
+        None => simulate(&file, opts, &stimulus),
+    }
+}
+
+// A built circuit plus its named ports.
+struct Bench {
+    circuit: Circuit,
+    // (name, key, width) per Input.
+    inputs: Vec<(String, CompKey, u8)>,
+    // (name, key) per Output, then per Probe.
+    watched: Vec<(String, CompKey)>,
+}
+
+impl Bench {
+    fn new(file: &ProjectFile, index: usize) -> Self {
+        let built = build_circuit(file, index);
+        let (inputs, outputs) = built.boundary_ports(file, index);
+        let records = &file.circuits[index].snapshot.components;
+        let probes = records
+            .iter()
+            .zip(&built.comp_keys)
+            .filter_map(|(r, &key)| match &r.spec {
+                ComponentSpec::Probe(p) => Some((p.name.clone(), key)),
+                _ => None,
+            });
+        let inputs = inputs
+            .into_iter()
+            .map(|p| {
+                (
+                    p.name,
+                    p.key,
+                    p.width.expect("Input ports carry their width"),
+                )
+            })
+            .collect();
+        let watched = outputs
+            .into_iter()
+            .map(|p| (p.name, p.key))
+            .chain(probes)
+            .collect();
+        Self {
+            circuit: built.circuit,
+            inputs,
+            watched,
+        }
+    }
+
+    fn set(&mut self, port: &str, bits: u64) -> Result<(), CliError> {
+        let &(_, comp, width) = self
+            .inputs
+            .iter()
+            .find(|(name, ..)| name == port)
+            .ok_or_else(|| CliError::UnknownPort(port.to_string()))?;
+        if bits & !Value::mask(width) != 0 {
+            return Err(CliError::BadValue {
+                port: port.to_string(),
+                text: bits.to_string(),
+            });
+        }
+        self.circuit.apply(Command::SetInput { comp, bits, width });
+        Ok(())
+    }
+
+    // One step of the clock sources; in timed mode also `units` of time, as
+    // the GUI's Step does (see gui::clock::Clock::step).
+    fn tick(&mut self, timed: Option<u32>) -> Result<(), SettleError> {
+        self.circuit
+            .apply(Command::StepClockSources)
+            .0
+            .unwrap_settle()?;
+        match timed {
+            Some(units) => self
+                .circuit
+                .apply(Command::AdvanceTime(units as u64))
+                .0
+                .unwrap_settle(),
+            None => Ok(()),
+        }
+    }
+
+    // The named ports test vectors bind to.
+    fn ports(&self) -> Vec<Port> {
+        let inputs = self.inputs.iter().map(|(name, key, width)| Port {
+            name: name.clone(),
+            key: *key,
+            width: Some(*width),
+        });
+        let watched = self.watched.iter().map(|(name, key)| Port {
+            name: name.clone(),
+            key: *key,
+            width: None,
+        });
+        inputs.chain(watched).collect()
+    }
+
+    fn header(&self) -> String {
+        std::iter::once("tick")
+            .chain(self.watched.iter().map(|(name, _)| name.as_str()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn row(&self, tick: usize) -> String {
+        std::iter::once(tick.to_string())
+            .chain(
+                self.watched
+                    .iter()
+                    .map(|&(_, key)| format_value(self.circuit.read_output(key), Radix::Binary)),
+            )
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+// The `--circuit` named, or the one saved as active.
+fn circuit_index(file: &ProjectFile, opts: &CliOptions) -> Result<usize, CliError> {
+    match &opts.circuit {
+        Some(name) => file
+            .circuits
+            .iter()
+            .position(|c| &c.name == name)
+            .ok_or_else(|| CliError::UnknownCircuit(name.clone())),
+        None => Ok(file.active),
+    }
+}
+
+// Builds the chosen circuit of a validated project, in timed mode if asked,
+// with the `--set` inputs applied and settled.
+fn prepare(file: &ProjectFile, opts: &CliOptions) -> Result<Bench, CliError> {
+    let mut bench = Bench::new(file, circuit_index(file, opts)?);
+    if opts.timed.is_some() {
+        bench.circuit.set_timed(true)?;
+    }
+    for (port, bits) in &opts.sets {
+        bench.set(port, *bits)?;
+    }
+    bench.circuit.settle()?;
+    Ok(bench)
+}
+
+// Simulates the chosen circuit of a validated project. Row 0 is the state
+// after the `--set` inputs settle; every tick after that adds a row.
+pub fn simulate(
+    file: &ProjectFile,
+    opts: &CliOptions,
+    stimulus: &[Vec<(String, u64)>],
+) -> Result<String, CliError> {
+    let mut bench = prepare(file, opts)?;
+    let mut lines = vec![bench.header(), bench.row(0)];
+    let mut tick = 0;
+    for step in stimulus {
+        for (port, bits) in step {
+            bench.set(port, *bits)?;
+        }
+        bench.tick(opts.timed)?;
+        tick += 1;
+        lines.push(bench.row(tick));
+    }
+    for _ in 0..opts.ticks {
+        bench.tick(opts.timed)?;
+        tick += 1;
+        lines.push(bench.row(tick));
+    }
+    lines.push(String::new());
+    Ok(lines.join("\n"))
+}
+
+// Runs test vectors against the chosen circuit, after any `--set` inputs.
+// Returns the report when every row passes; otherwise VectorsFailed carries it.
+pub fn check_vectors(
+    file: &ProjectFile,
+    opts: &CliOptions,
+    vectors: &TestVectors,
+) -> Result<String, CliError> {
+    let mut bench = prepare(file, opts)?;
+    let ports = bench.ports();
+    let time_per_tick = opts.timed.unwrap_or(0) as u64;
+    let results = vectors
+        .run(&mut bench.circuit, &ports, time_per_tick)
+        .map_err(CliError::Vectors)?;
+
+    // One result per step, in order: ticks are listed only when they fail.
+    let mut lines = Vec::new();
+    let mut failed = 0;
+    for (step, result) in vectors.steps.iter().zip(&results) {
+        if result.passed() {
+            if let Step::Row { line, .. } = step {
+                lines.push(format!("line {line}: PASS"));
+            }
+            continue;
+        }
+        failed += 1;
+        let mut why: Vec<String> = result
+            .mismatches
+            .iter()
+            .map(|m| {
+                format!(
+                    "{} expected {}, got {}",
+                    m.port,
+                    m.expected,
+                    format_value(m.actual, Radix::Unsigned)
+                )
+            })
+            .collect();
+        why.extend(result.error.as_ref().map(|e| e.to_string()));
+        lines.push(format!("line {}: FAIL {}", result.line, why.join("; ")));
+    }
+    lines.push(format!("{} of {} steps failed", failed, results.len()));
+    lines.push(String::new());
+    let report = lines.join("\n");
+    if failed == 0 {
+        Ok(report)
+    } else {
+        Err(CliError::VectorsFailed { report, failed })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::geometry::GridPos;
+    use crate::io::{
+        CircuitEntry, CircuitSnapshot, ComponentEntry, NodeAttachEntry, NodeEntry, SegEntry,
+    };
+    use crate::sim::component::{ClockEdge, Gate, GateOp, Input, Output, Probe, TFlipFlopConf};
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    fn wire(comp_a: usize, pin_a: u8, comp_b: usize, pin_b: u8) -> [NodeEntry; 2] {
+        let node = |comp, is_input, pin_index| NodeEntry {
+            pos: GridPos::new(0, 0),
+            attach: NodeAttachEntry::Pin {
+                comp,
+                is_input,
+                pin_index,
+            },
+        };
+        [node(comp_a, false, pin_a), node(comp_b, true, pin_b)]
+    }
+
+    // in0 (2 bits) -> NOT -> out0, and a free-running T flip-flop -> probe "q".
+    fn project() -> ProjectFile {
+        let entry = |spec, y| ComponentEntry {
+            spec,
+            grid_pos: GridPos::new(0, y),
+            delay: None,
+        };
+        let components = vec![
+            entry(ComponentSpec::Input(Input::new(0, 2)), 0),
+            entry(
+                ComponentSpec::Gate(Gate {
+                    op: GateOp::Not,
+                    n_inputs: 1,
+                    width: 2,
+                }),
+                0,
+            ),
+            entry(ComponentSpec::Output(Default::default()), 0),
+            entry(ComponentSpec::Input(Input::new(1, 1)), 4),
+            entry(
+                ComponentSpec::TFlipFlop(TFlipFlopConf {
+                    clock_edge: ClockEdge::Rising,
+                }),
+                4,
+            ),
+            entry(
+                ComponentSpec::Probe(Probe {
+                    name: "q".to_string(),
+                }),
+                4,
+            ),
+        ];
+        let nodes: Vec<NodeEntry> = [
+            wire(0, 0, 1, 0),
+            wire(1, 0, 2, 0),
+            wire(3, 0, 4, TFlipFlopConf::TOGGLE_PIN as u8),
+            wire(4, 0, 5, 0),
+        ]
+        .concat();
+        let segments = (0..nodes.len() / 2)
+            .map(|i| SegEntry {
+                a: 2 * i,
+                b: 2 * i + 1,
+            })
+            .collect();
+        ProjectFile::new(
+            0,
+            vec![CircuitEntry {
+                name: "Main".to_string(),
+                snapshot: CircuitSnapshot {
+                    components,
+                    tunnels: vec![],
+                    nodes,
+                    segments,
+                },
+                subcircuits: vec![],
+            }],
+        )
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let opts = CliOptions::parse(args(
+            "p.osm --set in0=0x3 --set in1=0b1 --ticks 4 --circuit Top --timed 10",
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(opts.project, PathBuf::from("p.osm"));
+        assert_eq!(opts.sets, vec![("in0".into(), 3), ("in1".into(), 1)]);
+        assert_eq!(opts.ticks, 4);
+        assert_eq!(opts.circuit.as_deref(), Some("Top"));
+        assert_eq!(opts.timed, Some(10));
+        assert!(!opts.verilog);
+        let opts = CliOptions::parse(args("p.osm --verilog")).unwrap().unwrap();
+        assert!(opts.verilog);
+        let opts = CliOptions::parse(args("p.osm --bom --estimate"))
+            .unwrap()
+            .unwrap();
+        assert!(opts.bom && opts.estimate);
+
+        assert_eq!(CliOptions::parse(args("--help")), Ok(None));
+        assert!(matches!(
+            CliOptions::parse(args("--ticks 4")),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            CliOptions::parse(args("p.osm --ticks many")),
+            Err(CliError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_stimulus_skips_blanks_and_comments() {
+        let steps = parse_stimulus("# header\nin0=1 in1=0x2\n\nin0=0 # back low\n").unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                vec![("in0".into(), 1), ("in1".into(), 2)],
+                vec![("in0".into(), 0)]
+            ]
+        );
+        assert_eq!(
+            parse_stimulus("in0=1\nin0").unwrap_err(),
+            CliError::Stimulus {
+                line: 2,
+                msg: "expected <port>=<value>, got \"in0\"".into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_simulate_prints_a_row_per_tick() {
+        let opts = CliOptions {
+            sets: vec![("in0".into(), 1)],
+            ticks: 2,
+            ..Default::default()
+        };
+        let out = simulate(&project(), &opts, &[vec![("in0".into(), 3)]]).unwrap();
+        assert_eq!(out, "tick out0 q\n0 10 0\n1 00 1\n2 00 0\n3 00 1\n");
+    }
+
+    #[test]
+    fn test_labels_name_ports() {
+        let mut file = project();
+        let components = &mut file.circuits[0].snapshot.components;
+        let ComponentSpec::Input(input) = &mut components[0].spec else {
+            unreachable!()
+        };
+        input.label = "x".to_string();
+        components[2].spec = ComponentSpec::Output(Output {
+            label: "nx".to_string(),
+        });
+        let opts = CliOptions {
+            sets: vec![("x".into(), 2)],
+            ..Default::default()
+        };
+        let out = simulate(&file, &opts, &[]).unwrap();
+        assert_eq!(out, "tick nx q\n0 01 0\n");
+    }
+
+    #[test]
+    fn test_check_vectors_reports_each_row() {
+        let opts = CliOptions::default();
+        let vectors = TestVectors::parse("in0 in1 | out0 q\n1 1 | 2 0\ntick\n- - | - 1").unwrap();
+        assert_eq!(
+            check_vectors(&project(), &opts, &vectors).unwrap(),
+            "line 2: PASS\nline 4: PASS\n0 of 3 steps failed\n"
+        );
+
+        let vectors = TestVectors::parse("in0 | out0\n0 | 0").unwrap();
+        assert_eq!(
+            check_vectors(&project(), &opts, &vectors),
+            Err(CliError::VectorsFailed {
+                report: "line 2: FAIL out0 expected 0, got 3\n1 of 1 steps failed\n".into(),
+                failed: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_simulate_rejects_unknown_ports_and_oversized_values() {
+        let opts = |sets| CliOptions {
+            sets,
+            ..Default::default()
+        };
+        assert_eq!(
+            simulate(&project(), &opts(vec![("in9".into(), 1)]), &[]),
+            Err(CliError::UnknownPort("in9".into()))
+        );
+        assert!(matches!(
+            simulate(&project(), &opts(vec![("in0".into(), 4)]), &[]),
+            Err(CliError::BadValue { .. })
+        ));
+    }
+}
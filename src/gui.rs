@@ -1,5 +1,8 @@
+pub mod analyze;
 pub mod app;
+pub mod breakpoints;
 pub mod canvas_draw;
+pub mod checkpoints;
 pub mod clipboard;
 pub mod clock;
 pub mod document;
@@ -11,6 +14,11 @@ pub mod placed_component;
 pub mod properties;
 pub mod shape;
 pub mod signal_viewer;
+pub mod statistics;
+pub mod stimulus_dialog;
+pub mod synthesize;
+pub mod test_vectors;
 pub mod theme;
+pub mod timing;
 pub mod utils;
 pub mod wiring;
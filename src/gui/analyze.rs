@@ -0,0 +1,343 @@
+//! The Analyze window: the full truth table of a combinational circuit (see
+//! `sim::truth_table`), for the active circuit or any other document, with CSV
+//! and Markdown export; and each Output bit as a minimized sum of products
+//! (see `sim::minimize`) beside its Karnaugh map. Opened from the View menu.
+//!
+//! Like test vectors, analysis runs on a fresh build of the saved form of the
+//! project (`OsmilogApp::analyze_circuit`), so the live circuits are never
+//! touched. App-level rather than per-document, since it can target any one.
+
+use egui::{Align2, FontId, Rect, Sense, Stroke, Vec2};
+
+use crate::gui::document::DocId;
+use crate::gui::theme::Theme;
+use crate::sim::minimize::{
+    self, kmap_minterm, kmap_shape, Expression, Implicant, MAX_KMAP_VARS, MAX_MINIMIZE_VARS,
+};
+use crate::sim::truth_table::{TruthTable, MAX_INPUT_BITS};
+
+// Side of one K-map cell, in points.
+const KMAP_CELL: f32 = 26.0;
+
+/// What the window asks the app to do after a frame.
+pub enum AnalyzeAction {
+    Analyze,
+    Export(TableFormat),
+}
+
+#[derive(Clone, Copy)]
+pub enum TableFormat {
+    Csv,
+    Markdown,
+}
+
+impl TableFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            TableFormat::Csv => "csv",
+            TableFormat::Markdown => "md",
+        }
+    }
+
+    pub fn render(self, table: &TruthTable) -> String {
+        match self {
+            TableFormat::Csv => table.to_csv(),
+            TableFormat::Markdown => table.to_markdown(),
+        }
+    }
+}
+
+// A finished analysis: the table, which document it's of, each column's
+// display width, and the minimized expressions - all computed once, as a
+// table can run to 65536 rows. `expressions` is None past MAX_MINIMIZE_VARS.
+pub struct Analysis {
+    pub doc: DocId,
+    pub table: TruthTable,
+    widths: Vec<usize>,
+    pub vars: Vec<String>,
+    pub expressions: Option<Vec<Expression>>,
+}
+
+impl Analysis {
+    pub fn new(doc: DocId, table: TruthTable) -> Self {
+        let mut widths: Vec<usize> = table.header().iter().map(|h| h.chars().count()).collect();
+        for row in &table.rows {
+            for (w, cell) in widths.iter_mut().zip(table.cells(row)) {
+                *w = (*w).max(cell.len());
+            }
+        }
+        Self {
+            doc,
+            vars: minimize::variables(&table),
+            expressions: minimize::expressions(&table),
+            table,
+            widths,
+        }
+    }
+
+    // One fixed-width text line, inputs and outputs split by a bar.
+    fn line<S: AsRef<str>>(&self, cells: &[S]) -> String {
+        let n_inputs = self.table.inputs.len();
+        let pad = |(cell, w): (&S, &usize)| format!("{:<w$}", cell.as_ref(), w = *w);
+        let inputs: Vec<String> = cells[..n_inputs]
+            .iter()
+            .zip(&self.widths)
+            .map(pad)
+            .collect();
+        let outputs: Vec<String> = cells[n_inputs..]
+            .iter()
+            .zip(&self.widths[n_inputs..])
+            .map(pad)
+            .collect();
+        format!("{} │ {}", inputs.join(" "), outputs.join(" "))
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum View {
+    #[default]
+    Table,
+    Expressions,
+}
+
+#[derive(Default)]
+pub struct AnalyzeWindow {
+    pub open: bool,
+    // The document to analyze; None follows the active one.
+    pub(crate) target: Option<DocId>,
+    pub(crate) outcome: Option<Result<Analysis, String>>,
+    view: View,
+    // The expression whose K-map is shown.
+    selected: usize,
+}
+
+impl AnalyzeWindow {
+    // `docs` lists every document's id and name, in palette order.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        theme: Theme,
+        docs: &[(DocId, &str)],
+    ) -> Option<AnalyzeAction> {
+        let mut action = None;
+        let mut open = self.open;
+        egui::Window::new("Analyze")
+            .open(&mut open)
+            .default_size([360.0, 420.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let name_of = |target: Option<DocId>| match target {
+                        None => "Active circuit",
+                        Some(id) => docs
+                            .iter()
+                            .find(|(d, _)| *d == id)
+                            .map_or("?", |&(_, name)| name),
+                    };
+                    let before = self.target;
+                    egui::ComboBox::from_id_salt("analyze_target")
+                        .selected_text(name_of(self.target))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.target, None, name_of(None));
+                            for &(id, name) in docs {
+                                ui.selectable_value(&mut self.target, Some(id), name);
+                            }
+                        });
+                    if self.target != before {
+                        self.outcome = None;
+                        self.selected = 0;
+                    }
+                    if ui.button("Analyze").clicked() {
+                        action = Some(AnalyzeAction::Analyze);
+                    }
+                });
+                ui.weak(format!(
+                    "Enumerates every Input combination, up to {MAX_INPUT_BITS} input bits in all. \
+                     Wider circuits are refused rather than cut short, since expressions \
+                     read off a partial table would be wrong."
+                ));
+                ui.separator();
+
+                match &self.outcome {
+                    None => {}
+                    Some(Err(err)) => {
+                        ui.colored_label(theme.error_text, err.as_str());
+                    }
+                    Some(Ok(analysis)) => {
+                        ui.horizontal(|ui| {
+                            ui.weak(format!("{} rows", analysis.table.rows.len()));
+                            for format in [TableFormat::Csv, TableFormat::Markdown] {
+                                let label = format!("Export .{}", format.extension());
+                                if ui.button(label).clicked() {
+                                    action = Some(AnalyzeAction::Export(format));
+                                }
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.selectable_value(&mut self.view, View::Table, "Truth table");
+                            ui.selectable_value(&mut self.view, View::Expressions, "Expressions");
+                        });
+                        ui.separator();
+                        match self.view {
+                            View::Table => show_table(ui, analysis),
+                            View::Expressions => {
+                                show_expressions(ui, theme, analysis, &mut self.selected)
+                            }
+                        }
+                    }
+                }
+            });
+        self.open = open;
+        action
+    }
+}
+
+// The table as fixed-width text, only the visible rows laid out.
+fn show_table(ui: &mut egui::Ui, analysis: &Analysis) {
+    ui.monospace(analysis.line(&analysis.table.header()));
+    let row_h = ui.text_style_height(&egui::TextStyle::Monospace);
+    let rows = &analysis.table.rows;
+    egui::ScrollArea::both().id_salt("truth_table").show_rows(
+        ui,
+        row_h,
+        rows.len(),
+        |ui, range| {
+            for row in &rows[range] {
+                ui.monospace(analysis.line(&analysis.table.cells(row)));
+            }
+        },
+    );
+}
+
+// One line per Output bit; clicking one shows its K-map below, and hovering a
+// term of the shown one highlights the cells it covers.
+fn show_expressions(ui: &mut egui::Ui, theme: Theme, analysis: &Analysis, selected: &mut usize) {
+    let Some(expressions) = &analysis.expressions else {
+        ui.weak(format!(
+            "Minimization is limited to {MAX_MINIMIZE_VARS} input bits; this table has {}.",
+            analysis.vars.len()
+        ));
+        return;
+    };
+    egui::ScrollArea::vertical()
+        .id_salt("expressions")
+        .max_height(160.0)
+        .show(ui, |ui| {
+            for (i, e) in expressions.iter().enumerate() {
+                let text = egui::RichText::new(e.to_text(&analysis.vars)).monospace();
+                if ui.selectable_label(*selected == i, text).clicked() {
+                    *selected = i;
+                }
+            }
+        });
+    let Some(expression) = expressions.get(*selected) else {
+        return;
+    };
+    ui.separator();
+    if analysis.vars.len() > MAX_KMAP_VARS {
+        ui.weak(format!(
+            "Karnaugh maps are drawn for up to {MAX_KMAP_VARS} input bits."
+        ));
+        return;
+    }
+    let mut hovered = None;
+    ui.horizontal_wrapped(|ui| {
+        ui.label(format!("{} =", expression.function.name));
+        if expression.terms.is_empty() {
+            ui.monospace("0");
+        }
+        for (i, &term) in expression.terms.iter().enumerate() {
+            if i > 0 {
+                ui.label("+");
+            }
+            if ui.monospace(term.to_text(&analysis.vars)).hovered() {
+                hovered = Some(term);
+            }
+        }
+    });
+    draw_kmap(ui, theme, &analysis.vars, expression, hovered);
+}
+
+// A Gray-coded grid: the first half of the variables (rounded down) down the
+// side, the rest across the top. Cells read 1, 0, or - for a don't-care.
+fn draw_kmap(
+    ui: &mut egui::Ui,
+    theme: Theme,
+    vars: &[String],
+    expression: &Expression,
+    highlight: Option<Implicant>,
+) {
+    let n = vars.len();
+    let (row_vars, col_vars) = kmap_shape(n);
+    ui.weak(format!(
+        "rows: {}   columns: {}",
+        if row_vars == 0 {
+            "-".to_string()
+        } else {
+            vars[..row_vars].join(" ")
+        },
+        vars[row_vars..].join(" ")
+    ));
+    let (rows, cols) = (1u32 << row_vars, 1u32 << col_vars);
+    // Up to three label bits fit a cell's width.
+    let (header_w, header_h) = (KMAP_CELL, KMAP_CELL);
+    let size = Vec2::new(
+        header_w + cols as f32 * KMAP_CELL,
+        header_h + rows as f32 * KMAP_CELL,
+    );
+    let (rect, _) = ui.allocate_exact_size(size, Sense::hover());
+    let painter = ui.painter_at(rect);
+    let font = FontId::monospace(11.0);
+    let label = |i: u32, bits: usize| {
+        let code = minimize::gray(i);
+        (0..bits)
+            .rev()
+            .map(|b| if code >> b & 1 == 1 { '1' } else { '0' })
+            .collect::<String>()
+    };
+
+    let origin = rect.min + Vec2::new(header_w, header_h);
+    for col in 0..cols {
+        let center = origin + Vec2::new((col as f32 + 0.5) * KMAP_CELL, -header_h / 2.0);
+        let text = label(col, col_vars);
+        painter.text(
+            center,
+            Align2::CENTER_CENTER,
+            text,
+            font.clone(),
+            theme.ghost_preview,
+        );
+    }
+    for row in 0..rows {
+        let center = origin + Vec2::new(-header_w / 2.0, (row as f32 + 0.5) * KMAP_CELL);
+        let text = label(row, row_vars);
+        painter.text(
+            center,
+            Align2::CENTER_CENTER,
+            text,
+            font.clone(),
+            theme.ghost_preview,
+        );
+        for col in 0..cols {
+            let min = origin + Vec2::new(col as f32, row as f32) * KMAP_CELL;
+            let cell = Rect::from_min_size(min, Vec2::splat(KMAP_CELL));
+            let m = kmap_minterm(n, row, col);
+            if highlight.is_some_and(|t| t.covers(m)) {
+                painter.rect_filled(cell, 0.0, theme.outline_selected.gamma_multiply(0.35));
+            }
+            let stroke = Stroke::new(1.0, theme.grid_dot);
+            painter.rect_stroke(cell, 0.0, stroke, egui::StrokeKind::Inside);
+            let text = match expression.function.at(m) {
+                Some(true) => "1",
+                Some(false) => "0",
+                None => "-",
+            };
+            painter.text(
+                cell.center(),
+                Align2::CENTER_CENTER,
+                text,
+                font.clone(),
+                theme.label_text,
+            );
+        }
+    }
+}
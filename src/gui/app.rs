@@ -3,15 +3,24 @@ use egui::{Pos2, Rect, Sense};
 use slotmap::SlotMap;
 use std::collections::HashMap;
 
+use crate::checkpoint::{Checkpoint, CheckpointFile, Layout, CHECKPOINT_FILE_EXT};
+use crate::gui::analyze::{Analysis, AnalyzeAction, AnalyzeWindow, TableFormat};
 use crate::gui::canvas_draw::draw_ghost;
+use crate::gui::checkpoints::CheckpointAction;
 use crate::gui::clipboard::{build_selection_snapshot, Clipboard};
 use crate::gui::document::{default_new_circuit_name, CircuitDoc, DocId, Document};
-use crate::gui::geometry::{tunnel_shape, Camera, GridPos, ZOOM_SCROLL_SPEED};
+use crate::gui::geometry::{pin_grid_pos, tunnel_shape, Camera, GridPos, ZOOM_SCROLL_SPEED};
 use crate::gui::gui_undo::GuiUndoAction;
 use crate::gui::history::History;
+use crate::gui::memory_editor::{MemFile, MemKind};
 use crate::gui::placed_component::PlacedComponent;
 use crate::gui::properties::PropGuiAction;
 use crate::gui::shape::ComponentShape;
+use crate::gui::signal_viewer::SignalViewerAction;
+use crate::gui::statistics::{StatisticsAction, StatisticsWindow};
+use crate::gui::stimulus_dialog::{StimulusDialog, StimulusDialogAction};
+use crate::gui::synthesize::{self, SynthesizeAction, SynthesizeDialog};
+use crate::gui::test_vectors::TestVectorAction;
 use crate::gui::theme::Theme;
 use crate::gui::utils::CanvasCtx;
 use crate::gui::wiring::{NodeAttach, WireNode, WireNodeKey, WireSegKey, Wiring};
@@ -19,11 +28,18 @@ use crate::io::{
     CircuitEntry, CircuitSnapshot, ComponentEntry, LoadError, NodeAttachEntry, NodeEntry,
     ProjectFile, SegEntry, SubcircuitRef, TunnelEntry,
 };
+use crate::logisim;
 use crate::platform;
+use crate::project;
 use crate::sim::circuit::{Circuit, TunnelKey, TunnelRole};
 use crate::sim::command::Command;
 use crate::sim::component::*;
+use crate::sim::synth::Netlist;
+use crate::sim::test_vector::{self, TestVectors};
+use crate::sim::truth_table::TruthTable;
 use crate::sim::value::Value;
+use crate::stats::Bom;
+use crate::verilog;
 
 // ── Constants ─────────────────────────────────────────────────────────────────
 
@@ -38,8 +54,8 @@ const GIT_SHA: &str = env!("OSMILOG_GIT_SHA");
 
 // ── PlacedTunnel ──────────────────────────────────────────────────────────────
 
-// Visual record for a Tunnel. Tunnels are the only entity with a user-editable
-// label; Components only show hardcoded per-type/pin labels.
+// Visual record for a Tunnel. Tunnels connect by matching label, so unlike a
+// component's label (see ComponentSpec::port_name) it is also live sim state.
 #[derive(Debug)]
 pub struct PlacedTunnel {
     pub key: TunnelKey,
@@ -142,6 +158,17 @@ pub struct OsmilogApp {
     // while closed. The snapshot is captured at dialog-open time so later
     // edits to the originals can't invalidate it.
     new_circuit_dialog: Option<(String, CircuitSnapshot)>,
+    // The Replay Stimulus dialog for a picked file; `None` while closed.
+    stimulus_dialog: Option<StimulusDialog>,
+    // The truth-table window; it can target any document, so it lives here.
+    analyze: AnalyzeWindow,
+    // The bill-of-materials window; like Analyze, it's app-wide.
+    statistics: StatisticsWindow,
+    // The Synthesize Circuit dialog; `None` while closed.
+    synthesize_dialog: Option<SynthesizeDialog>,
+    // What the last Logisim import left out, listed until dismissed; `None`
+    // while closed.
+    import_report: Option<Vec<String>>,
 }
 
 impl OsmilogApp {
@@ -163,6 +190,11 @@ impl OsmilogApp {
             doc_order: vec![active_id],
             active_id,
             new_circuit_dialog: None,
+            stimulus_dialog: None,
+            analyze: AnalyzeWindow::default(),
+            statistics: StatisticsWindow::default(),
+            synthesize_dialog: None,
+            import_report: None,
         }
     }
 
@@ -187,6 +219,10 @@ impl OsmilogApp {
         &mut self.documents[self.active_id].state
     }
 
+    pub(crate) fn active_id(&self) -> DocId {
+        self.active_id
+    }
+
     pub(crate) fn switch_document(&mut self, target: DocId) {
         if target == self.active_id {
             return;
@@ -227,11 +263,21 @@ impl OsmilogApp {
         self.active_mut().place_component(comp, spec, grid_pos)
     }
 
+    // Places a saved or copied record, with any delay set on it.
+    fn place_entry(&mut self, entry: &ComponentEntry) -> PlacedCompKey {
+        let comp = self.instantiate(&entry.spec).with_saved_delay(entry.delay);
+        let doc = self.active_mut();
+        let pc_key = doc.place_component(comp, entry.spec.clone(), entry.grid_pos);
+        doc.components.get_mut(&pc_key).unwrap().delay = entry.delay;
+        pc_key
+    }
+
     // The single place `PropGuiAction`s (from the read-only properties panel)
     // become mutations, keeping the panel decoupled from `OsmilogApp`.
     pub(crate) fn apply_prop_gui_action(&mut self, action: PropGuiAction) {
         match action {
             PropGuiAction::Reconfigure(key, spec) => self.reconfigure_component(key, spec),
+            PropGuiAction::SetDelay(key, delay) => self.set_component_delay(key, delay),
             PropGuiAction::OpenMemory(key, kind) => self.active_mut().memory_editor.open(key, kind),
             PropGuiAction::OpenCircuit(doc) => self.switch_document(doc),
             PropGuiAction::CreateCircuit => self.open_extract_circuit_dialog(),
@@ -275,11 +321,22 @@ impl OsmilogApp {
     }
 
     // Builds the new Component via `instantiate` (needs the document
-    // registry) and hands it to `Document::reconfigure_component`.
+    // registry) and hands it to `Document::reconfigure_component`. The
+    // component keeps any delay set on it.
     pub(crate) fn reconfigure_component(&mut self, pc_key: PlacedCompKey, new_spec: ComponentSpec) {
-        let new_comp = self.instantiate(&new_spec);
+        let delay = self.active().components[&pc_key].delay;
+        let new_comp = self.instantiate(&new_spec).with_saved_delay(delay);
         self.active_mut()
-            .reconfigure_component(pc_key, new_spec, new_comp);
+            .reconfigure_component(pc_key, new_spec, new_comp, delay);
+    }
+
+    // A delay edit rebuilds the component like any other property edit, so
+    // it undoes the same way.
+    fn set_component_delay(&mut self, pc_key: PlacedCompKey, delay: Option<u32>) {
+        let spec = self.active().components[&pc_key].spec.clone();
+        let new_comp = self.instantiate(&spec).with_saved_delay(delay);
+        self.active_mut()
+            .reconfigure_component(pc_key, spec, new_comp, delay);
     }
 
     // ── Subcircuits ───────────────────────────────────────────────────────────
@@ -327,8 +384,9 @@ impl OsmilogApp {
 
     // Builds a standalone Circuit from a document's records, the same way
     // Document::rebuild_circuit does but untracked and into a new Circuit.
-    // Returns Input/Output keys ordered top-down then left-to-right, the pin
-    // order the subcircuit exposes.
+    // Returns Input/Output keys in the pin order the subcircuit exposes (see
+    // project::boundary_pins). project::build_circuit is the same build
+    // straight from a ProjectFile.
     fn build_doc_circuit(
         &self,
         doc: DocId,
@@ -343,9 +401,13 @@ impl OsmilogApp {
 
         let mut circuit = Circuit::new();
         let mut comp_map: HashMap<PlacedCompKey, CompKey> = HashMap::new();
-        for (pck, pc) in state.components.iter() {
+        // In key order, so a live instance's inner parts can be told apart
+        // again by the order of their keys (see instance_layout).
+        let mut records: Vec<_> = state.components.iter().collect();
+        records.sort_by_key(|(pck, _)| pck.0);
+        for (pck, pc) in records {
             let comp = self.instantiate_with(&pc.spec, visited);
-            comp_map.insert(*pck, circuit.add_component(comp));
+            comp_map.insert(*pck, circuit.add_component(comp.with_saved_delay(pc.delay)));
         }
 
         let mut tunnel_map: HashMap<PlacedTunnelKey, TunnelKey> = HashMap::new();
@@ -359,39 +421,23 @@ impl OsmilogApp {
                 .iter()
                 .filter_map(|&(pck, pin)| comp_map.get(&pck).map(|&ck| (ck, pin)))
                 .collect();
-            let Some(&(anchor_comp, anchor_pin)) = pins.first() else {
-                continue;
-            };
-            for &(comp, pin) in &pins[1..] {
-                circuit.link(anchor_comp, anchor_pin, comp, pin);
-            }
-            for &ptk in &group.tunnels {
-                if let Some(&tk) = tunnel_map.get(&ptk) {
-                    circuit.link_tunnel(tk, anchor_comp, anchor_pin);
-                }
-            }
+            let tunnels: Vec<TunnelKey> = group
+                .tunnels
+                .iter()
+                .filter_map(|ptk| tunnel_map.get(ptk).copied())
+                .collect();
+            project::link_group(&mut circuit, &pins, &tunnels);
         }
 
-        // Boundary pins are ordered top-down (then left-to-right) by the
-        // Input/Output components' grid positions.
-        let mut inputs: Vec<(GridPos, CompKey)> = Vec::new();
-        let mut outputs: Vec<(GridPos, CompKey)> = Vec::new();
-        for (pck, pc) in state.components.iter() {
-            match pc.spec {
-                ComponentSpec::Input(_) => inputs.push((pc.grid_pos, comp_map[pck])),
-                ComponentSpec::Output => outputs.push((pc.grid_pos, comp_map[pck])),
-                _ => {}
-            }
-        }
-        inputs.sort_by_key(|(g, _)| (g.y, g.x));
-        outputs.sort_by_key(|(g, _)| (g.y, g.x));
+        let (inputs, outputs) = project::boundary_pins(
+            state
+                .components
+                .iter()
+                .map(|(pck, pc)| (&pc.spec, pc.grid_pos, comp_map[pck])),
+        );
 
         let _ = circuit.settle();
-        (
-            circuit,
-            inputs.into_iter().map(|(_, k)| k).collect(),
-            outputs.into_iter().map(|(_, k)| k).collect(),
-        )
+        (circuit, inputs, outputs)
     }
 
     // Display name plus boundary pin widths (top-down), cached on
@@ -417,10 +463,7 @@ impl OsmilogApp {
             .collect();
         let output_widths = out_keys
             .iter()
-            .map(|&k| match circuit.read_output(k) {
-                Value::Fixed { width, .. } => width,
-                _ => 1,
-            })
+            .map(|&k| circuit.read_output(k).width().unwrap_or(1))
             .collect();
         (name, input_widths, output_widths)
     }
@@ -548,6 +591,334 @@ impl OsmilogApp {
         false
     }
 
+    // Exports the active document's recorded probe traces as a VCD file named
+    // after the circuit. A tick spans one time unit, or the clock's step
+    // length in timed mode, matching the time the run actually simulated.
+    fn export_vcd(&mut self) {
+        let name = self.documents[self.active_id].name.clone();
+        let doc = self.active();
+        let vcd = doc
+            .signal_log
+            .to_vcd(&doc.components, &name, doc.tick_period());
+        let file_name = format!("{name}.vcd");
+        self.with_io(|io, app| io.request_export(app, &file_name, &vcd));
+    }
+
+    // Exports the whole project as structural Verilog (see verilog.rs), named
+    // after the active document.
+    fn export_verilog(&mut self) {
+        let verilog = verilog::export(&self.to_project_file());
+        let file_name = format!("{}.v", self.documents[self.active_id].name);
+        self.with_io(|io, app| io.request_export(app, &file_name, &verilog));
+    }
+
+    // ── Logisim import ───────────────────────────────────────────────────
+
+    // Replaces the project with a Logisim one platform IO has read (see
+    // IoState::request_logisim), then lists anything the import left out.
+    pub(crate) fn import_logisim(&mut self, text: &str) {
+        let imported = match logisim::import(text) {
+            Ok(imported) => imported,
+            Err(e) => {
+                self.io_error = Some(format!("import failed: {e}"));
+                return;
+            }
+        };
+        if let Err(e) = self.load_project_file(&imported.file) {
+            self.io_error = Some(format!("import failed: {e}"));
+            return;
+        }
+        self.import_report = (!imported.unsupported.is_empty()).then_some(imported.unsupported);
+    }
+
+    fn show_import_report(&mut self, ctx: &egui::Context) {
+        let Some(lines) = &self.import_report else {
+            return;
+        };
+        let mut open = true;
+        egui::Window::new("Logisim Import")
+            .open(&mut open)
+            .default_size([420.0, 260.0])
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} element(s) were left out or approximated:",
+                    lines.len()
+                ));
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for line in lines {
+                        ui.label(line);
+                    }
+                });
+            });
+        if !open {
+            self.import_report = None;
+        }
+    }
+
+    // ── Stimulus replay ──────────────────────────────────────────────────
+
+    // Opens the Replay Stimulus dialog on a file platform IO has read (see
+    // IoState::request_stimulus).
+    pub(crate) fn open_stimulus(&mut self, file_name: String, text: String) {
+        let period = self.active().tick_period();
+        self.stimulus_dialog = Some(StimulusDialog::new(file_name, text, period));
+    }
+
+    fn show_stimulus_dialog(&mut self, ctx: &egui::Context, theme: Theme) {
+        let Some(dialog) = &mut self.stimulus_dialog else {
+            return;
+        };
+        let doc = &mut self.documents[self.active_id].state;
+        let ports = test_vector::ports(doc.components.values().map(|pc| (&pc.spec, pc.key)));
+        match dialog.show(ctx, theme, &ports) {
+            None => {}
+            Some(StimulusDialogAction::Close) => self.stimulus_dialog = None,
+            Some(StimulusDialogAction::Replay(stimulus)) => {
+                if let Err(e) = doc.replay_stimulus(&stimulus) {
+                    self.io_error = Some(format!("replay failed: {e}"));
+                }
+                self.stimulus_dialog = None;
+            }
+        }
+    }
+
+    // ── Truth tables ─────────────────────────────────────────────────────
+
+    // Tabulates the Analyze window's target document from a fresh build of
+    // the saved project, like run_test_vectors; its boundary Inputs and
+    // Outputs are the columns.
+    fn analyze_circuit(&mut self) {
+        let doc = self.analyze.target.unwrap_or(self.active_id);
+        let file = self.to_project_file();
+        let index = self.doc_order.iter().position(|&d| d == doc).unwrap();
+        let mut built = project::build_circuit(&file, index);
+        let (inputs, outputs) = built.boundary_ports(&file, index);
+        let outcome = TruthTable::analyze(&mut built.circuit, inputs, outputs)
+            .map(|table| Analysis::new(doc, table))
+            .map_err(|e| e.to_string());
+        self.analyze.outcome = Some(outcome);
+    }
+
+    // Exports the last truth table, named after the circuit it's of.
+    fn export_truth_table(&mut self, format: TableFormat) {
+        let Some(Ok(analysis)) = &self.analyze.outcome else {
+            return;
+        };
+        let file_name = format!(
+            "{}.{}",
+            self.documents[analysis.doc].name,
+            format.extension()
+        );
+        let contents = format.render(&analysis.table);
+        self.with_io(|io, app| io.request_export(app, &file_name, &contents));
+    }
+
+    // ── Statistics ───────────────────────────────────────────────────────
+
+    // Counts the active circuit's parts from the saved project, so every
+    // subcircuit instance is counted from its definition.
+    fn count_parts(&mut self) {
+        let file = self.to_project_file();
+        let index = self
+            .doc_order
+            .iter()
+            .position(|&d| d == self.active_id)
+            .unwrap();
+        self.statistics.bom = Some((self.active_id, Bom::of(&file, index)));
+    }
+
+    // Exports the last count as CSV, named after the circuit it's of.
+    fn export_bom(&mut self) {
+        let Some((doc, bom)) = &self.statistics.bom else {
+            return;
+        };
+        let file_name = format!("{}.csv", self.documents[*doc].name);
+        let csv = bom.to_csv(self.statistics.estimate);
+        self.with_io(|io, app| io.request_export(app, &file_name, &csv));
+    }
+
+    // ── Checkpoints ──────────────────────────────────────────────────────
+
+    // Where every part of the active circuit's live build sits, through its
+    // subcircuit instances (see checkpoint::Layout).
+    fn checkpoint_layout(&self) -> Layout {
+        let doc = self.active();
+        Layout {
+            parts: doc
+                .components
+                .values()
+                .map(|pc| {
+                    let inner = self.instance_layout(&pc.spec, doc.circuit.components.get(&pc.key));
+                    (pc.key, (pc.grid_pos, inner))
+                })
+                .collect(),
+        }
+    }
+
+    // An instance's inner circuit was built by build_doc_circuit, which adds
+    // its document's records in key order, each under the next CompKey. A
+    // count that doesn't match is a placeholder, with no parts to name.
+    fn instance_layout(&self, spec: &ComponentSpec, comp: Option<&Component>) -> Layout {
+        let (ComponentSpec::Subcircuit { doc, .. }, Some(Logic::Sub(sub))) =
+            (spec, comp.map(|c| &c.logic))
+        else {
+            return Layout::default();
+        };
+        let Some(cdoc) = self.documents.get(*doc) else {
+            return Layout::default();
+        };
+        let mut records: Vec<_> = cdoc.state.components.iter().collect();
+        records.sort_by_key(|(pck, _)| pck.0);
+        let mut keys: Vec<CompKey> = sub.inner.components.keys().copied().collect();
+        keys.sort_by_key(|key| key.0);
+        if keys.len() != records.len() {
+            return Layout::default();
+        }
+        Layout {
+            parts: keys
+                .into_iter()
+                .zip(records)
+                .map(|(key, (_, pc))| {
+                    let inner = self.instance_layout(&pc.spec, sub.inner.components.get(&key));
+                    (key, (pc.grid_pos, inner))
+                })
+                .collect(),
+        }
+    }
+
+    // Snapshots the active circuit as it stands, named after its tick unless
+    // given a name.
+    fn take_checkpoint(&mut self, name: String) {
+        let layout = self.checkpoint_layout();
+        let doc = self.active_mut();
+        let tick = doc.current_tick();
+        let name = if name.is_empty() {
+            format!("Tick {tick}")
+        } else {
+            name
+        };
+        let checkpoint = Checkpoint::take(name, tick, &doc.circuit, &layout);
+        doc.checkpoints.checkpoints.push(checkpoint);
+    }
+
+    fn restore_checkpoint(&mut self, index: usize) {
+        let layout = self.checkpoint_layout();
+        let doc = self.active_mut();
+        let Some(checkpoint) = doc.checkpoints.checkpoints.get(index).cloned() else {
+            return;
+        };
+        doc.restore_checkpoint(&checkpoint, &layout);
+    }
+
+    // Saves the active circuit's checkpoints to a sidecar file named after it.
+    fn save_checkpoints(&mut self) {
+        let name = self.documents[self.active_id].name.clone();
+        let file = CheckpointFile::new(name.clone(), self.active().checkpoints.checkpoints.clone());
+        let json = match file.to_json() {
+            Ok(json) => json,
+            Err(e) => {
+                self.io_error = Some(format!("export failed: {e}"));
+                return;
+            }
+        };
+        let file_name = format!("{name}.{CHECKPOINT_FILE_EXT}");
+        self.with_io(|io, app| io.request_export(app, &file_name, &json));
+    }
+
+    // Adds the checkpoints of a sidecar file platform IO has read (see
+    // IoState::request_checkpoints) to the active circuit's list. Parts are
+    // matched by position on restore, so a file saved from another circuit
+    // loads fine and simply finds little to put back.
+    pub(crate) fn load_checkpoints(&mut self, text: &str) {
+        match CheckpointFile::from_json(text) {
+            Ok(file) => {
+                let panel = &mut self.active_mut().checkpoints;
+                panel.checkpoints.extend(file.checkpoints);
+                panel.open = true;
+            }
+            Err(e) => self.io_error = Some(format!("checkpoint load failed: {e}")),
+        }
+    }
+
+    // ── Memory images ────────────────────────────────────────────────────
+
+    // Writes an open memory editor's contents out as an image file, in the
+    // format its window has picked.
+    fn export_memory_image(&mut self, pc: PlacedCompKey, kind: MemKind) {
+        match self.active().export_memory_image(pc, kind) {
+            Some(Ok((file_name, bytes))) => {
+                self.with_io(|io, app| io.request_export_bytes(app, &file_name, &bytes))
+            }
+            Some(Err(e)) => self.io_error = Some(format!("memory export failed: {e}")),
+            None => {}
+        }
+    }
+
+    // Loads an image file platform IO has read (see
+    // IoState::request_memory_image) into a memory of document `doc`, the one
+    // active when Import… was clicked: the web's picker is async, and another
+    // tab may be active by the time the file arrives. A closed document has
+    // nothing left to load into.
+    pub(crate) fn import_memory_image(
+        &mut self,
+        doc: DocId,
+        pc: PlacedCompKey,
+        kind: MemKind,
+        bytes: &[u8],
+    ) {
+        let Some(doc) = self.documents.get_mut(doc) else {
+            return;
+        };
+        if let Err(e) = doc.state.import_memory_image(pc, kind, bytes) {
+            self.io_error = Some(format!("memory import failed: {e}"));
+        }
+    }
+
+    // ── Test vectors ─────────────────────────────────────────────────────
+
+    // Runs the panel's table against a fresh build of the active circuit -
+    // from the same records a save would write, so the live circuit and its
+    // run state are untouched. Mirrors the document's timed mode and step
+    // length. A failing run jumps to its first wrong output.
+    fn run_test_vectors(&mut self) {
+        let file = self.to_project_file();
+        let built = project::build_circuit(&file, file.active);
+        let mut circuit = built.circuit;
+        let records = &file.circuits[file.active].snapshot.components;
+        let ports = test_vector::ports(
+            records
+                .iter()
+                .zip(&built.comp_keys)
+                .map(|(r, &key)| (&r.spec, key)),
+        );
+        let doc = self.active_mut();
+        let timed = doc
+            .circuit
+            .is_timed()
+            .then_some(doc.clock.time_per_tick as u64);
+        let outcome = run_vectors(&doc.test_vectors.text, &mut circuit, &ports, timed);
+        doc.test_vectors.set_outcome(outcome);
+        if let Some(port) = doc.test_vectors.first_failure().map(str::to_string) {
+            self.focus_port(&port);
+        }
+    }
+
+    // Selects the active circuit's component named `port` (see
+    // ComponentSpec::port_name) and centers the canvas on it next frame.
+    fn focus_port(&mut self, port: &str) {
+        let doc = self.active_mut();
+        let Some(pck) = doc
+            .components
+            .iter()
+            .find(|(_, pc)| pc.spec.port_name() == Some(port))
+            .map(|(&pck, _)| pck)
+        else {
+            return;
+        };
+        doc.selected = Some(Selection::Single(Selected::Component(pck)));
+        doc.test_vectors.pending_focus = Some(pck);
+    }
+
     // ── Save / load ──────────────────────────────────────────────────────
 
     // Serializes every circuit document (not just active) into a ProjectFile.
@@ -659,7 +1030,7 @@ impl OsmilogApp {
         let comp_keys: Vec<PlacedCompKey> = snapshot
             .components
             .iter()
-            .map(|entry| self.place_component(entry.spec.clone(), entry.grid_pos))
+            .map(|entry| self.place_entry(entry))
             .collect();
 
         // Re-bind each Subcircuit's doc (serde-skipped, loaded as a null
@@ -792,6 +1163,48 @@ impl OsmilogApp {
         }
     }
 
+    fn show_synthesize_dialog(&mut self, ctx: &egui::Context, theme: Theme) {
+        let Some(dialog) = &mut self.synthesize_dialog else {
+            return;
+        };
+        match dialog.show(ctx, theme) {
+            None => {}
+            Some(SynthesizeAction::Close) => self.synthesize_dialog = None,
+            Some(SynthesizeAction::Create { name, netlist }) => {
+                let name = match name.trim() {
+                    "" => default_new_circuit_name(&self.documents),
+                    trimmed => trimmed.to_string(),
+                };
+                self.create_synthesized_document(name, &netlist);
+                self.synthesize_dialog = None;
+            }
+        }
+    }
+
+    // Makes a new active document holding `netlist`, placed and wired by
+    // synthesize::layout. Untracked, like an extracted circuit.
+    pub(crate) fn create_synthesized_document(&mut self, name: String, netlist: &Netlist) {
+        let layout = synthesize::layout(netlist);
+        self.create_document(name);
+        let keys: Vec<PlacedCompKey> = layout
+            .components
+            .into_iter()
+            .map(|(spec, grid_pos)| self.place_component(spec, grid_pos))
+            .collect();
+        let attach = |end: Option<(usize, PinId)>| {
+            end.map_or(NodeAttach::Free, |(i, pin)| NodeAttach::Pin(keys[i], pin))
+        };
+        for route in &layout.routes {
+            self.active_mut().wiring.add_route(
+                &route.points,
+                attach(route.start),
+                attach(route.end),
+            );
+        }
+        self.active_mut().rebuild_circuit();
+        self.active_mut().history = History::default();
+    }
+
     // No-op if nothing is selected. Read-only: never touches history.
     fn copy_selection(&mut self) {
         let doc = &self.documents[self.active_id].state;
@@ -817,7 +1230,7 @@ impl OsmilogApp {
         let comp_keys: Vec<PlacedCompKey> = file
             .components
             .iter()
-            .map(|entry| self.place_component(entry.spec.clone(), entry.grid_pos))
+            .map(|entry| self.place_entry(entry))
             .collect();
 
         let tunnel_keys: Vec<PlacedTunnelKey> = file
@@ -906,6 +1319,33 @@ impl OsmilogApp {
                         self.with_io(|io, app| io.request_load(app));
                         ui.close();
                     }
+                    if ui
+                        .add_enabled(!locked, egui::Button::new("Import Logisim…"))
+                        .clicked()
+                    {
+                        // Replaces the project, like Load.
+                        self.with_io(|io, app| io.request_logisim(app));
+                        ui.close();
+                    }
+                    ui.separator();
+                    // Allowed mid-run too: a replay starts from Stop.
+                    if ui.button("Replay Stimulus…").clicked() {
+                        self.with_io(|io, app| io.request_stimulus(app));
+                        ui.close();
+                    }
+                    if ui.button("Export Verilog…").clicked() {
+                        self.export_verilog();
+                        ui.close();
+                    }
+                    ui.separator();
+                    if ui
+                        .add_enabled(!locked, egui::Button::new("Synthesize Circuit…"))
+                        .clicked()
+                    {
+                        let name = default_new_circuit_name(&self.documents);
+                        self.synthesize_dialog = Some(SynthesizeDialog::new(name));
+                        ui.close();
+                    }
                 });
                 ui.add_enabled_ui(!locked, |ui| {
                     ui.menu_button("Edit", |ui| {
@@ -951,6 +1391,12 @@ impl OsmilogApp {
                 });
                 ui.menu_button("View", |ui| {
                     ui.checkbox(&mut self.active_mut().signal_viewer.open, "Signal Viewer");
+                    ui.checkbox(&mut self.active_mut().test_vectors.open, "Test Vectors");
+                    ui.checkbox(&mut self.analyze.open, "Analyze");
+                    ui.checkbox(&mut self.statistics.open, "Statistics");
+                    ui.checkbox(&mut self.active_mut().timing.open, "Timing");
+                    ui.checkbox(&mut self.active_mut().breakpoints.open, "Breakpoints");
+                    ui.checkbox(&mut self.active_mut().checkpoints.open, "Checkpoints");
                 });
                 ui.menu_button("Debug", |ui| {
                     ui.checkbox(&mut self.show_profiler, "Profiler");
@@ -1027,7 +1473,7 @@ impl OsmilogApp {
 
             if ui.button("Input").clicked() {
                 self.active_mut().mode = InteractionMode::Placing {
-                    spec: ComponentSpec::Input(Input { bits: 0, width: 1 }),
+                    spec: ComponentSpec::Input(Input::new(0, 1)),
                 };
             }
             if ui.button("Constant").clicked() {
@@ -1035,9 +1481,22 @@ impl OsmilogApp {
                     spec: ComponentSpec::Constant(Constant { bits: 0, width: 1 }),
                 };
             }
+            if ui.button("Pull Resistor").clicked() {
+                self.active_mut().mode = InteractionMode::Placing {
+                    spec: ComponentSpec::PullResistor(PullResistor {
+                        direction: PullDirection::Up,
+                        width: 1,
+                    }),
+                };
+            }
+            if ui.button("Clock").clicked() {
+                self.active_mut().mode = InteractionMode::Placing {
+                    spec: ComponentSpec::ClockSource(ClockSourceConf::default()),
+                };
+            }
             if ui.button("Output").clicked() {
                 self.active_mut().mode = InteractionMode::Placing {
-                    spec: ComponentSpec::Output,
+                    spec: ComponentSpec::Output(Default::default()),
                 };
             }
             if ui.button("Probe").clicked() {
@@ -1066,6 +1525,11 @@ impl OsmilogApp {
                         };
                     }
                 }
+                if ui.button("Controlled Buffer").clicked() {
+                    self.active_mut().mode = InteractionMode::Placing {
+                        spec: ComponentSpec::TriState(TriState { data_width: 1 }),
+                    };
+                }
             });
             egui::CollapsingHeader::new("Plexers").show(ui, |ui| {
                 if ui.button("Mux").clicked() {
@@ -1129,7 +1593,10 @@ impl OsmilogApp {
             egui::CollapsingHeader::new("Memory").show(ui, |ui| {
                 if ui.button("Register").clicked() {
                     self.active_mut().mode = InteractionMode::Placing {
-                        spec: ComponentSpec::Reg(RegConf { data_width: 1 }),
+                        spec: ComponentSpec::Reg(RegConf {
+                            data_width: 1,
+                            clock_edge: ClockEdge::default(),
+                        }),
                     };
                 }
                 if ui.button("Shift Register").clicked() {
@@ -1138,6 +1605,7 @@ impl OsmilogApp {
                             data_width: 1,
                             num_stages: 4,
                             parallel_load: false,
+                            clock_edge: ClockEdge::default(),
                         }),
                     };
                 }
@@ -1148,28 +1616,29 @@ impl OsmilogApp {
                             data_width,
                             max_value: Value::mask(data_width),
                             overflow_action: OverflowAction::default(),
+                            clock_edge: ClockEdge::default(),
                         }),
                     };
                 }
                 egui::CollapsingHeader::new("Flip-Flop").show(ui, |ui| {
                     if ui.button("D Flip-Flop").clicked() {
                         self.active_mut().mode = InteractionMode::Placing {
-                            spec: ComponentSpec::DFlipFlop(DFlipFlopConf),
+                            spec: ComponentSpec::DFlipFlop(DFlipFlopConf::default()),
                         };
                     }
                     if ui.button("T Flip-Flop").clicked() {
                         self.active_mut().mode = InteractionMode::Placing {
-                            spec: ComponentSpec::TFlipFlop(TFlipFlopConf),
+                            spec: ComponentSpec::TFlipFlop(TFlipFlopConf::default()),
                         };
                     }
                     if ui.button("JK Flip-Flop").clicked() {
                         self.active_mut().mode = InteractionMode::Placing {
-                            spec: ComponentSpec::JKFlipFlop(JKFlipFlopConf),
+                            spec: ComponentSpec::JKFlipFlop(JKFlipFlopConf::default()),
                         };
                     }
                     if ui.button("SR Flip-Flop").clicked() {
                         self.active_mut().mode = InteractionMode::Placing {
-                            spec: ComponentSpec::SRFlipFlop(SRFlipFlopConf),
+                            spec: ComponentSpec::SRFlipFlop(SRFlipFlopConf::default()),
                         };
                     }
                 });
@@ -1387,10 +1856,50 @@ impl eframe::App for OsmilogApp {
         }
 
         self.show_menu_bar(ui, theme);
-        self.active_mut().show_memory_editors(&ctx);
+        match self.active_mut().show_memory_editors(&ctx) {
+            Some(MemFile::Import(pc, kind)) => {
+                self.with_io(|io, app| io.request_memory_image(app, pc, kind))
+            }
+            Some(MemFile::Export(pc, kind)) => self.export_memory_image(pc, kind),
+            None => {}
+        }
+        self.active_mut().show_timing(&ctx, theme);
+        self.active_mut().show_breakpoints(&ctx, theme);
+        if self.active().checkpoints.open {
+            match self.active_mut().checkpoints.show(&ctx, theme) {
+                Some(CheckpointAction::Take(name)) => self.take_checkpoint(name),
+                Some(CheckpointAction::Restore(i)) => self.restore_checkpoint(i),
+                Some(CheckpointAction::Save) => self.save_checkpoints(),
+                Some(CheckpointAction::Load) => self.with_io(|io, app| io.request_checkpoints(app)),
+                None => {}
+            }
+        }
 
         // Covers both a blank circuit and an extracted bulk selection.
         self.create_extract_circuit_dialog(&ctx);
+        self.show_stimulus_dialog(&ctx, theme);
+        self.show_synthesize_dialog(&ctx, theme);
+        self.show_import_report(&ctx);
+        if self.analyze.open {
+            let docs: Vec<(DocId, &str)> = self
+                .doc_order
+                .iter()
+                .map(|&d| (d, self.documents[d].name.as_str()))
+                .collect();
+            let action = self.analyze.show(&ctx, theme, &docs);
+            match action {
+                Some(AnalyzeAction::Analyze) => self.analyze_circuit(),
+                Some(AnalyzeAction::Export(format)) => self.export_truth_table(format),
+                None => {}
+            }
+        }
+        if self.statistics.open {
+            match self.statistics.show(&ctx) {
+                Some(StatisticsAction::Count) => self.count_parts(),
+                Some(StatisticsAction::Export) => self.export_bom(),
+                None => {}
+            }
+        }
 
         // Web-only "Save As" modal; completes the download on confirm.
         // TODO: Figure out if this weird closure stuff is necessary
@@ -1435,18 +1944,50 @@ impl eframe::App for OsmilogApp {
                 .default_size(180.0)
                 .show(ui, |ui| {
                     let doc = self.active_mut();
-                    let clear = doc
-                        .signal_viewer
-                        .show(&doc.components, &doc.signal_log, theme, ui);
-                    if clear {
-                        doc.signal_log.clear();
+                    let scrubber = doc.scrubber();
+                    let action = doc.signal_viewer.show(
+                        &doc.components,
+                        &doc.signal_log,
+                        scrubber,
+                        theme,
+                        ui,
+                    );
+                    match action {
+                        Some(SignalViewerAction::Clear) => {
+                            doc.signal_log.clear();
+                            doc.rewind.clear();
+                        }
+                        Some(SignalViewerAction::ExportVcd) => self.export_vcd(),
+                        Some(SignalViewerAction::Seek(tick)) => doc.seek_tick(tick),
+                        None => {}
                     }
                 });
         }
 
+        if self.active().test_vectors.open {
+            let action = egui::Panel::right("test_vectors")
+                .resizable(true)
+                .default_size(260.0)
+                .show(ui, |ui| self.active_mut().test_vectors.show(theme, ui))
+                .inner;
+            match action {
+                Some(TestVectorAction::Run) => self.run_test_vectors(),
+                Some(TestVectorAction::Focus(port)) => self.focus_port(&port),
+                None => {}
+            }
+        }
+
         let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
         let clip_rect = painter.clip_rect();
 
+        // A test-vector jump needs the canvas rect, so it lands here.
+        let doc = self.active_mut();
+        if let Some(pck) = doc.test_vectors.pending_focus.take() {
+            if let Some(pc) = doc.components.get(&pck) {
+                let grid_pos = pc.grid_pos;
+                doc.camera.center_on(grid_pos, response.rect.center());
+            }
+        }
         // Updates before drawing so the change applies this same frame.
         self.active_mut().camera.handle_input(&response, &ctx);
         let camera = self.active().camera;
@@ -1465,6 +2006,24 @@ impl eframe::App for OsmilogApp {
     }
 }
 
+// Parses and runs a test-vector table; `timed` is the step length when the
+// document simulates in timed mode. Errors come back as display text.
+fn run_vectors(
+    text: &str,
+    circuit: &mut Circuit,
+    ports: &[test_vector::Port],
+    timed: Option<u64>,
+) -> Result<(TestVectors, Vec<test_vector::StepResult>), String> {
+    let vectors = TestVectors::parse(text).map_err(|e| e.to_string())?;
+    if timed.is_some() {
+        circuit.set_timed(true).map_err(|e| e.to_string())?;
+    }
+    let results = vectors
+        .run(circuit, ports, timed.unwrap_or(0))
+        .map_err(|e| e.to_string())?;
+    Ok((vectors, results))
+}
+
 // ── Geometry ─────────────────────────────────────────────────────────────────
 
 // Turns slotmap keys into plain vector indices for io.rs's persisted format.
@@ -1484,6 +2043,7 @@ fn extract_records(
             ComponentEntry {
                 spec: pc.spec.clone(),
                 grid_pos: pc.grid_pos,
+                delay: pc.delay,
             }
         })
         .collect();
@@ -1562,18 +2122,6 @@ pub(crate) fn component_bounding_rect(pc: &PlacedComponent, camera: Camera) -> R
     )
 }
 
-// The wiring counterpart of comp_pin_pos, in grid cells instead of pixels.
-pub(crate) fn pin_grid_pos(shape: &ComponentShape, grid_pos: GridPos, pin: PinId) -> GridPos {
-    let anchor = match pin {
-        PinId::In(InIdx(i)) => &shape.input_anchors[i as usize],
-        PinId::Out(OutIdx(i)) => &shape.output_anchors[i as usize],
-    };
-    GridPos {
-        x: grid_pos.x + anchor.cell.x as i32,
-        y: grid_pos.y + anchor.cell.y as i32,
-    }
-}
-
 pub(crate) fn tunnel_pin_grid(pt: &PlacedTunnel) -> GridPos {
     let shape = tunnel_shape(pt.role);
     let anchor = match pt.role {
@@ -1669,8 +2217,12 @@ pub(crate) fn tunnel_pin_at_pos<'a>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checkpoint::PartState;
+    use crate::gui::clock::ClockRun;
     use crate::gui::wiring::NodeAttach;
     use crate::sim::component::GateOp;
+    use crate::sim::synth::{self, Spec, Style};
+    use crate::stimulus::Stimulus;
 
     fn place(app: &mut OsmilogApp, spec: ComponentSpec) -> PlacedCompKey {
         app.place_component(spec, GridPos::new(0, 0))
@@ -1702,7 +2254,7 @@ mod tests {
     fn test_circuit_file_save_reflects_live_graph_after_delete() {
         // A wiring delete removes nodes/segments outright (no tombstones).
         let mut app = OsmilogApp::empty();
-        let a = place(&mut app, ComponentSpec::Input(Input { bits: 1, width: 1 }));
+        let a = place(&mut app, ComponentSpec::Input(Input::new(1, 1)));
         let g = place(
             &mut app,
             ComponentSpec::Gate(Gate {
@@ -1711,7 +2263,7 @@ mod tests {
                 width: 1,
             }),
         );
-        let o = place(&mut app, ComponentSpec::Output);
+        let o = place(&mut app, ComponentSpec::Output(Default::default()));
         connect_pins(&mut app, (a, PinId::output(0)), (g, PinId::input(0)));
         connect_pins(&mut app, (g, PinId::output(0)), (o, PinId::input(0)));
         app.active_mut().rebuild_circuit();
@@ -1746,11 +2298,263 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_failing_test_vector_selects_and_focuses_its_output() {
+        // a -> NOT -> y, and the table claims y follows a.
+        let mut app = OsmilogApp::empty();
+        let mut input = Input::new(0, 1);
+        input.label = "a".to_string();
+        let a = place(&mut app, ComponentSpec::Input(input));
+        let g = place(
+            &mut app,
+            ComponentSpec::Gate(Gate {
+                op: GateOp::Not,
+                n_inputs: 1,
+                width: 1,
+            }),
+        );
+        let y = place(
+            &mut app,
+            ComponentSpec::Output(Output {
+                label: "y".to_string(),
+            }),
+        );
+        connect_pins(&mut app, (a, PinId::output(0)), (g, PinId::input(0)));
+        connect_pins(&mut app, (g, PinId::output(0)), (y, PinId::input(0)));
+
+        app.active_mut().test_vectors.text = "a | y\n0 | 1\n1 | 1".to_string();
+        app.run_test_vectors();
+        assert_eq!(app.active().test_vectors.first_failure(), Some("y"));
+        assert_eq!(
+            app.active().selected,
+            Some(Selection::Single(Selected::Component(y)))
+        );
+        assert_eq!(app.active().test_vectors.pending_focus, Some(y));
+        // The run builds its own circuit: the live Input is untouched.
+        let live_a = app.active().components[&a].key;
+        assert_eq!(
+            app.active().circuit.components[&live_a].pins.out_cache[0],
+            Value::ZERO
+        );
+    }
+
+    #[test]
+    fn test_replay_stimulus_drives_inputs_until_stop() {
+        // a -> NOT -> probe q, two bits wide.
+        let mut app = OsmilogApp::empty();
+        let mut input = Input::new(0, 2);
+        input.label = "a".to_string();
+        let a = place(&mut app, ComponentSpec::Input(input));
+        let g = place(
+            &mut app,
+            ComponentSpec::Gate(Gate {
+                op: GateOp::Not,
+                n_inputs: 1,
+                width: 2,
+            }),
+        );
+        let q = place(
+            &mut app,
+            ComponentSpec::Probe(Probe {
+                name: "q".to_string(),
+            }),
+        );
+        connect_pins(&mut app, (a, PinId::output(0)), (g, PinId::input(0)));
+        connect_pins(&mut app, (g, PinId::output(0)), (q, PinId::input(0)));
+
+        let stimulus = Stimulus::from_csv("a,other\n1,0\n2,0\n-,0\n").unwrap();
+        let doc = app.active_mut();
+        assert_eq!(doc.replay_stimulus(&stimulus), Ok(3));
+        assert_eq!(doc.clock.run, ClockRun::Paused);
+        assert_eq!(
+            doc.signal_log.traces[&q],
+            vec![Value::new(2, 2), Value::new(1, 2), Value::new(1, 2)]
+        );
+
+        // Stop puts the Input back to its spec.
+        doc.stop_clock();
+        let live_a = doc.components[&a].key;
+        assert_eq!(doc.circuit.read_output(live_a), Value::new(0, 2));
+        assert_eq!(doc.signal_log.ticks, 0);
+
+        let unmatched = Stimulus::from_csv("b\n1\n").unwrap();
+        assert!(app.active_mut().replay_stimulus(&unmatched).is_err());
+    }
+
+    #[test]
+    fn test_import_logisim_replaces_the_project_and_lists_leftovers() {
+        let circ = "<project source=\"3.8.0\" version=\"1.0\">\
+                    <lib desc=\"#Wiring\" name=\"0\"/><lib desc=\"#Plexers\" name=\"2\"/>\
+                    <main name=\"top\"/>\
+                    <circuit name=\"top\">\
+                    <comp lib=\"0\" loc=\"(100,100)\" name=\"Pin\"/>\
+                    <comp lib=\"2\" loc=\"(200,100)\" name=\"Decoder\"/>\
+                    </circuit></project>";
+        let mut app = OsmilogApp::empty();
+        app.import_logisim(circ);
+        assert_eq!(app.io_error, None);
+        assert_eq!(app.documents[app.active_id].name, "top");
+        assert_eq!(app.active().components.len(), 1);
+        assert_eq!(
+            app.import_report,
+            Some(vec![
+                "top: Decoder at (200,100) is not supported".to_string()
+            ])
+        );
+
+        app.import_logisim("<project>");
+        assert!(app.io_error.is_some());
+        assert_eq!(app.documents[app.active_id].name, "top");
+    }
+
+    #[test]
+    fn test_analyze_tabulates_the_target_circuit() {
+        // a -> NOT -> y in Main; a register in a second document.
+        let mut app = OsmilogApp::empty();
+        let main = app.active_id;
+        let mut input = Input::new(0, 1);
+        input.label = "a".to_string();
+        let a = place(&mut app, ComponentSpec::Input(input));
+        let g = place(
+            &mut app,
+            ComponentSpec::Gate(Gate {
+                op: GateOp::Not,
+                n_inputs: 1,
+                width: 1,
+            }),
+        );
+        let y = place(&mut app, ComponentSpec::Output(Default::default()));
+        connect_pins(&mut app, (a, PinId::output(0)), (g, PinId::input(0)));
+        connect_pins(&mut app, (g, PinId::output(0)), (y, PinId::input(0)));
+
+        app.analyze_circuit();
+        let Some(Ok(analysis)) = &app.analyze.outcome else {
+            panic!("analysis failed");
+        };
+        assert_eq!(analysis.doc, main);
+        assert_eq!(analysis.table.to_csv(), "a,out0\n0,1\n1,0\n");
+        let expressions = analysis.expressions.as_ref().unwrap();
+        assert_eq!(expressions[0].to_text(&analysis.vars), "out0 = a'");
+
+        app.create_document("Seq".to_string());
+        place(&mut app, ComponentSpec::Input(Input::new(0, 1)));
+        place(&mut app, ComponentSpec::DFlipFlop(Default::default()));
+        app.analyze.target = Some(main);
+        app.analyze_circuit();
+        assert!(matches!(app.analyze.outcome, Some(Ok(_))));
+        app.analyze.target = None;
+        app.analyze_circuit();
+        assert!(matches!(app.analyze.outcome, Some(Err(_))));
+    }
+
+    #[test]
+    fn test_count_parts_multiplies_subcircuit_instances() {
+        // Main: one NOT. C2: two Main instances and an AND.
+        let mut app = OsmilogApp::empty();
+        let main = app.active_id;
+        let not = ComponentSpec::Gate(Gate {
+            op: GateOp::Not,
+            n_inputs: 1,
+            width: 1,
+        });
+        place(&mut app, not);
+        app.create_document("C2".to_string());
+        for _ in 0..2 {
+            let spec = app.subcircuit_spec(main);
+            place(&mut app, spec);
+        }
+        place(
+            &mut app,
+            ComponentSpec::Gate(Gate {
+                op: GateOp::And,
+                n_inputs: 2,
+                width: 1,
+            }),
+        );
+
+        app.count_parts();
+        let Some((doc, bom)) = &app.statistics.bom else {
+            panic!("nothing counted");
+        };
+        assert_eq!(*doc, app.active_id);
+        let count = |variant: &str| {
+            bom.rows
+                .iter()
+                .find(|r| r.part.variant == variant)
+                .map_or(0, |r| r.count)
+        };
+        assert_eq!(count("NOT"), 2);
+        assert_eq!(count("AND 2-input"), 1);
+        assert!(bom.to_csv(false).contains("Gate,NOT,1,2\n"));
+    }
+
+    #[test]
+    fn test_checkpoint_restores_state_inside_subcircuit_instances() {
+        // Main: a NOT and a clock source. C2: a Main instance and a clock.
+        let mut app = OsmilogApp::empty();
+        let main = app.active_id;
+        let not = ComponentSpec::Gate(Gate {
+            op: GateOp::Not,
+            n_inputs: 1,
+            width: 1,
+        });
+        place(&mut app, not);
+        app.place_component(
+            ComponentSpec::ClockSource(Default::default()),
+            GridPos::new(2, 3),
+        );
+        app.create_document("C2".to_string());
+        let spec = app.subcircuit_spec(main);
+        app.place_component(spec, GridPos::new(5, 5));
+        place(&mut app, ComponentSpec::ClockSource(Default::default()));
+
+        app.active_mut().tick_once();
+        app.take_checkpoint(String::new());
+        let taken = app.active().checkpoints.checkpoints[0].clone();
+        assert_eq!(taken.name, "Tick 1");
+        assert!(matches!(&taken.parts[1].state, PartState::Sub(parts) if parts.len() == 1));
+
+        app.active_mut().tick_once();
+        app.take_checkpoint("later".to_string());
+        assert_ne!(app.active().checkpoints.checkpoints[1].parts, taken.parts);
+
+        app.restore_checkpoint(0);
+        assert_eq!(app.active().checkpoints.note, None);
+        app.take_checkpoint(String::new());
+        assert_eq!(app.active().checkpoints.checkpoints[2].parts, taken.parts);
+    }
+
+    #[test]
+    fn test_synthesized_document_computes_its_expressions() {
+        let spec = Spec::from_expressions("Y = A & !B | C\nZ = A ^ C\nK = 1").unwrap();
+        for style in Style::ALL {
+            let mut app = OsmilogApp::empty();
+            let netlist = synth::synthesize(&spec, style).unwrap();
+            app.create_synthesized_document(style.label().to_string(), &netlist);
+            assert_eq!(app.documents[app.active_id].name, style.label());
+            assert!(!app.active().history.can_undo());
+
+            app.analyze_circuit();
+            let Some(Ok(analysis)) = &app.analyze.outcome else {
+                panic!("analysis failed");
+            };
+            let table = &analysis.table;
+            assert_eq!(&table.header()[..3], &["A", "B", "C"]);
+            for (m, row) in table.rows.iter().enumerate() {
+                for (port, &value) in table.outputs.iter().zip(&row.outputs) {
+                    let f = spec.outputs.iter().find(|f| f.name == port.name).unwrap();
+                    let want = f.at(m as u32).unwrap();
+                    assert_eq!(value, Value::new(want as u64, 1), "{} in {style:?}", f.name);
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_load_project_file_clears_undo_history() {
         let mut app = OsmilogApp::empty();
-        place(&mut app, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        place(&mut app, ComponentSpec::Output);
+        place(&mut app, ComponentSpec::Input(Input::new(1, 1)));
+        place(&mut app, ComponentSpec::Output(Default::default()));
         let file = app.to_project_file();
 
         let mut loaded = OsmilogApp::empty();
@@ -1766,8 +2570,9 @@ mod tests {
             name: "Main".to_string(),
             snapshot: CircuitSnapshot {
                 components: vec![ComponentEntry {
-                    spec: ComponentSpec::Output,
+                    spec: ComponentSpec::Output(Default::default()),
                     grid_pos: GridPos::ZERO,
+                    delay: None,
                 }],
                 tunnels: vec![],
                 nodes: vec![NodeEntry {
@@ -1797,7 +2602,7 @@ mod tests {
     #[test]
     fn test_copy_single_component_then_paste_creates_offset_copy() {
         let mut app = OsmilogApp::empty();
-        let a = place(&mut app, ComponentSpec::Output);
+        let a = place(&mut app, ComponentSpec::Output(Default::default()));
         let original = app.active().components[&a].grid_pos;
 
         app.active_mut().selected = Some(Selection::Single(Selected::Component(a)));
@@ -1821,7 +2626,7 @@ mod tests {
     #[test]
     fn test_paste_after_undo_of_original_still_works() {
         let mut app = OsmilogApp::empty();
-        let a = place(&mut app, ComponentSpec::Output);
+        let a = place(&mut app, ComponentSpec::Output(Default::default()));
         app.active_mut().selected = Some(Selection::Single(Selected::Component(a)));
         app.copy_selection();
 
@@ -1839,7 +2644,7 @@ mod tests {
     #[test]
     fn test_paste_after_editing_original_is_unaffected() {
         let mut app = OsmilogApp::empty();
-        let a = place(&mut app, ComponentSpec::Output);
+        let a = place(&mut app, ComponentSpec::Output(Default::default()));
         let original_pos = app.active().components[&a].grid_pos;
         app.active_mut().selected = Some(Selection::Single(Selected::Component(a)));
         app.copy_selection();
@@ -1860,7 +2665,7 @@ mod tests {
     #[test]
     fn test_paste_normalizes_selection_to_single_for_one_item() {
         let mut app = OsmilogApp::empty();
-        let a = place(&mut app, ComponentSpec::Output);
+        let a = place(&mut app, ComponentSpec::Output(Default::default()));
         app.active_mut().selected = Some(Selection::Single(Selected::Component(a)));
         app.copy_selection();
         app.paste_clipboard();
@@ -1870,8 +2675,8 @@ mod tests {
     #[test]
     fn test_paste_is_one_undo_batch() {
         let mut app = OsmilogApp::empty();
-        let a = place(&mut app, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let b = place(&mut app, ComponentSpec::Output);
+        let a = place(&mut app, ComponentSpec::Input(Input::new(1, 1)));
+        let b = place(&mut app, ComponentSpec::Output(Default::default()));
         connect_pins(&mut app, (a, PinId::output(0)), (b, PinId::input(0)));
         app.active_mut().rebuild_circuit();
         let seg = app.active().wiring.segments.keys().next().unwrap();
@@ -1895,7 +2700,7 @@ mod tests {
     #[test]
     fn test_paste_noop_when_clipboard_empty() {
         let mut app = OsmilogApp::empty();
-        place(&mut app, ComponentSpec::Output);
+        place(&mut app, ComponentSpec::Output(Default::default()));
         assert!(app.clipboard.is_empty());
 
         let before = app.active().components.len();
@@ -1919,7 +2724,7 @@ mod tests {
     fn create_circuit_doc_adds_active_blank_document() {
         let mut app = OsmilogApp::empty();
         let main = app.active_id;
-        place(&mut app, ComponentSpec::Input(Input { bits: 1, width: 1 }));
+        place(&mut app, ComponentSpec::Input(Input::new(1, 1)));
         assert_eq!(app.active().components.len(), 1);
 
         app.create_document("C2".to_string());
@@ -1939,8 +2744,8 @@ mod tests {
         let main = app.active_id;
 
         // Settled AND-of-two-highs -> Output on "Main".
-        let a = place(&mut app, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let b = place(&mut app, ComponentSpec::Input(Input { bits: 1, width: 1 }));
+        let a = place(&mut app, ComponentSpec::Input(Input::new(1, 1)));
+        let b = place(&mut app, ComponentSpec::Input(Input::new(1, 1)));
         let g = place(
             &mut app,
             ComponentSpec::Gate(Gate {
@@ -1949,7 +2754,7 @@ mod tests {
                 width: 1,
             }),
         );
-        let o = place(&mut app, ComponentSpec::Output);
+        let o = place(&mut app, ComponentSpec::Output(Default::default()));
         connect_pins(&mut app, (a, PinId::output(0)), (g, PinId::input(0)));
         connect_pins(&mut app, (b, PinId::output(0)), (g, PinId::input(1)));
         connect_pins(&mut app, (g, PinId::output(0)), (o, PinId::input(0)));
@@ -1972,7 +2777,7 @@ mod tests {
     fn switch_to_active_is_a_noop() {
         let mut app = OsmilogApp::empty();
         let main = app.active_id;
-        place(&mut app, ComponentSpec::Input(Input { bits: 1, width: 1 }));
+        place(&mut app, ComponentSpec::Input(Input::new(1, 1)));
 
         app.switch_document(main);
 
@@ -1987,8 +2792,8 @@ mod tests {
         let main = app.active_id;
 
         // Main: a 1-bit passthrough Input -> Output (one boundary pin each).
-        let in_main = place(&mut app, ComponentSpec::Input(Input { bits: 0, width: 1 }));
-        let out_main = place(&mut app, ComponentSpec::Output);
+        let in_main = place(&mut app, ComponentSpec::Input(Input::new(0, 1)));
+        let out_main = place(&mut app, ComponentSpec::Output(Default::default()));
         connect_pins(
             &mut app,
             (in_main, PinId::output(0)),
@@ -2007,8 +2812,8 @@ mod tests {
 
         // C2 Input(=1) -> sub -> C2 Output; the passthrough settles a 1
         // through the boundary.
-        let x = place(&mut app, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let y = place(&mut app, ComponentSpec::Output);
+        let x = place(&mut app, ComponentSpec::Input(Input::new(1, 1)));
+        let y = place(&mut app, ComponentSpec::Output(Default::default()));
         connect_pins(&mut app, (x, PinId::output(0)), (sub, PinId::input(0)));
         connect_pins(&mut app, (sub, PinId::output(0)), (y, PinId::input(0)));
         app.active_mut().rebuild_circuit();
@@ -2022,8 +2827,8 @@ mod tests {
         let main = app.active_id;
 
         // Main is a passthrough so it has a usable boundary.
-        let in_main = place(&mut app, ComponentSpec::Input(Input { bits: 0, width: 1 }));
-        let out_main = place(&mut app, ComponentSpec::Output);
+        let in_main = place(&mut app, ComponentSpec::Input(Input::new(0, 1)));
+        let out_main = place(&mut app, ComponentSpec::Output(Default::default()));
         connect_pins(
             &mut app,
             (in_main, PinId::output(0)),
@@ -2059,7 +2864,7 @@ mod tests {
         let mut app = OsmilogApp::empty();
 
         // Main: Input(1) -> NOT -> Output, which settles a 0.
-        let a = place(&mut app, ComponentSpec::Input(Input { bits: 1, width: 1 }));
+        let a = place(&mut app, ComponentSpec::Input(Input::new(1, 1)));
         let n = place(
             &mut app,
             ComponentSpec::Gate(Gate {
@@ -2068,15 +2873,15 @@ mod tests {
                 width: 1,
             }),
         );
-        let o = place(&mut app, ComponentSpec::Output);
+        let o = place(&mut app, ComponentSpec::Output(Default::default()));
         connect_pins(&mut app, (a, PinId::output(0)), (n, PinId::input(0)));
         connect_pins(&mut app, (n, PinId::output(0)), (o, PinId::input(0)));
         app.active_mut().rebuild_circuit();
 
         // C2 (now active): Input(1) -> Output, a passthrough settling a 1.
         app.create_document("C2".to_string());
-        let x = place(&mut app, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let y = place(&mut app, ComponentSpec::Output);
+        let x = place(&mut app, ComponentSpec::Input(Input::new(1, 1)));
+        let y = place(&mut app, ComponentSpec::Output(Default::default()));
         connect_pins(&mut app, (x, PinId::output(0)), (y, PinId::input(0)));
         app.active_mut().rebuild_circuit();
 
@@ -2104,7 +2909,7 @@ mod tests {
             .active()
             .components
             .values()
-            .find(|pc| matches!(pc.spec, ComponentSpec::Output))
+            .find(|pc| matches!(pc.spec, ComponentSpec::Output(_)))
             .unwrap()
             .key;
         assert_eq!(loaded.active().circuit.read_output(c2_out), Value::ONE);
@@ -2116,7 +2921,7 @@ mod tests {
             .active()
             .components
             .values()
-            .find(|pc| matches!(pc.spec, ComponentSpec::Output))
+            .find(|pc| matches!(pc.spec, ComponentSpec::Output(_)))
             .unwrap()
             .key;
         assert_eq!(loaded.active().circuit.read_output(main_out), Value::ZERO);
@@ -2131,12 +2936,14 @@ mod tests {
             snapshot: CircuitSnapshot {
                 components: vec![
                     ComponentEntry {
-                        spec: ComponentSpec::Input(Input { bits: 1, width: 1 }),
+                        spec: ComponentSpec::Input(Input::new(1, 1)),
                         grid_pos: GridPos::new(0, 0),
+                        delay: None,
                     },
                     ComponentEntry {
-                        spec: ComponentSpec::Output,
+                        spec: ComponentSpec::Output(Default::default()),
                         grid_pos: GridPos::new(5, 0),
+                        delay: None,
                     },
                 ],
                 tunnels: vec![],
@@ -2171,7 +2978,7 @@ mod tests {
             .active()
             .components
             .values()
-            .find(|pc| matches!(pc.spec, ComponentSpec::Output))
+            .find(|pc| matches!(pc.spec, ComponentSpec::Output(_)))
             .unwrap()
             .key;
         assert_eq!(loaded.active().circuit.read_output(out), Value::ONE);
@@ -2183,8 +2990,8 @@ mod tests {
         let main = app.active_id;
 
         // Main: Input(1) -> Output, a passthrough.
-        let in_main = place(&mut app, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let out_main = place(&mut app, ComponentSpec::Output);
+        let in_main = place(&mut app, ComponentSpec::Input(Input::new(1, 1)));
+        let out_main = place(&mut app, ComponentSpec::Output(Default::default()));
         connect_pins(
             &mut app,
             (in_main, PinId::output(0)),
@@ -2196,8 +3003,8 @@ mod tests {
         app.create_document("C2".to_string());
         let spec = app.subcircuit_spec(main);
         let sub = app.place_component(spec, GridPos::new(5, 5));
-        let x = place(&mut app, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let y = place(&mut app, ComponentSpec::Output);
+        let x = place(&mut app, ComponentSpec::Input(Input::new(1, 1)));
+        let y = place(&mut app, ComponentSpec::Output(Default::default()));
         connect_pins(&mut app, (x, PinId::output(0)), (sub, PinId::input(0)));
         connect_pins(&mut app, (sub, PinId::output(0)), (y, PinId::input(0)));
         app.active_mut().rebuild_circuit();
@@ -2227,7 +3034,7 @@ mod tests {
             .active()
             .components
             .values()
-            .find(|pc| matches!(pc.spec, ComponentSpec::Output))
+            .find(|pc| matches!(pc.spec, ComponentSpec::Output(_)))
             .unwrap()
             .key;
         assert_eq!(loaded.active().circuit.read_output(y_key), Value::ONE);
@@ -2244,8 +3051,8 @@ mod tests {
         // C2: Input(1) -> Output passthrough.
         app.create_document("C2".to_string());
         let c2 = app.active_id;
-        let c2_in = place(&mut app, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let c2_out = place(&mut app, ComponentSpec::Output);
+        let c2_in = place(&mut app, ComponentSpec::Input(Input::new(1, 1)));
+        let c2_out = place(&mut app, ComponentSpec::Output(Default::default()));
         connect_pins(
             &mut app,
             (c2_in, PinId::output(0)),
@@ -2257,8 +3064,8 @@ mod tests {
         app.switch_document(main);
         let spec = app.subcircuit_spec(c2);
         let sub = app.place_component(spec, GridPos::new(5, 5));
-        let x = place(&mut app, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let y = place(&mut app, ComponentSpec::Output);
+        let x = place(&mut app, ComponentSpec::Input(Input::new(1, 1)));
+        let y = place(&mut app, ComponentSpec::Output(Default::default()));
         connect_pins(&mut app, (x, PinId::output(0)), (sub, PinId::input(0)));
         connect_pins(&mut app, (sub, PinId::output(0)), (y, PinId::input(0)));
         app.active_mut().rebuild_circuit();
@@ -2276,7 +3083,7 @@ mod tests {
             .active()
             .components
             .values()
-            .find(|pc| matches!(pc.spec, ComponentSpec::Output))
+            .find(|pc| matches!(pc.spec, ComponentSpec::Output(_)))
             .unwrap()
             .key;
         assert_eq!(loaded.active().circuit.read_output(y_key), Value::ONE);
@@ -0,0 +1,178 @@
+//! The Breakpoints window: conditions (see `sim::breakpoint`) on the value a
+//! Probe or Output reads, or on a named net - every tunnel with one label -
+//! checked after each tick of a Playing run. The first to trip pauses the
+//! clock, and its signal stays highlighted on the canvas until the next tick
+//! or Stop. Opened from the View menu, and by a hit.
+//!
+//! Checking lives in `Document::run_ticks`, which owns the circuit. Like the
+//! test-vector panel, this is runtime-only state on `Document` and is never
+//! saved.
+
+use crate::gui::app::PlacedCompKey;
+use crate::gui::theme::Theme;
+use crate::sim::breakpoint::Condition;
+use crate::sim::test_vector::parse_value;
+use crate::sim::value::{format_value, Radix, Value};
+
+/// A signal a breakpoint watches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Watch {
+    // A Probe or Output, by the value on its input.
+    Component(PlacedCompKey),
+    // The net under this tunnel label.
+    Net(String),
+}
+
+pub struct Breakpoint {
+    pub watch: Watch,
+    pub condition: Condition,
+    pub enabled: bool,
+}
+
+// The breakpoint that paused a run, and the value it tripped on.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Hit {
+    pub(crate) watch: Watch,
+    pub(crate) condition: Condition,
+    pub(crate) value: Value,
+}
+
+#[derive(Default)]
+pub struct BreakpointPanel {
+    pub open: bool,
+    pub(crate) breakpoints: Vec<Breakpoint>,
+    // Cleared by the next tick or Stop.
+    pub(crate) hit: Option<Hit>,
+    // The Add row: a signal, an index into Condition::ALL, and the text of
+    // an Equals value.
+    watch: Option<Watch>,
+    condition: usize,
+    value: String,
+}
+
+impl BreakpointPanel {
+    // The first enabled breakpoint the tick from `before` to `after` trips;
+    // both hold one value per breakpoint, None where its signal is gone.
+    pub(crate) fn first_hit(
+        &self,
+        before: &[Option<Value>],
+        after: &[Option<Value>],
+    ) -> Option<Hit> {
+        self.breakpoints
+            .iter()
+            .zip(before.iter().zip(after))
+            .find_map(|(bp, pair)| match pair {
+                (&Some(before), &Some(after)) if bp.enabled && bp.condition.hit(before, after) => {
+                    Some(Hit {
+                        watch: bp.watch.clone(),
+                        condition: bp.condition,
+                        value: after,
+                    })
+                }
+                _ => None,
+            })
+    }
+
+    // `watches` names every signal that can be watched on this canvas.
+    pub fn show(&mut self, ctx: &egui::Context, theme: Theme, watches: &[(Watch, String)]) {
+        let name = |watch: &Watch| watch_name(watches, watch);
+        let mut open = self.open;
+        egui::Window::new("Breakpoints")
+            .open(&mut open)
+            .default_size([360.0, 300.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let selected = self.watch.as_ref().map_or("Signal…", name);
+                    egui::ComboBox::from_id_salt("breakpoint_watch")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            for (watch, label) in watches {
+                                ui.selectable_value(&mut self.watch, Some(watch.clone()), label);
+                            }
+                        });
+                    egui::ComboBox::from_id_salt("breakpoint_condition")
+                        .selected_text(Condition::ALL[self.condition].label())
+                        .show_ui(ui, |ui| {
+                            for (i, condition) in Condition::ALL.iter().enumerate() {
+                                ui.selectable_value(&mut self.condition, i, condition.label());
+                            }
+                        });
+                    let condition = match Condition::ALL[self.condition] {
+                        Condition::Equals(_) => {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.value)
+                                    .desired_width(60.0)
+                                    .hint_text("0x10"),
+                            );
+                            parse_value(self.value.trim()).map(Condition::Equals)
+                        }
+                        condition => Some(condition),
+                    };
+                    let ready = self.watch.clone().zip(condition);
+                    if ui
+                        .add_enabled(ready.is_some(), egui::Button::new("Add"))
+                        .clicked()
+                    {
+                        if let Some((watch, condition)) = ready {
+                            self.breakpoints.push(Breakpoint {
+                                watch,
+                                condition,
+                                enabled: true,
+                            });
+                        }
+                    }
+                });
+                ui.separator();
+
+                if let Some(hit) = &self.hit {
+                    ui.colored_label(
+                        theme.breakpoint_highlight,
+                        format!(
+                            "Paused: {} {} ({})",
+                            name(&hit.watch),
+                            describe(hit.condition),
+                            format_value(hit.value, Radix::Hex)
+                        ),
+                    );
+                    ui.separator();
+                }
+                if self.breakpoints.is_empty() {
+                    ui.weak("A Playing clock pauses when any breakpoint here trips.");
+                    return;
+                }
+                let mut remove = None;
+                egui::ScrollArea::vertical()
+                    .id_salt("breakpoints")
+                    .show(ui, |ui| {
+                        for (i, bp) in self.breakpoints.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                let text =
+                                    format!("{} {}", name(&bp.watch), describe(bp.condition));
+                                ui.checkbox(&mut bp.enabled, text);
+                                if ui.small_button("✕").clicked() {
+                                    remove = Some(i);
+                                }
+                            });
+                        }
+                    });
+                if let Some(i) = remove {
+                    self.breakpoints.remove(i);
+                }
+            });
+        self.open = open;
+    }
+}
+
+fn watch_name<'a>(watches: &'a [(Watch, String)], watch: &Watch) -> &'a str {
+    watches
+        .iter()
+        .find(|(w, _)| w == watch)
+        .map_or("(removed)", |(_, name)| name.as_str())
+}
+
+fn describe(condition: Condition) -> String {
+    match condition {
+        Condition::Equals(n) => format!("equals 0x{n:X}"),
+        condition => condition.label().to_string(),
+    }
+}
@@ -19,7 +19,7 @@ use crate::gui::placed_component::PlacedComponent;
 use crate::gui::shape::{tessellate_path, BUBBLE_R};
 use crate::gui::theme::Theme;
 use crate::sim::circuit::{Circuit, TunnelRole};
-use crate::sim::component::{ComponentSpec, Constant, PinId};
+use crate::sim::component::{ComponentSpec, Constant, Input, Output, PinId};
 use crate::sim::value::Value;
 
 /// `draw_grid` thins to a coarser cell stride rather than let dots crowd closer as it zooms out.
@@ -41,6 +41,20 @@ pub(crate) fn value_stroke(theme: Theme, val: Value) -> Stroke {
                 WIRE_THICKNESS_THICK
             },
         ),
+        // Some bits Z or X: a bus missing only drivers reads as floating, one
+        // with any X bit as unknown.
+        Value::Partial { x, width, .. } => (
+            if x == 0 {
+                theme.value_floating
+            } else {
+                theme.value_unknown
+            },
+            if width == 1 {
+                WIRE_THICKNESS_THIN
+            } else {
+                WIRE_THICKNESS_THICK
+            },
+        ),
     };
     Stroke::new(weight, color)
 }
@@ -116,6 +130,9 @@ pub(crate) fn draw_component(
     camera: Camera,
     circuit: &Circuit,
     is_selected: bool,
+    // Outline colour for an analysis result the component is part of: an
+    // oscillating loop, a timing path.
+    highlight: Option<Color32>,
     theme: Theme,
 ) {
     puffin::profile_function!();
@@ -124,6 +141,8 @@ pub(crate) fn draw_component(
     let fill = theme.component_fill;
     let (stroke_w, stroke_col) = if is_selected {
         (camera.scale(COMP_STROKE + 1.0), theme.outline_selected)
+    } else if let Some(color) = highlight {
+        (camera.scale(COMP_STROKE + 1.0), color)
     } else {
         (camera.scale(COMP_STROKE), theme.outline_default)
     };
@@ -226,6 +245,28 @@ pub(crate) fn draw_component(
         );
     }
 
+    // An Input's/Output's label sits outside the body, on the side away from
+    // its pin, so it never covers a wire.
+    let io_label = match &pc.spec {
+        ComponentSpec::Input(Input { label, .. }) => Some((label, Align2::RIGHT_CENTER)),
+        ComponentSpec::Output(Output { label }) => Some((label, Align2::LEFT_CENTER)),
+        _ => None,
+    };
+    if let Some((label, align)) = io_label.filter(|(label, _)| !label.is_empty()) {
+        let gap = camera.scale(LABEL_FONT_SIZE) * 0.5;
+        let label_pos = match align {
+            Align2::RIGHT_CENTER => egui::pos2(rect.left() - gap, rect.center().y),
+            _ => egui::pos2(rect.right() + gap, rect.center().y),
+        };
+        painter.text(
+            label_pos,
+            align,
+            label,
+            FontId::monospace(camera.scale(LABEL_FONT_SIZE)),
+            theme.label_text,
+        );
+    }
+
     let pin_r = camera.scale(PIN_RADIUS);
     for i in 0..pc.spec.n_inputs() {
         let pos = comp_pin_pos(shape, pc.grid_pos, camera, PinId::input(i as u8));
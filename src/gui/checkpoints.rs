@@ -0,0 +1,93 @@
+//! The Checkpoints window: named snapshots of the active circuit's sequential
+//! state (see `checkpoint.rs`) - every register, counter, shift-register
+//! stage and RAM, through its subcircuit instances - taken between ticks,
+//! restored later in the session, and saved to or loaded from a `.osmstate`
+//! sidecar file. Opened from the View menu.
+//!
+//! Taking and restoring need every document, to name the parts inside each
+//! instance, so they live on the app (`OsmilogApp::take_checkpoint`). The list
+//! is runtime state on `Document`; Stop leaves it alone, and only Save writes
+//! it anywhere.
+
+use crate::checkpoint::Checkpoint;
+use crate::gui::theme::Theme;
+
+/// What the window asks the app to do after a frame.
+pub enum CheckpointAction {
+    // The typed name, empty for a default one.
+    Take(String),
+    Restore(usize),
+    Save,
+    Load,
+}
+
+#[derive(Default)]
+pub struct CheckpointPanel {
+    pub open: bool,
+    pub(crate) checkpoints: Vec<Checkpoint>,
+    // What the last restore couldn't put back; cleared by the next.
+    pub(crate) note: Option<String>,
+    name: String,
+}
+
+impl CheckpointPanel {
+    pub fn show(&mut self, ctx: &egui::Context, theme: Theme) -> Option<CheckpointAction> {
+        let mut action = None;
+        let mut open = self.open;
+        egui::Window::new("Checkpoints")
+            .open(&mut open)
+            .default_size([320.0, 280.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.name)
+                            .desired_width(140.0)
+                            .hint_text("Name"),
+                    );
+                    if ui.button("Take").clicked() {
+                        action = Some(CheckpointAction::Take(self.name.trim().to_string()));
+                        self.name.clear();
+                    }
+                    if ui
+                        .add_enabled(!self.checkpoints.is_empty(), egui::Button::new("Save…"))
+                        .clicked()
+                    {
+                        action = Some(CheckpointAction::Save);
+                    }
+                    if ui.button("Load…").clicked() {
+                        action = Some(CheckpointAction::Load);
+                    }
+                });
+                if let Some(note) = &self.note {
+                    ui.colored_label(theme.error_text, note);
+                }
+                ui.separator();
+                if self.checkpoints.is_empty() {
+                    ui.weak("Take one to come back to this point of the run later.");
+                    return;
+                }
+                let mut remove = None;
+                egui::ScrollArea::vertical()
+                    .id_salt("checkpoints")
+                    .show(ui, |ui| {
+                        for (i, checkpoint) in self.checkpoints.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                if ui.button("Restore").clicked() {
+                                    action = Some(CheckpointAction::Restore(i));
+                                }
+                                ui.label(&checkpoint.name);
+                                ui.weak(format!("tick {}", checkpoint.tick));
+                                if ui.small_button("✕").clicked() {
+                                    remove = Some(i);
+                                }
+                            });
+                        }
+                    });
+                if let Some(i) = remove {
+                    self.checkpoints.remove(i);
+                }
+            });
+        self.open = open;
+        action
+    }
+}
@@ -75,6 +75,7 @@ impl Clipboard {
                 .map(|e| ComponentEntry {
                     spec: e.spec.clone(),
                     grid_pos: offset_grid_pos(e.grid_pos, offset),
+                    delay: e.delay,
                 })
                 .collect(),
             tunnels: file
@@ -142,6 +143,7 @@ pub(crate) fn build_selection_snapshot(
             ComponentEntry {
                 spec: pc.spec.clone(),
                 grid_pos: pc.grid_pos,
+                delay: pc.delay,
             }
         })
         .collect();
@@ -230,7 +232,7 @@ mod tests {
     use crate::sim::component::{ComponentSpec, Input};
 
     fn placed_component(grid_pos: GridPos) -> PlacedComponent {
-        let spec = ComponentSpec::Input(Input { bits: 0, width: 1 });
+        let spec = ComponentSpec::Input(Input::new(0, 1));
         PlacedComponent::new(crate::sim::component::CompKey(0), spec, grid_pos)
     }
 
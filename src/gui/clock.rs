@@ -34,6 +34,9 @@ pub struct Clock {
     // ctx.input(|i| i.time) value when the last auto-tick fired. Chosen over
     // std::time::Instant, which panics on wasm32.
     pub(crate) last_tick_time: f64,
+    // How far each tick runs simulated time in the circuit's timed mode (see
+    // Circuit::set_timed); long enough for most logic to settle between edges.
+    pub(crate) time_per_tick: u32,
 }
 
 impl Default for Clock {
@@ -42,6 +45,7 @@ impl Default for Clock {
             run: ClockRun::Stopped,
             ticks_per_second: 1.0,
             last_tick_time: 0.0,
+            time_per_tick: 10,
         }
     }
 }
@@ -54,9 +58,21 @@ impl Clock {
     // Advances the clock exactly one tick, untracked (bypassing the Command/undo
     // layer) so it never lands on the undo stack - clock stepping is a simulation
     // step, not a structural edit. Used by both the Step button and the
-    // auto-advance loop, via OsmilogApp::tick_once.
+    // auto-advance loop, via OsmilogApp::tick_once. A tick drives the clock
+    // sources: each Clock component advances a phase, and parts clocked from
+    // one latch on their own pin's edge; a part whose clock pin is unwired
+    // latches every tick (see Circuit::step_clock_sources). In timed mode
+    // the tick is followed by `time_per_tick` units of simulated time, which is
+    // when the edge actually ripples through the delayed parts.
     pub(crate) fn step(&mut self, circuit: &mut Circuit) -> Result<(), SettleError> {
-        circuit.apply(Command::TickClock).0.unwrap_settle()
+        circuit.apply(Command::StepClockSources).0.unwrap_settle()?;
+        if !circuit.is_timed() {
+            return Ok(());
+        }
+        circuit
+            .apply(Command::AdvanceTime(self.time_per_tick as u64))
+            .0
+            .unwrap_settle()
     }
 
     // Stops the clock: resets all sequential state to its power-on value
@@ -104,10 +120,11 @@ impl Clock {
 }
 
 impl Document {
-    // The clock transport: a speed setting plus Play / Pause / Step / Stop.
-    // Buttons are enable-gated on the current run state (see the state table in
-    // ClockRun); entering Play locks editing for the whole session and Stop
-    // resets sequential state. All ticks are issued untracked (see tick_once).
+    // The clock transport: a speed setting plus Play / Pause / Step Back /
+    // Step / Stop. Buttons are enable-gated on the current run state (see the
+    // state table in ClockRun); entering Play locks editing for the whole
+    // session and Stop resets sequential state. All ticks are issued
+    // untracked (see tick_once).
     pub(crate) fn show_clock_controls(&mut self, ui: &mut egui::Ui) {
         const MAX_CLOCK_TPS: f32 = 100.0;
         let run = self.clock.run;
@@ -141,6 +158,15 @@ impl Document {
             self.clock.pause();
         }
 
+        // Step Back: put a Paused run back as it stood one tick earlier (see
+        // sim::rewind). Step then re-runs forward from there.
+        if ui
+            .add_enabled(self.can_step_back(), egui::Button::new("Step Back"))
+            .clicked()
+        {
+            self.step_back();
+        }
+
         // Step: advance exactly one tick. Available when not playing - from
         // Stopped it's a single manual tick (stays editable); from Paused it
         // nudges the frozen run forward one step.
@@ -158,6 +184,29 @@ impl Document {
         {
             self.stop_clock();
         }
+
+        // Timed: simulate with per-component delays instead of settling each
+        // change instantly. Switched only while stopped, like the speed.
+        let mut timed = self.circuit.is_timed();
+        if ui
+            .add_enabled(
+                run == ClockRun::Stopped,
+                egui::Checkbox::new(&mut timed, "Timed"),
+            )
+            .clicked()
+        {
+            let result = self.circuit.set_timed(timed);
+            self.record_settle_result(result);
+        }
+        if timed {
+            ui.add_enabled(
+                run == ClockRun::Stopped,
+                egui::DragValue::new(&mut self.clock.time_per_tick)
+                    .range(1..=10_000)
+                    .suffix(" t/tick"),
+            );
+            ui.label(format!("t = {}", self.circuit.now()));
+        }
     }
 }
 
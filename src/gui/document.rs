@@ -14,34 +14,44 @@
 //! operations (subcircuit instantiation, save/load, UI) that need the whole
 //! `documents` registry in scope.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use egui::{Color32, Painter, Pos2, Rect, Stroke, StrokeKind};
 use slotmap::SlotMap;
 
+use crate::checkpoint::{Checkpoint, Layout};
 use crate::gui::app::{
-    component_bounding_rect, pin_at_pos, pin_grid_pos, tunnel_bounding_rect, tunnel_pin_at_pos,
-    tunnel_pin_grid, InteractionMode, PinKind, PlacedCompKey, PlacedTunnel, PlacedTunnelKey,
-    Selected, Selection, PIN_RADIUS, WIRE_THICKNESS_THIN,
+    component_bounding_rect, pin_at_pos, tunnel_bounding_rect, tunnel_pin_at_pos, tunnel_pin_grid,
+    InteractionMode, PinKind, PlacedCompKey, PlacedTunnel, PlacedTunnelKey, Selected, Selection,
+    PIN_RADIUS, WIRE_THICKNESS_THIN,
 };
+use crate::gui::breakpoints::{BreakpointPanel, Watch};
 use crate::gui::canvas_draw::{
     draw_component, draw_grid, draw_reticle, draw_tunnel, draw_tunnel_ghost, extend_segment,
     value_stroke,
 };
+use crate::gui::checkpoints::CheckpointPanel;
 use crate::gui::clock::{Clock, ClockRun};
-use crate::gui::geometry::{Camera, GridPos};
+use crate::gui::geometry::{pin_grid_pos, Camera, GridPos};
 use crate::gui::gui_undo::GuiUndoAction;
 use crate::gui::history::{History, HistoryEntry};
-use crate::gui::memory_editor::{MemKind, MemoryEditor};
+use crate::gui::memory_editor::{MemFile, MemKind, MemoryEditor};
 use crate::gui::placed_component::PlacedComponent;
-use crate::gui::signal_viewer::{SignalLog, SignalViewer};
+use crate::gui::signal_viewer::{Scrubber, SignalLog, SignalViewer};
+use crate::gui::test_vectors::TestVectorPanel;
 use crate::gui::theme::Theme;
+use crate::gui::timing::TimingWindow;
 use crate::gui::utils::CanvasCtx;
 use crate::gui::wiring::{NodeAttach, WireNodeKey, WireSegKey, Wiring};
-use crate::sim::circuit::{Circuit, TunnelKey, TunnelRole};
+use crate::memory_image::{self, ImageError};
+use crate::sim::circuit::{Circuit, SettleError, TunnelKey, TunnelRole};
 use crate::sim::command::{Command, CommandOutput};
 use crate::sim::component::{CompKey, Component, ComponentSpec, PinId};
+use crate::sim::rewind::Rewind;
+use crate::sim::test_vector;
+use crate::sim::timing::Timing;
 use crate::sim::value::Value;
+use crate::stimulus::{Stimulus, StimulusError};
 
 /// Defined in `sim::component` so `ComponentSpec::Subcircuit` can embed it without a gui
 /// dependency.
@@ -85,9 +95,19 @@ pub struct Document {
     // Runtime-only probe history + viewer UI state; never saved.
     pub(crate) signal_log: SignalLog,
     pub(crate) signal_viewer: SignalViewer,
+    // Every tick of the current run session, for Step Back and the signal
+    // viewer's scrubber; runtime-only like the probe history it lines up with.
+    pub(crate) rewind: Rewind,
     pub(crate) memory_editor: MemoryEditor,
+    pub(crate) test_vectors: TestVectorPanel,
+    pub(crate) timing: TimingWindow,
+    pub(crate) breakpoints: BreakpointPanel,
+    pub(crate) checkpoints: CheckpointPanel,
     // Distinct from OsmilogApp::io_error; the menu bar shows I/O errors first.
     pub(crate) settle_error: Option<String>,
+    // The components of the loop behind an Oscillation settle_error, drawn
+    // in red along with the wires between them; empty otherwise.
+    pub(crate) settle_loop: HashSet<CompKey>,
 }
 
 impl Document {
@@ -106,8 +126,14 @@ impl Document {
             clock: Clock::default(),
             signal_log: SignalLog::default(),
             signal_viewer: SignalViewer::default(),
+            rewind: Rewind::default(),
             memory_editor: MemoryEditor::default(),
+            test_vectors: TestVectorPanel::default(),
+            timing: TimingWindow::default(),
+            breakpoints: BreakpointPanel::default(),
+            checkpoints: CheckpointPanel::default(),
             settle_error: None,
+            settle_loop: HashSet::new(),
         }
     }
 
@@ -123,20 +149,36 @@ impl Document {
         self.clock.run == ClockRun::Playing
     }
 
-    pub(crate) fn record_settle_result<T>(
-        &mut self,
-        result: Result<T, crate::sim::circuit::SettleError>,
-    ) {
-        match result {
-            Ok(_) => self.settle_error = None,
-            Err(e) => self.settle_error = Some(e.to_string()),
+    // An Oscillation's loop is spelled out by kind and grid position, so it
+    // can be found in a design too big to scan for red.
+    pub(crate) fn record_settle_result<T>(&mut self, result: Result<T, SettleError>) {
+        self.settle_loop.clear();
+        let Err(e) = result else {
+            self.settle_error = None;
+            return;
+        };
+        let mut msg = e.to_string();
+        if let SettleError::Oscillation { components, .. } = e {
+            self.settle_loop = components.into_iter().collect();
+            let mut members: Vec<&PlacedComponent> = self
+                .components
+                .values()
+                .filter(|pc| self.settle_loop.contains(&pc.key))
+                .collect();
+            members.sort_by_key(|pc| (pc.grid_pos.y, pc.grid_pos.x));
+            let names: Vec<String> = members.iter().map(|pc| pc.describe()).collect();
+            if !names.is_empty() {
+                msg = format!("{msg}: {}", names.join(", "));
+            }
         }
+        self.settle_error = Some(msg);
     }
 
     // Untracked: never lands on the undo stack (see Clock::step). Records one
     // probe sample on a successful tick.
     pub(crate) fn tick_once(&mut self) {
-        let result = self.clock.step(&mut self.circuit);
+        self.breakpoints.hit = None;
+        let result = self.step();
         let ok = result.is_ok();
         self.record_settle_result(result);
         if ok {
@@ -145,24 +187,142 @@ impl Document {
     }
 
     // Resets all sequential state to its power-on value (see Clock::stop) and
-    // clears the probe history so the next run starts clean.
+    // clears the probe history so the next run starts clean. Inputs return to
+    // the values their specs hold, undoing any stimulus replay.
     pub(crate) fn stop_clock(&mut self) {
+        let restore: Vec<Command> = self
+            .components
+            .values()
+            .filter_map(|pc| match &pc.spec {
+                ComponentSpec::Input(input) => Some(Command::SetInput {
+                    comp: pc.key,
+                    bits: input.bits,
+                    width: input.width,
+                }),
+                _ => None,
+            })
+            .collect();
+        for command in restore {
+            self.circuit.apply(command);
+        }
         let result = self.clock.stop(&mut self.circuit);
         self.record_settle_result(result);
         self.signal_log.clear();
+        self.rewind.clear();
+        self.breakpoints.hit = None;
+    }
+
+    // One clock step (see Clock::step). Within a run session every tick that
+    // settles is recorded for stepping back (see sim::rewind); a tick taken
+    // from a stepped-back one replaces the ticks after it, probe history too.
+    fn step(&mut self) -> Result<(), SettleError> {
+        let recording = self.editing_locked();
+        if recording {
+            if self.rewind.is_empty() {
+                self.rewind.start(&self.circuit, self.signal_log.ticks);
+            }
+            self.signal_log.truncate(self.rewind.tick());
+        }
+        let result = self.clock.step(&mut self.circuit);
+        if recording && result.is_ok() {
+            self.rewind.record(&self.circuit);
+        }
+        result
+    }
+
+    // Puts the whole circuit back as it stood after `tick` of this run. The
+    // probe history stays whole, so the scrubber can come forward again.
+    pub(crate) fn seek_tick(&mut self, tick: usize) {
+        self.rewind.seek(&mut self.circuit, tick);
+        self.breakpoints.hit = None;
+        self.record_settle_result(Ok(()));
+    }
+
+    pub(crate) fn can_step_back(&self) -> bool {
+        self.clock.run == ClockRun::Paused
+            && self
+                .rewind
+                .ticks()
+                .is_some_and(|ticks| self.rewind.tick() > *ticks.start())
+    }
+
+    pub(crate) fn step_back(&mut self) {
+        if self.can_step_back() {
+            self.seek_tick(self.rewind.tick() - 1);
+        }
+    }
+
+    // What the signal viewer can scrub through: the recorded ticks while
+    // Paused, nothing otherwise.
+    pub(crate) fn scrubber(&self) -> Option<Scrubber> {
+        if self.clock.run != ClockRun::Paused {
+            return None;
+        }
+        let ticks = self.rewind.ticks()?;
+        Some(Scrubber {
+            ticks,
+            at: self.rewind.tick(),
+        })
+    }
+
+    // The tick the circuit stands at: the one stepped back to, if any.
+    pub(crate) fn current_tick(&self) -> usize {
+        if self.rewind.is_empty() {
+            self.signal_log.ticks
+        } else {
+            self.rewind.tick()
+        }
+    }
+
+    // Puts every part `checkpoint` names back as it was (see
+    // checkpoint::Checkpoint::restore), leaving the run state alone: a
+    // Playing run plays on from there. The probe history and the ticks Step
+    // Back could return to belong to the run being left, so both start over.
+    pub(crate) fn restore_checkpoint(&mut self, checkpoint: &Checkpoint, layout: &Layout) {
+        let result = checkpoint.restore(&mut self.circuit, layout);
+        self.checkpoints.note = match &result {
+            Ok(skipped) if *skipped > 0 => Some(format!(
+                "{skipped} part(s) of \"{}\" have moved or changed and kept their state",
+                checkpoint.name
+            )),
+            _ => None,
+        };
+        self.record_settle_result(result);
+        self.signal_log.clear();
+        self.rewind.clear();
+        self.breakpoints.hit = None;
+    }
+
+    // Folds a value poke made while Paused into the current tick's frame, so
+    // seeking away and back keeps it; ticks after it, if stepped back from,
+    // are dropped like a fresh tick would drop them.
+    fn amend_rewind(&mut self) {
+        if self.rewind.is_empty() {
+            return;
+        }
+        self.rewind.amend(&self.circuit);
+        self.signal_log.truncate(self.rewind.tick());
     }
 
     // Bridges the frame clock (wasm-safe) into the cadence math on Clock, then
-    // fires and samples each due tick here so every tick lands in the history
-    // (Clock::poll may report several ticks in one late frame). Auto-pauses on a
-    // settle failure so we don't hammer a broken circuit every frame.
+    // fires and samples each due tick (see run_ticks) so every tick lands in
+    // the history (Clock::poll may report several ticks in one late frame).
     pub(crate) fn advance_clock(&mut self, ctx: &egui::Context) {
         let now = ctx.input(|i| i.time);
         let n_ticks = self.clock.poll(now, |wait| {
             ctx.request_repaint_after(std::time::Duration::from_secs_f64(wait));
         });
-        for _ in 0..n_ticks {
-            let result = self.clock.step(&mut self.circuit);
+        self.run_ticks(n_ticks);
+    }
+
+    // Fires and samples up to `n` ticks of a Playing run. Auto-pauses on a
+    // settle failure so we don't hammer a broken circuit every frame, and on
+    // a tripped breakpoint, opening the Breakpoints window to say which.
+    pub(crate) fn run_ticks(&mut self, n: u32) {
+        for _ in 0..n {
+            self.breakpoints.hit = None;
+            let before = self.watched_values();
+            let result = self.step();
             let failed = result.is_err();
             self.record_settle_result(result);
             if failed {
@@ -170,6 +330,37 @@ impl Document {
                 return;
             }
             self.sample_probes();
+            let hit = self.breakpoints.first_hit(&before, &self.watched_values());
+            if hit.is_some() {
+                self.breakpoints.hit = hit;
+                self.breakpoints.open = true;
+                self.clock.pause();
+                return;
+            }
+        }
+    }
+
+    // The value each breakpoint watches, None where its signal is gone.
+    fn watched_values(&self) -> Vec<Option<Value>> {
+        self.breakpoints
+            .breakpoints
+            .iter()
+            .map(|bp| self.watch_value(&bp.watch))
+            .collect()
+    }
+
+    fn watch_value(&self, watch: &Watch) -> Option<Value> {
+        match watch {
+            Watch::Component(pck) => {
+                let pc = self.components.get(pck)?;
+                Some(self.circuit.read_output(pc.key))
+            }
+            Watch::Net(label) => self
+                .tunnels
+                .values()
+                .filter(|pt| pt.label == *label)
+                .find_map(|pt| self.circuit.tunnels.get(&pt.key)?.net)
+                .map(|net| self.circuit.nets[net].value),
         }
     }
 
@@ -185,6 +376,43 @@ impl Document {
         self.signal_log.record(&samples);
     }
 
+    // Simulated time units per clock tick: the step length in timed mode, else
+    // one. What VCD export stamps each sample with, and the default sampling
+    // period when replaying a VCD.
+    pub(crate) fn tick_period(&self) -> u64 {
+        if self.circuit.is_timed() {
+            self.clock.time_per_tick as u64
+        } else {
+            1
+        }
+    }
+
+    // Replays `stimulus` from power-on: each row's values go onto the labelled
+    // Inputs (untracked, like a tick), then the clock ticks and the probes are
+    // sampled. Leaves the run Paused with the traces in the signal viewer;
+    // Stop puts the Inputs back. Returns how many ticks ran, which is fewer
+    // than the rows if the circuit fails to settle.
+    pub(crate) fn replay_stimulus(&mut self, stimulus: &Stimulus) -> Result<usize, StimulusError> {
+        let ports = test_vector::ports(self.components.values().map(|pc| (&pc.spec, pc.key)));
+        let bindings = stimulus.bind(&ports)?;
+        self.stop_clock();
+        self.clock.pause();
+        self.signal_viewer.open = true;
+        for row in 0..stimulus.rows.len() {
+            for command in stimulus.commands(row, &bindings) {
+                self.circuit.apply(command);
+            }
+            let result = self.step();
+            let failed = result.is_err();
+            self.record_settle_result(result);
+            if failed {
+                return Ok(row);
+            }
+            self.sample_probes();
+        }
+        Ok(stimulus.rows.len())
+    }
+
     // A suggested unique-ish name for a newly placed probe. Names need not be
     // unique (like tunnels), so this is only a convenience default.
     pub(crate) fn next_probe_name(&self) -> String {
@@ -277,12 +505,13 @@ impl Document {
 
     // PlacedCompKey stays stable so wires survive; only pins the new arity
     // drops lose their wire nodes. new_comp is built by the caller via
-    // instantiate (needs the document registry).
+    // instantiate (needs the document registry), with `delay` applied.
     pub(crate) fn reconfigure_component(
         &mut self,
         pc_key: PlacedCompKey,
         new_spec: ComponentSpec,
         new_comp: Component,
+        delay: Option<u32>,
     ) {
         let old_key = self.components[&pc_key].key;
         let grid_pos = self.components[&pc_key].grid_pos;
@@ -294,15 +523,17 @@ impl Document {
         let new_key = self.apply(Command::comp(new_comp)).unwrap_comp();
         // Undo restores the old CompKey + spec; the Sim actions above only
         // handle the circuit component.
-        let old_spec = self
+        let mut pc = PlacedComponent::new(new_key, new_spec, grid_pos);
+        pc.delay = delay;
+        let old = self
             .components
-            .insert(pc_key, PlacedComponent::new(new_key, new_spec, grid_pos))
-            .expect("reconfigure of a live component")
-            .spec;
+            .insert(pc_key, pc)
+            .expect("reconfigure of a live component");
         self.history.push_gui(GuiUndoAction::SwapComponentSpec {
             key: pc_key,
             comp_key: old_key,
-            spec: old_spec,
+            spec: old.spec,
+            delay: old.delay,
         });
 
         let delta = self.wiring.prune_stale_pins(pc_key, new_n_in, new_n_out);
@@ -311,6 +542,9 @@ impl Document {
         self.rebuild_circuit();
         self.history.end_batch();
         self.selected = Some(Selection::Single(Selected::Component(pc_key)));
+        // Only a value poke reaches here mid-run: an Input's bits, while Paused.
+        self.rewind.rekey(old_key, new_key);
+        self.amend_rewind();
     }
 
     pub(crate) fn place_tunnel(&mut self, role: TunnelRole, grid_pos: GridPos) -> PlacedTunnelKey {
@@ -457,8 +691,75 @@ impl Document {
         out
     }
 
+    // Nodes of the wire groups that carry the loop behind settle_error from
+    // one of its components to another.
+    fn loop_wire_nodes(&self) -> HashSet<WireNodeKey> {
+        if self.settle_loop.is_empty() {
+            return HashSet::new();
+        }
+        self.wire_nodes_linking(|from, to| {
+            self.settle_loop.contains(&from) && self.settle_loop.contains(&to)
+        })
+    }
+
+    // Nodes of the wire groups whose net runs from one component to the next
+    // along the Timing window's selected path.
+    fn path_wire_nodes(&self, path: &[CompKey]) -> HashSet<WireNodeKey> {
+        if path.is_empty() {
+            return HashSet::new();
+        }
+        self.wire_nodes_linking(|from, to| path.windows(2).any(|w| w == [from, to]))
+    }
+
+    // Nodes of every wire group whose net is driven by some `from` and read
+    // by some `to` with `links(from, to)`.
+    fn wire_nodes_linking(&self, links: impl Fn(CompKey, CompKey) -> bool) -> HashSet<WireNodeKey> {
+        let mut out = HashSet::new();
+        for group in self.wiring.groups() {
+            let net = group.pins.iter().find_map(|&(pck, pin)| {
+                let pc = self.components.get(&pck)?;
+                self.circuit.components[&pc.key].net_of(pin)
+            });
+            let Some(net) = net else {
+                continue;
+            };
+            let n = &self.circuit.nets[net];
+            let linked = n
+                .sources
+                .iter()
+                .any(|&(from, _)| n.sinks.iter().any(|&(to, _)| links(from, to)));
+            if linked {
+                out.extend(group.nodes);
+            }
+        }
+        out
+    }
+
+    // Nodes of the wire groups carrying the signal behind a breakpoint hit.
+    fn hit_wire_nodes(&self) -> HashSet<WireNodeKey> {
+        let Some(hit) = &self.breakpoints.hit else {
+            return HashSet::new();
+        };
+        let mut out = HashSet::new();
+        for group in self.wiring.groups() {
+            let carries = match &hit.watch {
+                Watch::Component(pck) => group.pins.contains(&(*pck, PinId::input(0))),
+                Watch::Net(label) => group
+                    .tunnels
+                    .iter()
+                    .any(|ptk| self.tunnels.get(ptk).is_some_and(|pt| pt.label == *label)),
+            };
+            if carries {
+                out.extend(group.nodes);
+            }
+        }
+        out
+    }
+
     // Draws the whole canvas: grid, wires (coloured by their group's live
-    // value), junction dots, components, and tunnels.
+    // value, or highlighted along an oscillating loop, a breakpoint hit's
+    // signal or the selected timing path), junction dots, components, and
+    // tunnels.
     pub(crate) fn draw(&self, painter: &Painter, clip_rect: Rect, camera: Camera, theme: Theme) {
         puffin::profile_function!();
         painter.rect_filled(clip_rect, 0.0, theme.canvas_bg);
@@ -467,6 +768,10 @@ impl Document {
         // Colour comes from the group's net value; a dangling group (no
         // endpoints) is Floating.
         let node_value = self.wire_node_values();
+        let loop_nodes = self.loop_wire_nodes();
+        let hit_nodes = self.hit_wire_nodes();
+        let path = self.timing.highlighted();
+        let path_nodes = self.path_wire_nodes(&path);
 
         for (seg_key, seg) in &self.wiring.segments {
             let a = self.wiring.nodes[&seg.a];
@@ -478,6 +783,15 @@ impl Document {
             if self.is_highlighted(Selected::Wire(*seg_key)) {
                 stroke.color = theme.outline_selected;
                 stroke.width += 1.5;
+            } else if loop_nodes.contains(&seg.a) {
+                stroke.color = theme.error_text;
+                stroke.width += 1.5;
+            } else if hit_nodes.contains(&seg.a) {
+                stroke.color = theme.breakpoint_highlight;
+                stroke.width += 1.5;
+            } else if path_nodes.contains(&seg.a) {
+                stroke.color = theme.path_highlight;
+                stroke.width += 1.5;
             }
             stroke.width = camera.scale(stroke.width);
             let (p0, p1) = extend_segment(p0, p1, stroke.width / 2.0);
@@ -497,9 +811,27 @@ impl Document {
             }
         }
 
+        let hit = self.breakpoints.hit.as_ref().map(|hit| &hit.watch);
         for (&pc_key, pc) in &self.components {
             let is_selected = self.is_highlighted(Selected::Component(pc_key));
-            draw_component(painter, pc, camera, &self.circuit, is_selected, theme);
+            let highlight = if self.settle_loop.contains(&pc.key) {
+                Some(theme.error_text)
+            } else if hit == Some(&Watch::Component(pc_key)) {
+                Some(theme.breakpoint_highlight)
+            } else if path.contains(&pc.key) {
+                Some(theme.path_highlight)
+            } else {
+                None
+            };
+            draw_component(
+                painter,
+                pc,
+                camera,
+                &self.circuit,
+                is_selected,
+                highlight,
+                theme,
+            );
         }
 
         for (pt_key, pt) in self.active_tunnels() {
@@ -553,7 +885,7 @@ impl Document {
 
     // Spec and live component share one buffer (Rom::shared), so this updates
     // both. Not undoable, like a clock tick.
-    pub(crate) fn write_rom_cell(&mut self, pc: PlacedCompKey, index: usize, value: u32) {
+    pub(crate) fn write_rom_cell(&mut self, pc: PlacedCompKey, index: usize, value: u64) {
         let comp_key = self.components[&pc].key;
         self.circuit.write_rom(comp_key, index, value);
         let result = self.circuit.settle();
@@ -561,23 +893,133 @@ impl Document {
     }
 
     // No settle() needed: RAM's data_out is a registered output, only updated
-    // by tick_clock. Not undoable, like a ROM's.
-    pub(crate) fn write_ram_cell(&mut self, pc: PlacedCompKey, index: usize, value: u32) {
+    // by tick_clock. Not undoable, like a ROM's, though stepping back past the
+    // tick it was written at undoes it.
+    pub(crate) fn write_ram_cell(&mut self, pc: PlacedCompKey, index: usize, value: u64) {
         let comp_key = self.components[&pc].key;
         self.circuit.write_ram(comp_key, index, value);
+        self.amend_rewind();
+    }
+
+    // Analysis stays here: TimingWindow doesn't own the circuit, nor the
+    // placed components its report names.
+    pub(crate) fn show_timing(&mut self, ctx: &egui::Context, theme: Theme) {
+        if !self.timing.open {
+            return;
+        }
+        let names: HashMap<CompKey, String> = self
+            .components
+            .values()
+            .map(|pc| (pc.key, pc.describe()))
+            .collect();
+        if self.timing.show(ctx, theme, &names) {
+            let outcome =
+                Timing::analyze(&self.circuit, self.timing.metric).map_err(|e| e.to_string());
+            self.timing.set_outcome(outcome);
+        }
+    }
+
+    // The signals listed stay here, read from the placed Probes and Outputs
+    // and the tunnel labels.
+    pub(crate) fn show_breakpoints(&mut self, ctx: &egui::Context, theme: Theme) {
+        if !self.breakpoints.open {
+            return;
+        }
+        let mut watches: Vec<(Watch, String)> = self
+            .components
+            .iter()
+            .filter_map(|(&pck, pc)| {
+                let name = match &pc.spec {
+                    ComponentSpec::Probe(p) => format!("Probe {}", p.name),
+                    ComponentSpec::Output(o) if !o.label.is_empty() => {
+                        format!("Output {}", o.label)
+                    }
+                    ComponentSpec::Output(_) => pc.describe(),
+                    _ => return None,
+                };
+                Some((Watch::Component(pck), name))
+            })
+            .collect();
+        watches.sort_by(|a, b| a.1.cmp(&b.1));
+        let mut labels: Vec<&str> = self.tunnels.values().map(|pt| pt.label.as_str()).collect();
+        labels.sort_unstable();
+        labels.dedup();
+        watches.extend(
+            labels
+                .into_iter()
+                .map(|label| (Watch::Net(label.to_string()), format!("Net {label}"))),
+        );
+        self.breakpoints.show(ctx, theme, &watches);
     }
 
     // Applying stays here: the write paths need &mut Circuit + settle, which
-    // MemoryEditor doesn't own.
-    pub(crate) fn show_memory_editors(&mut self, ctx: &egui::Context) {
+    // MemoryEditor doesn't own. An image file request goes up to the app,
+    // which owns platform IO.
+    pub(crate) fn show_memory_editors(&mut self, ctx: &egui::Context) -> Option<MemFile> {
         let value_locked = self.value_editing_locked();
-        let edits = self.memory_editor.show(ctx, &self.components, value_locked);
+        let (edits, file) = self.memory_editor.show(ctx, &self.components, value_locked);
         for edit in edits {
             match edit.kind {
                 MemKind::Rom => self.write_rom_cell(edit.pc, edit.index, edit.value),
                 MemKind::Ram => self.write_ram_cell(edit.pc, edit.index, edit.value),
             }
         }
+        file
+    }
+
+    // Replaces a memory's whole contents with an image file's, read in the
+    // format its editor window has picked. Not undoable, like a cell edit.
+    pub(crate) fn import_memory_image(
+        &mut self,
+        pc: PlacedCompKey,
+        kind: MemKind,
+        bytes: &[u8],
+    ) -> Result<(), ImageError> {
+        let Some((data_width, len)) = self.components.get(&pc).and_then(|c| kind.dims(&c.spec))
+        else {
+            return Ok(()); // deleted while the file was picked
+        };
+        let options = self.memory_editor.image_options(kind);
+        let words = memory_image::import(
+            options.format,
+            bytes,
+            len,
+            data_width,
+            options.layout(data_width),
+        )?;
+        self.circuit.load_memory(self.components[&pc].key, &words);
+        match kind {
+            MemKind::Rom => {
+                let result = self.circuit.settle();
+                self.record_settle_result(result);
+            }
+            MemKind::Ram => self.amend_rewind(),
+        }
+        Ok(())
+    }
+
+    // A memory's contents as an image file in its editor window's format,
+    // with a file name to suggest. None if the memory is gone.
+    pub(crate) fn export_memory_image(
+        &self,
+        pc: PlacedCompKey,
+        kind: MemKind,
+    ) -> Option<Result<(String, Vec<u8>), ImageError>> {
+        let comp = self.components.get(&pc)?;
+        let (data_width, words) = kind.contents(&comp.spec)?;
+        let options = self.memory_editor.image_options(kind);
+        let bytes = memory_image::export(
+            options.format,
+            &words,
+            data_width,
+            options.layout(data_width),
+        );
+        let file_name = format!(
+            "{}.{}",
+            comp.spec.label().to_lowercase(),
+            options.format.extension()
+        );
+        Some(bytes.map(|bytes| (file_name, bytes)))
     }
 
     pub(crate) fn delete_component(&mut self, key: PlacedCompKey) {
@@ -1137,8 +1579,12 @@ fn route_elbow(from: GridPos, to: GridPos) -> Vec<GridPos> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gui::breakpoints::Breakpoint;
     use crate::gui::wiring::WireNode;
-    use crate::sim::component::{Gate, GateOp, Input, RegConf};
+    use crate::sim::breakpoint::Condition;
+    use crate::sim::component::{
+        ClockEdge, Gate, GateOp, Input, PullDirection, PullResistor, RegConf,
+    };
 
     fn place(doc: &mut Document, spec: ComponentSpec) -> PlacedCompKey {
         place_at(doc, spec, GridPos::new(0, 0))
@@ -1194,7 +1640,7 @@ mod tests {
         // NOT gate wired Input -> Output; deleting it must clean up nodes,
         // selection, and downstream state.
         let mut doc = Document::blank();
-        let a = place(&mut doc, ComponentSpec::Input(Input { bits: 1, width: 1 }));
+        let a = place(&mut doc, ComponentSpec::Input(Input::new(1, 1)));
         let g = place(
             &mut doc,
             ComponentSpec::Gate(Gate {
@@ -1203,7 +1649,7 @@ mod tests {
                 width: 1,
             }),
         );
-        let o = place(&mut doc, ComponentSpec::Output);
+        let o = place(&mut doc, ComponentSpec::Output(Default::default()));
         connect_pins(&mut doc, (a, PinId::output(0)), (g, PinId::input(0)));
         connect_pins(&mut doc, (g, PinId::output(0)), (o, PinId::input(0)));
         doc.rebuild_circuit();
@@ -1231,10 +1677,108 @@ mod tests {
         assert_eq!(doc.circuit.read_output(o_key), Value::Floating);
     }
 
+    #[test]
+    fn test_oscillating_loop_is_named_and_highlighted() {
+        let mut doc = Document::blank();
+        let not = ComponentSpec::Gate(Gate {
+            op: GateOp::Not,
+            n_inputs: 1,
+            width: 1,
+        });
+        let ring: Vec<PlacedCompKey> = (0..3)
+            .map(|i| place_at(&mut doc, not.clone(), GridPos::new(i * 4, 0)))
+            .collect();
+        let pull = place_at(
+            &mut doc,
+            ComponentSpec::PullResistor(PullResistor {
+                direction: PullDirection::Up,
+                width: 1,
+            }),
+            GridPos::new(0, 4),
+        );
+        for i in 0..3 {
+            let next = ring[(i + 1) % 3];
+            connect_pins(
+                &mut doc,
+                (ring[i], PinId::output(0)),
+                (next, PinId::input(0)),
+            );
+        }
+        connect_pins(
+            &mut doc,
+            (pull, PinId::output(0)),
+            (ring[0], PinId::input(0)),
+        );
+        doc.rebuild_circuit();
+
+        let err = doc.settle_error.clone().unwrap();
+        assert!(
+            err.ends_with("NOT at (0, 0), NOT at (4, 0), NOT at (8, 0)"),
+            "{err}"
+        );
+        let keys: HashSet<CompKey> = ring.iter().map(|pc| doc.components[pc].key).collect();
+        assert_eq!(doc.settle_loop, keys);
+        assert_eq!(doc.loop_wire_nodes().len(), doc.wiring.nodes.len());
+
+        doc.delete_component(ring[1]);
+        assert_eq!(doc.settle_error, None);
+        assert!(doc.settle_loop.is_empty());
+        assert!(doc.loop_wire_nodes().is_empty());
+    }
+
+    #[test]
+    fn test_timing_path_highlights_its_wires() {
+        let mut doc = Document::blank();
+        let a = place_at(
+            &mut doc,
+            ComponentSpec::Input(Input::new(0, 1)),
+            GridPos::new(0, 0),
+        );
+        let b = place_at(
+            &mut doc,
+            ComponentSpec::Input(Input::new(0, 1)),
+            GridPos::new(0, 8),
+        );
+        let not = place_at(
+            &mut doc,
+            ComponentSpec::Gate(Gate {
+                op: GateOp::Not,
+                n_inputs: 1,
+                width: 1,
+            }),
+            GridPos::new(4, 0),
+        );
+        let o = place_at(
+            &mut doc,
+            ComponentSpec::Output(Default::default()),
+            GridPos::new(8, 0),
+        );
+        let p = place_at(
+            &mut doc,
+            ComponentSpec::Output(Default::default()),
+            GridPos::new(8, 8),
+        );
+        connect_pins(&mut doc, (a, PinId::output(0)), (not, PinId::input(0)));
+        connect_pins(&mut doc, (not, PinId::output(0)), (o, PinId::input(0)));
+        connect_pins(&mut doc, (b, PinId::output(0)), (p, PinId::input(0)));
+        doc.rebuild_circuit();
+
+        doc.timing.set_outcome(
+            Timing::analyze(&doc.circuit, doc.timing.metric).map_err(|e| e.to_string()),
+        );
+        let path: Vec<CompKey> = [a, not, o]
+            .iter()
+            .map(|pc| doc.components[pc].key)
+            .collect();
+        assert_eq!(doc.timing.highlighted(), path);
+        // Both wires of the NOT's path, and not the bare b-to-p one.
+        assert_eq!(doc.path_wire_nodes(&path).len(), 4);
+    }
+
     #[test]
     fn test_delete_tunnel_drops_wire_nodes() {
         let mut doc = Document::blank();
-        let a = place(&mut doc, ComponentSpec::Input(Input { bits: 1, width: 1 }));
+        let a = place(&mut doc, ComponentSpec::Input(Input::new(1, 1)));
         let t = doc.place_tunnel(TunnelRole::Pull, GridPos::new(1, 1));
         let t_key = doc.tunnels[&t].key;
         connect_pin_tunnel(&mut doc, (a, PinId::output(0)), t);
@@ -1259,8 +1803,8 @@ mod tests {
         // circuit.rename_tunnel (e.g. rename committed by clicking away),
         // rebuild_circuit must reconcile the label so Feed/Pull still link.
         let mut doc = Document::blank();
-        let inp = place(&mut doc, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let out = place(&mut doc, ComponentSpec::Output);
+        let inp = place(&mut doc, ComponentSpec::Input(Input::new(1, 1)));
+        let out = place(&mut doc, ComponentSpec::Output(Default::default()));
         let pull = doc.place_tunnel(TunnelRole::Pull, GridPos::new(1, 1));
         let feed = doc.place_tunnel(TunnelRole::Feed, GridPos::new(2, 2));
 
@@ -1285,9 +1829,17 @@ mod tests {
         // cluster selects exactly those two; a bulk delete removes them and
         // leaves the far one (and clears the selection).
         let mut doc = Document::blank();
-        let a = place(&mut doc, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let b = place_at(&mut doc, ComponentSpec::Output, GridPos::new(2, 2));
-        let far = place_at(&mut doc, ComponentSpec::Output, GridPos::new(50, 50));
+        let a = place(&mut doc, ComponentSpec::Input(Input::new(1, 1)));
+        let b = place_at(
+            &mut doc,
+            ComponentSpec::Output(Default::default()),
+            GridPos::new(2, 2),
+        );
+        let far = place_at(
+            &mut doc,
+            ComponentSpec::Output(Default::default()),
+            GridPos::new(50, 50),
+        );
         connect_pins(&mut doc, (a, PinId::output(0)), (b, PinId::input(0)));
         doc.rebuild_circuit();
 
@@ -1315,7 +1867,7 @@ mod tests {
         // Input -> NOT -> Output as two wires; delete the input->gate wire and
         // the gate's input goes Floating (net split), so the output does too.
         let mut doc = Document::blank();
-        let a = place(&mut doc, ComponentSpec::Input(Input { bits: 1, width: 1 }));
+        let a = place(&mut doc, ComponentSpec::Input(Input::new(1, 1)));
         let g = place(
             &mut doc,
             ComponentSpec::Gate(Gate {
@@ -1324,7 +1876,7 @@ mod tests {
                 width: 1,
             }),
         );
-        let o = place(&mut doc, ComponentSpec::Output);
+        let o = place(&mut doc, ComponentSpec::Output(Default::default()));
         connect_pins(&mut doc, (a, PinId::output(0)), (g, PinId::input(0)));
         connect_pins(&mut doc, (g, PinId::output(0)), (o, PinId::input(0)));
         doc.rebuild_circuit();
@@ -1355,7 +1907,7 @@ mod tests {
         // exactly one entry - the Gui WiringDelta - with no Sim entries from the
         // relink (which used to pad the batch with RelinkAll + per-link undos).
         let mut doc = Document::blank();
-        let a = place(&mut doc, ComponentSpec::Input(Input { bits: 1, width: 1 }));
+        let a = place(&mut doc, ComponentSpec::Input(Input::new(1, 1)));
         let g = place(
             &mut doc,
             ComponentSpec::Gate(Gate {
@@ -1381,7 +1933,7 @@ mod tests {
     #[test]
     fn test_commit_move_pushes_undo_only_when_position_changed() {
         let mut doc = Document::blank();
-        let a = place(&mut doc, ComponentSpec::Input(Input { bits: 1, width: 1 }));
+        let a = place(&mut doc, ComponentSpec::Input(Input::new(1, 1)));
         let original = doc.components[&a].grid_pos;
         let stack_before = doc.history.len();
 
@@ -1405,8 +1957,16 @@ mod tests {
     #[test]
     fn test_bulk_move_commits_as_one_undo_batch() {
         let mut doc = Document::blank();
-        let a = place_at(&mut doc, ComponentSpec::Output, GridPos::new(0, 0));
-        let b = place_at(&mut doc, ComponentSpec::Output, GridPos::new(10, 0));
+        let a = place_at(
+            &mut doc,
+            ComponentSpec::Output(Default::default()),
+            GridPos::new(0, 0),
+        );
+        let b = place_at(
+            &mut doc,
+            ComponentSpec::Output(Default::default()),
+            GridPos::new(10, 0),
+        );
         let orig_a = doc.components[&a].grid_pos;
         let orig_b = doc.components[&b].grid_pos;
         let stack_before = doc.history.len();
@@ -1446,7 +2006,7 @@ mod tests {
     #[test]
     fn test_drag_grid_pos_excludes_wire_selection() {
         let mut doc = Document::blank();
-        let a = place(&mut doc, ComponentSpec::Output);
+        let a = place(&mut doc, ComponentSpec::Output(Default::default()));
         assert!(doc
             .drag_grid_pos(
                 Selected::Wire(crate::gui::wiring::WireSegKey(0)),
@@ -1464,10 +2024,14 @@ mod tests {
     ) -> (PlacedCompKey, PlacedCompKey, WireNodeKey, Vec<WireSegKey>) {
         let a = place_at(
             doc,
-            ComponentSpec::Input(Input { bits: 1, width: 1 }),
+            ComponentSpec::Input(Input::new(1, 1)),
             GridPos::new(0, 0),
         );
-        let b = place_at(doc, ComponentSpec::Output, GridPos::new(10, 0));
+        let b = place_at(
+            doc,
+            ComponentSpec::Output(Default::default()),
+            GridPos::new(10, 0),
+        );
         let pa = pin_grid_pos(
             &doc.components[&a].shape,
             doc.components[&a].grid_pos,
@@ -1601,8 +2165,8 @@ mod tests {
     #[test]
     fn undo_redo_wire_draw_round_trips_connectivity() {
         let mut doc = Document::blank();
-        let a = place(&mut doc, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let o = place(&mut doc, ComponentSpec::Output);
+        let a = place(&mut doc, ComponentSpec::Input(Input::new(1, 1)));
+        let o = place(&mut doc, ComponentSpec::Output(Default::default()));
         doc.commit_wire_route(
             vec![GridPos::new(0, 0), GridPos::new(10, 0)],
             NodeAttach::Pin(a, PinId::output(0)),
@@ -1627,8 +2191,8 @@ mod tests {
     #[test]
     fn undo_redo_delete_component_restores_wire_and_value() {
         let mut doc = Document::blank();
-        let a = place(&mut doc, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let o = place(&mut doc, ComponentSpec::Output);
+        let a = place(&mut doc, ComponentSpec::Input(Input::new(1, 1)));
+        let o = place(&mut doc, ComponentSpec::Output(Default::default()));
         doc.commit_wire_route(
             vec![GridPos::new(0, 0), GridPos::new(10, 0)],
             NodeAttach::Pin(a, PinId::output(0)),
@@ -1667,7 +2231,7 @@ mod tests {
             width: 1,
         });
         let new_comp = new_spec.to_component();
-        doc.reconfigure_component(g, new_spec, new_comp);
+        doc.reconfigure_component(g, new_spec, new_comp, None);
         assert!(matches!(
             doc.components[&g].spec,
             ComponentSpec::Gate(Gate {
@@ -1697,6 +2261,26 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn undo_redo_delay_edit_restores_delay() {
+        let mut doc = Document::blank();
+        let g = place(&mut doc, and2());
+        let spec = and2();
+        let new_comp = spec.to_component().with_saved_delay(Some(7));
+        doc.reconfigure_component(g, spec, new_comp, Some(7));
+        let live = |doc: &Document| doc.circuit.components[&doc.components[&g].key].delay;
+        assert_eq!(doc.components[&g].delay, Some(7));
+        assert_eq!(live(&doc), 7);
+
+        doc.undo();
+        assert_eq!(doc.components[&g].delay, None);
+        assert_eq!(live(&doc), and2().to_component().delay);
+
+        doc.redo();
+        assert_eq!(doc.components[&g].delay, Some(7));
+        assert_eq!(live(&doc), 7);
+    }
+
     #[test]
     fn undo_redo_move_restores_grid_pos() {
         let mut doc = Document::blank();
@@ -1720,7 +2304,7 @@ mod tests {
         doc.undo();
         assert!(doc.history.can_redo());
         // A fresh edit invalidates the redo branch.
-        place(&mut doc, ComponentSpec::Output);
+        place(&mut doc, ComponentSpec::Output(Default::default()));
         assert!(!doc.history.can_redo());
     }
 
@@ -1797,10 +2381,16 @@ mod tests {
     #[test]
     fn test_stop_clock_resets_register_through_gui() {
         let mut doc = Document::blank();
-        let data = place(&mut doc, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let we = place(&mut doc, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let reg = place(&mut doc, ComponentSpec::Reg(RegConf { data_width: 1 }));
-        let out = place(&mut doc, ComponentSpec::Output);
+        let data = place(&mut doc, ComponentSpec::Input(Input::new(1, 1)));
+        let we = place(&mut doc, ComponentSpec::Input(Input::new(1, 1)));
+        let reg = place(
+            &mut doc,
+            ComponentSpec::Reg(RegConf {
+                data_width: 1,
+                clock_edge: ClockEdge::default(),
+            }),
+        );
+        let out = place(&mut doc, ComponentSpec::Output(Default::default()));
 
         connect_pins(&mut doc, (data, PinId::output(0)), (reg, PinId::input(0)));
         connect_pins(&mut doc, (we, PinId::output(0)), (reg, PinId::input(1)));
@@ -1822,15 +2412,114 @@ mod tests {
         assert_eq!(doc.circuit.read_output(out_key), Value::ZERO);
     }
 
+    #[test]
+    fn test_breakpoint_pauses_a_run_and_highlights_its_signal() {
+        // A clock source drives an Output and, through tunnel "clk", nothing.
+        let mut doc = Document::blank();
+        let clk = place(&mut doc, ComponentSpec::ClockSource(Default::default()));
+        let out = place(&mut doc, ComponentSpec::Output(Default::default()));
+        let t = doc.place_tunnel_labeled("clk".to_string(), TunnelRole::Pull, GridPos::new(1, 1));
+        connect_pins(&mut doc, (clk, PinId::output(0)), (out, PinId::input(0)));
+        connect_pin_tunnel(&mut doc, (clk, PinId::output(0)), t);
+        doc.rebuild_circuit();
+        let out_key = doc.components[&out].key;
+
+        doc.breakpoints.breakpoints.push(Breakpoint {
+            watch: Watch::Component(out),
+            condition: Condition::Rising,
+            enabled: true,
+        });
+        doc.clock.run = ClockRun::Playing;
+        doc.run_ticks(8);
+        assert_eq!(doc.clock.run, ClockRun::Paused);
+        assert_eq!(doc.circuit.read_output(out_key), Value::ONE);
+        let hit = doc.breakpoints.hit.clone().unwrap();
+        assert_eq!(hit.watch, Watch::Component(out));
+        assert_eq!(hit.value, Value::ONE);
+        assert!(doc.breakpoints.open);
+        // Both ends of the wire into the Output, not the tunnel's.
+        assert_eq!(doc.hit_wire_nodes().len(), 2);
+
+        // Resumed, the falling tick runs on and the next rising one stops.
+        doc.clock.run = ClockRun::Playing;
+        doc.run_ticks(1);
+        assert_eq!(doc.clock.run, ClockRun::Playing);
+        assert!(doc.breakpoints.hit.is_none());
+        doc.run_ticks(1);
+        assert_eq!(doc.clock.run, ClockRun::Paused);
+
+        // A named net, once the Output's breakpoint is off.
+        doc.breakpoints.breakpoints[0].enabled = false;
+        doc.breakpoints.breakpoints.push(Breakpoint {
+            watch: Watch::Net("clk".to_string()),
+            condition: Condition::Equals(0),
+            enabled: true,
+        });
+        doc.clock.run = ClockRun::Playing;
+        doc.run_ticks(8);
+        assert_eq!(doc.clock.run, ClockRun::Paused);
+        assert_eq!(
+            doc.breakpoints.hit.as_ref().unwrap().watch,
+            Watch::Net("clk".to_string())
+        );
+        assert_eq!(doc.circuit.read_output(out_key), Value::ZERO);
+
+        doc.stop_clock();
+        assert!(doc.breakpoints.hit.is_none());
+        assert!(doc.hit_wire_nodes().is_empty());
+    }
+
+    #[test]
+    fn test_step_back_and_scrub_through_a_run() {
+        let mut doc = Document::blank();
+        let clk = place(&mut doc, ComponentSpec::ClockSource(Default::default()));
+        let out = place(&mut doc, ComponentSpec::Output(Default::default()));
+        connect_pins(&mut doc, (clk, PinId::output(0)), (out, PinId::input(0)));
+        doc.rebuild_circuit();
+        let out_key = doc.components[&out].key;
+
+        doc.clock.run = ClockRun::Playing;
+        doc.run_ticks(3);
+        assert!(!doc.can_step_back());
+        doc.clock.pause();
+        assert_eq!(doc.circuit.read_output(out_key), Value::ONE);
+        assert_eq!(doc.scrubber().unwrap().ticks, 0..=3);
+
+        doc.step_back();
+        assert_eq!(doc.circuit.read_output(out_key), Value::ZERO);
+        doc.seek_tick(0);
+        assert_eq!(doc.circuit.read_output(out_key), Value::ZERO);
+        assert!(!doc.can_step_back());
+        doc.seek_tick(3);
+        assert_eq!(doc.circuit.read_output(out_key), Value::ONE);
+
+        // Stepping on from tick 1 runs the clock from its phase there and
+        // replaces ticks 2 and 3, samples included.
+        doc.seek_tick(1);
+        doc.tick_once();
+        assert_eq!(doc.circuit.read_output(out_key), Value::ZERO);
+        assert_eq!(doc.scrubber().unwrap().ticks, 0..=2);
+        assert_eq!(doc.signal_log.ticks, 2);
+
+        doc.stop_clock();
+        assert!(doc.rewind.is_empty());
+    }
+
     #[test]
     fn undo_redo_delete_register_preserves_latched_state() {
         // Deleting a register moves its live Component into the undo entry, so
         // undo restores the exact latched value a spec-based re-creation would lose.
         let mut doc = Document::blank();
-        let data = place(&mut doc, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let we = place(&mut doc, ComponentSpec::Input(Input { bits: 1, width: 1 }));
-        let reg = place(&mut doc, ComponentSpec::Reg(RegConf { data_width: 1 }));
-        let out = place(&mut doc, ComponentSpec::Output);
+        let data = place(&mut doc, ComponentSpec::Input(Input::new(1, 1)));
+        let we = place(&mut doc, ComponentSpec::Input(Input::new(1, 1)));
+        let reg = place(
+            &mut doc,
+            ComponentSpec::Reg(RegConf {
+                data_width: 1,
+                clock_edge: ClockEdge::default(),
+            }),
+        );
+        let out = place(&mut doc, ComponentSpec::Output(Default::default()));
         connect_pins(&mut doc, (data, PinId::output(0)), (reg, PinId::input(0)));
         connect_pins(&mut doc, (we, PinId::output(0)), (reg, PinId::input(1)));
         connect_pins(&mut doc, (reg, PinId::output(0)), (out, PinId::input(0)));
@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::gui::shape::{ComponentLabel, ComponentShape, PinAnchor, ShapeCmd};
 use crate::sim::circuit::TunnelRole;
-use crate::sim::component::{FanDirection, GateOp};
+use crate::sim::component::{FanDirection, GateOp, InIdx, OutIdx, PinId, PullDirection};
 
 // ── Grid unit ───────────────────────────────────────────────────────────────
 //
@@ -57,6 +57,18 @@ const CONST_W: u32 = 4;
 // bare IO_W, like a Constant.
 const PROBE_W: u32 = 4;
 
+// A rail box plus a resistor zigzag out to the pin.
+const PULL_W: u32 = 3;
+// Normalized x where the rail box ends and the zigzag starts.
+const PULL_RAIL_X: f32 = 0.35;
+
+// A square wave drawn inside the box; one right pin like a Constant.
+const CLOCK_W: u32 = 3;
+
+// Sequential parts put their clock pin on the bottom edge, this many cells in
+// from the left corner (clear of the reset/write-enable pins further right).
+const CLOCK_PIN_COL: u32 = 1;
+
 const REG_W: u32 = 3;
 
 // Same width as Reg (fits "D"/"LD"/"SH"/"0" labels); ShiftReg's height instead
@@ -139,6 +151,12 @@ impl Camera {
         )
     }
 
+    /// Pans (keeping zoom) so grid point `gp` lands on screen point `center`.
+    pub fn center_on(&mut self, gp: GridPos, center: Pos2) {
+        let s = self.grid_scale();
+        self.pan = Vec2::new(center.x - gp.x as f32 * s, center.y - gp.y as f32 * s);
+    }
+
     /// Scale a fixed pixel measurement (radius / stroke width / font size) by zoom.
     pub fn scale(&self, px: f32) -> f32 {
         px * self.zoom
@@ -318,6 +336,11 @@ pub const fn gate_size(op: GateOp, n_inputs: usize) -> Vec2 {
     vec2(px(EDGE_BODY_W), px(gate_pitch(n).height(n)))
 }
 
+// A NOT-sized triangle; the enable pin on the bottom edge adds no height.
+pub const fn tri_state_size() -> Vec2 {
+    vec2(px(EDGE_BODY_W), px(stack_h(1)))
+}
+
 pub const fn mux_size(sel_width: u8) -> Vec2 {
     let branches = 1usize << sel_width;
     vec2(px(MUX_W), px(sel_pitch(sel_width).height(branches)))
@@ -401,6 +424,14 @@ pub const fn probe_size() -> Vec2 {
     vec2(px(PROBE_W), px(stack_h(1)))
 }
 
+pub const fn pull_resistor_size() -> Vec2 {
+    vec2(px(PULL_W), px(stack_h(1)))
+}
+
+pub const fn clock_source_size() -> Vec2 {
+    vec2(px(CLOCK_W), px(stack_h(1)))
+}
+
 // Height scales off whichever side has more pins; each side packs from row 1
 // with its own pitch. Both pitch heights are even, so their max is too.
 pub fn subcircuit_size(n_in: usize, n_out: usize) -> Vec2 {
@@ -481,6 +512,54 @@ pub fn probe_shape() -> ComponentShape {
     }
 }
 
+// One right pin like a Constant. The outline is only the rail box (labelled
+// with the level it pulls to); the resistor is an open zigzag stroke from it
+// to the pin.
+pub fn pull_resistor_shape(direction: PullDirection) -> ComponentShape {
+    let center_row = stack_h(1) / 2; // 1
+    let outline = vec![
+        ShapeCmd::MoveTo(vec2(0.0, 0.2)),
+        ShapeCmd::LineTo(vec2(PULL_RAIL_X, 0.2)),
+        ShapeCmd::LineTo(vec2(PULL_RAIL_X, 0.8)),
+        ShapeCmd::LineTo(vec2(0.0, 0.8)),
+    ];
+
+    // Three full zigzag periods between two short straight leads.
+    let (lead_in, lead_out) = (PULL_RAIL_X + 0.08, 0.92);
+    let step = (lead_out - lead_in) / 6.0;
+    let mut zigzag = vec![
+        ShapeCmd::MoveTo(vec2(PULL_RAIL_X, 0.5)),
+        ShapeCmd::LineTo(vec2(lead_in, 0.5)),
+    ];
+    for i in 0..6 {
+        let y = if i % 2 == 0 { 0.3 } else { 0.7 };
+        zigzag.push(ShapeCmd::LineTo(vec2(lead_in + step * (i as f32 + 0.5), y)));
+    }
+    zigzag.push(ShapeCmd::LineTo(vec2(lead_out, 0.5)));
+    zigzag.push(ShapeCmd::LineTo(vec2(1.0, 0.5)));
+
+    let level = match direction {
+        PullDirection::Down => "0",
+        PullDirection::Up => "1",
+    };
+
+    ComponentShape {
+        size: pull_resistor_size(),
+        outline,
+        fill_outline: None,
+        input_anchors: vec![],
+        output_anchors: vec![PinAnchor::right(PULL_W, center_row)],
+        extra_strokes: vec![zigzag],
+        output_bubbles: vec![false],
+        labels: vec![ComponentLabel {
+            text: level,
+            pos: vec2(PULL_RAIL_X / 2.0, 0.5),
+            ..Default::default()
+        }],
+        dynamic_label_pos: Vec2::ZERO,
+    }
+}
+
 pub fn gate_shape(op: GateOp, n_inputs: usize) -> ComponentShape {
     let n = if matches!(op, GateOp::Not) {
         1
@@ -522,6 +601,34 @@ pub fn gate_shape(op: GateOp, n_inputs: usize) -> ComponentShape {
     }
 }
 
+// input[0] = data (left), input[1] = enable (bottom-center, like the mux
+// selector); the output sits at the triangle's tip.
+pub fn tri_state_shape() -> ComponentShape {
+    let center_row = stack_h(1) / 2; // 1
+
+    // The triangle's lower edge passes y = 0.75 at the center column; a stub
+    // carries the enable pin's wire up to it.
+    let enable_stub = vec![
+        ShapeCmd::MoveTo(vec2(0.5, 0.75)),
+        ShapeCmd::LineTo(vec2(0.5, 1.0)),
+    ];
+
+    ComponentShape {
+        size: tri_state_size(),
+        outline: not_outline(),
+        fill_outline: None,
+        input_anchors: vec![
+            PinAnchor::left(center_row),
+            PinAnchor::bottom(MUX_CENTER_COL, stack_h(1)),
+        ],
+        output_anchors: vec![PinAnchor::right(EDGE_BODY_W, center_row)],
+        extra_strokes: vec![enable_stub],
+        output_bubbles: vec![false],
+        labels: vec![],
+        dynamic_label_pos: Vec2::ZERO,
+    }
+}
+
 pub fn mux_shape(sel_width: u8) -> ComponentShape {
     let branches = 1usize << sel_width;
     let pitch = sel_pitch(sel_width); // tight once sel_width >= 2
@@ -561,15 +668,56 @@ pub fn mux_shape(sel_width: u8) -> ComponentShape {
     }
 }
 
+pub fn clock_source_shape() -> ComponentShape {
+    let center_row = stack_h(1) / 2; // 1
+    let wave = vec![
+        ShapeCmd::MoveTo(vec2(0.15, 0.7)),
+        ShapeCmd::LineTo(vec2(0.35, 0.7)),
+        ShapeCmd::LineTo(vec2(0.35, 0.3)),
+        ShapeCmd::LineTo(vec2(0.6, 0.3)),
+        ShapeCmd::LineTo(vec2(0.6, 0.7)),
+        ShapeCmd::LineTo(vec2(0.85, 0.7)),
+    ];
+
+    ComponentShape {
+        size: clock_source_size(),
+        outline: rect_outline(),
+        fill_outline: None,
+        input_anchors: vec![],
+        output_anchors: vec![PinAnchor::right(CLOCK_W, center_row)],
+        extra_strokes: vec![wave],
+        output_bubbles: vec![false],
+        labels: vec![],
+        dynamic_label_pos: Vec2::ZERO,
+    }
+}
+
+// The conventional clock-input wedge, pointing into the body from a
+// bottom-edge pin at `col`.
+fn clock_wedge(col: u32, w_cells: u32, h_cells: u32) -> Vec<ShapeCmd> {
+    const HALF_W_PX: f32 = 4.0;
+    const H_PX: f32 = 5.0;
+    let x = col as f32 / w_cells as f32;
+    let dx = HALF_W_PX / px(w_cells);
+    let dy = H_PX / px(h_cells);
+    vec![
+        ShapeCmd::MoveTo(vec2(x - dx, 1.0)),
+        ShapeCmd::LineTo(vec2(x, 1.0 - dy)),
+        ShapeCmd::LineTo(vec2(x + dx, 1.0)),
+    ]
+}
+
 pub fn reg_shape() -> ComponentShape {
     let h_cells = stack_h(2); // 4
 
     // input[0]=data, input[1]=write_enable (left edge); input[2]=async reset
-    // (bottom edge, one cell in from the corner); output[0] centers on the right.
+    // (bottom edge, one cell in from the corner); input[3]=clock (bottom edge,
+    // CLOCK_PIN_COL); output[0] centers on the right.
     let input_anchors = vec![
         PinAnchor::left(pin_row(0)),
         PinAnchor::left(pin_row(1)),
         PinAnchor::bottom(REG_W - 1, h_cells),
+        PinAnchor::bottom(CLOCK_PIN_COL, h_cells),
     ];
 
     // Labels sit level with their pins; the reset "0" sits a fixed pixel inset
@@ -601,7 +749,7 @@ pub fn reg_shape() -> ComponentShape {
         fill_outline: None,
         input_anchors,
         output_anchors: vec![PinAnchor::right(REG_W, h_cells / 2)],
-        extra_strokes: vec![],
+        extra_strokes: vec![clock_wedge(CLOCK_PIN_COL, REG_W, h_cells)],
         output_bubbles: vec![false],
         labels,
         dynamic_label_pos: Vec2::ZERO,
@@ -619,6 +767,7 @@ pub fn counter_shape() -> ComponentShape {
         PinAnchor::left(pin_row(1)), // data -> middle row
         PinAnchor::left(pin_row(0)), // load -> top row
         PinAnchor::left(pin_row(2)), // count -> bottom row
+        PinAnchor::bottom(CLOCK_PIN_COL, h_cells),
     ];
     let output_anchors = vec![
         PinAnchor::right(REG_W, center_row - 1), // Q
@@ -660,7 +809,7 @@ pub fn counter_shape() -> ComponentShape {
         fill_outline: None,
         input_anchors,
         output_anchors,
-        extra_strokes: vec![],
+        extra_strokes: vec![clock_wedge(CLOCK_PIN_COL, REG_W, h_cells)],
         output_bubbles: vec![false, false],
         labels,
         dynamic_label_pos: Vec2::ZERO,
@@ -696,7 +845,7 @@ pub fn subcircuit_shape(n_in: usize, n_out: usize) -> ComponentShape {
 }
 
 // input order: data, load (parallel_load only), shift, then one per stage,
-// then async reset. Rows are contiguous, not a symmetric Pitch stack. Serial
+// then async reset, then clock. Rows are contiguous, not a symmetric Pitch stack. Serial
 // mode has one output (last stage); parallel_load has one output per stage.
 pub fn shift_reg_shape(num_stages: usize, parallel_load: bool) -> ComponentShape {
     let num_stages = num_stages.max(1);
@@ -757,6 +906,7 @@ pub fn shift_reg_shape(num_stages: usize, parallel_load: bool) -> ComponentShape
         pos: vec2((SHIFT_REG_W - 1) as f32 / SHIFT_REG_W as f32, reset_y),
         ..Default::default()
     });
+    input_anchors.push(PinAnchor::bottom(CLOCK_PIN_COL, h_cells));
 
     let output_bubbles = vec![false; output_anchors.len()];
     ComponentShape {
@@ -765,7 +915,7 @@ pub fn shift_reg_shape(num_stages: usize, parallel_load: bool) -> ComponentShape
         fill_outline: None,
         input_anchors,
         output_anchors,
-        extra_strokes: vec![],
+        extra_strokes: vec![clock_wedge(CLOCK_PIN_COL, SHIFT_REG_W, h_cells)],
         output_bubbles,
         labels,
         dynamic_label_pos: Vec2::ZERO,
@@ -803,12 +953,14 @@ fn two_input_flip_flop_shape(
     let center_row = h_cells / 2;
 
     // input[0]/[1] = control inputs (left), input[2] = write-enable (bottom
-    // center), input[3] = async reset (bottom edge, toward the right).
+    // center), input[3] = async reset (bottom edge, toward the right),
+    // input[4] = clock (bottom edge, toward the left).
     let input_anchors = vec![
         PinAnchor::left(pin_row(0)),
         PinAnchor::left(pin_row(1)),
         PinAnchor::bottom(ARITH_CENTER_COL, h_cells),
         PinAnchor::bottom(ARITH_W - 1, h_cells),
+        PinAnchor::bottom(CLOCK_PIN_COL, h_cells),
     ];
 
     const EDGE_LABEL_INSET_PX: f32 = 6.0;
@@ -845,7 +997,7 @@ fn two_input_flip_flop_shape(
         fill_outline: None,
         input_anchors,
         output_anchors: vec![PinAnchor::right(ARITH_W, center_row)],
-        extra_strokes: vec![],
+        extra_strokes: vec![clock_wedge(CLOCK_PIN_COL, ARITH_W, h_cells)],
         output_bubbles: vec![false],
         labels,
         dynamic_label_pos: Vec2::ZERO,
@@ -858,11 +1010,13 @@ fn flip_flop_shape(data_label: &'static str) -> ComponentShape {
     let h_cells = stack_h(2); // 4, matching op2's square proportions
     let center_row = h_cells / 2;
 
-    // input[2] = async reset (bottom edge, toward the right).
+    // input[2] = async reset (bottom edge, toward the right); input[3] =
+    // clock (bottom edge, toward the left).
     let input_anchors = vec![
         PinAnchor::left(center_row),
         PinAnchor::bottom(ARITH_CENTER_COL, h_cells),
         PinAnchor::bottom(ARITH_W - 1, h_cells),
+        PinAnchor::bottom(CLOCK_PIN_COL, h_cells),
     ];
 
     const EDGE_LABEL_INSET_PX: f32 = 6.0;
@@ -894,7 +1048,7 @@ fn flip_flop_shape(data_label: &'static str) -> ComponentShape {
         fill_outline: None,
         input_anchors,
         output_anchors: vec![PinAnchor::right(ARITH_W, center_row)],
-        extra_strokes: vec![],
+        extra_strokes: vec![clock_wedge(CLOCK_PIN_COL, ARITH_W, h_cells)],
         output_bubbles: vec![false],
         labels,
         dynamic_label_pos: Vec2::ZERO,
@@ -1137,6 +1291,7 @@ pub fn ram_shape() -> ComponentShape {
         PinAnchor::left(pin_row(1)),
         PinAnchor::left(pin_row(2)),
         PinAnchor::left(pin_row(3)),
+        PinAnchor::bottom(CLOCK_PIN_COL, h_cells),
     ];
 
     let row_y = |r: u32| r as f32 / h_cells as f32;
@@ -1179,7 +1334,7 @@ pub fn ram_shape() -> ComponentShape {
         fill_outline: None,
         input_anchors,
         output_anchors: vec![PinAnchor::right(RAM_W, center_row)],
-        extra_strokes: vec![],
+        extra_strokes: vec![clock_wedge(CLOCK_PIN_COL, RAM_W, h_cells)],
         output_bubbles: vec![false],
         labels,
         dynamic_label_pos: Vec2::ZERO,
@@ -1354,6 +1509,19 @@ pub fn tunnel_shape(role: TunnelRole) -> ComponentShape {
     }
 }
 
+// Where `pin` of a part with `shape` at `grid_pos` sits, in grid cells: the
+// wiring counterpart of gui::app::comp_pin_pos, which works in pixels.
+pub fn pin_grid_pos(shape: &ComponentShape, grid_pos: GridPos, pin: PinId) -> GridPos {
+    let anchor = match pin {
+        PinId::In(InIdx(i)) => &shape.input_anchors[i as usize],
+        PinId::Out(OutIdx(i)) => &shape.output_anchors[i as usize],
+    };
+    GridPos {
+        x: grid_pos.x + anchor.cell.x as i32,
+        y: grid_pos.y + anchor.cell.y as i32,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1467,6 +1635,9 @@ mod tests {
     fn all_component_pins_land_on_grid() {
         assert_shape_on_grid("input", &input_shape());
         assert_shape_on_grid("output", &output_shape());
+        assert_shape_on_grid("pull up", &pull_resistor_shape(PullDirection::Up));
+        assert_shape_on_grid("pull down", &pull_resistor_shape(PullDirection::Down));
+        assert_shape_on_grid("clock source", &clock_source_shape());
 
         for op in [
             GateOp::And,
@@ -1482,6 +1653,8 @@ mod tests {
             }
         }
 
+        assert_shape_on_grid("tri_state", &tri_state_shape());
+
         for sel in 0..=3u8 {
             assert_shape_on_grid(&format!("mux sel={sel}"), &mux_shape(sel));
             assert_shape_on_grid(&format!("demux sel={sel}"), &demux_shape(sel));
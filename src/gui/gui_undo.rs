@@ -43,11 +43,13 @@ pub enum GuiUndoAction {
     RemoveTunnel {
         key: PlacedTunnelKey,
     },
-    // Restores the pre-reconfigure (CompKey, ComponentSpec) pair.
+    // Restores the pre-reconfigure (CompKey, ComponentSpec) pair, and the
+    // delay set alongside it.
     SwapComponentSpec {
         key: PlacedCompKey,
         comp_key: CompKey,
         spec: ComponentSpec,
+        delay: Option<u32>,
     },
     SetTunnelLabel {
         key: PlacedTunnelKey,
@@ -140,15 +142,18 @@ impl Document {
                 key,
                 comp_key,
                 spec,
+                delay,
             } => {
                 let pc = self.components.get_mut(&key).unwrap();
                 let prev_comp_key = pc.key;
                 let prev_spec = std::mem::replace(&mut pc.spec, spec);
+                let prev_delay = std::mem::replace(&mut pc.delay, delay);
                 pc.key = comp_key;
                 GuiUndoAction::SwapComponentSpec {
                     key,
                     comp_key: prev_comp_key,
                     spec: prev_spec,
+                    delay: prev_delay,
                 }
             }
             GuiUndoAction::SetTunnelLabel { key, label } => {
@@ -7,16 +7,23 @@
 //! `&mut Circuit` + settle and stay on the app). ROM and RAM share one hex-dump
 //! layout, differing only in which spec variant supplies the dimensions/words -
 //! captured by `MemKind`.
+//!
+//! Each window also reads and writes its memory as an image file (see
+//! `memory_image.rs`); it only picks the format and asks, returning a
+//! `MemFile` for the app to carry out through platform IO.
 
 use std::collections::HashMap;
 
 use crate::gui::app::PlacedCompKey;
 use crate::gui::placed_component::PlacedComponent;
+use crate::gui::utils::{wide_hex_edit, DRAG_VALUE_MAX_WIDTH};
+use crate::memory_image::{Endian, Format, WordLayout};
 use crate::sim::component::ComponentSpec;
+use crate::sim::value::Value;
 
 const WORDS_PER_ROW: usize = 8;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MemKind {
     Rom,
     Ram,
@@ -32,7 +39,7 @@ impl MemKind {
 
     /// `None` if `spec` isn't this kind: the component was deleted or reconfigured, so the
     /// window should close.
-    fn dims(self, spec: &ComponentSpec) -> Option<(u8, usize)> {
+    pub(crate) fn dims(self, spec: &ComponentSpec) -> Option<(u8, usize)> {
         match (self, spec) {
             (MemKind::Rom, ComponentSpec::Rom(r)) => Some((r.data_width, r.len())),
             (MemKind::Ram, ComponentSpec::Ram(r)) => Some((r.data_width, r.len())),
@@ -40,26 +47,58 @@ impl MemKind {
         }
     }
 
-    fn word(self, spec: &ComponentSpec, i: usize) -> u32 {
+    fn word(self, spec: &ComponentSpec, i: usize) -> u64 {
         match (self, spec) {
             (MemKind::Rom, ComponentSpec::Rom(r)) => r.word(i),
             (MemKind::Ram, ComponentSpec::Ram(r)) => r.word(i),
             _ => 0,
         }
     }
+
+    /// The data width and every word, or `None` as for `dims`.
+    pub(crate) fn contents(self, spec: &ComponentSpec) -> Option<(u8, Vec<u64>)> {
+        let (data_width, len) = self.dims(spec)?;
+        Some((data_width, (0..len).map(|i| self.word(spec, i)).collect()))
+    }
 }
 
 pub struct MemEdit {
     pub pc: PlacedCompKey,
     pub kind: MemKind,
     pub index: usize,
-    pub value: u32,
+    pub value: u64,
+}
+
+/// An image file a window asked to read into, or write out of, its memory.
+pub enum MemFile {
+    Import(PlacedCompKey, MemKind),
+    Export(PlacedCompKey, MemKind),
+}
+
+// A window's image file settings. Word size and byte order only matter to
+// the byte-oriented formats; `bytes` of None fits the word width.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct ImageOptions {
+    pub(crate) format: Format,
+    pub(crate) bytes: Option<u8>,
+    pub(crate) endian: Endian,
+}
+
+impl ImageOptions {
+    pub(crate) fn layout(self, data_width: u8) -> WordLayout {
+        WordLayout {
+            bytes: self.bytes.unwrap_or(WordLayout::fitting(data_width).bytes),
+            endian: self.endian,
+        }
+    }
 }
 
 #[derive(Default)]
 pub struct MemoryEditor {
     pub(crate) rom_open: Option<PlacedCompKey>,
     pub(crate) ram_open: Option<PlacedCompKey>,
+    rom_image: ImageOptions,
+    ram_image: ImageOptions,
 }
 
 impl MemoryEditor {
@@ -70,6 +109,13 @@ impl MemoryEditor {
         }
     }
 
+    pub(crate) fn image_options(&self, kind: MemKind) -> ImageOptions {
+        match kind {
+            MemKind::Rom => self.rom_image,
+            MemKind::Ram => self.ram_image,
+        }
+    }
+
     /// Closes a window whose component is gone or no longer the right memory kind.
     /// `value_locked` dims the fields (but keeps them visible) while a clock run plays.
     pub(crate) fn show(
@@ -77,59 +123,93 @@ impl MemoryEditor {
         ctx: &egui::Context,
         components: &HashMap<PlacedCompKey, PlacedComponent>,
         value_locked: bool,
-    ) -> Vec<MemEdit> {
+    ) -> (Vec<MemEdit>, Option<MemFile>) {
         let mut edits = Vec::new();
+        let mut file = None;
         if let Some(pc) = self.rom_open {
-            if !show_window(ctx, pc, MemKind::Rom, components, value_locked, &mut edits) {
+            let options = &mut self.rom_image;
+            let (open, asked) = show_window(
+                ctx,
+                pc,
+                MemKind::Rom,
+                components,
+                value_locked,
+                options,
+                &mut edits,
+            );
+            if !open {
                 self.rom_open = None;
             }
+            file = file.or(asked);
         }
         if let Some(pc) = self.ram_open {
-            if !show_window(ctx, pc, MemKind::Ram, components, value_locked, &mut edits) {
+            let options = &mut self.ram_image;
+            let (open, asked) = show_window(
+                ctx,
+                pc,
+                MemKind::Ram,
+                components,
+                value_locked,
+                options,
+                &mut edits,
+            );
+            if !open {
                 self.ram_open = None;
             }
+            file = file.or(asked);
         }
-        edits
+        (edits, file)
     }
 }
 
 // Row list is virtualized (show_rows) so a 2^24-word memory only builds visible rows.
-// Returns whether the window should stay open.
+// Returns whether the window should stay open, and any image file it asked for.
 fn show_window(
     ctx: &egui::Context,
     pc: PlacedCompKey,
     kind: MemKind,
     components: &HashMap<PlacedCompKey, PlacedComponent>,
     value_locked: bool,
+    options: &mut ImageOptions,
     edits: &mut Vec<MemEdit>,
-) -> bool {
+) -> (bool, Option<MemFile>) {
     let dims = match components.get(&pc) {
         Some(c) => kind.dims(&c.spec),
         _ => None,
     };
     let Some((data_width, len)) = dims else {
-        return false;
+        return (false, None);
     };
 
     let word_nibbles = data_width.div_ceil(4) as usize;
     let addr_nibbles = (usize::BITS - (len.max(1) - 1).leading_zeros())
         .div_ceil(4)
         .max(1) as usize;
-    let mask = if data_width >= 32 {
-        u32::MAX
-    } else {
-        (1u32 << data_width) - 1
-    };
+    let mask = Value::mask(data_width);
     let total_rows = len.div_ceil(WORDS_PER_ROW);
 
     // A window opened during Stopped/Paused can survive into Play, so gate (not hide) the fields.
     let values_enabled = !value_locked;
     let mut open = true;
+    let mut file = None;
     egui::Window::new(kind.title())
         .open(&mut open)
         .default_size([440.0, 480.0])
         .resizable(true)
         .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                image_options_ui(ui, kind, options, data_width);
+                if ui
+                    .add_enabled(values_enabled, egui::Button::new("Import…"))
+                    .clicked()
+                {
+                    file = Some(MemFile::Import(pc, kind));
+                }
+                if ui.button("Export…").clicked() {
+                    file = Some(MemFile::Export(pc, kind));
+                }
+            });
+            ui.separator();
             // Forces monospace so digit widths don't drift columns out of alignment across rows.
             ui.style_mut().drag_value_text_style = egui::TextStyle::Monospace;
             let row_height = ui.spacing().interact_size.y;
@@ -147,13 +227,18 @@ fn show_window(
                                         break;
                                     }
                                     let mut val = kind.word(&components[&pc].spec, i);
-                                    let resp =
+                                    let changed = if data_width <= DRAG_VALUE_MAX_WIDTH {
                                         ui.add(
                                             egui::DragValue::new(&mut val)
                                                 .range(0..=mask)
                                                 .hexadecimal(word_nibbles, false, true),
-                                        );
-                                    if resp.changed() {
+                                        )
+                                        .changed()
+                                    } else {
+                                        let id = ui.make_persistent_id(("mem_word", kind, i));
+                                        wide_hex_edit(ui, id, &mut val, mask)
+                                    };
+                                    if changed {
                                         edits.push(MemEdit {
                                             pc,
                                             kind,
@@ -168,5 +253,46 @@ fn show_window(
             });
         });
 
-    open
+    (open, file)
+}
+
+// The format picker, plus word size and byte order for a byte-oriented one.
+fn image_options_ui(ui: &mut egui::Ui, kind: MemKind, options: &mut ImageOptions, data_width: u8) {
+    egui::ComboBox::from_id_salt(("mem_format", kind))
+        .selected_text(options.format.label())
+        .show_ui(ui, |ui| {
+            for format in Format::ALL {
+                ui.selectable_value(&mut options.format, format, format.label());
+            }
+        });
+    if !options.format.is_bytes() {
+        return;
+    }
+    let fit = WordLayout::fitting(data_width).bytes;
+    // The choice is kept per memory kind, so it can come from a narrower one.
+    if options.bytes.is_some_and(|n| n < fit) {
+        options.bytes = None;
+    }
+    let bytes_label = |bytes: Option<u8>| match bytes {
+        None => format!("{fit} B (fit)"),
+        Some(n) => format!("{n} B"),
+    };
+    egui::ComboBox::from_id_salt(("mem_bytes", kind))
+        .selected_text(bytes_label(options.bytes))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut options.bytes, None, bytes_label(None));
+            // Anything narrower would cut off the high bits on export.
+            for n in fit..=8 {
+                ui.selectable_value(&mut options.bytes, Some(n), bytes_label(Some(n)));
+            }
+        });
+    egui::ComboBox::from_id_salt(("mem_endian", kind))
+        .selected_text(match options.endian {
+            Endian::Little => "Little-endian",
+            Endian::Big => "Big-endian",
+        })
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut options.endian, Endian::Little, "Little-endian");
+            ui.selectable_value(&mut options.endian, Endian::Big, "Big-endian");
+        });
 }
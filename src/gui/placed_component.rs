@@ -2,7 +2,7 @@ use egui::Vec2;
 
 use crate::gui::geometry::*;
 use crate::gui::shape::ComponentShape;
-use crate::sim::component::{CompKey, ComponentSpec, FanDirection, GateOp};
+use crate::sim::component::{CompKey, ComponentSpec, FanDirection, GateOp, PullDirection};
 
 // ── PlacedComponent ───────────────────────────────────────────────────────────
 
@@ -14,6 +14,9 @@ pub struct PlacedComponent {
     // Cached `spec.shape()`: drawing/hit-testing reads it many times per frame.
     // Stays in lockstep with `spec` by only being built here, in `new`.
     pub shape: ComponentShape,
+    // The timed-mode delay set in the properties panel, saved with the record;
+    // None keeps the part's default (see Component::with_saved_delay).
+    pub delay: Option<u32>,
 }
 
 impl PlacedComponent {
@@ -24,8 +27,19 @@ impl PlacedComponent {
             spec,
             grid_pos,
             shape,
+            delay: None,
         }
     }
+
+    // How a report names it for the user to find: its kind, or a subcircuit's
+    // document name, and where it sits.
+    pub fn describe(&self) -> String {
+        let kind = match &self.spec {
+            ComponentSpec::Subcircuit { name, .. } => name.as_str(),
+            spec => spec.label(),
+        };
+        format!("{kind} at ({}, {})", self.grid_pos.x, self.grid_pos.y)
+    }
 }
 
 // ── GUI-only visual concerns for ComponentSpec ────────────────────────────────
@@ -34,10 +48,13 @@ impl ComponentSpec {
     // Matches shape().size without building the full ComponentShape; used every frame.
     pub fn size(&self) -> Vec2 {
         match self {
-            Self::Input(_) | Self::Output => io_size(),
+            Self::Input(_) | Self::Output(_) => io_size(),
             Self::Constant(_) => constant_size(),
+            Self::PullResistor(_) => pull_resistor_size(),
+            Self::ClockSource(_) => clock_source_size(),
             Self::Probe(_) => probe_size(),
             Self::Gate(g) => gate_size(g.op, g.n_inputs),
+            Self::TriState(_) => tri_state_size(),
             Self::Mux(m) => mux_size(m.sel_width),
             Self::Demux(d) => demux_size(d.sel_width),
             Self::Reg(_) => reg_size(),
@@ -68,7 +85,12 @@ impl ComponentSpec {
             Self::Input(_) => "IN",
             // Fallback only; the canvas draws the live value dynamically (see draw_component).
             Self::Constant(_) => "CONST",
-            Self::Output => "OUT",
+            Self::PullResistor(p) => match p.direction {
+                PullDirection::Down => "PULL-DOWN",
+                PullDirection::Up => "PULL-UP",
+            },
+            Self::ClockSource(_) => "CLK",
+            Self::Output(_) => "OUT",
             // Fallback only; the canvas draws the probe's name dynamically.
             Self::Probe(_) => "PROBE",
             Self::Gate(g) => match g.op {
@@ -80,6 +102,7 @@ impl ComponentSpec {
                 GateOp::Xnor => "XNOR",
                 GateOp::Not => "NOT",
             },
+            Self::TriState(_) => "TRI",
             Self::Mux(_) => "MUX",
             Self::Demux(_) => "DEMUX",
             Self::Reg(_) => "REG",
@@ -111,9 +134,12 @@ impl ComponentSpec {
         match self {
             Self::Input(_) => input_shape(),
             Self::Constant(_) => constant_shape(),
-            Self::Output => output_shape(),
+            Self::PullResistor(p) => pull_resistor_shape(p.direction),
+            Self::ClockSource(_) => clock_source_shape(),
+            Self::Output(_) => output_shape(),
             Self::Probe(_) => probe_shape(),
             Self::Gate(g) => gate_shape(g.op, g.n_inputs),
+            Self::TriState(_) => tri_state_shape(),
             Self::Mux(m) => mux_shape(m.sel_width),
             Self::Demux(d) => demux_shape(d.sel_width),
             Self::Reg(_) => reg_shape(),
@@ -7,14 +7,17 @@
 use crate::gui::app::{PlacedCompKey, PlacedTunnelKey, Selected, Selection};
 use crate::gui::document::{DocId, Document};
 use crate::gui::memory_editor::MemKind;
+use crate::gui::utils::{wide_hex_edit, DRAG_VALUE_MAX_WIDTH};
 use crate::sim::circuit::TunnelRole;
 use crate::sim::component::*;
-use crate::sim::value::Value;
+use crate::sim::value::{format_value, Radix, Value};
 
 /// A user intent from the properties panel. The panel only describes what to do; the caller
 /// applies it via `OsmilogApp::apply_prop_gui_action`.
 pub(crate) enum PropGuiAction {
     Reconfigure(PlacedCompKey, ComponentSpec),
+    /// Sets or clears (None) a component's timed-mode delay. Undoable.
+    SetDelay(PlacedCompKey, Option<u32>),
     OpenMemory(PlacedCompKey, MemKind),
     OpenCircuit(DocId),
     CreateCircuit,
@@ -123,17 +126,51 @@ fn labeled_drag<Num: egui::emath::Numeric>(
     changed
 }
 
-// Shared "bits" widget: a checkbox when width == 1, else a DragValue clamped to the width.
-fn bits_widget(ui: &mut egui::Ui, bits: &mut u32, width: u8) -> bool {
+// Shared "Clock edge: [Rising] [Falling]" selector for every sequential part.
+fn clock_edge_widget(ui: &mut egui::Ui, edge: &mut ClockEdge) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Clock edge:");
+        changed |= ui
+            .selectable_value(edge, ClockEdge::Rising, "Rising")
+            .changed();
+        changed |= ui
+            .selectable_value(edge, ClockEdge::Falling, "Falling")
+            .changed();
+    });
+    changed
+}
+
+// Shared "Label: [text]" field for the Input/Output names test vectors key on.
+fn label_edit(ui: &mut egui::Ui, label: &mut String) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Label:");
+        changed = ui.text_edit_singleline(label).changed();
+    });
+    changed
+}
+
+// Shared "bits" widget: a checkbox when width == 1, a DragValue clamped to the
+// width, or a hex field once the width outgrows what a DragValue holds exactly.
+fn bits_widget(ui: &mut egui::Ui, bits: &mut u64, width: u8) -> bool {
     if width == 1 {
         let mut high = *bits != 0;
         if ui.checkbox(&mut high, "Toggle").clicked() {
-            *bits = high as u32;
+            *bits = high as u64;
             return true;
         }
         false
-    } else {
+    } else if width <= DRAG_VALUE_MAX_WIDTH {
         labeled_drag(ui, "Bits:", bits, 0..=Value::mask(width))
+    } else {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Bits: 0x");
+            let id = ui.make_persistent_id("bits_widget");
+            changed = wide_hex_edit(ui, id, bits, Value::mask(width));
+        });
+        changed
     }
 }
 
@@ -160,13 +197,18 @@ pub(crate) fn show_component_properties(
         Value::Fixed { bits, width } => format!("0x{:X} ({}b)", bits, width),
         Value::Floating => "Floating".to_string(),
         Value::Invalid => "Invalid (width mismatch)".to_string(),
+        Value::Partial { width, .. } => {
+            format!("{} ({}b)", format_value(v, Radix::Binary), width)
+        }
     };
 
     match &doc.components[&key].spec {
         ComponentSpec::Input(Input {
             mut bits,
             mut width,
+            label,
         }) => {
+            let mut label = label.clone();
             let mut changed = false;
             ui.label(format!("Value: 0x{:X}", bits));
             // `bits` is the live value: editable while Paused.
@@ -175,13 +217,14 @@ pub(crate) fn show_component_properties(
             });
             // `width` is structural: locked for the whole run session.
             ui.add_enabled_ui(structural_ok, |ui| {
-                changed |= labeled_drag(ui, "Width:", &mut width, 1..=32);
+                changed |= labeled_drag(ui, "Width:", &mut width, 1..=Value::MAX_WIDTH);
+                changed |= label_edit(ui, &mut label);
             });
             if changed {
                 bits &= Value::mask(width); // In case width was changed below max `bits` value
                 edit = Some(PropGuiAction::Reconfigure(
                     key,
-                    ComponentSpec::Input(Input { bits, width }),
+                    ComponentSpec::Input(Input { bits, width, label }),
                 ));
             }
         }
@@ -193,7 +236,7 @@ pub(crate) fn show_component_properties(
             ui.label(format!("Value: 0x{:X}", bits));
             ui.add_enabled_ui(structural_ok, |ui| {
                 changed |= bits_widget(ui, &mut bits, width);
-                changed |= labeled_drag(ui, "Width:", &mut width, 1..=32);
+                changed |= labeled_drag(ui, "Width:", &mut width, 1..=Value::MAX_WIDTH);
             });
             if changed {
                 bits &= Value::mask(width); // In case width was changed below max `bits` value
@@ -203,9 +246,64 @@ pub(crate) fn show_component_properties(
                 ));
             }
         }
-        ComponentSpec::Output => {
+        ComponentSpec::PullResistor(PullResistor {
+            mut direction,
+            mut width,
+        }) => {
+            let mut changed = false;
+            ui.add_enabled_ui(structural_ok, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Pull:");
+                    changed |= ui
+                        .selectable_value(&mut direction, PullDirection::Up, "Up")
+                        .changed();
+                    changed |= ui
+                        .selectable_value(&mut direction, PullDirection::Down, "Down")
+                        .changed();
+                });
+                changed |= labeled_drag(ui, "Width:", &mut width, 1..=Value::MAX_WIDTH);
+            });
+            if changed {
+                edit = Some(PropGuiAction::Reconfigure(
+                    key,
+                    ComponentSpec::PullResistor(PullResistor { direction, width }),
+                ));
+            }
+        }
+        ComponentSpec::ClockSource(ClockSourceConf {
+            mut high_ticks,
+            mut low_ticks,
+        }) => {
+            let mut changed = false;
+            ui.add_enabled_ui(structural_ok, |ui| {
+                changed |= labeled_drag(ui, "High ticks:", &mut high_ticks, 1..=u32::MAX);
+                changed |= labeled_drag(ui, "Low ticks:", &mut low_ticks, 1..=u32::MAX);
+            });
+            if changed {
+                edit = Some(PropGuiAction::Reconfigure(
+                    key,
+                    ComponentSpec::ClockSource(ClockSourceConf {
+                        high_ticks,
+                        low_ticks,
+                    }),
+                ));
+            }
+
+            let cur = doc.circuit.components[&comp_key].pins.out_cache[0];
+            ui.label(format!("Value: {}", fmt_val(cur)));
+        }
+        ComponentSpec::Output(Output { label }) => {
             let val = doc.circuit.read_output(comp_key);
             ui.label(format!("Value: {}", fmt_val(val)));
+            let mut label = label.clone();
+            ui.add_enabled_ui(structural_ok, |ui| {
+                if label_edit(ui, &mut label) {
+                    edit = Some(PropGuiAction::Reconfigure(
+                        key,
+                        ComponentSpec::Output(Output { label }),
+                    ));
+                }
+            });
         }
         ComponentSpec::Probe(Probe { name }) => {
             let val = doc.circuit.read_output(comp_key);
@@ -234,7 +332,7 @@ pub(crate) fn show_component_properties(
                 if op != GateOp::Not {
                     changed |= labeled_drag(ui, "Inputs:", &mut n_inputs, 2..=8);
                 }
-                changed |= labeled_drag(ui, "Width:", &mut width, 1..=32);
+                changed |= labeled_drag(ui, "Width:", &mut width, 1..=Value::MAX_WIDTH);
             });
             if changed {
                 edit = Some(PropGuiAction::Reconfigure(
@@ -247,13 +345,25 @@ pub(crate) fn show_component_properties(
                 ));
             }
         }
+        ComponentSpec::TriState(TriState { mut data_width }) => {
+            let mut changed = false;
+            ui.add_enabled_ui(structural_ok, |ui| {
+                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=Value::MAX_WIDTH);
+            });
+            if changed {
+                edit = Some(PropGuiAction::Reconfigure(
+                    key,
+                    ComponentSpec::TriState(TriState { data_width }),
+                ));
+            }
+        }
         ComponentSpec::Mux(Mux {
             mut data_width,
             mut sel_width,
         }) => {
             let mut changed = false;
             ui.add_enabled_ui(structural_ok, |ui| {
-                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=32);
+                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=Value::MAX_WIDTH);
                 changed |= labeled_drag(ui, "Sel width:", &mut sel_width, 1..=4);
             });
             if changed {
@@ -272,7 +382,7 @@ pub(crate) fn show_component_properties(
         }) => {
             let mut changed = false;
             ui.add_enabled_ui(structural_ok, |ui| {
-                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=32);
+                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=Value::MAX_WIDTH);
                 changed |= labeled_drag(ui, "Sel width:", &mut sel_width, 1..=4);
             });
             if changed {
@@ -285,15 +395,22 @@ pub(crate) fn show_component_properties(
                 ));
             }
         }
-        ComponentSpec::Reg(RegConf { mut data_width }) => {
+        ComponentSpec::Reg(RegConf {
+            mut data_width,
+            mut clock_edge,
+        }) => {
             let mut changed = false;
             ui.add_enabled_ui(structural_ok, |ui| {
-                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=32);
+                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=Value::MAX_WIDTH);
+                changed |= clock_edge_widget(ui, &mut clock_edge);
             });
             if changed {
                 edit = Some(PropGuiAction::Reconfigure(
                     key,
-                    ComponentSpec::Reg(RegConf { data_width }),
+                    ComponentSpec::Reg(RegConf {
+                        data_width,
+                        clock_edge,
+                    }),
                 ));
             }
 
@@ -304,14 +421,16 @@ pub(crate) fn show_component_properties(
             mut data_width,
             mut num_stages,
             mut parallel_load,
+            mut clock_edge,
         }) => {
             let mut changed = false;
             ui.add_enabled_ui(structural_ok, |ui| {
-                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=32);
+                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=Value::MAX_WIDTH);
                 changed |= labeled_drag(ui, "Stages:", &mut num_stages, 1..=16);
                 ui.horizontal(|ui| {
                     changed |= ui.checkbox(&mut parallel_load, "Parallel load").changed();
                 });
+                changed |= clock_edge_widget(ui, &mut clock_edge);
             });
             if changed {
                 edit = Some(PropGuiAction::Reconfigure(
@@ -320,6 +439,7 @@ pub(crate) fn show_component_properties(
                         data_width,
                         num_stages,
                         parallel_load,
+                        clock_edge,
                     }),
                 ));
             }
@@ -337,10 +457,11 @@ pub(crate) fn show_component_properties(
             mut data_width,
             mut max_value,
             mut overflow_action,
+            mut clock_edge,
         }) => {
             let mut changed = false;
             ui.add_enabled_ui(structural_ok, |ui| {
-                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=32);
+                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=Value::MAX_WIDTH);
                 changed |= labeled_drag(
                     ui,
                     "Max value:",
@@ -368,6 +489,7 @@ pub(crate) fn show_component_properties(
                             }
                         });
                 });
+                changed |= clock_edge_widget(ui, &mut clock_edge);
             });
             if changed {
                 max_value = max_value.min(Value::mask(data_width)); // Re-cap in case data_width shrank below max_value
@@ -377,6 +499,7 @@ pub(crate) fn show_component_properties(
                         data_width,
                         max_value,
                         overflow_action,
+                        clock_edge,
                     }),
                 ));
             }
@@ -386,10 +509,30 @@ pub(crate) fn show_component_properties(
             ui.label(format!("Q: {}", fmt_val(q)));
             ui.label(format!("Carry: {}", fmt_val(carry)));
         }
-        ComponentSpec::DFlipFlop(_)
-        | ComponentSpec::TFlipFlop(_)
-        | ComponentSpec::JKFlipFlop(_)
-        | ComponentSpec::SRFlipFlop(_) => {
+        spec @ (ComponentSpec::DFlipFlop(DFlipFlopConf { mut clock_edge })
+        | ComponentSpec::TFlipFlop(TFlipFlopConf { mut clock_edge })
+        | ComponentSpec::JKFlipFlop(JKFlipFlopConf { mut clock_edge })
+        | ComponentSpec::SRFlipFlop(SRFlipFlopConf { mut clock_edge })) => {
+            let mut changed = false;
+            ui.add_enabled_ui(structural_ok, |ui| {
+                changed |= clock_edge_widget(ui, &mut clock_edge);
+            });
+            if changed {
+                let spec = match spec {
+                    ComponentSpec::DFlipFlop(_) => {
+                        ComponentSpec::DFlipFlop(DFlipFlopConf { clock_edge })
+                    }
+                    ComponentSpec::TFlipFlop(_) => {
+                        ComponentSpec::TFlipFlop(TFlipFlopConf { clock_edge })
+                    }
+                    ComponentSpec::JKFlipFlop(_) => {
+                        ComponentSpec::JKFlipFlop(JKFlipFlopConf { clock_edge })
+                    }
+                    _ => ComponentSpec::SRFlipFlop(SRFlipFlopConf { clock_edge }),
+                };
+                edit = Some(PropGuiAction::Reconfigure(key, spec));
+            }
+
             let cur = doc.circuit.components[&comp_key].pins.out_cache[0];
             ui.label(format!("Value: {}", fmt_val(cur)));
         }
@@ -408,7 +551,7 @@ pub(crate) fn show_component_properties(
         ComponentSpec::Adder(Adder { mut data_width }) => {
             let mut changed = false;
             ui.add_enabled_ui(structural_ok, |ui| {
-                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=32);
+                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=Value::MAX_WIDTH);
             });
             if changed {
                 edit = Some(PropGuiAction::Reconfigure(
@@ -420,7 +563,7 @@ pub(crate) fn show_component_properties(
         ComponentSpec::Subtractor(Subtractor { mut data_width }) => {
             let mut changed = false;
             ui.add_enabled_ui(structural_ok, |ui| {
-                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=32);
+                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=Value::MAX_WIDTH);
             });
             if changed {
                 edit = Some(PropGuiAction::Reconfigure(
@@ -432,7 +575,7 @@ pub(crate) fn show_component_properties(
         ComponentSpec::Multiplier(Multiplier { mut data_width }) => {
             let mut changed = false;
             ui.add_enabled_ui(structural_ok, |ui| {
-                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=32);
+                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=Value::MAX_WIDTH);
             });
             if changed {
                 edit = Some(PropGuiAction::Reconfigure(
@@ -444,7 +587,7 @@ pub(crate) fn show_component_properties(
         ComponentSpec::Divider(Divider { mut data_width }) => {
             let mut changed = false;
             ui.add_enabled_ui(structural_ok, |ui| {
-                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=32);
+                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=Value::MAX_WIDTH);
             });
             if changed {
                 edit = Some(PropGuiAction::Reconfigure(
@@ -456,7 +599,7 @@ pub(crate) fn show_component_properties(
         ComponentSpec::Comparator(Comparator { mut data_width }) => {
             let mut changed = false;
             ui.add_enabled_ui(structural_ok, |ui| {
-                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=32);
+                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=Value::MAX_WIDTH);
             });
             if changed {
                 edit = Some(PropGuiAction::Reconfigure(
@@ -475,7 +618,7 @@ pub(crate) fn show_component_properties(
         ) => {
             let mut changed = false;
             ui.add_enabled_ui(structural_ok, |ui| {
-                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=32);
+                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=Value::MAX_WIDTH);
                 changed |= labeled_drag(
                     ui,
                     "Address width:",
@@ -496,18 +639,21 @@ pub(crate) fn show_component_properties(
                 }
             });
         }
-        // Read behavior joins the widths as structural.
+        // Read behavior, clock edge and the power-on image join the widths as
+        // structural.
         ComponentSpec::Ram(
             ram @ Ram {
                 mut data_width,
                 mut address_width,
                 mut read_behavior,
+                mut clock_edge,
+                mut keep_contents,
                 ..
             },
         ) => {
             let mut changed = false;
             ui.add_enabled_ui(structural_ok, |ui| {
-                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=32);
+                changed |= labeled_drag(ui, "Data width:", &mut data_width, 1..=Value::MAX_WIDTH);
                 changed |= labeled_drag(
                     ui,
                     "Address width:",
@@ -527,10 +673,41 @@ pub(crate) fn show_component_properties(
                             }
                         });
                 });
+                changed |= clock_edge_widget(ui, &mut clock_edge);
+                changed |= ui
+                    .checkbox(&mut keep_contents, "Keep contents across Stop")
+                    .changed();
             });
-            if changed {
+            // None leaves the image as it is.
+            let mut initial = None;
+            ui.add_enabled_ui(structural_ok, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Initial contents:");
+                    match ram.initial.len() {
+                        0 => ui.weak("zeros"),
+                        n => ui.label(format!("{n} words")),
+                    };
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Set from current").clicked() {
+                        initial = Some(ram.image(ram.data.borrow().clone()));
+                    }
+                    if ui
+                        .add_enabled(!ram.initial.is_empty(), egui::Button::new("Clear"))
+                        .clicked()
+                    {
+                        initial = Some(Vec::new());
+                    }
+                });
+            });
+            if changed || initial.is_some() {
                 let mut resized = ram.resized(data_width, address_width);
                 resized.read_behavior = read_behavior;
+                resized.clock_edge = clock_edge;
+                resized.keep_contents = keep_contents;
+                if let Some(image) = initial {
+                    resized.initial = image;
+                }
                 edit = Some(PropGuiAction::Reconfigure(key, ComponentSpec::Ram(resized)));
             }
             ui.add_enabled_ui(value_ok, |ui| {
@@ -558,7 +735,7 @@ pub(crate) fn show_component_properties(
                 });
                 changed |= direction != before_dir;
 
-                changed |= labeled_drag(ui, "Data width:", &mut width, 1..=32);
+                changed |= labeled_drag(ui, "Data width:", &mut width, 1..=Value::MAX_WIDTH);
                 let mut arms = arm_bits.len() as u8;
                 changed |= labeled_drag(ui, "Arms:", &mut arms, 1..=16);
 
@@ -637,5 +814,29 @@ pub(crate) fn show_component_properties(
         }
     }
 
+    // Timed-mode delay (see Circuit::set_timed). A subcircuit's comes from
+    // the parts inside it, and a part with no outputs has nothing to delay.
+    let spec = &doc.components[&key].spec;
+    if !matches!(spec, ComponentSpec::Subcircuit { .. }) && spec.n_outputs() > 0 {
+        let saved = doc.components[&key].delay;
+        let mut delay = doc.circuit.components[&comp_key].delay;
+        ui.add_enabled_ui(structural_ok, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Delay:");
+                if ui
+                    .add(egui::DragValue::new(&mut delay).range(0..=10_000))
+                    .changed()
+                {
+                    edit = Some(PropGuiAction::SetDelay(key, Some(delay)));
+                }
+                if saved.is_none() {
+                    ui.weak("(default)");
+                } else if ui.button("Default").clicked() {
+                    edit = Some(PropGuiAction::SetDelay(key, None));
+                }
+            });
+        });
+    }
+
     edit
 }
@@ -5,8 +5,13 @@
 //! sample per clock tick. Both live on `Document` as runtime-only state - they
 //! are never saved. History keys on `PlacedCompKey` (GUI-stable), so a probe's
 //! waveform survives a rename.
+//!
+//! While a run is Paused, a scrubber picks any tick it recorded (see
+//! `sim::rewind`) and the whole canvas jumps to that tick's state; a cursor
+//! marks it on every waveform and the value column reads there.
 
 use std::collections::HashMap;
+use std::ops::RangeInclusive;
 
 use egui::{Align2, FontId, Rect, Sense, Stroke, Vec2};
 
@@ -15,57 +20,8 @@ use crate::gui::canvas_draw::value_stroke;
 use crate::gui::placed_component::PlacedComponent;
 use crate::gui::theme::Theme;
 use crate::sim::component::ComponentSpec;
-use crate::sim::value::Value;
-
-/// The number base a probe's value is shown in.
-#[derive(Clone, Copy, PartialEq, Eq, Default)]
-pub enum Radix {
-    #[default]
-    Hex,
-    Binary,
-    Unsigned,
-    Signed,
-}
-
-impl Radix {
-    fn label(self) -> &'static str {
-        match self {
-            Radix::Hex => "hex",
-            Radix::Binary => "bin",
-            Radix::Unsigned => "uint",
-            Radix::Signed => "int",
-        }
-    }
-
-    const ALL: [Radix; 4] = [Radix::Hex, Radix::Binary, Radix::Unsigned, Radix::Signed];
-}
-
-/// A value's text in the given radix. `Floating` shows as `Z`, `Invalid` as `X`.
-pub fn format_value(v: Value, radix: Radix) -> String {
-    match v {
-        Value::Floating => "Z".to_string(),
-        Value::Invalid => "X".to_string(),
-        Value::Fixed { bits, width } => match radix {
-            Radix::Hex => format!("0x{:X}", bits),
-            Radix::Binary => format!("{:0width$b}", bits, width = width as usize),
-            Radix::Unsigned => bits.to_string(),
-            Radix::Signed => sign_extend(bits, width).to_string(),
-        },
-    }
-}
-
-// Reads `bits` as a two's-complement value of `width` bits.
-fn sign_extend(bits: u32, width: u8) -> i64 {
-    let w = width as u32;
-    if w == 0 || w >= 32 {
-        return bits as i32 as i64;
-    }
-    if bits & (1 << (w - 1)) != 0 {
-        bits as i64 - (1i64 << w)
-    } else {
-        bits as i64
-    }
-}
+use crate::sim::value::{format_value, Radix, Value};
+use crate::vcd;
 
 /// One probe's recorded waveform, aligned to a shared tick axis. Runtime-only.
 #[derive(Default)]
@@ -75,6 +31,24 @@ pub struct SignalLog {
 }
 
 impl SignalLog {
+    /// The recorded traces as a VCD document (see `vcd::write`), in the
+    /// viewer's probe order. Tick `k` is written at time `k * tick_period`.
+    pub fn to_vcd(
+        &self,
+        components: &HashMap<PlacedCompKey, PlacedComponent>,
+        scope: &str,
+        tick_period: u64,
+    ) -> String {
+        let traces: Vec<vcd::Trace> = probes_in_order(components)
+            .into_iter()
+            .map(|(key, name)| vcd::Trace {
+                name,
+                samples: self.traces.get(&key).map_or(&[], Vec::as_slice),
+            })
+            .collect();
+        vcd::write(scope, &traces, tick_period)
+    }
+
     pub fn clear(&mut self) {
         self.traces.clear();
         self.ticks = 0;
@@ -92,6 +66,45 @@ impl SignalLog {
         }
         self.ticks += 1;
     }
+
+    /// Drops every sample after the first `ticks`.
+    pub fn truncate(&mut self, ticks: usize) {
+        for trace in self.traces.values_mut() {
+            trace.truncate(ticks);
+        }
+        self.ticks = self.ticks.min(ticks);
+    }
+}
+
+/// The ticks a Paused run can be put back at, and the one it's at.
+pub struct Scrubber {
+    pub ticks: RangeInclusive<usize>,
+    pub at: usize,
+}
+
+/// What the viewer's buttons ask of the caller, which owns the log (a sibling
+/// field) and the I/O backend.
+pub enum SignalViewerAction {
+    Clear,
+    ExportVcd,
+    // Put the circuit at this tick.
+    Seek(usize),
+}
+
+// Every placed Probe with its name. Stable order: by name, then by key so
+// equal names never reshuffle.
+fn probes_in_order(
+    components: &HashMap<PlacedCompKey, PlacedComponent>,
+) -> Vec<(PlacedCompKey, &str)> {
+    let mut probes: Vec<(PlacedCompKey, &str)> = components
+        .iter()
+        .filter_map(|(&k, pc)| match &pc.spec {
+            ComponentSpec::Probe(p) => Some((k, p.name.as_str())),
+            _ => None,
+        })
+        .collect();
+    probes.sort_by(|a, b| a.1.cmp(b.1).then(a.0 .0.cmp(&b.0 .0)));
+    probes
 }
 
 /// The viewer's own UI state: the panel-open flag and each probe's radix.
@@ -106,47 +119,58 @@ const CONTROL_W: f32 = 220.0;
 const ROW_H: f32 = 24.0;
 
 impl SignalViewer {
-    /// Draws the table and waveforms. Returns `true` if the user pressed Clear
-    /// (the caller clears the log, since the log is a sibling field).
+    /// Draws the table and waveforms. Returns the button the user pressed, if
+    /// any.
     pub fn show(
         &mut self,
         components: &HashMap<PlacedCompKey, PlacedComponent>,
         log: &SignalLog,
+        scrubber: Option<Scrubber>,
         theme: Theme,
         ui: &mut egui::Ui,
-    ) -> bool {
-        let mut clear = false;
+    ) -> Option<SignalViewerAction> {
+        let mut action = None;
         ui.horizontal(|ui| {
             ui.strong("Signal Viewer");
             if ui.button("Clear").clicked() {
-                clear = true;
+                action = Some(SignalViewerAction::Clear);
+            }
+            if ui
+                .add_enabled(log.ticks > 0, egui::Button::new("Export VCD"))
+                .clicked()
+            {
+                action = Some(SignalViewerAction::ExportVcd);
             }
             ui.weak(format!("{} ticks", log.ticks));
+            if let Some(scrubber) = &scrubber {
+                let mut at = scrubber.at;
+                if ui
+                    .add(egui::Slider::new(&mut at, scrubber.ticks.clone()).text("tick"))
+                    .changed()
+                {
+                    action = Some(SignalViewerAction::Seek(at));
+                }
+            }
         });
         ui.separator();
 
-        // Stable order: by name, then by key so equal names never reshuffle.
-        let mut probes: Vec<(PlacedCompKey, &str)> = components
-            .iter()
-            .filter_map(|(&k, pc)| match &pc.spec {
-                ComponentSpec::Probe(p) => Some((k, p.name.as_str())),
-                _ => None,
-            })
-            .collect();
-        probes.sort_by(|a, b| a.1.cmp(b.1).then(a.0 .0.cmp(&b.0 .0)));
-
+        let probes = probes_in_order(components);
         if probes.is_empty() {
             ui.weak("Place a Probe on a wire to watch its value here.");
-            return clear;
+            return action;
         }
 
         egui::ScrollArea::vertical().show(ui, |ui| {
             for (key, name) in probes {
                 let radix = *self.radix.get(&key).unwrap_or(&Radix::default());
+                // Sample k - 1 is the value after tick k.
                 let current = log
                     .traces
                     .get(&key)
-                    .and_then(|t| t.last().copied())
+                    .and_then(|t| match &scrubber {
+                        Some(scrubber) => t.get(scrubber.at.checked_sub(1)?).copied(),
+                        None => t.last().copied(),
+                    })
                     .unwrap_or(Value::Floating);
 
                 ui.horizontal(|ui| {
@@ -177,12 +201,15 @@ impl SignalViewer {
                         ui.allocate_exact_size(Vec2::new(avail.x.max(60.0), ROW_H), Sense::hover());
                     if let Some(trace) = log.traces.get(&key) {
                         draw_waveform(ui, rect, trace, radix, theme);
+                        if let Some(scrubber) = &scrubber {
+                            draw_cursor(ui, rect, trace.len(), scrubber.at, theme);
+                        }
                     }
                 });
             }
         });
 
-        clear
+        action
     }
 }
 
@@ -202,7 +229,7 @@ fn draw_waveform(ui: &egui::Ui, rect: Rect, trace: &[Value], radix: Radix, theme
 
     let is_bus = trace
         .iter()
-        .any(|v| matches!(v, Value::Fixed { width, .. } if *width > 1));
+        .any(|v| v.width().is_some_and(|width| width > 1));
 
     let mut prev_level: Option<f32> = None;
     let mut i = 0;
@@ -251,6 +278,20 @@ fn draw_waveform(ui: &egui::Ui, rect: Rect, trace: &[Value], radix: Radix, theme
     }
 }
 
+// A vertical line through the sample after tick `at`, or along the left edge
+// for the state before the first.
+fn draw_cursor(ui: &egui::Ui, rect: Rect, n: usize, at: usize, theme: Theme) {
+    if n == 0 {
+        return;
+    }
+    let dx = rect.width() / n as f32;
+    let x = rect.left() + (at as f32 - 0.5).max(0.0) * dx;
+    ui.painter_at(rect).line_segment(
+        [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+        Stroke::new(1.5, theme.outline_selected),
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -259,18 +300,6 @@ mod tests {
         PlacedCompKey(n)
     }
 
-    #[test]
-    fn test_format_value_radices() {
-        let v = Value::new(0xB, 4);
-        assert_eq!(format_value(v, Radix::Hex), "0xB");
-        assert_eq!(format_value(v, Radix::Binary), "1011");
-        assert_eq!(format_value(v, Radix::Unsigned), "11");
-        // 0b1011 as a signed 4-bit value is -5.
-        assert_eq!(format_value(v, Radix::Signed), "-5");
-        assert_eq!(format_value(Value::Floating, Radix::Hex), "Z");
-        assert_eq!(format_value(Value::Invalid, Radix::Hex), "X");
-    }
-
     #[test]
     fn test_log_records_and_aligns_late_probe() {
         let mut log = SignalLog::default();
@@ -288,8 +317,33 @@ mod tests {
         assert_eq!(log.traces[&b][0], Value::Floating);
         assert_eq!(log.traces[&b][2], Value::new(7, 4));
 
+        log.truncate(1);
+        assert_eq!(log.ticks, 1);
+        assert_eq!(log.traces[&a], vec![Value::new(0, 1)]);
+        assert_eq!(log.traces[&b], vec![Value::Floating]);
+
         log.clear();
         assert_eq!(log.ticks, 0);
         assert!(log.traces.is_empty());
     }
+
+    #[test]
+    fn test_to_vcd_names_probes_in_viewer_order() {
+        use crate::gui::geometry::GridPos;
+        use crate::sim::component::{CompKey, Probe};
+
+        let probe = |name: &str| {
+            let spec = ComponentSpec::Probe(Probe {
+                name: name.to_string(),
+            });
+            PlacedComponent::new(CompKey(0), spec, GridPos::new(0, 0))
+        };
+        let components = HashMap::from([(key(1), probe("b")), (key(2), probe("a"))]);
+        let mut log = SignalLog::default();
+        log.record(&[(key(1), Value::ONE), (key(2), Value::new(5, 4))]);
+
+        let vcd = log.to_vcd(&components, "Main", 1);
+        assert!(vcd.contains("$var wire 4 ! a $end\n$var wire 1 \" b $end\n"));
+        assert!(vcd.contains("$dumpvars\nb0101 !\n1\"\n$end\n"));
+    }
 }
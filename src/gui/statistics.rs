@@ -0,0 +1,124 @@
+//! The Statistics window: a circuit's bill of materials (see `stats.rs`) -
+//! its parts counted through every subcircuit instance, per kind, variant and
+//! width - with an optional transistor and NAND2-equivalent estimate and CSV
+//! export. Opened from the View menu.
+//!
+//! Like the Analyze window, counting reads the saved form of the project
+//! (`OsmilogApp::count_parts`), so the result is a snapshot of the active
+//! circuit as of the last Count click.
+
+use crate::gui::document::DocId;
+use crate::stats::Bom;
+
+/// What the window asks the app to do after a frame.
+pub enum StatisticsAction {
+    Count,
+    Export,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum View {
+    #[default]
+    Parts,
+    Kinds,
+    Widths,
+}
+
+#[derive(Default)]
+pub struct StatisticsWindow {
+    pub open: bool,
+    pub(crate) estimate: bool,
+    // The last count, and which document it's of.
+    pub(crate) bom: Option<(DocId, Bom)>,
+    view: View,
+}
+
+impl StatisticsWindow {
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<StatisticsAction> {
+        let mut action = None;
+        let mut open = self.open;
+        egui::Window::new("Statistics")
+            .open(&mut open)
+            .default_size([420.0, 360.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Count").clicked() {
+                        action = Some(StatisticsAction::Count);
+                    }
+                    ui.checkbox(&mut self.estimate, "Estimate transistors");
+                    if ui
+                        .add_enabled(self.bom.is_some(), egui::Button::new("Export .csv"))
+                        .clicked()
+                    {
+                        action = Some(StatisticsAction::Export);
+                    }
+                });
+                let Some((_, bom)) = &self.bom else {
+                    ui.weak("Counts the active circuit's parts, through its subcircuits.");
+                    return;
+                };
+                let parts: u64 = bom.rows.iter().map(|r| r.count).sum();
+                ui.label(format!("{parts} parts"));
+                if self.estimate {
+                    ui.label(format!(
+                        "≈ {} transistors, {} NAND2 equivalents",
+                        bom.transistors(),
+                        bom.nand2_equivalents()
+                    ));
+                }
+                if !bom.recursive.is_empty() {
+                    ui.weak(format!(
+                        "Not counted inside, as they would contain themselves: {}",
+                        bom.recursive.join(", ")
+                    ));
+                }
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.view, View::Parts, "Parts");
+                    ui.selectable_value(&mut self.view, View::Kinds, "By kind");
+                    ui.selectable_value(&mut self.view, View::Widths, "By width");
+                });
+                ui.separator();
+                let lines: Vec<String> = match self.view {
+                    View::Parts => bom
+                        .rows
+                        .iter()
+                        .map(|row| {
+                            let mut line = format!("{:>6} × {}", row.count, row.part.kind);
+                            if !row.part.variant.is_empty() {
+                                line += &format!(" {}", row.part.variant);
+                            }
+                            if let Some(width) = row.part.width {
+                                line += &format!(", {width}-bit");
+                            }
+                            if self.estimate && row.transistors > 0 {
+                                line += &format!(" (≈ {} T each)", row.transistors);
+                            }
+                            line
+                        })
+                        .collect(),
+                    View::Kinds => bom
+                        .by_kind()
+                        .into_iter()
+                        .map(|(kind, count)| format!("{count:>6} × {kind}"))
+                        .collect(),
+                    View::Widths => bom
+                        .by_width()
+                        .into_iter()
+                        .map(|(width, count)| match width {
+                            Some(width) => format!("{count:>6} × {width}-bit"),
+                            None => format!("{count:>6} × no single width"),
+                        })
+                        .collect(),
+                };
+                egui::ScrollArea::vertical()
+                    .id_salt("statistics")
+                    .show(ui, |ui| {
+                        for line in lines {
+                            ui.monospace(line);
+                        }
+                    });
+            });
+        self.open = open;
+        action
+    }
+}
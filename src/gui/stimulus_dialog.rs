@@ -0,0 +1,118 @@
+//! The Replay Stimulus dialog: opened by File > Replay Stimulus… once platform
+//! IO has read the picked file (see `OsmilogApp::open_stimulus`). Shows which
+//! of the file's signals will drive which labelled Inputs and, for a VCD, how
+//! often to sample it, before `Document::replay_stimulus` runs the replay.
+
+use crate::gui::theme::Theme;
+use crate::sim::test_vector::Port;
+use crate::stimulus::Stimulus;
+
+pub enum StimulusDialogAction {
+    Replay(Stimulus),
+    Close,
+}
+
+pub struct StimulusDialog {
+    file_name: String,
+    text: String,
+    // Time units between samples; VCD only, as a CSV row is already one tick.
+    period: u64,
+    // `text` parsed at the period it holds; redone when the period changes.
+    parsed: Option<(u64, Result<Stimulus, String>)>,
+}
+
+impl StimulusDialog {
+    // `period` is the initial sampling period - the document's own tick
+    // period, so a replayed VCD export lines up tick for tick.
+    pub fn new(file_name: String, text: String, period: u64) -> Self {
+        Self {
+            file_name,
+            text,
+            period,
+            parsed: None,
+        }
+    }
+
+    fn is_vcd(&self) -> bool {
+        self.file_name.to_ascii_lowercase().ends_with(".vcd")
+    }
+
+    fn parse(&mut self) -> &Result<Stimulus, String> {
+        if self.parsed.as_ref().is_none_or(|(p, _)| *p != self.period) {
+            let parsed = if self.is_vcd() {
+                Stimulus::from_vcd(&self.text, self.period)
+            } else {
+                Stimulus::from_csv(&self.text)
+            };
+            self.parsed = Some((self.period, parsed.map_err(|e| e.to_string())));
+        }
+        &self.parsed.as_ref().unwrap().1
+    }
+
+    // `ports` are the active circuit's named components (see
+    // test_vector::ports), to preview the binding against.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        theme: Theme,
+        ports: &[Port],
+    ) -> Option<StimulusDialogAction> {
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new("Replay Stimulus")
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(&self.file_name);
+                if self.is_vcd() {
+                    ui.horizontal(|ui| {
+                        ui.label("Sample every");
+                        ui.add(
+                            egui::DragValue::new(&mut self.period)
+                                .range(1..=1_000_000)
+                                .suffix(" t"),
+                        );
+                    });
+                }
+                ui.separator();
+
+                let ready = match self.parse() {
+                    Err(err) => {
+                        ui.colored_label(theme.error_text, err.as_str());
+                        None
+                    }
+                    Ok(stimulus) => match stimulus.bind(ports) {
+                        Err(err) => {
+                            ui.colored_label(theme.error_text, err.to_string());
+                            None
+                        }
+                        Ok(bindings) => {
+                            ui.weak(format!("{} ticks", stimulus.rows.len()));
+                            for b in &bindings {
+                                ui.label(format!("{} → Input ({} bit)", b.name, b.width));
+                            }
+                            let ignored = stimulus.signals.len() - bindings.len();
+                            if ignored > 0 {
+                                ui.weak(format!("{ignored} signals match no Input"));
+                            }
+                            Some(stimulus)
+                        }
+                    },
+                };
+                ui.horizontal(|ui| {
+                    let replay = ui.add_enabled(ready.is_some(), egui::Button::new("Replay"));
+                    if let Some(stimulus) = ready.filter(|_| replay.clicked()) {
+                        action = Some(StimulusDialogAction::Replay(stimulus.clone()));
+                    }
+                    if ui.button("Cancel").clicked() {
+                        action = Some(StimulusDialogAction::Close);
+                    }
+                });
+            });
+        if !open {
+            action = Some(StimulusDialogAction::Close);
+        }
+        action
+    }
+}
@@ -0,0 +1,399 @@
+//! The Synthesize Circuit dialog (File menu): Boolean expressions or a truth
+//! table in, a new circuit document out, built by `sim::synth` in the chosen
+//! gate style. `layout` places the netlist and routes its wires; the app then
+//! creates the document from it (`OsmilogApp::create_synthesized_document`).
+//!
+//! The layout is a staircase, one column and one horizontal band per logic
+//! level: Inputs top left, each later level right of and below the one it
+//! reads. A signal read by gates runs down a vertical rail just right of its
+//! column, and each reading pin taps the rail with a straight horizontal wire.
+//! Since bands never share rows, a tap only ever crosses other wires where
+//! they have no node, so nothing joins that shouldn't. An Output fed by a gate
+//! nothing else reads sits level with it; the rest, with any constants, line
+//! up in a last band below.
+
+use crate::gui::geometry::{pin_grid_pos, GridPos, GRID_SIZE};
+use crate::gui::theme::Theme;
+use crate::sim::component::{ComponentSpec, Constant, Gate, Input, Output, PinId};
+use crate::sim::synth::{self, Netlist, Signal, Spec, Style};
+
+// Cells between stacked components, and between neighbouring rails.
+const ROW_GAP: i32 = 2;
+const RAIL_PITCH: i32 = 2;
+
+pub enum SynthesizeAction {
+    Create { name: String, netlist: Netlist },
+    Close,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    #[default]
+    Expressions,
+    Table,
+}
+
+pub struct SynthesizeDialog {
+    name: String,
+    source: Source,
+    expressions: String,
+    table: String,
+    style: Style,
+    // The netlist and the text and style it was built from; rebuilt when
+    // they change, as minimizing a wide table takes a moment.
+    built: Option<(Source, String, Style, Result<Netlist, String>)>,
+}
+
+impl SynthesizeDialog {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            source: Source::default(),
+            expressions: "Y = A & !B | C".to_string(),
+            table: "A B | Y\n0 0 | 0\n0 1 | 1\n1 0 | 1\n1 1 | 0".to_string(),
+            style: Style::default(),
+            built: None,
+        }
+    }
+
+    fn text(&self) -> &str {
+        match self.source {
+            Source::Expressions => &self.expressions,
+            Source::Table => &self.table,
+        }
+    }
+
+    fn build(&mut self) -> &Result<Netlist, String> {
+        let stale = self.built.as_ref().is_none_or(|(source, text, style, _)| {
+            *source != self.source || text != self.text() || *style != self.style
+        });
+        if stale {
+            let spec = match self.source {
+                Source::Expressions => Spec::from_expressions(&self.expressions),
+                Source::Table => Spec::from_table(&self.table),
+            };
+            let built = spec
+                .and_then(|spec| synth::synthesize(&spec, self.style))
+                .map_err(|e| e.to_string());
+            self.built = Some((self.source, self.text().to_string(), self.style, built));
+        }
+        &self.built.as_ref().unwrap().3
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, theme: Theme) -> Option<SynthesizeAction> {
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new("Synthesize Circuit")
+            .collapsible(false)
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut self.name);
+                });
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.source, Source::Expressions, "Expressions");
+                    ui.selectable_value(&mut self.source, Source::Table, "Truth table");
+                });
+                let (text, hint) = match self.source {
+                    Source::Expressions => (
+                        &mut self.expressions,
+                        "One output per line: Y = A & !B | C. Also ~ ' + * ^ ( ) 0 1.",
+                    ),
+                    Source::Table => (
+                        &mut self.table,
+                        "Inputs | outputs, then rows of 0, 1 or -. Unlisted rows are 0.",
+                    ),
+                };
+                ui.add(
+                    egui::TextEdit::multiline(text)
+                        .code_editor()
+                        .desired_rows(8)
+                        .desired_width(f32::INFINITY),
+                );
+                ui.weak(hint);
+                ui.horizontal(|ui| {
+                    ui.label("Gates:");
+                    for style in Style::ALL {
+                        ui.selectable_value(&mut self.style, style, style.label());
+                    }
+                });
+                ui.separator();
+
+                let ready = match self.build() {
+                    Err(err) => {
+                        ui.colored_label(theme.error_text, err.as_str());
+                        None
+                    }
+                    Ok(netlist) => {
+                        ui.weak(format!(
+                            "{} inputs, {} outputs, {} gates",
+                            netlist.inputs.len(),
+                            netlist.outputs.len(),
+                            netlist.gates.len()
+                        ));
+                        Some(netlist.clone())
+                    }
+                };
+                ui.horizontal(|ui| {
+                    let create = ui.add_enabled(ready.is_some(), egui::Button::new("Create"));
+                    if let Some(netlist) = ready.filter(|_| create.clicked()) {
+                        action = Some(SynthesizeAction::Create {
+                            name: self.name.clone(),
+                            netlist,
+                        });
+                    }
+                    if ui.button("Cancel").clicked() {
+                        action = Some(SynthesizeAction::Close);
+                    }
+                });
+            });
+        if !open {
+            action = Some(SynthesizeAction::Close);
+        }
+        action
+    }
+}
+
+// ── Layout ────────────────────────────────────────────────────────────────────
+
+// A wire for `Wiring::add_route`; each bound end names a component (an index
+// into `Layout::components`) and its pin.
+pub struct Route {
+    pub points: Vec<GridPos>,
+    pub start: Option<(usize, PinId)>,
+    pub end: Option<(usize, PinId)>,
+}
+
+pub struct Layout {
+    pub components: Vec<(ComponentSpec, GridPos)>,
+    pub routes: Vec<Route>,
+}
+
+// A component's height in whole cells.
+fn height(spec: &ComponentSpec) -> i32 {
+    (spec.size().y / GRID_SIZE).round() as i32
+}
+
+fn pin_at(spec: &ComponentSpec, pos: GridPos, pin: PinId) -> GridPos {
+    pin_grid_pos(&spec.shape(), pos, pin)
+}
+
+// Components are the Inputs, then one per gate, then the Outputs, in netlist
+// order; then a Constant for each constant Output.
+pub fn layout(net: &Netlist) -> Layout {
+    let (n_in, n_gates) = (net.inputs.len(), net.gates.len());
+    let comp_of = |s: Signal| match s {
+        Signal::Input(i) => i,
+        Signal::Gate(g) => n_in + g,
+        Signal::Const(_) => unreachable!("constants only drive Outputs"),
+    };
+
+    // Levels, as late as each gate can go: a gate only Outputs read is on the
+    // last one. Inputs are level 0.
+    let mut asap = vec![0usize; n_gates];
+    for (g, gate) in net.gates.iter().enumerate() {
+        asap[g] = 1 + gate
+            .inputs
+            .iter()
+            .map(|&s| match s {
+                Signal::Gate(h) => asap[h],
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0);
+    }
+    let depth = asap.iter().copied().max().unwrap_or(0);
+    let mut level = vec![depth; n_gates];
+    for (g, gate) in net.gates.iter().enumerate().rev() {
+        for &s in &gate.inputs {
+            if let Signal::Gate(h) = s {
+                level[h] = level[h].min(level[g] - 1);
+            }
+        }
+    }
+    let level_of = |comp: usize| if comp < n_in { 0 } else { level[comp - n_in] };
+
+    // Who reads each Input and gate: (component, pin).
+    let mut readers: Vec<Vec<(usize, PinId)>> = vec![Vec::new(); n_in + n_gates];
+    for (g, gate) in net.gates.iter().enumerate() {
+        for (k, &s) in gate.inputs.iter().enumerate() {
+            readers[comp_of(s)].push((n_in + g, PinId::input(k as u8)));
+        }
+    }
+    // Outputs wired straight to a gate that nothing else reads.
+    let direct: Vec<bool> = net
+        .outputs
+        .iter()
+        .map(|&(_, s)| match s {
+            Signal::Gate(g) => {
+                readers[n_in + g].is_empty()
+                    && net.outputs.iter().filter(|&&(_, o)| o == s).count() == 1
+            }
+            _ => false,
+        })
+        .collect();
+    let out_comp = |j: usize| n_in + n_gates + j;
+    for (j, &(_, s)) in net.outputs.iter().enumerate() {
+        if !direct[j] && !matches!(s, Signal::Const(_)) {
+            readers[comp_of(s)].push((out_comp(j), PinId::input(0)));
+        }
+    }
+
+    let mut specs: Vec<ComponentSpec> = net
+        .inputs
+        .iter()
+        .map(|name| {
+            let mut input = Input::new(0, 1);
+            input.label = name.clone();
+            ComponentSpec::Input(input)
+        })
+        .collect();
+    specs.extend(net.gates.iter().map(|gate| {
+        ComponentSpec::Gate(Gate {
+            op: gate.op,
+            n_inputs: gate.inputs.len(),
+            width: 1,
+        })
+    }));
+    specs.extend(net.outputs.iter().map(|(name, _)| {
+        ComponentSpec::Output(Output {
+            label: name.clone(),
+        })
+    }));
+
+    // Rows: the bands top to bottom, each component stacked in order.
+    let mut y = vec![0; specs.len()];
+    let mut cursor = 0;
+    for band in 0..=depth {
+        for comp in (0..n_in + n_gates).filter(|&c| level_of(c) == band) {
+            y[comp] = cursor;
+            cursor += height(&specs[comp]) + ROW_GAP;
+        }
+    }
+
+    // Columns, each followed by its level's rails, topmost source rightmost
+    // so that no source's wire crosses a neighbouring rail.
+    let mut x = vec![0; specs.len()];
+    let mut rail_x = vec![0; n_in + n_gates];
+    let mut column = 0;
+    for band in 0..=depth {
+        let members: Vec<usize> = (0..n_in + n_gates)
+            .filter(|&c| level_of(c) == band)
+            .collect();
+        let mut right = column;
+        for &comp in &members {
+            x[comp] = column;
+            let out = pin_at(&specs[comp], GridPos::new(column, 0), PinId::output(0));
+            right = right.max(out.x);
+        }
+        let rails: Vec<usize> = members
+            .into_iter()
+            .filter(|&c| !readers[c].is_empty())
+            .collect();
+        let first = right + 2;
+        for (rank, &comp) in rails.iter().enumerate() {
+            rail_x[comp] = first + RAIL_PITCH * (rails.len() - 1 - rank) as i32;
+        }
+        column = match rails.len() {
+            0 => right + 3,
+            r => first + RAIL_PITCH * (r as i32 - 1) + 3,
+        };
+    }
+    let out_x = column;
+
+    let mut routes = Vec::new();
+    for (j, &(_, s)) in net.outputs.iter().enumerate() {
+        let comp = out_comp(j);
+        x[comp] = out_x;
+        if direct[j] {
+            // Level with its gate's output pin.
+            let from = comp_of(s);
+            let pin = pin_at(
+                &specs[from],
+                GridPos::new(x[from], y[from]),
+                PinId::output(0),
+            );
+            let offset = pin_at(&specs[comp], GridPos::ZERO, PinId::input(0)).y;
+            y[comp] = pin.y - offset;
+        } else if !matches!(s, Signal::Const(_)) {
+            y[comp] = cursor;
+            cursor += height(&specs[comp]) + ROW_GAP;
+        }
+    }
+    // Constant Outputs go last, below the end of every rail, each with its
+    // Constant just left of it.
+    let mut constants = Vec::new();
+    for (j, &(_, s)) in net.outputs.iter().enumerate() {
+        let Signal::Const(b) = s else {
+            continue;
+        };
+        let comp = out_comp(j);
+        y[comp] = cursor;
+        cursor += height(&specs[comp]) + ROW_GAP;
+        let spec = ComponentSpec::Constant(Constant {
+            bits: b as u64,
+            width: 1,
+        });
+        let offset = pin_at(&spec, GridPos::ZERO, PinId::output(0));
+        let to = pin_at(
+            &specs[comp],
+            GridPos::new(x[comp], y[comp]),
+            PinId::input(0),
+        );
+        let pos = GridPos::new(to.x - 2 - offset.x, to.y - offset.y);
+        routes.push(Route {
+            points: vec![pin_at(&spec, pos, PinId::output(0)), to],
+            start: Some((specs.len() + constants.len(), PinId::output(0))),
+            end: Some((comp, PinId::input(0))),
+        });
+        constants.push((spec, pos));
+    }
+
+    let pos_of = |comp: usize| GridPos::new(x[comp], y[comp]);
+    for (src, taps) in readers.iter().enumerate() {
+        if taps.is_empty() {
+            continue;
+        }
+        let from = pin_at(&specs[src], pos_of(src), PinId::output(0));
+        let pins: Vec<(usize, PinId, GridPos)> = taps
+            .iter()
+            .map(|&(comp, pin)| (comp, pin, pin_at(&specs[comp], pos_of(comp), pin)))
+            .collect();
+        let bottom = pins.iter().map(|&(_, _, p)| p.y).max().unwrap();
+        let rx = rail_x[src];
+        routes.push(Route {
+            points: vec![from, GridPos::new(rx, from.y), GridPos::new(rx, bottom)],
+            start: Some((src, PinId::output(0))),
+            end: None,
+        });
+        for (comp, pin, p) in pins {
+            routes.push(Route {
+                points: vec![GridPos::new(rx, p.y), p],
+                start: None,
+                end: Some((comp, pin)),
+            });
+        }
+    }
+    for (j, &(_, s)) in net.outputs.iter().enumerate() {
+        if direct[j] {
+            let (from, to) = (comp_of(s), out_comp(j));
+            routes.push(Route {
+                points: vec![
+                    pin_at(&specs[from], pos_of(from), PinId::output(0)),
+                    pin_at(&specs[to], pos_of(to), PinId::input(0)),
+                ],
+                start: Some((from, PinId::output(0))),
+                end: Some((to, PinId::input(0))),
+            });
+        }
+    }
+    let mut components: Vec<(ComponentSpec, GridPos)> = specs
+        .into_iter()
+        .enumerate()
+        .map(|(comp, spec)| (spec, pos_of(comp)))
+        .collect();
+    components.extend(constants);
+    Layout { components, routes }
+}
@@ -0,0 +1,134 @@
+//! The test-vector panel: a right-side panel holding a test-vector table (see
+//! `sim::test_vector` for the format), a Run button, and the pass/fail outcome
+//! of each row. Clicking a failing row - or running a table that fails - jumps
+//! the canvas to the offending output.
+//!
+//! Runs never touch the document's live circuit: `OsmilogApp::run_test_vectors`
+//! builds a fresh one from the saved form of the project. Like the signal
+//! viewer, this is runtime-only state on `Document` and is never saved.
+
+use crate::gui::app::PlacedCompKey;
+use crate::gui::theme::Theme;
+use crate::sim::test_vector::{Step, StepResult, TestVectors};
+use crate::sim::value::{format_value, Radix};
+
+/// What the panel asks the app to do after a frame.
+pub enum TestVectorAction {
+    Run,
+    // Jump to the output with this port name.
+    Focus(String),
+}
+
+#[derive(Default)]
+pub struct TestVectorPanel {
+    pub open: bool,
+    pub text: String,
+    // The last run: the table as parsed, one result per step; or why the
+    // table couldn't run at all.
+    outcome: Option<Result<(TestVectors, Vec<StepResult>), String>>,
+    // A component the canvas should center on next frame. Set here, taken by
+    // OsmilogApp::ui once it knows the canvas rect.
+    pub(crate) pending_focus: Option<PlacedCompKey>,
+}
+
+impl TestVectorPanel {
+    pub(crate) fn set_outcome(&mut self, outcome: Result<(TestVectors, Vec<StepResult>), String>) {
+        self.outcome = Some(outcome);
+    }
+
+    // The first failing output of the last run, if any.
+    pub(crate) fn first_failure(&self) -> Option<&str> {
+        let Some(Ok((_, results))) = &self.outcome else {
+            return None;
+        };
+        results
+            .iter()
+            .flat_map(|r| r.mismatches.first())
+            .map(|m| m.port.as_str())
+            .next()
+    }
+
+    pub fn show(&mut self, theme: Theme, ui: &mut egui::Ui) -> Option<TestVectorAction> {
+        let mut action = None;
+        ui.horizontal(|ui| {
+            ui.strong("Test Vectors");
+            if ui.button("Run").clicked() {
+                action = Some(TestVectorAction::Run);
+            }
+            if let Some(Ok((_, results))) = &self.outcome {
+                let failed = results.iter().filter(|r| !r.passed()).count();
+                ui.weak(format!("{failed} of {} steps failed", results.len()));
+            }
+        });
+        ui.separator();
+        ui.add(
+            egui::TextEdit::multiline(&mut self.text)
+                .code_editor()
+                .desired_rows(10)
+                .desired_width(f32::INFINITY)
+                .hint_text("a b | y\n0 1 | 1\ntick\n- - | 0"),
+        );
+        ui.separator();
+
+        match &self.outcome {
+            None => {}
+            Some(Err(err)) => {
+                ui.colored_label(theme.error_text, err.as_str());
+            }
+            Some(Ok((vectors, results))) => {
+                egui::ScrollArea::vertical()
+                    .id_salt("test_vector_results")
+                    .show(ui, |ui| {
+                        for (step, result) in vectors.steps.iter().zip(results) {
+                            if let Some(a) = show_result(ui, theme, step, result) {
+                                action = Some(a);
+                            }
+                        }
+                    });
+            }
+        }
+        action
+    }
+}
+
+// One line per step: rows always, ticks only when they fail to settle. A
+// failing row with a mismatch is a link to its first wrong output.
+fn show_result(
+    ui: &mut egui::Ui,
+    theme: Theme,
+    step: &Step,
+    result: &StepResult,
+) -> Option<TestVectorAction> {
+    if result.passed() {
+        if matches!(step, Step::Row { .. }) {
+            ui.label(format!("line {}: PASS", result.line));
+        }
+        return None;
+    }
+    let mut why: Vec<String> = result
+        .mismatches
+        .iter()
+        .map(|m| {
+            format!(
+                "{} expected {}, got {}",
+                m.port,
+                m.expected,
+                format_value(m.actual, Radix::Unsigned)
+            )
+        })
+        .collect();
+    why.extend(result.error.as_ref().map(|e| e.to_string()));
+    let text = egui::RichText::new(format!("line {}: FAIL {}", result.line, why.join("; ")))
+        .color(theme.error_text);
+    match result.mismatches.first() {
+        Some(m) => ui
+            .link(text)
+            .on_hover_text("Show this output")
+            .clicked()
+            .then(|| TestVectorAction::Focus(m.port.clone())),
+        None => {
+            ui.label(text);
+            None
+        }
+    }
+}
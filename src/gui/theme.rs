@@ -19,6 +19,11 @@ pub struct Theme {
     pub value_low: Color32,
     pub value_high: Color32,
     pub value_invalid: Color32,
+    pub value_unknown: Color32,
+    // The Timing window's selected path; apart from every value colour.
+    pub path_highlight: Color32,
+    // The signal behind a breakpoint hit; apart from the path's, too.
+    pub breakpoint_highlight: Color32,
 }
 
 impl Theme {
@@ -38,6 +43,9 @@ impl Theme {
             value_low: Color32::from_rgb(40, 40, 80),
             value_high: Color32::from_rgb(50, 200, 80),
             value_invalid: Color32::from_rgb(0xDE, 0x6B, 0x2F),
+            value_unknown: Color32::from_rgb(0xC8, 0x32, 0x32),
+            path_highlight: Color32::from_rgb(0xF0, 0xA0, 0x20),
+            breakpoint_highlight: Color32::from_rgb(0xB0, 0x60, 0xF0),
         }
     }
 }
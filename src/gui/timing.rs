@@ -0,0 +1,183 @@
+//! The Timing window: the longest combinational path between each start point
+//! (an Input or register output) and end point (a register input or Output)
+//! of the document's circuit, by gate levels or by each part's timed-mode
+//! delay (see `sim::timing`). The selected path - the worst until another is
+//! picked - is drawn over the canvas. Opened from the View menu.
+//!
+//! Analysis reads the live circuit without settling it, and subcircuits are
+//! looked through, so steps inside one list under the subcircuit on this
+//! canvas. Like the test-vector panel, this is runtime-only state on
+//! `Document` and is never saved; a result stays as it was until re-run.
+
+use std::collections::HashMap;
+
+use crate::gui::theme::Theme;
+use crate::sim::component::CompKey;
+use crate::sim::timing::{Metric, Path, Timing};
+
+#[derive(Default)]
+pub struct TimingWindow {
+    pub open: bool,
+    pub(crate) metric: Metric,
+    outcome: Option<Result<(Metric, Timing), String>>,
+    // Index into the last result's paths.
+    selected: usize,
+}
+
+impl TimingWindow {
+    pub(crate) fn set_outcome(&mut self, outcome: Result<Timing, String>) {
+        self.outcome = Some(outcome.map(|timing| (self.metric, timing)));
+        self.selected = 0;
+    }
+
+    pub(crate) fn selected_path(&self) -> Option<&Path> {
+        match &self.outcome {
+            Some(Ok((_, timing))) => timing.paths.get(self.selected),
+            _ => None,
+        }
+    }
+
+    // The components of this canvas the selected path crosses, in order,
+    // each once: a path through a subcircuit lights up the subcircuit.
+    pub(crate) fn highlighted(&self) -> Vec<CompKey> {
+        let mut keys: Vec<CompKey> = vec![];
+        for chain in self.selected_path().map_or(&[][..], |p| &p.components) {
+            if keys.last() != chain.first() {
+                keys.extend(chain.first());
+            }
+        }
+        keys
+    }
+
+    // `names` describes each component on this canvas. Returns true when
+    // Analyze was clicked.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        theme: Theme,
+        names: &HashMap<CompKey, String>,
+    ) -> bool {
+        let mut analyze = false;
+        let mut open = self.open;
+        egui::Window::new("Timing")
+            .open(&mut open)
+            .default_size([380.0, 420.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.metric, Metric::Levels, "Gate levels");
+                    ui.radio_value(&mut self.metric, Metric::Delays, "Delays")
+                        .on_hover_text("Each part's delay, as set in its properties");
+                    if ui.button("Analyze").clicked() {
+                        analyze = true;
+                    }
+                });
+                ui.separator();
+
+                let (metric, timing) = match &self.outcome {
+                    None => return,
+                    Some(Err(err)) => {
+                        ui.colored_label(theme.error_text, err.as_str());
+                        return;
+                    }
+                    Some(Ok((metric, timing))) => (*metric, timing),
+                };
+                let Some(worst) = timing.worst() else {
+                    ui.weak("No path joins a start point to an end point.");
+                    return;
+                };
+                ui.strong(format!("Critical path: {}", length(metric, worst.length)));
+                egui::ScrollArea::vertical()
+                    .id_salt("timing_paths")
+                    .max_height(180.0)
+                    .show(ui, |ui| {
+                        for (i, path) in timing.paths.iter().enumerate() {
+                            let steps = steps(path, names);
+                            let text = format!(
+                                "{}: {} → {}",
+                                length(metric, path.length),
+                                steps.first().map_or("?", String::as_str),
+                                steps.last().map_or("?", String::as_str),
+                            );
+                            if ui.selectable_label(self.selected == i, text).clicked() {
+                                self.selected = i;
+                            }
+                        }
+                    });
+                ui.separator();
+                if let Some(path) = timing.paths.get(self.selected) {
+                    egui::ScrollArea::vertical()
+                        .id_salt("timing_steps")
+                        .show(ui, |ui| {
+                            for step in steps(path, names) {
+                                ui.monospace(step);
+                            }
+                        });
+                }
+            });
+        self.open = open;
+        analyze
+    }
+}
+
+fn length(metric: Metric, length: u64) -> String {
+    match metric {
+        Metric::Levels => format!("{length} levels"),
+        Metric::Delays => format!("{length} time units"),
+    }
+}
+
+// A path as the components of this canvas it crosses; consecutive steps
+// inside one subcircuit collapse into a single line counting them.
+fn steps(path: &Path, names: &HashMap<CompKey, String>) -> Vec<String> {
+    let mut lines: Vec<(CompKey, usize)> = vec![];
+    for chain in &path.components {
+        let (top, inside) = (chain[0], chain.len() > 1);
+        match lines.last_mut() {
+            Some((key, count)) if *key == top && inside => *count += 1,
+            _ => lines.push((top, usize::from(inside))),
+        }
+    }
+    lines
+        .into_iter()
+        .map(|(key, count)| {
+            let name = names.get(&key).map_or("(removed)", String::as_str);
+            match count {
+                0 => name.to_string(),
+                1 => format!("{name}: 1 part inside"),
+                n => format!("{name}: {n} parts inside"),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_inside_a_subcircuit_collapse() {
+        let [a, sub, out] = [CompKey(1), CompKey(2), CompKey(3)];
+        let names: HashMap<CompKey, String> = [(a, "IN"), (sub, "adder"), (out, "OUT")]
+            .into_iter()
+            .map(|(k, n)| (k, n.to_string()))
+            .collect();
+        let path = Path {
+            length: 3,
+            components: vec![
+                vec![a],
+                vec![sub, CompKey(10)],
+                vec![sub, CompKey(11)],
+                vec![sub, CompKey(12)],
+                vec![out],
+            ],
+        };
+        assert_eq!(
+            steps(&path, &names),
+            vec!["IN", "adder: 3 parts inside", "OUT"]
+        );
+
+        let mut window = TimingWindow::default();
+        window.set_outcome(Ok(Timing { paths: vec![path] }));
+        assert_eq!(window.highlighted(), vec![a, sub, out]);
+    }
+}
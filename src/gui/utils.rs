@@ -1,5 +1,5 @@
-//! Small shared types with no other natural home - kept separate from
-//! `geometry.rs` (grid/pixel geometry constants and shape builders).
+//! Small shared types and widgets with no other natural home - kept separate
+//! from `geometry.rs` (grid/pixel geometry constants and shape builders).
 
 use egui::Painter;
 
@@ -15,3 +15,37 @@ pub(crate) struct CanvasCtx<'a> {
     pub(crate) camera: Camera,
     pub(crate) theme: Theme,
 }
+
+// Widest value a DragValue edits exactly: it round-trips through f64, whose
+// 53-bit mantissa silently drops the low bits of anything wider.
+pub(crate) const DRAG_VALUE_MAX_WIDTH: u8 = 53;
+
+// A hex text field for a value wider than DRAG_VALUE_MAX_WIDTH. The text being
+// typed lives in egui's temp memory under `id` until focus leaves, so a
+// half-typed or unparsable entry doesn't snap back every frame. Returns
+// whether `value` changed.
+pub(crate) fn wide_hex_edit(ui: &mut egui::Ui, id: egui::Id, value: &mut u64, mask: u64) -> bool {
+    let mut text = ui
+        .data(|d| d.get_temp::<String>(id))
+        .unwrap_or_else(|| format!("{:X}", *value));
+    let resp = ui.add(
+        egui::TextEdit::singleline(&mut text)
+            .id(id)
+            .font(egui::TextStyle::Monospace)
+            .desired_width(ui.spacing().interact_size.x * 3.0),
+    );
+    let mut changed = false;
+    if resp.changed() {
+        if let Ok(parsed) = u64::from_str_radix(text.trim().trim_start_matches("0x"), 16) {
+            let parsed = parsed & mask;
+            changed = parsed != *value;
+            *value = parsed;
+        }
+    }
+    if resp.has_focus() {
+        ui.data_mut(|d| d.insert_temp(id, text));
+    } else {
+        ui.data_mut(|d| d.remove::<String>(id));
+    }
+    changed
+}
@@ -11,10 +11,23 @@ use crate::sim::component::ComponentSpec;
 // Bumped on breaking on-disk changes; checked by `validate()`. v2: wires became a grid
 // segment graph, replacing v1's pin-to-pin lists (v1 rejected). v3: the top-level file
 // became a `ProjectFile` of several named circuits, so subcircuits round-trip; v2 files
-// still load, upgraded to a one-circuit project.
-pub const CURRENT_VERSION: u32 = 3;
+// still load, upgraded to a one-circuit project. v4: widths go up to 64 bits, so
+// values and ROM words may exceed u32 - a v3 build must reject such a file rather
+// than fail mid-parse; v3 files load unchanged, relabelled as v4. v5: an Output
+// spec carries a label, so it is saved as an object rather than the bare
+// string "Output"; older files are upgraded on load (see `from_json`). v6: a RAM
+// may carry a saved power-on image and keep-contents flag, and a placed part its
+// own delay - fields an older build would drop without a word and then save the
+// file back without, so it must reject the file instead; v5 files load unchanged.
+pub const CURRENT_VERSION: u32 = 6;
 // Predates subcircuits, so a v2 file never carries cross-circuit refs.
 pub const LEGACY_SINGLE_CIRCUIT_VERSION: u32 = 2;
+// Same shape as v6, with every width capped at 32 bits and unlabelled Outputs.
+pub const LEGACY_NARROW_BUS_VERSION: u32 = 3;
+// Same shape as v6, with unlabelled Outputs.
+pub const LEGACY_UNLABELLED_OUTPUT_VERSION: u32 = 4;
+// Same shape as v6, with no RAM images or part delays saved.
+pub const LEGACY_NO_SAVED_STATE_VERSION: u32 = 5;
 pub const CIRCUIT_FILE_EXT: &str = "osm";
 
 // NOT a file itself: the reusable payload shared by the clipboard snapshot, each project
@@ -31,6 +44,10 @@ pub struct CircuitSnapshot {
 pub struct ComponentEntry {
     pub spec: ComponentSpec,
     pub grid_pos: GridPos,
+    // Timed-mode delay in place of the part's default (see Component::delay).
+    // Left out when unset, and absent from files saved before it existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -226,20 +243,31 @@ impl ProjectFile {
     }
 
     // Parses a project file, transparently upgrading a legacy v2 single-circuit
-    // file into a one-circuit project. Bounds are not checked here - call
-    // `validate()` before installing the result.
+    // file into a one-circuit project and a v3-v5 file in place. Bounds are not
+    // checked here - call `validate()` before installing the result.
     pub fn from_json(s: &str) -> Result<Self, LoadError> {
         #[derive(Deserialize)]
         struct VersionProbe {
             version: u32,
         }
-        let probe: VersionProbe =
-            serde_json::from_str(s).map_err(|e| LoadError::Parse(e.to_string()))?;
+        let parse_err = |e: serde_json::Error| LoadError::Parse(e.to_string());
+        let probe: VersionProbe = serde_json::from_str(s).map_err(parse_err)?;
         if probe.version == CURRENT_VERSION {
-            serde_json::from_str(s).map_err(|e| LoadError::Parse(e.to_string()))
+            return serde_json::from_str(s).map_err(parse_err);
+        }
+        let mut json: serde_json::Value = serde_json::from_str(s).map_err(parse_err)?;
+        label_unit_outputs(&mut json);
+        if probe.version == LEGACY_NARROW_BUS_VERSION
+            || probe.version == LEGACY_UNLABELLED_OUTPUT_VERSION
+            || probe.version == LEGACY_NO_SAVED_STATE_VERSION
+        {
+            // Every u32 field widened to u64 and every v6 field defaults, so
+            // the older shapes parse as-is.
+            let mut project: Self = serde_json::from_value(json).map_err(parse_err)?;
+            project.version = CURRENT_VERSION;
+            Ok(project)
         } else if probe.version == LEGACY_SINGLE_CIRCUIT_VERSION {
-            let legacy: LegacyV2File =
-                serde_json::from_str(s).map_err(|e| LoadError::Parse(e.to_string()))?;
+            let legacy: LegacyV2File = serde_json::from_value(json).map_err(parse_err)?;
             Ok(Self::from_snapshot(legacy.snapshot))
         } else {
             Err(LoadError::UnsupportedVersion {
@@ -306,10 +334,30 @@ impl ProjectFile {
     }
 }
 
+// Rewrites every pre-v5 unit Output spec (`"spec": "Output"`) into the v5
+// shape, an unlabelled `{"Output": {}}`, wherever it sits in the file.
+fn label_unit_outputs(json: &mut serde_json::Value) {
+    match json {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if key == "spec" && value.as_str() == Some("Output") {
+                    *value = serde_json::json!({ "Output": {} });
+                } else {
+                    label_unit_outputs(value);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(label_unit_outputs),
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sim::component::Input;
+    use crate::sim::component::{
+        ClockEdge, Constant, DFlipFlopConf, Input, JKFlipFlopConf, Ram, ReadBehavior, RegConf,
+    };
 
     #[test]
     fn test_from_json_upgrades_legacy_v2_to_single_circuit_project() {
@@ -318,12 +366,14 @@ mod tests {
             snapshot: CircuitSnapshot {
                 components: vec![
                     ComponentEntry {
-                        spec: ComponentSpec::Input(Input { bits: 1, width: 1 }),
+                        spec: ComponentSpec::Input(Input::new(1, 1)),
                         grid_pos: GridPos::new(0, 0),
+                        delay: None,
                     },
                     ComponentEntry {
-                        spec: ComponentSpec::Output,
+                        spec: ComponentSpec::Output(Default::default()),
                         grid_pos: GridPos::new(5, 0),
+                        delay: None,
                     },
                 ],
                 tunnels: vec![],
@@ -362,6 +412,183 @@ mod tests {
         project.validate().unwrap();
     }
 
+    #[test]
+    fn test_from_json_upgrades_v3_in_place() {
+        let mut v3 = ProjectFile::new(
+            0,
+            vec![CircuitEntry {
+                name: "Main".to_string(),
+                snapshot: CircuitSnapshot {
+                    components: vec![ComponentEntry {
+                        spec: ComponentSpec::Input(Input::new(0xFFFF_FFFF, 32)),
+                        grid_pos: GridPos::new(0, 0),
+                        delay: None,
+                    }],
+                    ..Default::default()
+                },
+                subcircuits: vec![],
+            }],
+        );
+        v3.version = LEGACY_NARROW_BUS_VERSION;
+        let json = v3.to_json().unwrap();
+
+        let project = ProjectFile::from_json(&json).unwrap();
+        assert_eq!(project.version, CURRENT_VERSION);
+        assert_eq!(
+            project.circuits[0].snapshot.components[0].spec,
+            ComponentSpec::Input(Input::new(0xFFFF_FFFF, 32))
+        );
+        project.validate().unwrap();
+    }
+
+    #[test]
+    fn test_from_json_upgrades_v4_unit_outputs() {
+        let project = ProjectFile::new(
+            0,
+            vec![CircuitEntry {
+                name: "Main".to_string(),
+                snapshot: CircuitSnapshot {
+                    components: vec![ComponentEntry {
+                        spec: ComponentSpec::Output(Default::default()),
+                        grid_pos: GridPos::new(0, 0),
+                        delay: None,
+                    }],
+                    ..Default::default()
+                },
+                subcircuits: vec![],
+            }],
+        );
+        let v4 = serde_json::to_string(&project)
+            .unwrap()
+            .replace(r#""spec":{"Output":{"label":""}}"#, r#""spec":"Output""#)
+            .replace(
+                &format!(r#""version":{CURRENT_VERSION}"#),
+                &format!(r#""version":{LEGACY_UNLABELLED_OUTPUT_VERSION}"#),
+            );
+        assert!(v4.contains(r#""spec":"Output""#));
+
+        let loaded = ProjectFile::from_json(&v4).unwrap();
+        assert_eq!(loaded.version, CURRENT_VERSION);
+        assert_eq!(
+            loaded.circuits[0].snapshot.components[0].spec,
+            ComponentSpec::Output(Default::default())
+        );
+        loaded.validate().unwrap();
+    }
+
+    #[test]
+    fn test_from_json_upgrades_v5_without_saved_ram_images() {
+        let ram = Ram::new(8, 4, ReadBehavior::default());
+        let mut v5 = ProjectFile::new(
+            0,
+            vec![CircuitEntry {
+                name: "Main".to_string(),
+                snapshot: CircuitSnapshot {
+                    components: vec![ComponentEntry {
+                        spec: ComponentSpec::Ram(ram.clone()),
+                        grid_pos: GridPos::new(0, 0),
+                        delay: None,
+                    }],
+                    ..Default::default()
+                },
+                subcircuits: vec![],
+            }],
+        );
+        v5.version = LEGACY_NO_SAVED_STATE_VERSION;
+        let json = v5.to_json().unwrap();
+        assert!(!json.contains("initial") && !json.contains("keep_contents"));
+
+        let project = ProjectFile::from_json(&json).unwrap();
+        assert_eq!(project.version, CURRENT_VERSION);
+        assert_eq!(
+            project.circuits[0].snapshot.components[0].spec,
+            ComponentSpec::Ram(ram)
+        );
+        project.validate().unwrap();
+    }
+
+    #[test]
+    fn test_wide_values_round_trip() {
+        let project = ProjectFile::new(
+            0,
+            vec![CircuitEntry {
+                name: "Main".to_string(),
+                snapshot: CircuitSnapshot {
+                    components: vec![ComponentEntry {
+                        spec: ComponentSpec::Constant(Constant {
+                            bits: u64::MAX,
+                            width: 64,
+                        }),
+                        grid_pos: GridPos::new(0, 0),
+                        delay: None,
+                    }],
+                    ..Default::default()
+                },
+                subcircuits: vec![],
+            }],
+        );
+        let loaded = ProjectFile::from_json(&project.to_json().unwrap()).unwrap();
+        assert_eq!(
+            loaded.circuits[0].snapshot.components[0].spec,
+            ComponentSpec::Constant(Constant {
+                bits: u64::MAX,
+                width: 64
+            })
+        );
+    }
+
+    // Sequential specs saved before clock pins existed have no clock_edge (the
+    // flip-flops were saved as bare `null`); they load as rising-edge parts.
+    #[test]
+    fn test_specs_without_clock_edge_load_as_rising() {
+        let spec = |json: &str| serde_json::from_str::<ComponentSpec>(json).unwrap();
+        assert_eq!(
+            spec(r#"{"DFlipFlop":null}"#),
+            ComponentSpec::DFlipFlop(DFlipFlopConf {
+                clock_edge: ClockEdge::Rising
+            })
+        );
+        assert_eq!(
+            spec(r#"{"Reg":{"data_width":4}}"#),
+            ComponentSpec::Reg(RegConf {
+                data_width: 4,
+                clock_edge: ClockEdge::Rising
+            })
+        );
+
+        let falling = ComponentSpec::JKFlipFlop(JKFlipFlopConf {
+            clock_edge: ClockEdge::Falling,
+        });
+        assert_eq!(spec(&serde_json::to_string(&falling).unwrap()), falling);
+    }
+
+    // A delay is saved only when set, so files from before it existed load
+    // with none.
+    #[test]
+    fn test_component_delay_is_optional() {
+        let entry = ComponentEntry {
+            spec: ComponentSpec::Output(Default::default()),
+            grid_pos: GridPos::ZERO,
+            delay: None,
+        };
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(!json.contains("delay"));
+        assert_eq!(
+            serde_json::from_str::<ComponentEntry>(&json).unwrap().delay,
+            None
+        );
+
+        let delayed = ComponentEntry {
+            delay: Some(3),
+            ..entry
+        };
+        let json = serde_json::to_string(&delayed).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ComponentEntry>(&json).unwrap().delay,
+            Some(3)
+        );
+    }
+
     #[test]
     fn test_project_file_validate_rejects_bad_files() {
         let good_circuit = || CircuitEntry {
@@ -399,8 +626,9 @@ mod tests {
 
         let mut c = good_circuit();
         c.snapshot.components.push(ComponentEntry {
-            spec: ComponentSpec::Output,
+            spec: ComponentSpec::Output(Default::default()),
             grid_pos: GridPos::ZERO,
+            delay: None,
         });
         c.subcircuits.push(SubcircuitRef {
             component: 0,
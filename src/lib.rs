@@ -1,4 +1,13 @@
+pub mod checkpoint;
+pub mod cli;
 pub mod gui;
 pub mod io;
+pub mod logisim;
+pub mod memory_image;
 pub mod platform;
+pub mod project;
 pub mod sim;
+pub mod stats;
+pub mod stimulus;
+pub mod vcd;
+pub mod verilog;
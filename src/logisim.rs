@@ -0,0 +1,1784 @@
+// Imports Logisim-evolution `.circ` projects. A `.circ` file is XML: a
+// `<project>` of `<circuit>`s, each a flat list of `<comp>`s (a library
+// component at a pixel location, configured by `<a name=.. val=..>` children)
+// and `<wire>`s between pixel points. Logisim joins whatever meets at a point:
+// wire ends, a wire end on another wire, and component ports.
+//
+// Each component with an osmilog counterpart is placed where Logisim had it
+// (Logisim's 10-pixel grid is osmilog's cell grid), each wire becomes segments,
+// and a short stub runs from every connected Logisim port to wherever osmilog
+// draws that pin. Ports are located with Logisim's own layout rules, known
+// here for its classic appearance only; a part drawn any other way is still
+// placed, just left unwired. Whatever is dropped or approximated is listed in
+// `Imported::unsupported` instead of failing the import.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::gui::geometry::{pin_grid_pos, tunnel_shape, GridPos};
+use crate::gui::shape::{ComponentShape, PinAnchor};
+use crate::io::{
+    CircuitEntry, CircuitSnapshot, ComponentEntry, NodeAttachEntry, NodeEntry, ProjectFile,
+    SegEntry, SubcircuitRef, TunnelEntry,
+};
+use crate::project::boundary_pins;
+use crate::sim::circuit::TunnelRole;
+use crate::sim::component::{
+    Adder, ClockEdge, ClockSourceConf, Comparator, ComponentSpec, Constant, CounterConf,
+    DFlipFlopConf, Demux, Divider, DocId, FanDirection, Gate, GateOp, InIdx, Input, JKFlipFlopConf,
+    Multiplier, Mux, OutIdx, Output, OverflowAction, PinId, Probe, PullDirection, PullResistor,
+    Ram, ReadBehavior, RegConf, Rom, SRFlipFlopConf, Subtractor, TFlipFlopConf, TriState,
+    MAX_ADDRESS_WIDTH,
+};
+use crate::sim::value::Value;
+
+pub const LOGISIM_FILE_EXT: &str = "circ";
+
+pub struct Imported {
+    pub file: ProjectFile,
+    // One line per element left out or approximated, naming its circuit and
+    // Logisim location.
+    pub unsupported: Vec<String>,
+}
+
+pub fn import(text: &str) -> Result<Imported, String> {
+    let root = parse_xml(text)?;
+    if root.name != "project" {
+        return Err("not a Logisim project: no <project> element".to_string());
+    }
+    let libs: HashMap<&str, &str> = root
+        .children("lib")
+        .filter_map(|lib| Some((lib.attr("name")?, lib.attr("desc")?)))
+        .collect();
+    let circuits = root
+        .children("circuit")
+        .map(|c| Circuit::read(c, &libs))
+        .collect::<Result<Vec<_>, _>>()?;
+    if circuits.is_empty() {
+        return Err("the project has no circuits".to_string());
+    }
+    let main = root.children("main").find_map(|m| m.attr("name"));
+    let active = main
+        .and_then(|name| circuits.iter().position(|c| c.name == name))
+        .unwrap_or(0);
+
+    // Logisim 2.7 and older gave a gate five inputs unless told otherwise;
+    // Logisim-evolution gives it two.
+    let version: Vec<u32> = root
+        .attr("source")
+        .unwrap_or("")
+        .split('.')
+        .map_while(|n| n.parse().ok())
+        .collect();
+    let gate_inputs = if version.len() >= 2 && (version[0], version[1]) <= (2, 7) {
+        5
+    } else {
+        2
+    };
+
+    let interfaces: Vec<Interface> = circuits.iter().map(Interface::of).collect();
+    let project = Project {
+        circuits: &circuits,
+        interfaces: &interfaces,
+        gate_inputs,
+    };
+    let mut unsupported = Vec::new();
+    let entries = circuits
+        .iter()
+        .map(|c| project.import(c, &mut unsupported))
+        .collect();
+    Ok(Imported {
+        file: ProjectFile::new(active, entries),
+        unsupported,
+    })
+}
+
+// ── XML ──────────────────────────────────────────────────────────────────
+
+// Just enough XML for a .circ file: elements, attributes, text, CDATA, the
+// predefined entities and character references. Comments, processing
+// instructions and a DOCTYPE are skipped.
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+}
+
+fn parse_xml(text: &str) -> Result<Element, String> {
+    let mut xml = Xml { rest: text };
+    xml.skip_markup()?;
+    let root = xml.element()?;
+    xml.skip_markup()?;
+    if !xml.rest.is_empty() {
+        return Err("malformed XML: content after the root element".to_string());
+    }
+    Ok(root)
+}
+
+struct Xml<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Xml<'a> {
+    // Skips whitespace, comments, processing instructions and declarations.
+    fn skip_markup(&mut self) -> Result<(), String> {
+        loop {
+            self.rest = self.rest.trim_start();
+            if self.rest.starts_with("<?") {
+                self.take_until("?>")?;
+            } else if self.rest.starts_with("<!--") {
+                self.take_until("-->")?;
+            } else if self.rest.starts_with("<!") && !self.rest.starts_with("<![CDATA[") {
+                self.take_until(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    // Everything before `end`, consuming `end` too.
+    fn take_until(&mut self, end: &str) -> Result<&'a str, String> {
+        let i = self
+            .rest
+            .find(end)
+            .ok_or_else(|| format!("malformed XML: missing {end:?}"))?;
+        let taken = &self.rest[..i];
+        self.rest = &self.rest[i + end.len()..];
+        Ok(taken)
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        match self.rest.strip_prefix(prefix) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        let end = self
+            .rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '='))
+            .unwrap_or(self.rest.len());
+        if end == 0 {
+            return Err("malformed XML: expected a name".to_string());
+        }
+        let name = self.rest[..end].to_string();
+        self.rest = &self.rest[end..];
+        Ok(name)
+    }
+
+    fn element(&mut self) -> Result<Element, String> {
+        if !self.eat("<") {
+            return Err("malformed XML: expected an element".to_string());
+        }
+        let mut el = Element {
+            name: self.name()?,
+            ..Default::default()
+        };
+        loop {
+            self.rest = self.rest.trim_start();
+            if self.eat("/>") {
+                return Ok(el);
+            }
+            if self.eat(">") {
+                break;
+            }
+            let key = self.name()?;
+            self.rest = self.rest.trim_start();
+            if !self.eat("=") {
+                return Err(format!("malformed XML: attribute {key} has no value"));
+            }
+            self.rest = self.rest.trim_start();
+            let value = if self.eat("\"") {
+                self.take_until("\"")?
+            } else if self.eat("'") {
+                self.take_until("'")?
+            } else {
+                return Err(format!("malformed XML: attribute {key} is not quoted"));
+            };
+            el.attrs.push((key, unescape(value)?));
+        }
+        loop {
+            let text = self.rest.find('<').unwrap_or(self.rest.len());
+            el.text.push_str(&unescape(&self.rest[..text])?);
+            self.rest = &self.rest[text..];
+            if self.rest.is_empty() {
+                return Err(format!("malformed XML: <{}> is never closed", el.name));
+            } else if self.eat("</") {
+                let name = self.name()?;
+                self.rest = self.rest.trim_start();
+                if name != el.name || !self.eat(">") {
+                    return Err(format!("malformed XML: <{}> closed by </{name}>", el.name));
+                }
+                return Ok(el);
+            } else if self.eat("<![CDATA[") {
+                el.text.push_str(self.take_until("]]>")?);
+            } else if self.eat("<!--") {
+                self.take_until("-->")?;
+            } else if self.eat("<?") {
+                self.take_until("?>")?;
+            } else {
+                el.children.push(self.element()?);
+            }
+        }
+    }
+}
+
+fn unescape(s: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i + 1..];
+        let end = rest.find(';').ok_or("malformed XML: unterminated entity")?;
+        let entity = &rest[..end];
+        let c = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix('#')
+                .and_then(|n| match n.strip_prefix('x') {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => n.parse().ok(),
+                })
+                .and_then(char::from_u32),
+        };
+        out.push(c.ok_or_else(|| format!("malformed XML: unknown entity &{entity};"))?);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+// ── Logisim's model ──────────────────────────────────────────────────────
+
+// A Logisim location, in pixels.
+type Point = (i32, i32);
+
+fn parse_point(text: &str) -> Option<Point> {
+    let (x, y) = text
+        .trim()
+        .strip_prefix('(')?
+        .strip_suffix(')')?
+        .split_once(',')?;
+    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+fn add((x, y): Point, (dx, dy): Point) -> Point {
+    (x + dx, y + dy)
+}
+
+// The cell a Logisim point falls in: one cell per 10-pixel grid step.
+fn cell((x, y): Point) -> GridPos {
+    let snap = |v: i32| (v as f64 / 10.0).round() as i32;
+    GridPos::new(snap(x), snap(y))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Facing {
+    East,
+    West,
+    North,
+    South,
+}
+
+impl Facing {
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "east" => Some(Self::East),
+            "west" => Some(Self::West),
+            "north" => Some(Self::North),
+            "south" => Some(Self::South),
+            _ => None,
+        }
+    }
+
+    // Turns an offset laid out facing east the way Logisim turns a gate's:
+    // the axis swings round, but the inputs keep their order across it.
+    fn turn_gate(self, (x, y): Point) -> Point {
+        match self {
+            Self::East => (x, y),
+            Self::West => (-x, y),
+            Self::North => (y, -x),
+            Self::South => (y, x),
+        }
+    }
+
+    // A true rotation of an offset laid out facing east.
+    fn rotate(self, (x, y): Point) -> Point {
+        match self {
+            Self::East => (x, y),
+            Self::West => (-x, -y),
+            Self::North => (y, -x),
+            Self::South => (-y, x),
+        }
+    }
+}
+
+// A `<comp>`: a library component (or, with no library, a circuit of this
+// project) at `loc`, configured by `attrs`.
+struct Comp<'a> {
+    // The library's `desc`, e.g. "#Gates"; None for a subcircuit.
+    lib: Option<&'a str>,
+    name: &'a str,
+    loc: Point,
+    attrs: HashMap<&'a str, &'a str>,
+}
+
+impl Comp<'_> {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.get(name).copied()
+    }
+
+    // A decimal or 0x-hex number, or `default` if absent or unreadable.
+    fn number(&self, name: &str, default: u64) -> u64 {
+        self.attr(name).and_then(parse_number).unwrap_or(default)
+    }
+
+    fn width(&self, name: &str, default: u8) -> u8 {
+        self.number(name, default as u64).clamp(1, 64) as u8
+    }
+
+    fn flag(&self, name: &str, default: bool) -> bool {
+        self.attr(name).map_or(default, |v| v == "true")
+    }
+
+    fn facing(&self) -> Option<Facing> {
+        self.attr("facing")
+            .map_or(Some(Facing::East), Facing::parse)
+    }
+
+    fn label(&self) -> String {
+        self.attr("label").unwrap_or("").to_string()
+    }
+
+    // Whether the component is drawn the classic way, the only one whose
+    // ports are located here.
+    fn classic(&self) -> bool {
+        self.attr("appearance").is_none_or(|a| a == "classic")
+    }
+
+    fn edge(&self) -> ClockEdge {
+        match self.attr("trigger") {
+            Some("falling") => ClockEdge::Falling,
+            _ => ClockEdge::Rising,
+        }
+    }
+}
+
+fn parse_number(text: &str) -> Option<u64> {
+    let text = text.trim();
+    match text.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+struct Circuit<'a> {
+    name: &'a str,
+    comps: Vec<Comp<'a>>,
+    wires: Vec<(Point, Point)>,
+    // Whether instances are drawn as Logisim's default box, the only
+    // subcircuit appearance whose ports are located here.
+    classic: bool,
+}
+
+impl<'a> Circuit<'a> {
+    fn read(el: &'a Element, libs: &HashMap<&str, &'a str>) -> Result<Self, String> {
+        let name = el.attr("name").ok_or("a <circuit> has no name")?;
+        let mut comps = Vec::new();
+        let mut wires = Vec::new();
+        for child in &el.children {
+            let at = |key: &str| {
+                child
+                    .attr(key)
+                    .and_then(parse_point)
+                    .ok_or_else(|| format!("{name}: a <{}> has no valid {key}", child.name))
+            };
+            match child.name.as_str() {
+                "comp" => comps.push(Comp {
+                    lib: child.attr("lib").map(|l| libs.get(l).copied().unwrap_or(l)),
+                    name: child.attr("name").unwrap_or(""),
+                    loc: at("loc")?,
+                    // A long value such as ROM contents is the element's text.
+                    attrs: child
+                        .children("a")
+                        .filter_map(|a| Some((a.attr("name")?, a.attr("val").unwrap_or(&a.text))))
+                        .collect(),
+                }),
+                "wire" => wires.push((at("from")?, at("to")?)),
+                _ => {}
+            }
+        }
+        let appearance = el
+            .children("a")
+            .find(|a| a.attr("name") == Some("appearance"))
+            .and_then(|a| a.attr("val"));
+        let custom = el.children("appear").any(|a| !a.children.is_empty());
+        Ok(Self {
+            name,
+            comps,
+            wires,
+            classic: !custom && appearance.is_none_or(|a| a == "classic"),
+        })
+    }
+
+    // This circuit's Pins, by index into `comps`.
+    fn pins(&self) -> impl Iterator<Item = (usize, &Comp<'a>)> {
+        self.comps
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.lib == Some("#Wiring") && c.name == "Pin")
+    }
+}
+
+// ── Mapping components ───────────────────────────────────────────────────
+
+// A Logisim port: where it is and the osmilog pin it becomes. `width` is 0
+// for a port that takes its net's width (a tunnel's or pull resistor's).
+#[derive(Debug, Clone, Copy)]
+struct Port {
+    at: Point,
+    pin: PinId,
+    width: u8,
+}
+
+fn port(at: Point, pin: PinId, width: u8) -> Port {
+    Port { at, pin, width }
+}
+
+enum Body {
+    Spec(ComponentSpec),
+    Tunnel(String),
+    // A Logisim Buffer: osmilog has no buffer gate, and a buffer only passes
+    // its input on, so its two ports are simply joined.
+    Joint,
+}
+
+// One imported component before layout. A splitter's trunk is its first
+// port, taken as the input until `settle_splitters` decides otherwise.
+struct Part {
+    body: Body,
+    // Relative to the component's location until `Project::import` makes
+    // them absolute. The first port decides where the component goes. None
+    // when Logisim's layout for it isn't known.
+    ports: Option<Vec<Port>>,
+    // Logisim ports with no osmilog counterpart; reported if anything
+    // connects to them.
+    dropped: Vec<(Point, &'static str)>,
+    // Approximations to report either way.
+    notes: Vec<String>,
+    // For a subcircuit, the index of the circuit it instantiates.
+    circuit: Option<usize>,
+}
+
+impl Part {
+    fn new(body: Body, ports: Vec<Port>) -> Self {
+        Self {
+            body,
+            ports: Some(ports),
+            dropped: Vec::new(),
+            notes: Vec::new(),
+            circuit: None,
+        }
+    }
+
+    fn spec(spec: ComponentSpec, ports: Vec<Port>) -> Self {
+        Self::new(Body::Spec(spec), ports)
+    }
+
+    // Placed where Logisim had it, with no wires.
+    fn unwired(spec: ComponentSpec, why: String) -> Self {
+        Self {
+            ports: None,
+            notes: vec![why],
+            ..Self::spec(spec, Vec::new())
+        }
+    }
+
+    fn drop_port(mut self, at: Point, what: &'static str) -> Self {
+        self.dropped.push((at, what));
+        self
+    }
+
+    fn note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+}
+
+fn input(i: usize) -> PinId {
+    PinId::input(i as u8)
+}
+
+fn output(i: usize) -> PinId {
+    PinId::output(i as u8)
+}
+
+// A subcircuit's face: its Pins in osmilog's order, and where Logisim's
+// default box puts each relative to an instance's location.
+struct Interface {
+    input_widths: Vec<u8>,
+    output_widths: Vec<u8>,
+    // None unless the circuit is drawn as the default box.
+    ports: Option<Vec<Port>>,
+}
+
+impl Interface {
+    fn of(circuit: &Circuit) -> Self {
+        // Ordered as the imported circuit will order them: by where its Input
+        // and Output components end up.
+        let specs: Vec<(usize, ComponentSpec, GridPos)> = circuit
+            .pins()
+            .map(|(i, comp)| {
+                let (spec, pin) = pin_spec(comp);
+                let pos = place(&spec.shape(), pin, comp.loc);
+                (i, spec, pos)
+            })
+            .collect();
+        let (inputs, outputs) = boundary_pins(specs.iter().map(|(i, spec, pos)| (spec, *pos, *i)));
+        let width = |i: usize| circuit.comps[i].width("width", 1);
+
+        let ports = circuit.classic.then(|| {
+            let pins: Vec<(usize, Point, Facing)> = circuit
+                .pins()
+                .map(|(i, c)| (i, c.loc, c.facing().unwrap_or(Facing::East)))
+                .collect();
+            default_box(&pins)
+                .into_iter()
+                .map(|(i, at)| match inputs.iter().position(|&k| k == i) {
+                    Some(k) => port(at, input(k), width(i)),
+                    None => {
+                        let k = outputs.iter().position(|&k| k == i).unwrap();
+                        port(at, output(k), width(i))
+                    }
+                })
+                .collect()
+        });
+        Self {
+            input_widths: inputs.iter().map(|&i| width(i)).collect(),
+            output_widths: outputs.iter().map(|&i| width(i)).collect(),
+            ports,
+        }
+    }
+}
+
+// Logisim's default subcircuit box: a pin facing east sits on its west edge
+// (and so on round), each edge's pins 10 pixels apart in the circuit's own
+// top-down (or left-right) order, and the instance's location is the first
+// pin on the east edge - failing that, on the north, west or south one.
+// Returns each pin's offset from that location.
+fn default_box(pins: &[(usize, Point, Facing)]) -> Vec<(usize, Point)> {
+    let edge = |side: Facing| {
+        let mut on: Vec<_> = pins.iter().filter(|p| p.2 == side).copied().collect();
+        match side {
+            Facing::East | Facing::West => on.sort_by_key(|&(_, (x, y), _)| (y, x)),
+            Facing::North | Facing::South => on.sort_by_key(|&(_, at, _)| at),
+        }
+        on
+    };
+    // A pin faces away from the edge it sits on.
+    let (west, east) = (edge(Facing::East), edge(Facing::West));
+    let (north, south) = (edge(Facing::South), edge(Facing::North));
+    let (n_w, n_e, n_n, n_s) = (
+        west.len() as i32,
+        east.len() as i32,
+        north.len() as i32,
+        south.len() as i32,
+    );
+    let (max_vert, max_horz) = (n_n.max(n_s), n_e.max(n_w));
+    let offset = |facing: i32, opposite: i32, others: i32| {
+        let most = facing.max(opposite);
+        let base = match most {
+            0 | 1 if others == 0 => 15,
+            0..=2 => 10,
+            _ if others == 0 => 5,
+            _ => 10,
+        };
+        base + 10 * ((most - facing) / 2)
+    };
+    let dimension = |this: i32, others: i32| match (this, others) {
+        (0..=2, _) => 30,
+        (_, 0) => 10 * this,
+        _ => 10 * this + 10,
+    };
+    let (off_n, off_s) = (offset(n_n, n_s, max_horz), offset(n_s, n_n, max_horz));
+    let (off_e, off_w) = (offset(n_e, n_w, max_vert), offset(n_w, n_e, max_vert));
+    let (width, height) = (dimension(max_vert, max_horz), dimension(max_horz, max_vert));
+    let anchor = if n_e > 0 {
+        (width, off_e)
+    } else if n_n > 0 {
+        (off_n, 0)
+    } else if n_w > 0 {
+        (0, off_w)
+    } else {
+        (off_s, height)
+    };
+
+    let mut out = Vec::new();
+    let mut line = |on: &[(usize, Point, Facing)], (x, y): Point, (dx, dy): Point| {
+        for (k, &(i, ..)) in on.iter().enumerate() {
+            let k = k as i32;
+            out.push((i, (x + k * dx - anchor.0, y + k * dy - anchor.1)));
+        }
+    };
+    line(&west, (0, off_w), (0, 10));
+    line(&east, (width, off_e), (0, 10));
+    line(&north, (off_n, 0), (10, 0));
+    line(&south, (off_s, height), (10, 0));
+    out
+}
+
+// A Pin's spec, and the osmilog pin its one port becomes.
+fn pin_spec(comp: &Comp) -> (ComponentSpec, PinId) {
+    if comp.flag("output", false) {
+        let spec = ComponentSpec::Output(Output {
+            label: comp.label(),
+        });
+        (spec, input(0))
+    } else {
+        let spec = ComponentSpec::Input(Input {
+            label: comp.label(),
+            ..Input::new(0, comp.width("width", 1))
+        });
+        (spec, output(0))
+    }
+}
+
+// Where a component goes so that `pin` lands at Logisim point `at`.
+fn place(shape: &ComponentShape, pin: PinId, at: Point) -> GridPos {
+    let offset = pin_grid_pos(shape, GridPos::ZERO, pin);
+    let at = cell(at);
+    GridPos::new(at.x - offset.x, at.y - offset.y)
+}
+
+fn anchor(shape: &ComponentShape, pin: PinId) -> &PinAnchor {
+    match pin {
+        PinId::In(InIdx(i)) => &shape.input_anchors[i as usize],
+        PinId::Out(OutIdx(i)) => &shape.output_anchors[i as usize],
+    }
+}
+
+struct Project<'a> {
+    circuits: &'a [Circuit<'a>],
+    interfaces: &'a [Interface],
+    gate_inputs: u64,
+}
+
+impl Project<'_> {
+    // Maps one component, with its ports relative to its location. Err is
+    // why it can't be imported at all.
+    fn part(&self, comp: &Comp) -> Result<Part, String> {
+        let Some(lib) = comp.lib else {
+            return self.subcircuit(comp);
+        };
+        match lib {
+            "#Wiring" => wiring(comp),
+            "#Gates" => self.gate(comp),
+            "#Plexers" => plexer(comp),
+            "#Arithmetic" => arithmetic(comp),
+            "#Memory" => memory(comp),
+            _ => Err(format!("is not supported (library {lib})")),
+        }
+    }
+
+    fn subcircuit(&self, comp: &Comp) -> Result<Part, String> {
+        let index = self
+            .circuits
+            .iter()
+            .position(|c| c.name == comp.name)
+            .ok_or("names a circuit this project doesn't have")?;
+        let interface = &self.interfaces[index];
+        let spec = ComponentSpec::Subcircuit {
+            doc: DocId::default(),
+            name: comp.name.to_string(),
+            input_widths: interface.input_widths.clone(),
+            output_widths: interface.output_widths.clone(),
+        };
+        let mut part = match (&interface.ports, comp.facing()) {
+            (Some(ports), Some(Facing::East)) => {
+                // Its location is a pin, so start from that one.
+                let mut ports = ports.clone();
+                if let Some(i) = ports.iter().position(|p| p.at == (0, 0)) {
+                    ports.swap(0, i);
+                }
+                Part::spec(spec, ports)
+            }
+            (None, _) => Part::unwired(
+                spec,
+                "was left unwired: its circuit has a custom or non-classic appearance".into(),
+            ),
+            _ => Part::unwired(spec, "was left unwired: it doesn't face east".into()),
+        };
+        part.circuit = Some(index);
+        Ok(part)
+    }
+
+    fn gate(&self, comp: &Comp) -> Result<Part, String> {
+        let facing = comp.facing().ok_or("has an unknown facing")?;
+        let width = comp.width("width", 1);
+        let (op, negated_output, xor_bonus) = match comp.name {
+            "NOT Gate" => {
+                let spec = ComponentSpec::Gate(Gate {
+                    op: GateOp::Not,
+                    n_inputs: 1,
+                    width,
+                });
+                let size = comp.number("size", 30) as i32;
+                let ports = vec![
+                    port((0, 0), output(0), width),
+                    port(facing.turn_gate((-size, 0)), input(0), width),
+                ];
+                return Ok(Part::spec(spec, ports));
+            }
+            "Buffer" => {
+                let ports = vec![
+                    port((0, 0), output(0), width),
+                    port(facing.turn_gate((-20, 0)), input(0), width),
+                ];
+                return Ok(Part::new(Body::Joint, ports));
+            }
+            "Controlled Buffer" => {
+                let spec = ComponentSpec::TriState(TriState { data_width: width });
+                let side = if comp.attr("control") == Some("left") {
+                    -10
+                } else {
+                    10
+                };
+                let ports = vec![
+                    port((0, 0), output(0), width),
+                    port(facing.rotate((-20, 0)), input(0), width),
+                    port(facing.rotate((-10, side)), input(1), 1),
+                ];
+                return Ok(Part::spec(spec, ports));
+            }
+            "AND Gate" => (GateOp::And, false, false),
+            "OR Gate" => (GateOp::Or, false, false),
+            "NAND Gate" => (GateOp::Nand, true, false),
+            "NOR Gate" => (GateOp::Nor, true, false),
+            "XOR Gate" => (GateOp::Xor, false, true),
+            "XNOR Gate" => (GateOp::Xnor, true, true),
+            "Odd Parity" => (GateOp::Xor, false, false),
+            "Even Parity" => (GateOp::Xnor, false, false),
+            _ => return Err("is not supported".to_string()),
+        };
+        let n = (comp.number("inputs", self.gate_inputs) as usize).max(2);
+        let size = comp.number("size", 50) as i32;
+        let axis = size + if xor_bonus { 10 } else { 0 } + if negated_output { 10 } else { 0 };
+        let negated = |i: usize| comp.flag(&format!("negate{i}"), false);
+        let mut ports = vec![port((0, 0), output(0), width)];
+        for i in 0..n {
+            let bubble = if negated(i) { 10 } else { 0 };
+            let at = gate_input(n, size, axis + bubble, i);
+            ports.push(port(facing.turn_gate(at), input(i), width));
+        }
+        let spec = ComponentSpec::Gate(Gate {
+            op,
+            n_inputs: n,
+            width,
+        });
+        let mut part = Part::spec(spec, ports);
+        if (0..n).any(negated) {
+            part = part.note("has negated inputs, imported without their bubbles");
+        }
+        if xor_bonus && n > 2 && comp.attr("xor") != Some("odd") {
+            part = part.note("is one-hot with more than two inputs, imported as odd parity");
+        }
+        Ok(part)
+    }
+}
+
+// Where Logisim puts input `index` of an `n`-input gate facing east, relative
+// to its output: `axis` pixels behind it, spread out by the gate's size.
+fn gate_input(n: usize, size: i32, axis: i32, index: usize) -> Point {
+    let (start, step, lower) = if n <= 3 {
+        if size < 40 {
+            (-5, 10, 10)
+        } else if size < 60 || n <= 2 {
+            (-10, 20, 20)
+        } else {
+            (-15, 30, 30)
+        }
+    } else if n == 4 && size >= 60 {
+        (-5, 20, 0)
+    } else {
+        (-5, 10, 10)
+    };
+    let (n, i) = (n as i32, index as i32);
+    let y = if n % 2 == 1 {
+        start * (n - 1) + step * i
+    } else {
+        start * n + step * i + if i >= n / 2 { lower } else { 0 }
+    };
+    (-axis, y)
+}
+
+fn wiring(comp: &Comp) -> Result<Part, String> {
+    let width = comp.width("width", 1);
+    let spec = match comp.name {
+        "Pin" => {
+            let (spec, pin) = pin_spec(comp);
+            return Ok(Part::spec(spec, vec![port((0, 0), pin, width)]));
+        }
+        "Tunnel" => {
+            let label = comp.label();
+            if label.is_empty() {
+                return Err("has no label, so connects nothing".to_string());
+            }
+            return Ok(Part::new(
+                Body::Tunnel(label),
+                vec![port((0, 0), input(0), 0)],
+            ));
+        }
+        "Probe" => {
+            let spec = ComponentSpec::Probe(Probe { name: comp.label() });
+            return Ok(Part::spec(spec, vec![port((0, 0), input(0), 0)]));
+        }
+        "Splitter" => return splitter(comp),
+        "Constant" => ComponentSpec::Constant(Constant {
+            bits: comp.number("value", 1) & Value::mask(width),
+            width,
+        }),
+        "Ground" => ComponentSpec::Constant(Constant { bits: 0, width }),
+        "Power" => ComponentSpec::Constant(Constant {
+            bits: Value::mask(width),
+            width,
+        }),
+        "Clock" => ComponentSpec::ClockSource(ClockSourceConf {
+            high_ticks: comp.number("highDuration", 1).max(1) as u32,
+            low_ticks: comp.number("lowDuration", 1).max(1) as u32,
+        }),
+        "Pull Resistor" => {
+            let direction = match comp.attr("pull").unwrap_or("0") {
+                "0" => PullDirection::Down,
+                "1" => PullDirection::Up,
+                _ => return Err("pulls to X, which osmilog has no resistor for".to_string()),
+            };
+            // Its width follows the net; see `Project::import`.
+            let spec = ComponentSpec::PullResistor(PullResistor {
+                direction,
+                width: 1,
+            });
+            return Ok(Part::spec(spec, vec![port((0, 0), output(0), 0)]));
+        }
+        _ => return Err("is not supported".to_string()),
+    };
+    Ok(Part::spec(spec, vec![port((0, 0), output(0), width)]))
+}
+
+fn splitter(comp: &Comp) -> Result<Part, String> {
+    let facing = comp.facing().ok_or("has an unknown facing")?;
+    let fanout = comp.number("fanout", 2).clamp(1, 64) as usize;
+    let width = comp.width("incoming", 2);
+
+    // Logisim deals the bits out in order, as evenly as it can, unless a
+    // `bitN` attribute routes bit N elsewhere (or, as "none", nowhere).
+    let mut arm_bits = vec![Vec::new(); fanout];
+    for bit in 0..width as usize {
+        let default = if fanout >= width as usize {
+            Some(bit)
+        } else {
+            let (per, extra) = (width as usize / fanout, width as usize % fanout);
+            let arm = if bit < extra * (per + 1) {
+                bit / (per + 1)
+            } else {
+                extra + (bit - extra * (per + 1)) / per
+            };
+            Some(arm)
+        };
+        let arm = match comp.attr(&format!("bit{bit}")) {
+            Some("none") => None,
+            Some(n) => n.parse().ok().filter(|&a: &usize| a < fanout),
+            None => default,
+        };
+        if let Some(arm) = arm {
+            arm_bits[arm].push(bit as u8);
+        }
+    }
+    let arm_widths: Vec<u8> = arm_bits.iter().map(|b| b.len() as u8).collect();
+    let spec = ComponentSpec::Splitter {
+        width,
+        arm_bits,
+        direction: FanDirection::Right,
+    };
+    if comp.number("spacing", 1) != 1 {
+        return Ok(Part::unwired(
+            spec,
+            "was left unwired: only unit arm spacing is supported".into(),
+        ));
+    }
+
+    // The trunk is at the location; the arms stand 20 pixels ahead of it, to
+    // its left, right or centred on it as `appear` says.
+    let justify = match comp.attr("appear") {
+        Some("center") | Some("legacy") => 0,
+        Some("right") => 1,
+        _ => -1,
+    };
+    let n = fanout as i32;
+    let arm = |i: i32| match facing {
+        Facing::East | Facing::West => {
+            let m = if facing == Facing::West { -1 } else { 1 };
+            let y0 = match justify {
+                0 => -10 * (n / 2),
+                _ if m * justify > 0 => 10,
+                _ => -10 * n,
+            };
+            (m * 20, y0 + 10 * i)
+        }
+        Facing::North | Facing::South => {
+            let m = if facing == Facing::North { 1 } else { -1 };
+            let x0 = match justify {
+                0 => 10 * ((n + 1) / 2 - 1),
+                _ if m * justify < 0 => -10,
+                _ => 10 * n,
+            };
+            (x0 - 10 * i, -m * 20)
+        }
+    };
+    let mut ports = vec![port((0, 0), input(0), width)];
+    for (j, &w) in arm_widths.iter().enumerate() {
+        ports.push(port(arm(j as i32), output(j), w));
+    }
+    Ok(Part::spec(spec, ports))
+}
+
+// Turns a splitter into a combiner: arms in, trunk out.
+fn combine(part: &mut Part) {
+    if let Body::Spec(ComponentSpec::Splitter { direction, .. }) = &mut part.body {
+        *direction = FanDirection::Left;
+    }
+    for (k, p) in part.ports.iter_mut().flatten().enumerate() {
+        p.pin = if k == 0 { output(0) } else { input(k - 1) };
+    }
+}
+
+fn is_splitter(part: &Part) -> bool {
+    matches!(part.body, Body::Spec(ComponentSpec::Splitter { .. }))
+}
+
+fn plexer(comp: &Comp) -> Result<Part, String> {
+    let width = comp.width("width", 1);
+    let select = comp.width("select", 1).min(5);
+    let (spec, data) = match comp.name {
+        "Multiplexer" => (
+            ComponentSpec::Mux(Mux {
+                data_width: width,
+                sel_width: select,
+            }),
+            -1,
+        ),
+        "Demultiplexer" => (
+            ComponentSpec::Demux(Demux {
+                data_width: width,
+                sel_width: select,
+            }),
+            1,
+        ),
+        _ => return Err("is not supported".to_string()),
+    };
+    if comp.facing() != Some(Facing::East) {
+        return Ok(Part::unwired(
+            spec,
+            "was left unwired: only east-facing plexers are supported".into(),
+        ));
+    }
+    // The many-pin side is 30 (two pins) or 40 pixels off the single pin;
+    // `data` says which side that is: behind it for a mux, ahead for a demux.
+    let n = 1i32 << select;
+    let bottom = if matches!(comp.attr("selloc"), Some("tr") | Some("top")) {
+        -1
+    } else {
+        1
+    };
+    let (many, sel): (Vec<Point>, Point) = if n == 2 {
+        (
+            vec![(30 * data, -10), (30 * data, 10)],
+            (20 * data, 20 * bottom),
+        )
+    } else {
+        let y0 = -(n / 2) * 10;
+        let many = (0..n).map(|i| (40 * data, y0 + 10 * i)).collect();
+        (many, (20 * data, bottom * (y0 + 10 * n)))
+    };
+    let enable = (sel.0 - 10 * data, sel.1);
+    let sel_port = port(sel, input(if data < 0 { 0 } else { 1 }), select);
+    let ports = if data < 0 {
+        let mut ports = vec![port((0, 0), output(0), width), sel_port];
+        ports.extend(
+            many.iter()
+                .enumerate()
+                .map(|(i, &at)| port(at, input(i + 1), width)),
+        );
+        ports
+    } else {
+        let mut ports = vec![port((0, 0), input(0), width), sel_port];
+        ports.extend(
+            many.iter()
+                .enumerate()
+                .map(|(i, &at)| port(at, output(i), width)),
+        );
+        ports
+    };
+    let mut part = Part::spec(spec, ports);
+    if comp.flag("enable", true) {
+        part = part.drop_port(enable, "enable input");
+    }
+    Ok(part)
+}
+
+fn arithmetic(comp: &Comp) -> Result<Part, String> {
+    let width = comp.width("width", 8);
+    let (spec, carry_in, carry_out) = match comp.name {
+        "Adder" => (ComponentSpec::Adder(Adder { data_width: width }), 1, 1),
+        "Subtractor" => (
+            ComponentSpec::Subtractor(Subtractor { data_width: width }),
+            1,
+            1,
+        ),
+        "Multiplier" => (
+            ComponentSpec::Multiplier(Multiplier { data_width: width }),
+            width,
+            width,
+        ),
+        "Divider" => (
+            ComponentSpec::Divider(Divider { data_width: width }),
+            width,
+            width,
+        ),
+        "Comparator" => {
+            let spec = ComponentSpec::Comparator(Comparator { data_width: width });
+            let ports = vec![
+                port((0, 0), output(1), 1),
+                port((-40, -10), input(0), width),
+                port((-40, 10), input(1), width),
+                port((0, -10), output(0), 1),
+                port((0, 10), output(2), 1),
+            ];
+            let part = Part::spec(spec, ports);
+            return Ok(if comp.attr("mode") == Some("unsigned") {
+                part
+            } else {
+                part.note("compares two's complement values, imported as unsigned")
+            });
+        }
+        _ => return Err("is not supported".to_string()),
+    };
+    let ports = vec![
+        port((0, 0), output(0), width),
+        port((-40, -10), input(0), width),
+        port((-40, 10), input(1), width),
+        port((-20, -20), input(2), carry_in),
+        port((-20, 20), output(1), carry_out),
+    ];
+    Ok(Part::spec(spec, ports))
+}
+
+fn memory(comp: &Comp) -> Result<Part, String> {
+    let spec = match comp.name {
+        "D Flip-Flop" => ComponentSpec::DFlipFlop(DFlipFlopConf {
+            clock_edge: comp.edge(),
+        }),
+        "T Flip-Flop" => ComponentSpec::TFlipFlop(TFlipFlopConf {
+            clock_edge: comp.edge(),
+        }),
+        "J-K Flip-Flop" => ComponentSpec::JKFlipFlop(JKFlipFlopConf {
+            clock_edge: comp.edge(),
+        }),
+        "S-R Flip-Flop" => ComponentSpec::SRFlipFlop(SRFlipFlopConf {
+            clock_edge: comp.edge(),
+        }),
+        "Register" => ComponentSpec::Reg(RegConf {
+            data_width: comp.width("width", 8),
+            clock_edge: comp.edge(),
+        }),
+        "Counter" => {
+            let width = comp.width("width", 8);
+            ComponentSpec::Counter(CounterConf {
+                data_width: width,
+                max_value: comp.number("max", Value::mask(width)) & Value::mask(width),
+                overflow_action: match comp.attr("ongoal") {
+                    Some("stay") => OverflowAction::StayMax,
+                    Some("continue") => OverflowAction::PassMax,
+                    Some("load") => OverflowAction::LoadNext,
+                    _ => OverflowAction::Wrap,
+                },
+                clock_edge: comp.edge(),
+            })
+        }
+        "ROM" => {
+            let (rom, note) = rom(comp);
+            let part = classic_memory(comp, ComponentSpec::Rom(rom))?;
+            return Ok(match note {
+                Some(note) => part.note(note),
+                None => part,
+            });
+        }
+        "RAM" => {
+            let address_width = comp.width("addrWidth", 8).min(MAX_ADDRESS_WIDTH);
+            let ram = Ram::new(
+                comp.width("dataWidth", 8),
+                address_width,
+                ReadBehavior::default(),
+            );
+            ComponentSpec::Ram(ram)
+        }
+        _ => return Err("is not supported".to_string()),
+    };
+    let part = classic_memory(comp, spec)?;
+    Ok(match comp.attr("trigger") {
+        Some("high") | Some("low") => part.note("is level-triggered, imported as rising-edge"),
+        _ => part,
+    })
+}
+
+// Ports of Logisim's classic memory components.
+fn classic_memory(comp: &Comp, spec: ComponentSpec) -> Result<Part, String> {
+    if !comp.classic() {
+        return Ok(Part::unwired(
+            spec,
+            "was left unwired: only the classic appearance is supported".into(),
+        ));
+    }
+    let part = match &spec {
+        // Q top right with Q' below it; data (or J/S over K/R) on the left
+        // with the clock below or between them; reset, enable and preset
+        // along the bottom.
+        ComponentSpec::DFlipFlop(_) | ComponentSpec::TFlipFlop(_) => {
+            let ports = vec![
+                port((0, 0), output(0), 1),
+                port((-40, 0), input(0), 1),
+                port((-20, 30), input(1), 1),
+                port((-10, 30), input(2), 1),
+                port((-40, 20), input(3), 1),
+            ];
+            Part::spec(spec, ports)
+        }
+        ComponentSpec::JKFlipFlop(_) | ComponentSpec::SRFlipFlop(_) => {
+            let ports = vec![
+                port((0, 0), output(0), 1),
+                port((-40, 0), input(0), 1),
+                port((-40, 20), input(1), 1),
+                port((-20, 30), input(2), 1),
+                port((-10, 30), input(3), 1),
+                port((-40, 10), input(4), 1),
+            ];
+            Part::spec(spec, ports)
+        }
+        ComponentSpec::Reg(r) => {
+            let w = r.data_width;
+            let ports = vec![
+                port((0, 0), output(0), w),
+                port((-30, 0), input(RegConf::DATA_PIN), w),
+                port((-30, 10), input(RegConf::WRITE_EN_PIN), 1),
+                port((-10, 20), input(RegConf::RESET_PIN), 1),
+                port((-20, 20), input(RegConf::CLOCK_PIN), 1),
+            ];
+            Part::spec(spec, ports)
+        }
+        ComponentSpec::Counter(c) => {
+            let w = c.data_width;
+            let ports = vec![
+                port((0, 0), output(CounterConf::Q_PIN), w),
+                port((0, 10), output(CounterConf::CARRY_PIN), 1),
+                port((-30, 0), input(CounterConf::DATA_PIN), w),
+                port((-30, -10), input(CounterConf::LOAD_PIN), 1),
+                port((-30, 10), input(CounterConf::COUNT_PIN), 1),
+                port((-20, 20), input(CounterConf::CLOCK_PIN), 1),
+            ];
+            Part::spec(spec, ports).drop_port((-10, 20), "clear input")
+        }
+        ComponentSpec::Rom(r) => {
+            let ports = vec![
+                port((0, 0), output(0), r.data_width),
+                port((-140, 0), input(0), r.address_width),
+            ];
+            Part::spec(spec, ports).drop_port((-90, 40), "chip select")
+        }
+        ComponentSpec::Ram(r) => {
+            if comp.attr("bus") != Some("separate") {
+                return Ok(Part::unwired(
+                    spec,
+                    "was left unwired: only separate load and store ports are supported".into(),
+                ));
+            }
+            let (d, a) = (r.data_width, r.address_width);
+            let ports = vec![
+                port((0, 0), output(Ram::DATA_OUT_PIN), d),
+                port((-140, 0), input(Ram::ADDR_PIN), a),
+                port((-110, 40), input(Ram::WE_PIN), 1),
+                port((-50, 40), input(Ram::LE_PIN), 1),
+                port((-140, 20), input(Ram::DATA_IN_PIN), d),
+                port((-70, 40), input(Ram::CLOCK_PIN), 1),
+            ];
+            Part::spec(spec, ports)
+                .drop_port((-90, 40), "chip select")
+                .drop_port((-30, 40), "clear input")
+        }
+        _ => unreachable!("not a memory component"),
+    };
+    Ok(match part.body {
+        Body::Spec(
+            ComponentSpec::DFlipFlop(_)
+            | ComponentSpec::TFlipFlop(_)
+            | ComponentSpec::JKFlipFlop(_)
+            | ComponentSpec::SRFlipFlop(_),
+        ) => part
+            .drop_port((0, 20), "inverted output")
+            .drop_port((-30, 30), "preset input"),
+        _ => part,
+    })
+}
+
+// A ROM with Logisim's `contents`: an "addr/data: A D" header, then hex
+// words from address 0, where "N*W" repeats word W N times. The note says
+// what didn't fit.
+fn rom(comp: &Comp) -> (Rom, Option<String>) {
+    let address_width = comp.width("addrWidth", 8);
+    let rom = Rom::new(
+        comp.width("dataWidth", 8),
+        address_width.min(MAX_ADDRESS_WIDTH),
+    );
+    let mut note = (address_width > MAX_ADDRESS_WIDTH)
+        .then(|| format!("has {address_width} address bits, cut to {MAX_ADDRESS_WIDTH}"));
+    let Some(contents) = comp.attr("contents") else {
+        return (rom, note);
+    };
+    let mask = rom.mask();
+    let mut data = rom.data.borrow_mut();
+    let mut addr = 0;
+    let words = contents.lines().skip(1).flat_map(str::split_whitespace);
+    for word in words {
+        let (count, value) = match word.split_once('*') {
+            Some((n, w)) => (n.parse().ok(), w),
+            None => (Some(1), word),
+        };
+        let (Some(count), Ok(value)) = (count, u64::from_str_radix(value, 16)) else {
+            note = Some(format!("has unreadable contents from address {addr:#x} on"));
+            break;
+        };
+        let end = (addr + count).min(data.len());
+        data[addr.min(end)..end].fill(value & mask);
+        addr += count;
+    }
+    drop(data);
+    (rom, note)
+}
+
+// ── Connectivity ─────────────────────────────────────────────────────────
+
+// Union-find over Logisim points.
+#[derive(Clone, Default)]
+struct Nets {
+    ids: HashMap<Point, usize>,
+    parent: Vec<usize>,
+}
+
+impl Nets {
+    fn id(&mut self, p: Point) -> usize {
+        let next = self.parent.len();
+        let id = *self.ids.entry(p).or_insert(next);
+        if id == next {
+            self.parent.push(id);
+        }
+        id
+    }
+
+    fn root(&mut self, p: Point) -> usize {
+        let mut i = self.id(p);
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn join(&mut self, a: Point, b: Point) {
+        let (a, b) = (self.root(a), self.root(b));
+        self.parent[a] = b;
+    }
+}
+
+// Every point on a wire that something else meets there, ends first: other
+// wires' ends on it become junctions, so the wire is cut at each.
+fn wire_points(wires: &[(Point, Point)]) -> Vec<Vec<Point>> {
+    let inside = |&(a, b): &(Point, Point), (x, y): Point| {
+        let between = |v: i32, p: i32, q: i32| p.min(q) < v && v < p.max(q);
+        (a.0 == b.0 && x == a.0 && between(y, a.1, b.1))
+            || (a.1 == b.1 && y == a.1 && between(x, a.0, b.0))
+    };
+    wires
+        .iter()
+        .map(|w| {
+            let mut points = vec![w.0, w.1];
+            for other in wires {
+                for end in [other.0, other.1] {
+                    if inside(w, end) && !points.contains(&end) {
+                        points.push(end);
+                    }
+                }
+            }
+            let (a, b) = (w.0, w.1);
+            points.sort_by_key(|&(x, y)| (x - a.0).abs() + (y - a.1).abs());
+            debug_assert_eq!(points.last(), Some(&b));
+            points
+        })
+        .collect()
+}
+
+// ── Layout ───────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct Builder {
+    snapshot: CircuitSnapshot,
+    // The node standing at each Logisim point that has one.
+    points: HashMap<Point, usize>,
+}
+
+impl Builder {
+    fn node(&mut self, pos: GridPos, attach: NodeAttachEntry) -> usize {
+        self.snapshot.nodes.push(NodeEntry { pos, attach });
+        self.snapshot.nodes.len() - 1
+    }
+
+    fn point(&mut self, p: Point) -> usize {
+        if let Some(&node) = self.points.get(&p) {
+            return node;
+        }
+        let node = self.node(cell(p), NodeAttachEntry::Free);
+        self.points.insert(p, node);
+        node
+    }
+
+    fn segment(&mut self, a: usize, b: usize) {
+        if a != b {
+            self.snapshot.segments.push(SegEntry { a, b });
+        }
+    }
+
+    // Ties a pin at `pos` to Logisim point `p`: on it if it's there (and the
+    // point's node is still free), otherwise by a straight or L-shaped stub
+    // leaving the pin along `dir`.
+    fn link(&mut self, attach: NodeAttachEntry, pos: GridPos, dir: &PinAnchor, p: Point) {
+        let target = self.point(p);
+        let to = self.snapshot.nodes[target].pos;
+        if to == pos && matches!(self.snapshot.nodes[target].attach, NodeAttachEntry::Free) {
+            self.snapshot.nodes[target].attach = attach;
+            return;
+        }
+        let pin = self.node(pos, attach);
+        if pos.x == to.x || pos.y == to.y {
+            self.segment(pin, target);
+        } else {
+            let corner = if dir.wire_dir.x != 0.0 {
+                GridPos::new(to.x, pos.y)
+            } else {
+                GridPos::new(pos.x, to.y)
+            };
+            let corner = self.node(corner, NodeAttachEntry::Free);
+            self.segment(pin, corner);
+            self.segment(corner, target);
+        }
+    }
+}
+
+impl Project<'_> {
+    fn import(&self, circuit: &Circuit, unsupported: &mut Vec<String>) -> CircuitEntry {
+        let mut report = |comp: &Comp, why: &str| {
+            let (x, y) = comp.loc;
+            unsupported.push(format!(
+                "{}: {} at ({x},{y}) {why}",
+                circuit.name, comp.name
+            ));
+        };
+
+        let mut parts: Vec<(&Comp, Part)> = Vec::new();
+        for comp in &circuit.comps {
+            if comp.name == "Text" {
+                continue; // an annotation, not a component
+            }
+            match self.part(comp) {
+                Ok(mut part) => {
+                    for p in part.ports.iter_mut().flatten() {
+                        p.at = add(comp.loc, p.at);
+                    }
+                    for (at, _) in &mut part.dropped {
+                        *at = add(comp.loc, *at);
+                    }
+                    parts.push((comp, part));
+                }
+                Err(why) => report(comp, &why),
+            }
+        }
+
+        // Nets as Logisim sees them: `local` by wires (and buffers) alone,
+        // `nets` with same-labelled tunnels joined too.
+        let wire_points = wire_points(&circuit.wires);
+        let mut local = Nets::default();
+        for points in &wire_points {
+            for pair in points.windows(2) {
+                local.join(pair[0], pair[1]);
+            }
+        }
+        for (_, part) in &parts {
+            if let (Body::Joint, Some([a, b])) = (&part.body, part.ports.as_deref()) {
+                local.join(a.at, b.at);
+            }
+        }
+        let mut nets = local.clone();
+        let mut labels: HashMap<&str, Point> = HashMap::new();
+        for (_, part) in &parts {
+            if let (Body::Tunnel(label), Some([p])) = (&part.body, part.ports.as_deref()) {
+                match labels.get(label.as_str()) {
+                    Some(&first) => nets.join(first, p.at),
+                    None => {
+                        labels.insert(label, p.at);
+                    }
+                }
+            }
+        }
+
+        // Splitters and tunnels go either way in Logisim. A splitter whose
+        // trunk something else drives splits; one whose arms are driven
+        // combines, which may in turn drive another's trunk.
+        let drives = |part: &Part| matches!(part.body, Body::Spec(_));
+        let driven = |nets: &mut Nets, parts: &[(&Comp, Part)], settled: &[bool]| {
+            let mut driven = HashSet::new();
+            for (i, (_, part)) in parts.iter().enumerate() {
+                if drives(part) && settled[i] {
+                    for p in part.ports.iter().flatten() {
+                        if matches!(p.pin, PinId::Out(_)) {
+                            driven.insert(nets.root(p.at));
+                        }
+                    }
+                }
+            }
+            driven
+        };
+        let mut settled: Vec<bool> = parts.iter().map(|(_, p)| !is_splitter(p)).collect();
+        loop {
+            let driven = driven(&mut nets, &parts, &settled);
+            let mut progress = false;
+            for (i, (_, part)) in parts.iter_mut().enumerate() {
+                let Some(ports) = part.ports.as_ref().filter(|_| !settled[i]) else {
+                    continue;
+                };
+                let trunk = driven.contains(&nets.root(ports[0].at));
+                let arms = ports[1..].iter().any(|p| driven.contains(&nets.root(p.at)));
+                if trunk || arms {
+                    if !trunk {
+                        combine(part);
+                    }
+                    settled[i] = true;
+                    progress = true;
+                }
+            }
+            if !progress {
+                break;
+            }
+        }
+        settled.fill(true);
+
+        // A tunnel whose own wires are driven feeds its label (a Pull); the
+        // rest deliver it (Feeds).
+        let locally_driven = driven(&mut local, &parts, &settled);
+        let mut widths: HashMap<usize, u8> = HashMap::new();
+        for (_, part) in &parts {
+            for p in part.ports.iter().flatten() {
+                let w = widths.entry(nets.root(p.at)).or_default();
+                *w = (*w).max(p.width);
+            }
+        }
+
+        // Everything a port could meet at its point.
+        let mut meets: HashMap<Point, usize> = HashMap::new();
+        for points in &wire_points {
+            for &p in points {
+                *meets.entry(p).or_default() += 2;
+            }
+        }
+        for (_, part) in &parts {
+            for p in part.ports.iter().flatten() {
+                *meets.entry(p.at).or_default() += 1;
+            }
+        }
+        let connected = |p: Point| meets.get(&p).is_some_and(|&n| n > 1);
+
+        let mut b = Builder::default();
+        for points in &wire_points {
+            for pair in points.windows(2) {
+                let (x, y) = (b.point(pair[0]), b.point(pair[1]));
+                b.segment(x, y);
+            }
+        }
+        let mut subcircuits = Vec::new();
+        for (comp, part) in parts {
+            for why in &part.notes {
+                report(comp, why);
+            }
+            for &(at, what) in &part.dropped {
+                if connected(at) {
+                    report(comp, &format!("has its {what} wired, which osmilog lacks"));
+                }
+            }
+            let ports = part.ports.unwrap_or_default();
+            match part.body {
+                Body::Spec(mut spec) => {
+                    if let ComponentSpec::PullResistor(pull) = &mut spec {
+                        pull.width = widths[&nets.root(ports[0].at)].max(1);
+                    }
+                    let shape = spec.shape();
+                    let grid_pos = match ports.first() {
+                        Some(first) => place(&shape, first.pin, first.at),
+                        None => cell(comp.loc),
+                    };
+                    let index = b.snapshot.components.len();
+                    if let Some(circuit) = part.circuit {
+                        subcircuits.push(SubcircuitRef {
+                            component: index,
+                            circuit,
+                        });
+                    }
+                    for p in ports.iter().filter(|p| connected(p.at)) {
+                        let (is_input, pin_index) = match p.pin {
+                            PinId::In(InIdx(i)) => (true, i),
+                            PinId::Out(OutIdx(i)) => (false, i),
+                        };
+                        let attach = NodeAttachEntry::Pin {
+                            comp: index,
+                            is_input,
+                            pin_index,
+                        };
+                        let pos = pin_grid_pos(&shape, grid_pos, p.pin);
+                        b.link(attach, pos, anchor(&shape, p.pin), p.at);
+                    }
+                    b.snapshot.components.push(ComponentEntry {
+                        spec,
+                        grid_pos,
+                        delay: None,
+                    });
+                }
+                Body::Tunnel(label) => {
+                    let p = ports[0];
+                    let role = if locally_driven.contains(&local.root(p.at)) {
+                        TunnelRole::Pull
+                    } else {
+                        TunnelRole::Feed
+                    };
+                    let shape = tunnel_shape(role);
+                    let pin = match role {
+                        TunnelRole::Feed => output(0),
+                        TunnelRole::Pull => input(0),
+                    };
+                    let grid_pos = place(&shape, pin, p.at);
+                    let tunnel = b.snapshot.tunnels.len();
+                    b.snapshot.tunnels.push(TunnelEntry {
+                        label,
+                        role,
+                        grid_pos,
+                    });
+                    if connected(p.at) {
+                        let attach = NodeAttachEntry::Tunnel { tunnel };
+                        b.link(attach, cell(p.at), anchor(&shape, pin), p.at);
+                    }
+                }
+                Body::Joint => {
+                    let (x, y) = (b.point(ports[0].at), b.point(ports[1].at));
+                    b.segment(x, y);
+                }
+            }
+        }
+        CircuitEntry {
+            name: circuit.name.to_string(),
+            snapshot: b.snapshot,
+            subcircuits,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::build_circuit;
+    use crate::sim::command::Command;
+
+    // A project of one circuit per (name, body) pair, in Logisim's layout:
+    // the built-in libraries numbered as Logisim numbers them.
+    fn circ(circuits: &[(&str, &str)]) -> String {
+        let mut text = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n\
+             <project source=\"3.8.0\" version=\"1.0\">\n\
+             <lib desc=\"#Wiring\" name=\"0\"/>\n\
+             <lib desc=\"#Gates\" name=\"1\"/>\n\
+             <lib desc=\"#Plexers\" name=\"2\"/>\n\
+             <lib desc=\"#Arithmetic\" name=\"3\"/>\n\
+             <lib desc=\"#Memory\" name=\"4\"/>\n\
+             <lib desc=\"#I/O\" name=\"5\"/>\n",
+        );
+        text += &format!("<main name=\"{}\"/>\n", circuits[0].0);
+        for (name, body) in circuits {
+            text += &format!("<circuit name=\"{name}\">\n{body}</circuit>\n");
+        }
+        text + "</project>\n"
+    }
+
+    fn pin(x: i32, y: i32, label: &str, output: bool) -> String {
+        let dir = if output {
+            "<a name=\"facing\" val=\"west\"/><a name=\"output\" val=\"true\"/>"
+        } else {
+            ""
+        };
+        format!("<comp lib=\"0\" loc=\"({x},{y})\" name=\"Pin\">{dir}<a name=\"label\" val=\"{label}\"/></comp>\n")
+    }
+
+    fn wire(from: Point, to: Point) -> String {
+        format!(
+            "<wire from=\"({},{})\" to=\"({},{})\"/>\n",
+            from.0, from.1, to.0, to.1
+        )
+    }
+
+    // Builds circuit `index`, drives its labelled Inputs and reads one Output.
+    fn eval(file: &ProjectFile, index: usize, inputs: &[(&str, u64)], out: &str) -> Value {
+        let mut built = build_circuit(file, index);
+        let records = &file.circuits[index].snapshot.components;
+        let find = |label: &str| {
+            let i = records
+                .iter()
+                .position(|r| r.spec.port_name() == Some(label));
+            i.unwrap_or_else(|| panic!("no port {label}"))
+        };
+        for &(label, bits) in inputs {
+            let i = find(label);
+            let ComponentSpec::Input(input) = &records[i].spec else {
+                panic!("{label} is not an Input");
+            };
+            let (comp, width) = (built.comp_keys[i], input.width);
+            built.circuit.apply(Command::SetInput { comp, bits, width });
+        }
+        built.circuit.settle().unwrap();
+        built.circuit.read_output(built.comp_keys[find(out)])
+    }
+
+    #[test]
+    fn test_gates_connect_through_wires_and_touching_ports() {
+        // a and b wired into an AND gate; its output touches an output pin
+        // directly, as Logisim allows.
+        let body = pin(100, 80, "a", false)
+            + &pin(100, 120, "b", false)
+            + &wire((100, 80), (150, 80))
+            + &wire((100, 120), (150, 120))
+            + "<comp lib=\"1\" loc=\"(200,100)\" name=\"AND Gate\"/>\n"
+            + &pin(200, 100, "y", true);
+        let imported = import(&circ(&[("main", &body)])).unwrap();
+        assert!(
+            imported.unsupported.is_empty(),
+            "{:?}",
+            imported.unsupported
+        );
+        let file = &imported.file;
+        file.validate().unwrap();
+        for (a, b) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+            let y = eval(file, 0, &[("a", a), ("b", b)], "y");
+            assert_eq!(y, Value::new(a & b, 1), "{a} AND {b}");
+        }
+    }
+
+    #[test]
+    fn test_splitter_arms_reach_through_tunnels() {
+        // A 4-bit x split in two; the upper arm's tunnel reappears by an
+        // output pin elsewhere.
+        let body = pin(100, 100, "x", false).replace("</comp>", "<a name=\"width\" val=\"4\"/></comp>")
+            + "<comp lib=\"0\" loc=\"(120,100)\" name=\"Splitter\"><a name=\"incoming\" val=\"4\"/></comp>\n"
+            + &wire((100, 100), (120, 100))
+            + &wire((140, 90), (160, 90))
+            + "<comp lib=\"0\" loc=\"(160,90)\" name=\"Tunnel\"><a name=\"label\" val=\"hi\"/></comp>\n"
+            + "<comp lib=\"0\" loc=\"(200,200)\" name=\"Tunnel\"><a name=\"label\" val=\"hi\"/></comp>\n"
+            + &wire((200, 200), (220, 200))
+            + &pin(220, 200, "h", true);
+        let imported = import(&circ(&[("main", &body)])).unwrap();
+        assert!(
+            imported.unsupported.is_empty(),
+            "{:?}",
+            imported.unsupported
+        );
+        let snapshot = &imported.file.circuits[0].snapshot;
+        let roles: Vec<_> = snapshot.tunnels.iter().map(|t| t.role).collect();
+        assert_eq!(roles, vec![TunnelRole::Pull, TunnelRole::Feed]);
+        for x in [0b0000, 0b0110, 0b1011] {
+            let h = eval(&imported.file, 0, &[("x", x)], "h");
+            assert_eq!(h, Value::new(x >> 2, 2), "x = {x:04b}");
+        }
+    }
+
+    #[test]
+    fn test_subcircuit_ports_follow_the_default_box() {
+        let inv = pin(100, 100, "a", false)
+            + &wire((100, 100), (120, 100))
+            + "<comp lib=\"1\" loc=\"(150,100)\" name=\"NOT Gate\"/>\n"
+            + &pin(150, 100, "y", true);
+        // The box is 30 pixels wide, located at its output.
+        let main = pin(250, 100, "a", false)
+            + &wire((250, 100), (270, 100))
+            + "<comp loc=\"(300,100)\" name=\"inv\"/>\n"
+            + &pin(300, 100, "y", true);
+        let imported = import(&circ(&[("main", &main), ("inv", &inv)])).unwrap();
+        assert!(
+            imported.unsupported.is_empty(),
+            "{:?}",
+            imported.unsupported
+        );
+        let file = &imported.file;
+        file.validate().unwrap();
+        assert_eq!(file.circuits[0].subcircuits.len(), 1);
+        assert_eq!(file.circuits[0].subcircuits[0].circuit, 1);
+        for a in [0, 1] {
+            assert_eq!(eval(file, 0, &[("a", a)], "y"), Value::new(a ^ 1, 1));
+        }
+    }
+
+    #[test]
+    fn test_rom_contents_load_and_unknown_parts_are_reported() {
+        let rom = "<comp lib=\"4\" loc=\"(300,100)\" name=\"ROM\">\
+                   <a name=\"addrWidth\" val=\"2\"/>\
+                   <a name=\"contents\">addr/data: 2 8\n1 2*ff\n</a></comp>\n";
+        let body = pin(160, 100, "addr", false)
+            .replace("</comp>", "<a name=\"width\" val=\"2\"/></comp>")
+            + rom
+            + &pin(300, 100, "d&amp;q", true)
+            + "<!-- a decoder osmilog lacks -->\n"
+            + "<comp lib=\"2\" loc=\"(50,50)\" name=\"Decoder\"/>\n";
+        let imported = import(&circ(&[("main", &body)])).unwrap();
+        assert_eq!(
+            imported.unsupported,
+            vec!["main: Decoder at (50,50) is not supported".to_string()]
+        );
+        let file = &imported.file;
+        for (addr, word) in [(0, 0x01), (1, 0xff), (2, 0xff), (3, 0x00)] {
+            assert_eq!(eval(file, 0, &[("addr", addr)], "d&q"), Value::new(word, 8));
+        }
+
+        assert!(import("<project><circuit name=\"x\">").is_err());
+        assert!(import("<circuit name=\"x\"/>").is_err());
+    }
+}
@@ -0,0 +1,591 @@
+// Memory images: a ROM's or RAM's words to and from the files other tools
+// read and write - Intel HEX, Motorola S-records, Logisim's "v2.0 raw" and
+// v3 hex, and raw binary. Headless; the memory editor offers them (see
+// gui::memory_editor) through the platform file seam.
+//
+// Intel HEX, S-records and binary hold bytes, so there a word takes
+// `WordLayout::bytes` bytes from byte address `index * bytes`, in the
+// layout's byte order. Logisim's formats hold one hex number per word.
+// Importing fills the whole memory - zeros wherever the image says nothing -
+// and masks each word to the memory's width; an image that runs past the
+// memory's end is refused rather than cut short.
+
+use crate::sim::value::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    IntelHex,
+    SRecord,
+    LogisimRaw,
+    LogisimHex,
+    Binary,
+}
+
+impl Format {
+    pub const ALL: [Format; 5] = [
+        Format::IntelHex,
+        Format::SRecord,
+        Format::LogisimRaw,
+        Format::LogisimHex,
+        Format::Binary,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Format::IntelHex => "Intel HEX",
+            Format::SRecord => "Motorola S-record",
+            Format::LogisimRaw => "Logisim v2.0 raw",
+            Format::LogisimHex => "Logisim v3 hex",
+            Format::Binary => "Raw binary",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::IntelHex => "hex",
+            Format::SRecord => "srec",
+            Format::LogisimRaw | Format::LogisimHex => "txt",
+            Format::Binary => "bin",
+        }
+    }
+
+    // Whether the format holds bytes, laid out by a WordLayout.
+    pub fn is_bytes(self) -> bool {
+        matches!(self, Format::IntelHex | Format::SRecord | Format::Binary)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endian {
+    #[default]
+    Little,
+    Big,
+}
+
+// How a word sits in a byte-oriented image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordLayout {
+    // 1 to 8.
+    pub bytes: u8,
+    pub endian: Endian,
+}
+
+impl WordLayout {
+    // The fewest bytes a `data_width`-bit word fits in, little-endian.
+    pub fn fitting(data_width: u8) -> Self {
+        Self {
+            bytes: data_width.div_ceil(8).clamp(1, 8),
+            endian: Endian::Little,
+        }
+    }
+
+    // Where byte `k` of a word sits in it, in bits.
+    fn shift(self, k: usize) -> usize {
+        let n = self.bytes as usize;
+        8 * match self.endian {
+            Endian::Little => k,
+            Endian::Big => n - 1 - k,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageError {
+    // 1-based; 0 when the error isn't tied to a line.
+    pub line: usize,
+    pub msg: String,
+}
+
+impl std::fmt::Display for ImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            0 => write!(f, "{}", self.msg),
+            line => write!(f, "line {line}: {}", self.msg),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+fn error(line: usize, msg: impl Into<String>) -> ImageError {
+    ImageError {
+        line,
+        msg: msg.into(),
+    }
+}
+
+// Intel HEX and S-records put this many data bytes on a line.
+const RECORD_BYTES: usize = 16;
+// Logisim's formats put this many words on a line.
+const LINE_WORDS: usize = 8;
+
+// A `len`-word memory's contents read from `data`, each word masked to
+// `data_width`. `layout` is only read for the byte-oriented formats.
+pub fn import(
+    format: Format,
+    data: &[u8],
+    len: usize,
+    data_width: u8,
+    layout: WordLayout,
+) -> Result<Vec<u64>, ImageError> {
+    let mut image = Image {
+        words: vec![0; len],
+        layout,
+    };
+    match format {
+        Format::Binary => {
+            for (address, &byte) in data.iter().enumerate() {
+                image.put(address, byte, 0)?;
+            }
+        }
+        Format::IntelHex => read_intel_hex(text(data)?, &mut image)?,
+        Format::SRecord => read_srecords(text(data)?, &mut image)?,
+        // Either Logisim format reads either; the header says which it is.
+        Format::LogisimRaw | Format::LogisimHex => read_logisim(text(data)?, &mut image.words)?,
+    }
+    let mask = Value::mask(data_width);
+    for word in &mut image.words {
+        *word &= mask;
+    }
+    Ok(image.words)
+}
+
+// `words` as a file in `format`. Runs of zero words are left out where the
+// format can say so, as importing fills them back in. A byte-oriented format
+// refuses a word size too small for `data_width` rather than drop high bits.
+pub fn export(
+    format: Format,
+    words: &[u64],
+    data_width: u8,
+    layout: WordLayout,
+) -> Result<Vec<u8>, ImageError> {
+    if format.is_bytes() && (layout.bytes as u32) * 8 < data_width as u32 {
+        return Err(error(
+            0,
+            format!(
+                "{}-byte words can't hold {data_width}-bit data",
+                layout.bytes
+            ),
+        ));
+    }
+    let bytes = |start: usize, end: usize| -> Vec<u8> {
+        (start..end)
+            .map(|address| {
+                let n = layout.bytes as usize;
+                (words[address / n] >> layout.shift(address % n)) as u8
+            })
+            .collect()
+    };
+    let total = words.len() * layout.bytes as usize;
+    Ok(match format {
+        Format::Binary => bytes(0, total),
+        Format::IntelHex => write_intel_hex(total, bytes).into_bytes(),
+        Format::SRecord => write_srecords(total, bytes).into_bytes(),
+        Format::LogisimRaw => write_logisim_raw(words).into_bytes(),
+        Format::LogisimHex => write_logisim_hex(words, data_width).into_bytes(),
+    })
+}
+
+struct Image {
+    words: Vec<u64>,
+    layout: WordLayout,
+}
+
+impl Image {
+    fn put(&mut self, address: usize, byte: u8, line: usize) -> Result<(), ImageError> {
+        let n = self.layout.bytes as usize;
+        let shift = self.layout.shift(address % n);
+        let len = self.words.len();
+        let word = self
+            .words
+            .get_mut(address / n)
+            .ok_or_else(|| past_end(line, len))?;
+        *word = (*word & !(0xFF << shift)) | (byte as u64) << shift;
+        Ok(())
+    }
+}
+
+fn past_end(line: usize, len: usize) -> ImageError {
+    error(
+        line,
+        format!("the image runs past the memory's {len} words"),
+    )
+}
+
+fn text(data: &[u8]) -> Result<&str, ImageError> {
+    std::str::from_utf8(data).map_err(|_| error(0, "not a text file"))
+}
+
+// The non-blank lines of `text`, numbered from 1.
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+fn hex_bytes(digits: &str, line: usize) -> Result<Vec<u8>, ImageError> {
+    if !digits.len().is_multiple_of(2) || !digits.is_ascii() {
+        return Err(error(line, "expected pairs of hex digits"));
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16)
+                .map_err(|_| error(line, format!("bad hex byte `{}`", &digits[i..i + 2])))
+        })
+        .collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02X}")).collect()
+}
+
+// ── Intel HEX ─────────────────────────────────────────────────────────────
+
+// Data (00), end-of-file (01), and the segment (02) and linear (04) address
+// records that reach past 64 KiB; start addresses (03, 05) mean nothing to a
+// memory and are skipped.
+fn read_intel_hex(text: &str, image: &mut Image) -> Result<(), ImageError> {
+    let mut base = 0usize;
+    for (line, record) in lines(text) {
+        let Some(digits) = record.strip_prefix(':') else {
+            return Err(error(line, "expected a record starting with `:`"));
+        };
+        let bytes = hex_bytes(digits, line)?;
+        if bytes.len() < 5 || bytes.len() != 5 + bytes[0] as usize {
+            return Err(error(line, "record length doesn't match its byte count"));
+        }
+        if bytes.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)) != 0 {
+            return Err(error(line, "bad checksum"));
+        }
+        let offset = u16::from_be_bytes([bytes[1], bytes[2]]) as usize;
+        let data = &bytes[4..bytes.len() - 1];
+        let value = || data.iter().fold(0usize, |v, &b| v << 8 | b as usize);
+        match bytes[3] {
+            0x00 => {
+                for (i, &byte) in data.iter().enumerate() {
+                    image.put(base + offset + i, byte, line)?;
+                }
+            }
+            0x01 => return Ok(()),
+            0x02 => base = value() << 4,
+            0x04 => base = value() << 16,
+            0x03 | 0x05 => {}
+            kind => return Err(error(line, format!("unknown record type {kind:02X}"))),
+        }
+    }
+    Ok(())
+}
+
+fn write_intel_hex(total: usize, bytes: impl Fn(usize, usize) -> Vec<u8>) -> String {
+    let mut out = String::new();
+    let mut upper = 0;
+    for start in (0..total).step_by(RECORD_BYTES) {
+        let data = bytes(start, (start + RECORD_BYTES).min(total));
+        if data.iter().all(|&b| b == 0) {
+            continue;
+        }
+        if start >> 16 != upper {
+            upper = start >> 16;
+            out.push_str(&intel_record(0, 0x04, &(upper as u16).to_be_bytes()));
+        }
+        out.push_str(&intel_record(start as u16, 0x00, &data));
+    }
+    out.push_str(&intel_record(0, 0x01, &[]));
+    out
+}
+
+fn intel_record(offset: u16, kind: u8, data: &[u8]) -> String {
+    let mut bytes = vec![data.len() as u8];
+    bytes.extend(offset.to_be_bytes());
+    bytes.push(kind);
+    bytes.extend(data);
+    let sum = bytes.iter().fold(0u8, |sum, &b| sum.wrapping_add(b));
+    bytes.push(sum.wrapping_neg());
+    format!(":{}\n", hex(&bytes))
+}
+
+// ── Motorola S-records ────────────────────────────────────────────────────
+
+// Data in S1, S2 and S3 records (2-, 3- and 4-byte addresses); the header
+// (S0), counts (S5, S6) and start addresses (S7 to S9) are checked and
+// skipped.
+fn read_srecords(text: &str, image: &mut Image) -> Result<(), ImageError> {
+    for (line, record) in lines(text) {
+        let mut chars = record.chars();
+        let (Some('S'), Some(kind)) = (chars.next(), chars.next()) else {
+            return Err(error(line, "expected a record starting with `S`"));
+        };
+        let bytes = hex_bytes(chars.as_str(), line)?;
+        if bytes.is_empty() || bytes.len() != 1 + bytes[0] as usize {
+            return Err(error(line, "record length doesn't match its byte count"));
+        }
+        if bytes.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)) != 0xFF {
+            return Err(error(line, "bad checksum"));
+        }
+        let address_bytes = match kind {
+            '1' => 2,
+            '2' => 3,
+            '3' => 4,
+            '0' | '5' | '6' | '7' | '8' | '9' => continue,
+            _ => return Err(error(line, format!("unknown record type S{kind}"))),
+        };
+        if bytes.len() < 2 + address_bytes {
+            return Err(error(line, "record too short for its address"));
+        }
+        let (address, data) = bytes[1..bytes.len() - 1].split_at(address_bytes);
+        let address = address.iter().fold(0usize, |a, &b| a << 8 | b as usize);
+        for (i, &byte) in data.iter().enumerate() {
+            image.put(address + i, byte, line)?;
+        }
+    }
+    Ok(())
+}
+
+// The shortest address records `total` bytes fit in, each closed by its
+// matching start-address record.
+fn write_srecords(total: usize, bytes: impl Fn(usize, usize) -> Vec<u8>) -> String {
+    let (data_kind, end_kind, address_bytes) = match total {
+        0..=0x1_0000 => ('1', '9', 2),
+        0x1_0001..=0x100_0000 => ('2', '8', 3),
+        _ => ('3', '7', 4),
+    };
+    let mut out = srecord('0', &[0, 0]);
+    for start in (0..total).step_by(RECORD_BYTES) {
+        let data = bytes(start, (start + RECORD_BYTES).min(total));
+        if data.iter().all(|&b| b == 0) {
+            continue;
+        }
+        let mut payload = (start as u32).to_be_bytes()[4 - address_bytes..].to_vec();
+        payload.extend(data);
+        out.push_str(&srecord(data_kind, &payload));
+    }
+    out.push_str(&srecord(end_kind, &vec![0; address_bytes]));
+    out
+}
+
+fn srecord(kind: char, payload: &[u8]) -> String {
+    let mut bytes = vec![payload.len() as u8 + 1];
+    bytes.extend(payload);
+    let sum = bytes.iter().fold(0u8, |sum, &b| sum.wrapping_add(b));
+    bytes.push(!sum);
+    format!("S{kind}{}\n", hex(&bytes))
+}
+
+// ── Logisim ───────────────────────────────────────────────────────────────
+
+// "v2.0 raw" and "v3.0 hex words plain": hex words in order, `N*w` for N
+// copies of one. "v3.0 hex words addressed": lines of `address: words…`.
+// `#` starts a comment in all of them.
+fn read_logisim(text: &str, words: &mut [u64]) -> Result<(), ImageError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or("").trim()))
+        .filter(|(_, line)| !line.is_empty());
+    let (line, header) = lines.next().ok_or_else(|| error(0, "empty file"))?;
+    let addressed = match header {
+        "v2.0 raw" | "v3.0 hex words plain" => false,
+        "v3.0 hex words addressed" => true,
+        _ => {
+            return Err(error(
+                line,
+                format!("unsupported Logisim header `{header}`"),
+            ))
+        }
+    };
+    let len = words.len();
+    let mut next = 0;
+    for (line, text) in lines {
+        let mut tokens = text.split_whitespace();
+        if addressed {
+            let address = tokens
+                .next()
+                .and_then(|t| t.strip_suffix(':'))
+                .and_then(|t| usize::from_str_radix(t, 16).ok())
+                .ok_or_else(|| error(line, "expected `address:`"))?;
+            next = address;
+        }
+        for token in tokens {
+            let (count, word) = match token.split_once('*') {
+                Some((count, word)) => (
+                    count
+                        .parse::<usize>()
+                        .map_err(|_| error(line, format!("bad repeat count `{count}`")))?,
+                    word,
+                ),
+                None => (1, token),
+            };
+            let word = u64::from_str_radix(word, 16)
+                .map_err(|_| error(line, format!("bad hex word `{word}`")))?;
+            let end = next.checked_add(count).filter(|&end| end <= len);
+            let end = end.ok_or_else(|| past_end(line, len))?;
+            words[next..end].fill(word);
+            next = end;
+        }
+    }
+    Ok(())
+}
+
+fn write_logisim_raw(words: &[u64]) -> String {
+    let used = words.iter().rposition(|&w| w != 0).map_or(0, |i| i + 1);
+    let mut tokens = Vec::new();
+    let mut rest = &words[..used];
+    while let Some(&word) = rest.first() {
+        let run = rest.iter().take_while(|&&w| w == word).count();
+        // A run is only shorter written as N*w from four on.
+        if run >= 4 {
+            tokens.push(format!("{run}*{word:x}"));
+        } else {
+            tokens.extend(std::iter::repeat_n(format!("{word:x}"), run));
+        }
+        rest = &rest[run..];
+    }
+    let mut out = String::from("v2.0 raw\n");
+    for line in tokens.chunks(LINE_WORDS) {
+        out.push_str(&line.join(" "));
+        out.push('\n');
+    }
+    out
+}
+
+fn write_logisim_hex(words: &[u64], data_width: u8) -> String {
+    let digits = data_width.div_ceil(4) as usize;
+    let address_digits = format!("{:x}", words.len().saturating_sub(1)).len();
+    let mut out = String::from("v3.0 hex words addressed\n");
+    for (row, chunk) in words.chunks(LINE_WORDS).enumerate() {
+        if chunk.iter().all(|&w| w == 0) {
+            continue;
+        }
+        let line: Vec<String> = chunk.iter().map(|w| format!("{w:0digits$x}")).collect();
+        out.push_str(&format!(
+            "{:0address_digits$x}: {}\n",
+            row * LINE_WORDS,
+            line.join(" ")
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<u64> {
+        let mut words = vec![0; 64];
+        words[..6].copy_from_slice(&[0x1234, 0xFFFF, 7, 7, 7, 7]);
+        words[40] = 0xBEEF;
+        words
+    }
+
+    #[test]
+    fn test_every_format_round_trips() {
+        let words = sample();
+        let layouts = [
+            WordLayout::fitting(16),
+            WordLayout {
+                bytes: 3,
+                endian: Endian::Big,
+            },
+        ];
+        for format in Format::ALL {
+            for layout in layouts {
+                let file = export(format, &words, 16, layout).unwrap();
+                let read = import(format, &file, words.len(), 16, layout);
+                assert_eq!(read, Ok(words.clone()), "{}", format.label());
+            }
+        }
+    }
+
+    #[test]
+    fn test_byte_formats_lay_words_out_in_byte_order() {
+        let words = [0x1234, 0x00AB];
+        let little = WordLayout::fitting(16);
+        let big = WordLayout {
+            bytes: 2,
+            endian: Endian::Big,
+        };
+        assert_eq!(
+            export(Format::Binary, &words, 16, little),
+            Ok(vec![0x34, 0x12, 0xAB, 0x00])
+        );
+        assert_eq!(
+            export(Format::Binary, &words, 16, big),
+            Ok(vec![0x12, 0x34, 0x00, 0xAB])
+        );
+        // A short last word is padded; every word is masked to the width.
+        assert_eq!(
+            import(Format::Binary, &[0xFF, 0x1F, 0x05], 2, 12, little),
+            Ok(vec![0xFFF, 0x005])
+        );
+    }
+
+    #[test]
+    fn test_export_refuses_words_too_small_for_the_data() {
+        let words = [0x1234, 0x00AB];
+        let one_byte = WordLayout {
+            bytes: 1,
+            endian: Endian::Little,
+        };
+        for format in [Format::Binary, Format::IntelHex, Format::SRecord] {
+            let err = export(format, &words, 16, one_byte).unwrap_err();
+            assert_eq!(err.msg, "1-byte words can't hold 16-bit data");
+        }
+        // Logisim's formats write whole words, so the layout doesn't apply.
+        assert!(export(Format::LogisimHex, &words, 16, one_byte).is_ok());
+        // Wider than needed is fine: the high bytes are zero.
+        assert!(export(Format::Binary, &words, 9, WordLayout::fitting(9)).is_ok());
+    }
+
+    #[test]
+    fn test_reads_files_from_other_tools() {
+        let hex = ":10010000214601360121470136007EFE09D2190140\n:00000001FF\n";
+        let words = import(
+            Format::IntelHex,
+            hex.as_bytes(),
+            512,
+            8,
+            WordLayout::fitting(8),
+        );
+        let words = words.unwrap();
+        assert_eq!(words[0x100..0x104], [0x21, 0x46, 0x01, 0x36]);
+        assert_eq!(words[0x10F], 0x01);
+
+        let srec = "S00600004844521B\nS1137AF00A0A0D0000000000000000000000000061\nS9030000FC\n";
+        let words = import(
+            Format::SRecord,
+            srec.as_bytes(),
+            0x8000,
+            8,
+            WordLayout::fitting(8),
+        );
+        assert_eq!(words.unwrap()[0x7AF0..0x7AF3], [0x0A, 0x0A, 0x0D]);
+
+        let raw = "v2.0 raw\n# a comment\n1 2 3*ff\n a\n";
+        let words = import(
+            Format::LogisimRaw,
+            raw.as_bytes(),
+            8,
+            8,
+            WordLayout::fitting(8),
+        );
+        assert_eq!(words, Ok(vec![1, 2, 0xFF, 0xFF, 0xFF, 0xA, 0, 0]));
+    }
+
+    #[test]
+    fn test_bad_files_name_the_line() {
+        let layout = WordLayout::fitting(8);
+        let bad_sum = ":0100000001FF\n";
+        let e = import(Format::IntelHex, bad_sum.as_bytes(), 4, 8, layout).unwrap_err();
+        assert_eq!(e.to_string(), "line 1: bad checksum");
+
+        let too_long = "v2.0 raw\n1 2\n3*0\n";
+        let e = import(Format::LogisimRaw, too_long.as_bytes(), 4, 8, layout).unwrap_err();
+        assert_eq!(e.line, 3);
+
+        let e = import(Format::LogisimHex, b"v3.0 hex bytes plain\n", 4, 8, layout).unwrap_err();
+        assert!(e.msg.contains("unsupported"));
+    }
+}
@@ -2,8 +2,12 @@
 // exit. The web counterpart (platform/web.rs) mirrors this exact interface with
 // async browser APIs; see platform.rs for how the two are swapped.
 
-use crate::gui::app::OsmilogApp;
+use crate::checkpoint::CHECKPOINT_FILE_EXT;
+use crate::gui::app::{OsmilogApp, PlacedCompKey};
+use crate::gui::memory_editor::MemKind;
 use crate::io::{ProjectFile, CIRCUIT_FILE_EXT};
+use crate::logisim::LOGISIM_FILE_EXT;
+use crate::stimulus::STIMULUS_FILE_EXTS;
 
 // Native dialogs are synchronous, so there's no cross-frame IO state to hold -
 // this is a zero-sized placeholder that mirrors web::IoState's method surface.
@@ -62,7 +66,101 @@ impl IoState {
         }
     }
 
-    // No async load to complete on native (`request_load` is synchronous).
+    // File > Import Logisim: picks a Logisim `.circ` file and imports it in
+    // place of the open project (see OsmilogApp::import_logisim).
+    pub fn request_logisim(&mut self, app: &mut OsmilogApp) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Logisim circuit", &[LOGISIM_FILE_EXT])
+            .pick_file()
+        else {
+            return; // user cancelled
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => app.import_logisim(&text),
+            Err(e) => app.io_error = Some(format!("import failed: {e}")),
+        }
+    }
+
+    // File > Replay Stimulus: picks a VCD or CSV file and opens the replay
+    // dialog on its contents (see OsmilogApp::open_stimulus).
+    pub fn request_stimulus(&mut self, app: &mut OsmilogApp) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("waveform", STIMULUS_FILE_EXTS)
+            .pick_file()
+        else {
+            return; // user cancelled
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                let file_name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                app.open_stimulus(file_name, text);
+            }
+            Err(e) => app.io_error = Some(format!("stimulus import failed: {e}")),
+        }
+    }
+
+    // The Checkpoints window's Load…: picks a checkpoint sidecar file and adds its
+    // checkpoints to the active circuit's (see OsmilogApp::load_checkpoints).
+    pub fn request_checkpoints(&mut self, app: &mut OsmilogApp) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("osmilog checkpoints", &[CHECKPOINT_FILE_EXT])
+            .pick_file()
+        else {
+            return; // user cancelled
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => app.load_checkpoints(&text),
+            Err(e) => app.io_error = Some(format!("checkpoint load failed: {e}")),
+        }
+    }
+
+    // A memory editor's Import…: picks an image file in the window's format
+    // and loads it into that memory (see OsmilogApp::import_memory_image).
+    pub fn request_memory_image(&mut self, app: &mut OsmilogApp, pc: PlacedCompKey, kind: MemKind) {
+        let format = app.active().memory_editor.image_options(kind).format;
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(format.label(), &[format.extension()])
+            .pick_file()
+        else {
+            return; // user cancelled
+        };
+        match std::fs::read(&path) {
+            Ok(bytes) => app.import_memory_image(app.active_id(), pc, kind, &bytes),
+            Err(e) => app.io_error = Some(format!("memory import failed: {e}")),
+        }
+    }
+
+    // Writes generated text (an export such as a VCD trace, not a project) to
+    // a file picked in the OS "Save As" dialog. `file_name` is the suggested
+    // name; its extension sets the dialog's filter.
+    pub fn request_export(&mut self, app: &mut OsmilogApp, file_name: &str, contents: &str) {
+        self.request_export_bytes(app, file_name, contents.as_bytes());
+    }
+
+    // Like `request_export`, for a binary export such as a memory image.
+    pub fn request_export_bytes(&mut self, app: &mut OsmilogApp, file_name: &str, contents: &[u8]) {
+        let ext = std::path::Path::new(file_name)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("");
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(ext, &[ext])
+            .set_file_name(file_name)
+            .save_file()
+        else {
+            return; // user cancelled
+        };
+        if let Err(e) = std::fs::write(path, contents) {
+            app.io_error = Some(format!("export failed: {e}"));
+        }
+    }
+
+    // No async load to complete on native (`request_load`,
+    // `request_logisim`, `request_stimulus`, `request_checkpoints` and
+    // `request_memory_image` are synchronous).
     pub fn poll_pending_load(&mut self, _app: &mut OsmilogApp) {}
 
     // No in-app save modal on native - the OS "Save As" dialog in
@@ -11,19 +11,37 @@ use std::rc::Rc;
 use wasm_bindgen::JsCast;
 use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
 
-use crate::gui::app::OsmilogApp;
+use crate::checkpoint::CHECKPOINT_FILE_EXT;
+use crate::gui::app::{OsmilogApp, PlacedCompKey};
+use crate::gui::document::DocId;
+use crate::gui::memory_editor::MemKind;
 use crate::io::{ProjectFile, CIRCUIT_FILE_EXT};
+use crate::logisim::LOGISIM_FILE_EXT;
+use crate::stimulus::STIMULUS_FILE_EXTS;
 
 // The browser has no synchronous file dialogs, so a spawned load task delivers
 // its outcome into this shared slot; `poll_pending_load` drains it on a later
 // frame.
 type PendingLoad = Rc<RefCell<Option<Result<ProjectFile, String>>>>;
+// Likewise for File > Replay Stimulus: the picked file's name and text.
+type PendingStimulus = Rc<RefCell<Option<Result<(String, String), String>>>>;
+// And for File > Import Logisim: the picked file's text.
+type PendingLogisim = Rc<RefCell<Option<Result<String, String>>>>;
+// And for the Checkpoints window's Load: the picked sidecar file's text.
+type PendingCheckpoints = Rc<RefCell<Option<Result<String, String>>>>;
+// And for a memory editor's Import…: the memory it's for, in the document
+// that was active when it was clicked, and the file's bytes.
+type PendingMemoryImage = Rc<RefCell<Option<(DocId, PlacedCompKey, MemKind, Vec<u8>)>>>;
 
-// Web-only IO state: the async-load delivery slot plus the in-app "Save As"
+// Web-only IO state: the async-load delivery slots plus the in-app "Save As"
 // modal's contents. native::IoState is a ZST with this same method surface -
 // there, poll/drive are no-ops.
 pub struct IoState {
     pending_load: PendingLoad,
+    pending_stimulus: PendingStimulus,
+    pending_logisim: PendingLogisim,
+    pending_checkpoints: PendingCheckpoints,
+    pending_memory_image: PendingMemoryImage,
     // Some(name) while the "Save As" modal is open, holding the text field's
     // current contents; None when closed.
     save_as_dialog: Option<String>,
@@ -33,6 +51,10 @@ impl Default for IoState {
     fn default() -> Self {
         Self {
             pending_load: Rc::new(RefCell::new(None)),
+            pending_stimulus: Rc::new(RefCell::new(None)),
+            pending_logisim: Rc::new(RefCell::new(None)),
+            pending_checkpoints: Rc::new(RefCell::new(None)),
+            pending_memory_image: Rc::new(RefCell::new(None)),
             save_as_dialog: None,
         }
     }
@@ -68,9 +90,120 @@ impl IoState {
         });
     }
 
+    // File > Import Logisim: kicks off the async pick + read of a `.circ`
+    // file; `poll_pending_load` imports it later.
+    pub fn request_logisim(&mut self, _app: &mut OsmilogApp) {
+        let slot = self.pending_logisim.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let Some(handle) = rfd::AsyncFileDialog::new()
+                .add_filter("Logisim circuit", &[LOGISIM_FILE_EXT])
+                .pick_file()
+                .await
+            else {
+                return; // user cancelled
+            };
+            let bytes = handle.read().await;
+            *slot.borrow_mut() = Some(String::from_utf8(bytes).map_err(|e| e.to_string()));
+        });
+    }
+
+    // File > Replay Stimulus: kicks off the async pick + read of a VCD or CSV
+    // file; `poll_pending_load` opens the replay dialog on it later.
+    pub fn request_stimulus(&mut self, _app: &mut OsmilogApp) {
+        let slot = self.pending_stimulus.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let Some(handle) = rfd::AsyncFileDialog::new()
+                .add_filter("waveform", STIMULUS_FILE_EXTS)
+                .pick_file()
+                .await
+            else {
+                return; // user cancelled
+            };
+            let bytes = handle.read().await;
+            let outcome = String::from_utf8(bytes)
+                .map(|text| (handle.file_name(), text))
+                .map_err(|e| e.to_string());
+            *slot.borrow_mut() = Some(outcome);
+        });
+    }
+
+    // The Checkpoints window's Load…: kicks off the async pick + read of a checkpoint
+    // sidecar file; `poll_pending_load` adds its checkpoints later.
+    pub fn request_checkpoints(&mut self, _app: &mut OsmilogApp) {
+        let slot = self.pending_checkpoints.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let Some(handle) = rfd::AsyncFileDialog::new()
+                .add_filter("osmilog checkpoints", &[CHECKPOINT_FILE_EXT])
+                .pick_file()
+                .await
+            else {
+                return; // user cancelled
+            };
+            let bytes = handle.read().await;
+            *slot.borrow_mut() = Some(String::from_utf8(bytes).map_err(|e| e.to_string()));
+        });
+    }
+
+    // A memory editor's Import…: kicks off the async pick + read of an image
+    // file in the window's format; `poll_pending_load` loads it later.
+    pub fn request_memory_image(&mut self, app: &mut OsmilogApp, pc: PlacedCompKey, kind: MemKind) {
+        let format = app.active().memory_editor.image_options(kind).format;
+        let doc = app.active_id();
+        let slot = self.pending_memory_image.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let Some(handle) = rfd::AsyncFileDialog::new()
+                .add_filter(format.label(), &[format.extension()])
+                .pick_file()
+                .await
+            else {
+                return; // user cancelled
+            };
+            *slot.borrow_mut() = Some((doc, pc, kind, handle.read().await));
+        });
+    }
+
+    // Downloads generated text (an export such as a VCD trace, not a
+    // project) straight away as `file_name` - unlike a project save, an
+    // export needs no naming modal.
+    pub fn request_export(&mut self, _app: &mut OsmilogApp, file_name: &str, contents: &str) {
+        let text = wasm_bindgen::JsValue::from_str(contents);
+        trigger_download(&text, file_name, "text/plain");
+    }
+
+    // Like `request_export`, for a binary export such as a memory image.
+    pub fn request_export_bytes(
+        &mut self,
+        _app: &mut OsmilogApp,
+        file_name: &str,
+        contents: &[u8],
+    ) {
+        let bytes = js_sys::Uint8Array::from(contents);
+        trigger_download(&bytes, file_name, "application/octet-stream");
+    }
+
     // Installs a File > Load result a spawned task has delivered, if any is
-    // waiting. No-op most frames.
+    // waiting, and likewise opens a delivered stimulus file, imports a
+    // delivered Logisim file, adds delivered checkpoints or loads a delivered
+    // memory image. No-op most frames.
     pub fn poll_pending_load(&mut self, app: &mut OsmilogApp) {
+        match self.pending_stimulus.borrow_mut().take() {
+            Some(Ok((file_name, text))) => app.open_stimulus(file_name, text),
+            Some(Err(e)) => app.io_error = Some(format!("stimulus import failed: {e}")),
+            None => {}
+        }
+        match self.pending_logisim.borrow_mut().take() {
+            Some(Ok(text)) => app.import_logisim(&text),
+            Some(Err(e)) => app.io_error = Some(format!("import failed: {e}")),
+            None => {}
+        }
+        match self.pending_checkpoints.borrow_mut().take() {
+            Some(Ok(text)) => app.load_checkpoints(&text),
+            Some(Err(e)) => app.io_error = Some(format!("checkpoint load failed: {e}")),
+            None => {}
+        }
+        if let Some((doc, pc, kind, bytes)) = self.pending_memory_image.borrow_mut().take() {
+            app.import_memory_image(doc, pc, kind, &bytes);
+        }
         let Some(outcome) = self.pending_load.borrow_mut().take() else {
             return;
         };
@@ -118,7 +251,11 @@ impl IoState {
         if confirmed {
             let filename = save_filename(name);
             match app.to_project_file().to_json() {
-                Ok(json) => trigger_download(&json, &filename),
+                Ok(json) => trigger_download(
+                    &wasm_bindgen::JsValue::from_str(&json),
+                    &filename,
+                    "application/json",
+                ),
                 Err(e) => app.io_error = Some(format!("save failed: {e}")),
             }
         }
@@ -150,16 +287,17 @@ fn save_filename(base: &str) -> String {
 // click, rather than the File System Access API's save picker (which `rfd`'s
 // wasm backend uses) - that API is Chromium-only. `filename` is used as-is (see
 // `save_filename` for turning a user-typed base name into a well-formed one).
-fn trigger_download(contents: &str, filename: &str) {
+// `contents` is a JS string or a Uint8Array, either of which a Blob takes.
+fn trigger_download(contents: &wasm_bindgen::JsValue, filename: &str, mime: &str) {
     let window = web_sys::window().expect("no window");
     let document = window.document().expect("no document");
 
     let parts = js_sys::Array::new();
-    parts.push(&wasm_bindgen::JsValue::from_str(contents));
+    parts.push(contents);
     let opts = BlobPropertyBag::new();
-    opts.set_type("application/json");
+    opts.set_type(mime);
     let blob =
-        Blob::new_with_str_sequence_and_options(&parts, &opts).expect("failed to build blob");
+        Blob::new_with_u8_array_sequence_and_options(&parts, &opts).expect("failed to build blob");
     let url = Url::create_object_url_with_blob(&blob).expect("failed to create object url");
 
     let anchor: HtmlAnchorElement = document
@@ -0,0 +1,403 @@
+// Builds a live sim `Circuit` from saved circuit records, with no GUI in the
+// loop: what the headless CLI (see cli.rs) runs. The GUI builds its circuits
+// from live documents instead (gui::app::build_doc_circuit), but links nets
+// and orders a subcircuit's boundary pins through the same helpers here, so
+// both agree on what a circuit means.
+
+use std::collections::HashMap;
+
+use crate::gui::geometry::GridPos;
+use crate::io::{NodeAttachEntry, ProjectFile};
+use crate::sim::circuit::{Circuit, TunnelKey};
+use crate::sim::component::{CompKey, Component, ComponentSpec, PinId};
+use crate::sim::test_vector::Port;
+
+// One connected wire group of a saved circuit: the pins (by component record
+// index) and tunnels (by tunnel record index) it ties into a single net.
+pub(crate) struct WireGroup {
+    pub(crate) pins: Vec<(usize, PinId)>,
+    pub(crate) tunnels: Vec<usize>,
+}
+
+// Links a group's pins into one net, anchored on its first pin, and binds its
+// tunnels to that net. A group with no pins carries nothing to link.
+pub(crate) fn link_group(circuit: &mut Circuit, pins: &[(CompKey, PinId)], tunnels: &[TunnelKey]) {
+    let Some(&(anchor_comp, anchor_pin)) = pins.first() else {
+        return;
+    };
+    for &(comp, pin) in &pins[1..] {
+        circuit.link(anchor_comp, anchor_pin, comp, pin);
+    }
+    for &tunnel in tunnels {
+        circuit.link_tunnel(tunnel, anchor_comp, anchor_pin);
+    }
+}
+
+// A circuit's Input and Output components in the order a subcircuit exposes
+// them as pins: top-down, then left-to-right, by grid position. `K` is
+// whatever names a component to the caller: a live key, or a record index.
+pub(crate) fn boundary_pins<'a, K>(
+    components: impl Iterator<Item = (&'a ComponentSpec, GridPos, K)>,
+) -> (Vec<K>, Vec<K>) {
+    let mut inputs: Vec<(GridPos, K)> = Vec::new();
+    let mut outputs: Vec<(GridPos, K)> = Vec::new();
+    for (spec, grid_pos, key) in components {
+        match spec {
+            ComponentSpec::Input(_) => inputs.push((grid_pos, key)),
+            ComponentSpec::Output(_) => outputs.push((grid_pos, key)),
+            _ => {}
+        }
+    }
+    inputs.sort_by_key(|(g, _)| (g.y, g.x));
+    outputs.sort_by_key(|(g, _)| (g.y, g.x));
+    (
+        inputs.into_iter().map(|(_, k)| k).collect(),
+        outputs.into_iter().map(|(_, k)| k).collect(),
+    )
+}
+
+// One project circuit, built and settled.
+pub struct BuiltCircuit {
+    pub circuit: Circuit,
+    // The live key of each record in the entry's `components`, by index.
+    pub comp_keys: Vec<CompKey>,
+    // Boundary Inputs/Outputs in pin order (see boundary_pins).
+    pub inputs: Vec<CompKey>,
+    pub outputs: Vec<CompKey>,
+}
+
+impl BuiltCircuit {
+    // The boundary Inputs and Outputs as named ports, in pin order: by label
+    // (see ComponentSpec::port_name), else `in{i}`/`out{i}` by position.
+    // `file` and `index` must be what this was built from.
+    pub fn boundary_ports(&self, file: &ProjectFile, index: usize) -> (Vec<Port>, Vec<Port>) {
+        let records = &file.circuits[index].snapshot.components;
+        let spec_of = |key: CompKey| {
+            let i = self.comp_keys.iter().position(|&k| k == key).unwrap();
+            &records[i].spec
+        };
+        let port = |prefix: &str, i: usize, key: CompKey| {
+            let spec = spec_of(key);
+            Port {
+                name: spec
+                    .port_name()
+                    .map_or_else(|| format!("{prefix}{i}"), str::to_string),
+                key,
+                width: match spec {
+                    ComponentSpec::Input(input) => Some(input.width),
+                    _ => None,
+                },
+            }
+        };
+        let inputs = self.inputs.iter().enumerate();
+        let outputs = self.outputs.iter().enumerate();
+        (
+            inputs.map(|(i, &key)| port("in", i, key)).collect(),
+            outputs.map(|(i, &key)| port("out", i, key)).collect(),
+        )
+    }
+}
+
+// Builds circuit `index` of a project that has passed `ProjectFile::validate`,
+// each placed subcircuit from the circuit it references. Like the GUI, a
+// subcircuit that can't be built (a reference cycle, or a boundary whose pin
+// counts no longer match its cached interface) becomes an all-Floating
+// placeholder. The cached widths are display-only and aren't compared.
+pub fn build_circuit(file: &ProjectFile, index: usize) -> BuiltCircuit {
+    let mut visited = Vec::new();
+    build_with(file, index, &mut visited)
+}
+
+fn build_with(file: &ProjectFile, index: usize, visited: &mut Vec<usize>) -> BuiltCircuit {
+    let entry = &file.circuits[index];
+    let snapshot = &entry.snapshot;
+    let sub_refs: HashMap<usize, usize> = entry
+        .subcircuits
+        .iter()
+        .map(|s| (s.component, s.circuit))
+        .collect();
+
+    let mut circuit = Circuit::new();
+    let comp_keys: Vec<CompKey> = snapshot
+        .components
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let comp = instantiate(file, &c.spec, sub_refs.get(&i).copied(), visited);
+            circuit.add_component(comp.with_saved_delay(c.delay))
+        })
+        .collect();
+    let tunnel_keys: Vec<TunnelKey> = snapshot
+        .tunnels
+        .iter()
+        .map(|t| circuit.add_tunnel(t.label.clone(), t.role))
+        .collect();
+
+    for group in snapshot_groups(file, index) {
+        let pins: Vec<(CompKey, PinId)> = group
+            .pins
+            .iter()
+            .map(|&(comp, pin)| (comp_keys[comp], pin))
+            .collect();
+        let tunnels: Vec<TunnelKey> = group.tunnels.iter().map(|&t| tunnel_keys[t]).collect();
+        link_group(&mut circuit, &pins, &tunnels);
+    }
+
+    let (inputs, outputs) = boundary_pins(
+        snapshot
+            .components
+            .iter()
+            .zip(&comp_keys)
+            .map(|(c, &key)| (&c.spec, c.grid_pos, key)),
+    );
+    let _ = circuit.settle();
+    BuiltCircuit {
+        circuit,
+        comp_keys,
+        inputs,
+        outputs,
+    }
+}
+
+// `spec.to_component()`, except a Subcircuit is built from the project
+// circuit it references (`sub_ref`).
+fn instantiate(
+    file: &ProjectFile,
+    spec: &ComponentSpec,
+    sub_ref: Option<usize>,
+    visited: &mut Vec<usize>,
+) -> Component {
+    let ComponentSpec::Subcircuit {
+        input_widths,
+        output_widths,
+        ..
+    } = spec
+    else {
+        return spec.to_component();
+    };
+    let placeholder = || Component::subcircuit_placeholder(input_widths.len(), output_widths.len());
+    let Some(target) = sub_ref.filter(|t| !visited.contains(t)) else {
+        return placeholder();
+    };
+    visited.push(target);
+    let inner = build_with(file, target, visited);
+    visited.pop();
+    if inner.inputs.len() == input_widths.len() && inner.outputs.len() == output_widths.len() {
+        Component::subcircuit(inner.circuit, inner.inputs, inner.outputs)
+    } else {
+        placeholder()
+    }
+}
+
+// The connected wire groups of one project circuit, by record index - the
+// file-side twin of gui::wiring::Wiring::groups. Nodes no segment touches are
+// skipped.
+pub(crate) fn snapshot_groups(file: &ProjectFile, index: usize) -> Vec<WireGroup> {
+    let snapshot = &file.circuits[index].snapshot;
+    let mut parent: Vec<usize> = (0..snapshot.nodes.len()).collect();
+    fn find(parent: &mut [usize], x: usize) -> usize {
+        let mut root = x;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        let mut cur = x;
+        while parent[cur] != root {
+            let next = parent[cur];
+            parent[cur] = root;
+            cur = next;
+        }
+        root
+    }
+
+    let mut connected = vec![false; snapshot.nodes.len()];
+    for s in &snapshot.segments {
+        connected[s.a] = true;
+        connected[s.b] = true;
+        let (ra, rb) = (find(&mut parent, s.a), find(&mut parent, s.b));
+        if ra != rb {
+            parent[ra] = rb;
+        }
+    }
+
+    // In order of each group's first node, so the build is deterministic.
+    let mut slot_of_root: HashMap<usize, usize> = HashMap::new();
+    let mut groups: Vec<WireGroup> = Vec::new();
+    for (i, node) in snapshot.nodes.iter().enumerate() {
+        if !connected[i] {
+            continue;
+        }
+        let root = find(&mut parent, i);
+        let slot = *slot_of_root.entry(root).or_insert_with(|| {
+            groups.push(WireGroup {
+                pins: Vec::new(),
+                tunnels: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        let group = &mut groups[slot];
+        match node.attach {
+            NodeAttachEntry::Free => {}
+            NodeAttachEntry::Pin {
+                comp,
+                is_input,
+                pin_index,
+            } => {
+                let pin = if is_input {
+                    PinId::input(pin_index)
+                } else {
+                    PinId::output(pin_index)
+                };
+                group.pins.push((comp, pin));
+            }
+            NodeAttachEntry::Tunnel { tunnel } => group.tunnels.push(tunnel),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::{
+        CircuitEntry, CircuitSnapshot, ComponentEntry, NodeEntry, SegEntry, SubcircuitRef,
+    };
+    use crate::sim::component::{Gate, GateOp, Input};
+    use crate::sim::timing::{Metric, Timing};
+    use crate::sim::value::Value;
+
+    fn pin_node(comp: usize, is_input: bool, pin_index: u8) -> NodeEntry {
+        NodeEntry {
+            pos: GridPos::new(0, 0),
+            attach: NodeAttachEntry::Pin {
+                comp,
+                is_input,
+                pin_index,
+            },
+        }
+    }
+
+    fn component(spec: ComponentSpec, y: i32) -> ComponentEntry {
+        ComponentEntry {
+            spec,
+            grid_pos: GridPos::new(0, y),
+            delay: None,
+        }
+    }
+
+    // Two Inputs into an AND gate, out to an Output; the wire from input 1
+    // runs through a free corner node.
+    fn and_circuit(a: u64, b: u64) -> CircuitEntry {
+        CircuitEntry {
+            name: "And".to_string(),
+            snapshot: CircuitSnapshot {
+                components: vec![
+                    component(ComponentSpec::Input(Input::new(a, 1)), 0),
+                    component(ComponentSpec::Input(Input::new(b, 1)), 2),
+                    component(
+                        ComponentSpec::Gate(Gate {
+                            op: GateOp::And,
+                            n_inputs: 2,
+                            width: 1,
+                        }),
+                        0,
+                    ),
+                    component(ComponentSpec::Output(Default::default()), 0),
+                ],
+                tunnels: vec![],
+                nodes: vec![
+                    pin_node(0, false, 0),
+                    pin_node(2, true, 0),
+                    pin_node(1, false, 0),
+                    NodeEntry {
+                        pos: GridPos::new(1, 1),
+                        attach: NodeAttachEntry::Free,
+                    },
+                    pin_node(2, true, 1),
+                    pin_node(2, false, 0),
+                    pin_node(3, true, 0),
+                ],
+                segments: vec![
+                    SegEntry { a: 0, b: 1 },
+                    SegEntry { a: 2, b: 3 },
+                    SegEntry { a: 3, b: 4 },
+                    SegEntry { a: 5, b: 6 },
+                ],
+            },
+            subcircuits: vec![],
+        }
+    }
+
+    #[test]
+    fn test_build_circuit_links_wire_groups() {
+        let file = ProjectFile::new(0, vec![and_circuit(1, 1)]);
+        let built = build_circuit(&file, 0);
+        assert_eq!(built.inputs, vec![built.comp_keys[0], built.comp_keys[1]]);
+        assert_eq!(built.outputs, vec![built.comp_keys[3]]);
+        assert_eq!(built.circuit.read_output(built.comp_keys[3]), Value::ONE);
+
+        let file = ProjectFile::new(0, vec![and_circuit(1, 0)]);
+        let built = build_circuit(&file, 0);
+        assert_eq!(built.circuit.read_output(built.comp_keys[3]), Value::ZERO);
+    }
+
+    #[test]
+    fn test_build_circuit_applies_saved_delays() {
+        let mut circuit = and_circuit(1, 1);
+        circuit.snapshot.components[2].delay = Some(4);
+        let built = build_circuit(&ProjectFile::new(0, vec![circuit]), 0);
+        let delay = |i: usize| built.circuit.components[&built.comp_keys[i]].delay;
+        assert_eq!(delay(2), 4);
+        assert_eq!(delay(0), 0); // unset: the Input's default
+
+        // So timing by delay reads the saved value, not the gate's level.
+        let worst = |metric| {
+            let timing = Timing::analyze(&built.circuit, metric).unwrap();
+            timing.worst().unwrap().length
+        };
+        assert_eq!(worst(Metric::Levels), 1);
+        assert_eq!(worst(Metric::Delays), 4);
+    }
+
+    #[test]
+    fn test_build_circuit_instantiates_subcircuits() {
+        // Main: two Inputs -> a subcircuit of the AND circuit -> Output.
+        let sub_spec = ComponentSpec::Subcircuit {
+            doc: Default::default(),
+            name: "And".to_string(),
+            input_widths: vec![1, 1],
+            output_widths: vec![1],
+        };
+        let main = CircuitEntry {
+            name: "Main".to_string(),
+            snapshot: CircuitSnapshot {
+                components: vec![
+                    component(ComponentSpec::Input(Input::new(1, 1)), 0),
+                    component(ComponentSpec::Input(Input::new(1, 1)), 1),
+                    component(sub_spec, 0),
+                    component(ComponentSpec::Output(Default::default()), 0),
+                ],
+                tunnels: vec![],
+                nodes: vec![
+                    pin_node(0, false, 0),
+                    pin_node(2, true, 0),
+                    pin_node(1, false, 0),
+                    pin_node(2, true, 1),
+                    pin_node(2, false, 0),
+                    pin_node(3, true, 0),
+                ],
+                segments: vec![
+                    SegEntry { a: 0, b: 1 },
+                    SegEntry { a: 2, b: 3 },
+                    SegEntry { a: 4, b: 5 },
+                ],
+            },
+            subcircuits: vec![SubcircuitRef {
+                component: 2,
+                circuit: 1,
+            }],
+        };
+        let file = ProjectFile::new(0, vec![main, and_circuit(0, 0)]);
+        let built = build_circuit(&file, 0);
+        assert_eq!(built.circuit.read_output(built.comp_keys[3]), Value::ONE);
+    }
+}
@@ -1,5 +1,15 @@
+pub mod batch;
+pub mod breakpoint;
 pub mod circuit;
 pub mod command;
 pub mod component;
+pub mod minimize;
 pub mod net;
+pub mod program;
+pub mod rewind;
+pub mod synth;
+pub mod test_vector;
+pub mod timeline;
+pub mod timing;
+pub mod truth_table;
 pub mod value;
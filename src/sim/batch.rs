@@ -0,0 +1,855 @@
+// Bit-parallel evaluation of combinational circuits: up to LANES independent
+// input vectors run at once, each net bit held as machine words with one bit
+// per lane, so a single pass over the gates settles all of them. Truth tables
+// and tick-free test-vector runs go through it whenever it applies.
+//
+// Only part of the component set compiles: Inputs, Constants, gates,
+// multiplexers and splitters, plus subcircuits made of the same, which are
+// flattened into their parent. Feedback, a net with several drivers, a
+// tunnel or a width conflict is Unsupported too, and the caller falls back to
+// settling one vector at a time. Within that subset every lane reads back
+// exactly the Value settle() would give.
+
+use crate::sim::circuit::Circuit;
+use crate::sim::component::{CompKey, FanDirection, GateOp, InIdx, Logic, LogicComb, OutIdx};
+use crate::sim::net::NetKey;
+use crate::sim::value::{Bit, Value};
+
+use slotmap::SecondaryMap;
+use std::collections::HashMap;
+
+// Input vectors evaluated per pass: one per bit of a u64.
+pub const LANES: usize = 64;
+
+// Why a circuit can't be batched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unsupported {
+    // A component with no bit-parallel form, or a sequential one.
+    Part,
+    // A combinational loop.
+    Loop,
+    // A net with several drivers, or one a tunnel joins to others.
+    SharedNet,
+    // Pins disagreeing on a net's width, which settle() shows as Invalid.
+    Width,
+}
+
+impl std::fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Unsupported::Part => write!(f, "a component has no bit-parallel form"),
+            Unsupported::Loop => write!(f, "the circuit has a combinational loop"),
+            Unsupported::SharedNet => write!(f, "a net has several drivers or a tunnel"),
+            Unsupported::Width => write!(f, "a net's pins disagree on its width"),
+        }
+    }
+}
+
+impl std::error::Error for Unsupported {}
+
+// A net's bits sit at `at..at + width` in each plane.
+#[derive(Debug, Clone, Copy)]
+struct Net {
+    at: usize,
+    width: u8,
+}
+
+// Net operands are indices into Batch::nets (node ids while compiling).
+#[derive(Debug, Clone)]
+enum Op {
+    Gate {
+        op: GateOp,
+        inputs: Vec<usize>,
+        out: usize,
+    },
+    Mux {
+        sel: usize,
+        data: Vec<usize>,
+        out: usize,
+    },
+    // A splitter fanning out: each arm's bits, by trunk bit.
+    Split {
+        trunk: usize,
+        arms: Vec<(usize, Vec<u8>)>,
+    },
+    // A splitter combining: each trunk bit as (arm, bit of it), or None for a
+    // bit no arm owns, which reads 0.
+    Combine {
+        bits: Vec<Option<(usize, u8)>>,
+        out: usize,
+    },
+}
+
+impl Op {
+    fn inputs(&self) -> Vec<usize> {
+        match self {
+            Op::Gate { inputs, .. } => inputs.clone(),
+            Op::Mux { sel, data, .. } => std::iter::once(*sel).chain(data.clone()).collect(),
+            Op::Split { trunk, .. } => vec![*trunk],
+            Op::Combine { bits, .. } => bits.iter().flatten().map(|&(arm, _)| arm).collect(),
+        }
+    }
+
+    fn map(&mut self, mut f: impl FnMut(usize) -> usize) {
+        match self {
+            Op::Gate { inputs, out, .. } => {
+                inputs.iter_mut().for_each(|n| *n = f(*n));
+                *out = f(*out);
+            }
+            Op::Mux { sel, data, out } => {
+                *sel = f(*sel);
+                data.iter_mut().for_each(|n| *n = f(*n));
+                *out = f(*out);
+            }
+            Op::Split { trunk, arms } => {
+                *trunk = f(*trunk);
+                arms.iter_mut().for_each(|(arm, _)| *arm = f(*arm));
+            }
+            Op::Combine { bits, out } => {
+                bits.iter_mut()
+                    .flatten()
+                    .for_each(|(arm, _)| *arm = f(*arm));
+                *out = f(*out);
+            }
+        }
+    }
+}
+
+// What sets a node's value.
+#[derive(Debug, Clone, Copy)]
+enum Driver {
+    // A top-level Input run() sets per lane.
+    Port,
+    // An Input left out of the port list, or a Constant: its current value.
+    Fixed(Value),
+    Op(usize),
+}
+
+// A compiled circuit. Per lane, a bit is 1 in `ones`, 0 in `zeros`, Z in `z`
+// and X in none of them. A lane set in a net's `floating` word reads as
+// Value::Floating, and its bits are kept all Z.
+#[derive(Debug)]
+pub struct Batch {
+    nets: Vec<Net>,
+    ones: Vec<u64>,
+    zeros: Vec<u64>,
+    z: Vec<u64>,
+    floating: Vec<u64>,
+    // In dependency order.
+    ops: Vec<Op>,
+    // The net each port Input drives, with its width; None if unwired.
+    inputs: Vec<(Option<usize>, u8)>,
+    // The net each Output or Probe reads; None if unwired.
+    outputs: Vec<Option<usize>>,
+}
+
+impl Batch {
+    // `inputs` are top-level Inputs, enumerated per lane by run(); every
+    // other Input holds its current value. `outputs` are Outputs or Probes.
+    pub fn compile(
+        circuit: &Circuit,
+        inputs: &[CompKey],
+        outputs: &[CompKey],
+    ) -> Result<Self, Unsupported> {
+        let mut c = Compiler {
+            ports: inputs,
+            ..Default::default()
+        };
+        let top = c.scope(circuit, None)?;
+        let pin_node = |key: &CompKey, pin: Option<NetKey>| {
+            circuit.components.get(key).and(pin).map(|net| top[net])
+        };
+        let inputs: Vec<(Option<usize>, u8)> = inputs
+            .iter()
+            .map(|key| {
+                let comp = &circuit.components[key];
+                let width = comp.output_width(OutIdx(0)).unwrap_or(0);
+                (pin_node(key, comp.pins.outputs[0]), width)
+            })
+            .collect();
+        let outputs: Vec<Option<usize>> = outputs
+            .iter()
+            .map(|key| pin_node(key, circuit.components[key].pins.inputs[0]))
+            .collect();
+        c.finish(inputs, outputs)
+    }
+
+    // Evaluates one input vector per lane, each holding a value per port
+    // Input, and returns each lane's Output values in order.
+    pub fn run(&mut self, vectors: &[Vec<u64>]) -> Vec<Vec<Value>> {
+        puffin::profile_function!();
+        assert!(vectors.len() <= LANES, "at most {LANES} vectors per run");
+        for (port, &(net, width)) in self.inputs.iter().enumerate() {
+            let Some(net) = net else { continue };
+            let at = self.nets[net].at;
+            for bit in 0..width as usize {
+                let ones = vectors
+                    .iter()
+                    .enumerate()
+                    .fold(0, |acc, (lane, v)| acc | ((v[port] >> bit) & 1) << lane);
+                self.ones[at + bit] = ones;
+                self.zeros[at + bit] = !ones;
+                self.z[at + bit] = 0;
+            }
+            self.floating[net] = 0;
+        }
+        for i in 0..self.ops.len() {
+            self.eval(i);
+        }
+        (0..vectors.len())
+            .map(|lane| {
+                self.outputs
+                    .iter()
+                    .map(|&net| net.map_or(Value::Floating, |net| self.value(net, lane)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn value(&self, net: usize, lane: usize) -> Value {
+        if self.floating[net] >> lane & 1 != 0 {
+            return Value::Floating;
+        }
+        let Net { at, width } = self.nets[net];
+        let (mut bits, mut z, mut x) = (0, 0, 0);
+        for i in 0..width as usize {
+            let plane = |p: &[u64]| (p[at + i] >> lane & 1) << i;
+            bits |= plane(&self.ones);
+            z |= plane(&self.z);
+            x |= !(plane(&self.ones) | plane(&self.zeros) | plane(&self.z)) & 1 << i;
+        }
+        Value::from_masks(bits, z, x, width)
+    }
+
+    // Writes one bit of `net`, blanking it to Z on floating lanes.
+    fn set(&mut self, net: usize, bit: usize, ones: u64, zeros: u64, z: u64) {
+        let at = self.nets[net].at + bit;
+        let floating = self.floating[net];
+        self.ones[at] = ones & !floating;
+        self.zeros[at] = zeros & !floating;
+        self.z[at] = z | floating;
+    }
+
+    // Mirrors Value::from_masks: a lane with every bit Z is Floating.
+    fn normalize(&mut self, net: usize) {
+        let Net { at, width } = self.nets[net];
+        let all_z = self.z[at..at + width as usize]
+            .iter()
+            .fold(!0, |m, &z| m & z);
+        self.floating[net] |= all_z;
+        for bit in 0..width as usize {
+            let (ones, zeros, z) = (self.ones[at + bit], self.zeros[at + bit], self.z[at + bit]);
+            self.set(net, bit, ones, zeros, z);
+        }
+    }
+
+    fn eval(&mut self, i: usize) {
+        let planes = |b: &Self, net: usize, bit: usize| {
+            let at = b.nets[net].at + bit;
+            (b.ones[at], b.zeros[at], b.z[at])
+        };
+        match &self.ops[i] {
+            // Gate::evaluate: a Floating input floats the output, and a Z or
+            // X bit only matters where the others don't decide it.
+            Op::Gate { op, inputs, out } => {
+                let (op, out) = (*op, *out);
+                let inputs = match op {
+                    GateOp::Not => inputs[..1].to_vec(),
+                    _ => inputs.clone(),
+                };
+                self.floating[out] = inputs.iter().fold(0, |m, &n| m | self.floating[n]);
+                for bit in 0..self.nets[out].width as usize {
+                    let (mut ones, mut zeros) = match op {
+                        GateOp::And | GateOp::Nand => (!0, 0),
+                        _ => (0, !0),
+                    };
+                    for &n in &inputs {
+                        let (a1, a0, _) = planes(self, n, bit);
+                        (ones, zeros) = match op {
+                            GateOp::And | GateOp::Nand => (ones & a1, zeros | a0),
+                            GateOp::Or | GateOp::Nor => (ones | a1, zeros & a0),
+                            GateOp::Xor | GateOp::Xnor => {
+                                ((ones & a0) | (zeros & a1), (ones & a1) | (zeros & a0))
+                            }
+                            GateOp::Not => (a0, a1),
+                        };
+                    }
+                    if matches!(op, GateOp::Nand | GateOp::Nor | GateOp::Xnor) {
+                        (ones, zeros) = (zeros, ones);
+                    }
+                    self.set(out, bit, ones, zeros, 0);
+                }
+            }
+            // Mux::evaluate: a known selector passes its branch through
+            // whole, a partly known one gives all X.
+            Op::Mux { sel, data, out } => {
+                let (sel, out, data) = (*sel, *out, data.clone());
+                let Net { at, width } = self.nets[sel];
+                let sel_floating = self.floating[sel];
+                let known = (0..width as usize).fold(!sel_floating, |m, bit| {
+                    m & (self.ones[at + bit] | self.zeros[at + bit])
+                });
+                let picks: Vec<u64> = (0..data.len())
+                    .map(|k| {
+                        (0..width as usize).fold(known, |m, bit| {
+                            m & if k >> bit & 1 != 0 {
+                                self.ones[at + bit]
+                            } else {
+                                self.zeros[at + bit]
+                            }
+                        })
+                    })
+                    .collect();
+                self.floating[out] = data
+                    .iter()
+                    .zip(&picks)
+                    .fold(sel_floating, |m, (&d, &pick)| m | (pick & self.floating[d]));
+                for bit in 0..self.nets[out].width as usize {
+                    let (mut ones, mut zeros, mut z) = (0, 0, 0);
+                    for (&d, &pick) in data.iter().zip(&picks) {
+                        let (d1, d0, dz) = planes(self, d, bit);
+                        ones |= pick & d1;
+                        zeros |= pick & d0;
+                        z |= pick & dz;
+                    }
+                    self.set(out, bit, ones, zeros, z);
+                }
+                self.normalize(out);
+            }
+            // Splitter::evaluate, fanning out: a Floating trunk floats every
+            // arm, otherwise each bit lands on its own arm.
+            Op::Split { trunk, arms } => {
+                let (trunk, arms) = (*trunk, arms.clone());
+                for (arm, bits) in arms {
+                    self.floating[arm] = self.floating[trunk];
+                    for (slot, &bit) in bits.iter().enumerate() {
+                        let (ones, zeros, z) = planes(self, trunk, bit as usize);
+                        self.set(arm, slot, ones, zeros, z);
+                    }
+                    self.normalize(arm);
+                }
+            }
+            // Splitter::evaluate, combining: a Floating arm only leaves its
+            // own bits Z.
+            Op::Combine { bits, out } => {
+                let (bits, out) = (bits.clone(), *out);
+                self.floating[out] = 0;
+                for (bit, source) in bits.into_iter().enumerate() {
+                    let (ones, zeros, z) = match source {
+                        Some((arm, slot)) => planes(self, arm, slot as usize),
+                        None => (0, !0, 0),
+                    };
+                    self.set(out, bit, ones, zeros, z);
+                }
+                self.normalize(out);
+            }
+        }
+    }
+}
+
+// Flattens a circuit and its subcircuits into nodes, one per net, then joins
+// the nodes a subcircuit boundary ties together into the batch's nets.
+#[derive(Debug, Default)]
+struct Compiler<'a> {
+    ports: &'a [CompKey],
+    parent: Vec<usize>,
+    // The width a node's pins agree on, if any declares one.
+    declared: Vec<Option<u8>>,
+    drivers: Vec<(usize, Option<u8>, Driver)>,
+    ops: Vec<Op>,
+}
+
+impl Compiler<'_> {
+    fn node(&mut self, declared: Option<u8>) -> usize {
+        self.parent.push(self.parent.len());
+        self.declared.push(declared);
+        self.parent.len() - 1
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parent[a] = b;
+    }
+
+    // Adds `circuit`'s nets and components. `bound` maps a subcircuit's
+    // boundary Inputs to the outer nodes driving them; None at the top.
+    fn scope(
+        &mut self,
+        circuit: &Circuit,
+        bound: Option<&HashMap<CompKey, Option<usize>>>,
+    ) -> Result<SecondaryMap<NetKey, usize>, Unsupported> {
+        if circuit.tunnels.values().any(|t| t.net.is_some()) {
+            return Err(Unsupported::SharedNet);
+        }
+        let mut nodes = SecondaryMap::new();
+        for (key, net) in &circuit.nets {
+            if net.sources.len() > 1 {
+                return Err(Unsupported::SharedNet);
+            }
+            if circuit.net_width_conflict(key) {
+                return Err(Unsupported::Width);
+            }
+            let declared = net
+                .sources
+                .iter()
+                .map(|&(comp, i)| circuit.components[&comp].output_width(i))
+                .chain(
+                    net.sinks
+                        .iter()
+                        .map(|&(comp, i)| circuit.components[&comp].input_width(i)),
+                )
+                .flatten()
+                .next();
+            nodes.insert(key, self.node(declared));
+        }
+
+        for (key, comp) in &circuit.components {
+            let ins: Vec<Option<usize>> = comp
+                .pins
+                .inputs
+                .iter()
+                .map(|n| n.map(|n| nodes[n]))
+                .collect();
+            let outs: Vec<Option<usize>> = comp
+                .pins
+                .outputs
+                .iter()
+                .map(|n| n.map(|n| nodes[n]))
+                .collect();
+            let logic = match &comp.logic {
+                Logic::Comb(logic) => logic,
+                Logic::Seq(_) => return Err(Unsupported::Part),
+                Logic::Sub(sub) => {
+                    let bound: HashMap<CompKey, Option<usize>> = sub
+                        .inputs
+                        .iter()
+                        .copied()
+                        .zip(ins.iter().copied())
+                        .collect();
+                    let inner = self.scope(&sub.inner, Some(&bound))?;
+                    for (i, out) in outs.iter().enumerate() {
+                        let source = sub
+                            .outputs
+                            .get(i)
+                            .and_then(|key| sub.inner.components.get(key))
+                            .and_then(|c| c.pins.inputs[0]);
+                        if let (Some(out), Some(source)) = (out, source) {
+                            self.union(*out, inner[source]);
+                        }
+                    }
+                    continue;
+                }
+            };
+            let width = comp.output_width(OutIdx(0));
+            if width == Some(0) {
+                return Err(Unsupported::Part);
+            }
+            let fixed = || Driver::Fixed(comp.pins.out_cache[0]);
+            match logic {
+                LogicComb::Output | LogicComb::Probe => {}
+                // A subcircuit's boundary Input is its outer net under
+                // another name; a top-level one is a port or holds its value.
+                LogicComb::Input(_) => match bound.and_then(|b| b.get(key)) {
+                    Some(&outer) => {
+                        if let (Some(outer), Some(inner)) = (outer, outs[0]) {
+                            self.union(inner, outer);
+                        }
+                    }
+                    None => {
+                        let driver = if bound.is_none() && self.ports.contains(key) {
+                            Driver::Port
+                        } else {
+                            fixed()
+                        };
+                        self.drive(outs[0], width, driver);
+                    }
+                },
+                LogicComb::Constant(_) => self.drive(outs[0], width, fixed()),
+                LogicComb::Gate(gate) => {
+                    let inputs = self.operands(&ins, |_| Some(gate.width));
+                    self.op(outs[0], width, |out| Op::Gate {
+                        op: gate.op,
+                        inputs,
+                        out,
+                    });
+                }
+                LogicComb::Mux(_) => {
+                    let mut operands = self.operands(&ins, |i| comp.input_width(InIdx(i as u8)));
+                    let sel = operands.remove(0);
+                    self.op(outs[0], width, |out| Op::Mux {
+                        sel,
+                        data: operands,
+                        out,
+                    });
+                }
+                LogicComb::Splitter(splitter) => {
+                    let arm_bits = splitter.arm_bits();
+                    if arm_bits.iter().any(Vec::is_empty) {
+                        return Err(Unsupported::Part);
+                    }
+                    match splitter.direction() {
+                        FanDirection::Right => {
+                            let trunk = self.operands(&ins, |_| Some(splitter.data_width()))[0];
+                            let arms: Vec<(usize, Vec<u8>)> = outs
+                                .iter()
+                                .zip(arm_bits)
+                                .filter_map(|(out, bits)| Some(((*out)?, bits)))
+                                .collect();
+                            let op = self.ops.len();
+                            for &(arm, ref bits) in &arms {
+                                let width = Some(bits.len() as u8);
+                                self.drivers.push((arm, width, Driver::Op(op)));
+                            }
+                            self.ops.push(Op::Split { trunk, arms });
+                        }
+                        FanDirection::Left => {
+                            let arms = self.operands(&ins, |i| Some(arm_bits[i].len() as u8));
+                            let mut bits = vec![None; splitter.data_width() as usize];
+                            for (arm, slots) in arm_bits.iter().enumerate() {
+                                for (slot, &bit) in slots.iter().enumerate() {
+                                    bits[bit as usize] = Some((arms[arm], slot as u8));
+                                }
+                            }
+                            self.op(outs[0], width, |out| Op::Combine { bits, out });
+                        }
+                    }
+                }
+                _ => return Err(Unsupported::Part),
+            }
+        }
+        Ok(nodes)
+    }
+
+    // An operand node per input pin; an unwired pin gets a fresh node of the
+    // pin's width that nothing drives, so it reads Floating.
+    fn operands(
+        &mut self,
+        ins: &[Option<usize>],
+        width: impl Fn(usize) -> Option<u8>,
+    ) -> Vec<usize> {
+        ins.iter()
+            .enumerate()
+            .map(|(i, node)| node.unwrap_or_else(|| self.node(width(i))))
+            .collect()
+    }
+
+    fn drive(&mut self, node: Option<usize>, width: Option<u8>, driver: Driver) {
+        if let Some(node) = node {
+            self.drivers.push((node, width, driver));
+        }
+    }
+
+    // Adds an op writing `out`; one whose output goes nowhere is dropped.
+    fn op(&mut self, out: Option<usize>, width: Option<u8>, op: impl FnOnce(usize) -> Op) {
+        if let Some(out) = out {
+            self.drivers.push((out, width, Driver::Op(self.ops.len())));
+            self.ops.push(op(out));
+        }
+    }
+
+    fn finish(
+        mut self,
+        inputs: Vec<(Option<usize>, u8)>,
+        outputs: Vec<Option<usize>>,
+    ) -> Result<Batch, Unsupported> {
+        // One batch net per group of joined nodes.
+        let mut net_of: HashMap<usize, usize> = HashMap::new();
+        let mut group: Vec<usize> = Vec::with_capacity(self.parent.len());
+        for node in 0..self.parent.len() {
+            let root = self.find(node);
+            let next = net_of.len();
+            group.push(*net_of.entry(root).or_insert(next));
+        }
+        let n = net_of.len();
+
+        let mut driver: Vec<Option<(Option<u8>, Driver)>> = vec![None; n];
+        for &(node, width, d) in &self.drivers {
+            if driver[group[node]].replace((width, d)).is_some() {
+                return Err(Unsupported::SharedNet);
+            }
+        }
+        // A driven net is as wide as its driver, and every pin on it must
+        // agree; an undriven one only ever floats.
+        let mut widths = vec![0u8; n];
+        for (node, &declared) in self.declared.iter().enumerate() {
+            let net = group[node];
+            match (driver[net], declared) {
+                (Some((Some(w), _)), Some(d)) if w != d => return Err(Unsupported::Width),
+                (None, Some(d)) => widths[net] = widths[net].max(d),
+                _ => {}
+            }
+        }
+        for (net, d) in driver.iter().enumerate() {
+            if let Some((Some(w), _)) = d {
+                widths[net] = *w;
+            }
+        }
+
+        let mut ops = std::mem::take(&mut self.ops);
+        for op in &mut ops {
+            op.map(|node| group[node]);
+        }
+        let order = levelize(&ops, &driver)?;
+        let ops: Vec<Op> = order.into_iter().map(|i| ops[i].clone()).collect();
+
+        let mut nets = Vec::with_capacity(n);
+        let mut at = 0;
+        for &width in &widths {
+            nets.push(Net { at, width });
+            at += width as usize;
+        }
+        let mut batch = Batch {
+            nets,
+            ones: vec![0; at],
+            zeros: vec![0; at],
+            z: vec![!0; at],
+            floating: vec![!0; n],
+            ops,
+            inputs: inputs
+                .into_iter()
+                .map(|(node, width)| (node.map(|node| group[node]), width))
+                .collect(),
+            outputs: outputs
+                .into_iter()
+                .map(|node| node.map(|node| group[node]))
+                .collect(),
+        };
+        for (net, d) in driver.iter().enumerate() {
+            if let Some((_, Driver::Fixed(value))) = d {
+                fill(&mut batch, net, *value);
+            }
+        }
+        Ok(batch)
+    }
+}
+
+// Every lane of `net` set to `value`.
+fn fill(batch: &mut Batch, net: usize, value: Value) {
+    batch.floating[net] = match value {
+        Value::Floating | Value::Invalid => !0,
+        _ => 0,
+    };
+    for bit in 0..batch.nets[net].width {
+        let (ones, zeros, z) = match value.bit(bit) {
+            Bit::One => (!0, 0, 0),
+            Bit::Zero => (0, !0, 0),
+            Bit::Z => (0, 0, !0),
+            Bit::X => (0, 0, 0),
+        };
+        batch.set(net, bit as usize, ones, zeros, z);
+    }
+}
+
+// The ops in an order where each comes after the ones driving its inputs.
+fn levelize(
+    ops: &[Op],
+    driver: &[Option<(Option<u8>, Driver)>],
+) -> Result<Vec<usize>, Unsupported> {
+    let mut waiting = vec![0usize; ops.len()];
+    let mut readers: Vec<Vec<usize>> = vec![Vec::new(); ops.len()];
+    for (i, op) in ops.iter().enumerate() {
+        for net in op.inputs() {
+            if let Some((_, Driver::Op(from))) = driver[net] {
+                waiting[i] += 1;
+                readers[from].push(i);
+            }
+        }
+    }
+    let mut ready: Vec<usize> = (0..ops.len()).filter(|&i| waiting[i] == 0).collect();
+    let mut order = Vec::with_capacity(ops.len());
+    while let Some(i) = ready.pop() {
+        order.push(i);
+        for &r in &readers[i] {
+            waiting[r] -= 1;
+            if waiting[r] == 0 {
+                ready.push(r);
+            }
+        }
+    }
+    if order.len() < ops.len() {
+        return Err(Unsupported::Loop);
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::circuit::TunnelRole;
+    use crate::sim::component::{ClockEdge, Component, PinId};
+
+    // Runs every combination of the inputs' bits (at most LANES of them)
+    // through a batch and through settle(), and checks they agree.
+    fn assert_matches_settle(c: &mut Circuit, inputs: &[(CompKey, u8)], outputs: &[CompKey]) {
+        let keys: Vec<CompKey> = inputs.iter().map(|&(key, _)| key).collect();
+        let mut batch = Batch::compile(c, &keys, outputs).unwrap();
+        let bits: u32 = inputs.iter().map(|&(_, w)| w as u32).sum();
+        let vectors: Vec<Vec<u64>> = (0..1u64 << bits)
+            .map(|n| {
+                let mut shift = 0;
+                inputs
+                    .iter()
+                    .map(|&(_, w)| {
+                        let v = n >> shift & Value::mask(w);
+                        shift += w;
+                        v
+                    })
+                    .collect()
+            })
+            .collect();
+        let lanes = batch.run(&vectors);
+        for (vector, lane) in vectors.iter().zip(&lanes) {
+            for (&(key, width), &v) in inputs.iter().zip(vector) {
+                c.set_input(key, v, width);
+            }
+            c.settle().unwrap();
+            let expected: Vec<Value> = outputs.iter().map(|&o| c.read_output(o)).collect();
+            assert_eq!(lane, &expected, "inputs {vector:?}");
+        }
+    }
+
+    fn output_of(c: &mut Circuit, comp: CompKey, pin: u8) -> CompKey {
+        let o = c.add_component(Component::output());
+        c.link(comp, PinId::output(pin), o, PinId::input(0));
+        o
+    }
+
+    #[test]
+    fn test_lanes_match_settle_on_gates_muxes_and_splitters() {
+        let mut c = Circuit::new();
+        let a = c.add_component(Component::input(0, 2));
+        let b = c.add_component(Component::input(0, 2));
+        let s = c.add_component(Component::input(0, 1));
+        let d = c.add_component(Component::input(0, 1));
+        let split = c.add_component(Component::splitter(
+            vec![vec![0], vec![1]],
+            FanDirection::Right,
+        ));
+        c.link(a, PinId::output(0), split, PinId::input(0));
+        let xor = c.add_component(Component::gate(GateOp::Xor, 2, 2));
+        c.link(a, PinId::output(0), xor, PinId::input(0));
+        c.link(b, PinId::output(0), xor, PinId::input(1));
+        let mux = c.add_component(Component::mux(2, 1));
+        c.link(s, PinId::output(0), mux, PinId::input(0));
+        c.link(xor, PinId::output(0), mux, PinId::input(1));
+        c.link(b, PinId::output(0), mux, PinId::input(2));
+        // Bit 0 from the splitter, bit 1 unwired and so Z, bit 2 owned by
+        // nobody and so 0: the combined bus is partly known.
+        let combine = c.add_component(Component::splitter(
+            vec![vec![0], vec![1]],
+            FanDirection::Left,
+        ));
+        c.link(split, PinId::output(1), combine, PinId::input(0));
+        let wide = c.add_component(Component::splitter(
+            vec![vec![0, 1], vec![3]],
+            FanDirection::Left,
+        ));
+        c.link(combine, PinId::output(0), wide, PinId::input(0));
+        c.link(d, PinId::output(0), wide, PinId::input(1));
+        // A Z bit goes X through a gate, and a partly known selector gives
+        // all X out of a mux.
+        let nand = c.add_component(Component::gate(GateOp::Nand, 2, 2));
+        c.link(combine, PinId::output(0), nand, PinId::input(0));
+        c.link(b, PinId::output(0), nand, PinId::input(1));
+        let blind = c.add_component(Component::mux(1, 2));
+        c.link(combine, PinId::output(0), blind, PinId::input(0));
+        for i in 1..5 {
+            c.link(d, PinId::output(0), blind, PinId::input(i));
+        }
+        // An unwired input floats the whole gate.
+        let or = c.add_component(Component::gate(GateOp::Or, 2, 1));
+        c.link(s, PinId::output(0), or, PinId::input(0));
+        let not = c.add_component(Component::gate(GateOp::Not, 1, 1));
+        c.link(split, PinId::output(0), not, PinId::input(0));
+
+        let outputs: Vec<CompKey> = [(split, 0), (mux, 0), (combine, 0), (wide, 0)]
+            .into_iter()
+            .chain([(nand, 0), (blind, 0), (or, 0), (not, 0)])
+            .map(|(comp, pin)| output_of(&mut c, comp, pin))
+            .collect();
+        c.settle().unwrap();
+        assert_matches_settle(&mut c, &[(a, 2), (b, 2), (s, 1), (d, 1)], &outputs);
+    }
+
+    #[test]
+    fn test_subcircuits_flatten_into_the_batch() {
+        let and = || {
+            let mut inner = Circuit::new();
+            let a = inner.add_component(Component::input(0, 1));
+            let b = inner.add_component(Component::input(0, 1));
+            let g = inner.add_component(Component::gate(GateOp::And, 2, 1));
+            let o = inner.add_component(Component::output());
+            inner.link(a, PinId::output(0), g, PinId::input(0));
+            inner.link(b, PinId::output(0), g, PinId::input(1));
+            inner.link(g, PinId::output(0), o, PinId::input(0));
+            inner.settle().unwrap();
+            Component::subcircuit(inner, vec![a, b], vec![o])
+        };
+        let mut c = Circuit::new();
+        let ins: Vec<CompKey> = (0..3)
+            .map(|_| c.add_component(Component::input(0, 1)))
+            .collect();
+        let first = c.add_component(and());
+        let second = c.add_component(and());
+        c.link(ins[0], PinId::output(0), first, PinId::input(0));
+        c.link(ins[1], PinId::output(0), first, PinId::input(1));
+        c.link(first, PinId::output(0), second, PinId::input(0));
+        c.link(ins[2], PinId::output(0), second, PinId::input(1));
+        // Half wired: the other boundary input floats.
+        let half = c.add_component(and());
+        c.link(ins[2], PinId::output(0), half, PinId::input(0));
+        let outputs = [output_of(&mut c, second, 0), output_of(&mut c, half, 0)];
+        c.settle().unwrap();
+        let inputs: Vec<(CompKey, u8)> = ins.iter().map(|&k| (k, 1)).collect();
+        assert_matches_settle(&mut c, &inputs, &outputs);
+    }
+
+    #[test]
+    fn test_circuits_outside_the_subset_are_refused() {
+        let refused = |build: &dyn Fn(&mut Circuit, CompKey)| {
+            let mut c = Circuit::new();
+            let a = c.add_component(Component::input(0, 1));
+            build(&mut c, a);
+            Batch::compile(&c, &[a], &[]).unwrap_err()
+        };
+        let tri_state = |c: &mut Circuit, a: CompKey| {
+            let t = c.add_component(Component::tri_state(1));
+            c.link(a, PinId::output(0), t, PinId::input(0));
+        };
+        assert_eq!(refused(&tri_state), Unsupported::Part);
+        let reg = |c: &mut Circuit, a: CompKey| {
+            let r = c.add_component(Component::reg(1, ClockEdge::Rising));
+            c.link(a, PinId::output(0), r, PinId::input(0));
+        };
+        assert_eq!(refused(&reg), Unsupported::Part);
+        let ring = |c: &mut Circuit, a: CompKey| {
+            let g = c.add_component(Component::gate(GateOp::Or, 2, 1));
+            c.link(a, PinId::output(0), g, PinId::input(0));
+            c.link(g, PinId::output(0), g, PinId::input(1));
+        };
+        assert_eq!(refused(&ring), Unsupported::Loop);
+        let shorted = |c: &mut Circuit, a: CompKey| {
+            let b = c.add_component(Component::input(1, 1));
+            c.link(a, PinId::output(0), b, PinId::output(0));
+        };
+        assert_eq!(refused(&shorted), Unsupported::SharedNet);
+        let tunnel = |c: &mut Circuit, a: CompKey| {
+            let t = c.add_tunnel("t".to_string(), TunnelRole::Pull);
+            c.link_tunnel(t, a, PinId::output(0));
+        };
+        assert_eq!(refused(&tunnel), Unsupported::SharedNet);
+        let mismatch = |c: &mut Circuit, a: CompKey| {
+            let g = c.add_component(Component::gate(GateOp::Not, 1, 4));
+            c.link(a, PinId::output(0), g, PinId::input(0));
+        };
+        assert_eq!(refused(&mismatch), Unsupported::Width);
+    }
+}
@@ -0,0 +1,105 @@
+// Breakpoint conditions: what a watched signal has to do across one clock
+// tick for a running clock to pause. Only the value logic lives here; which
+// signals are watched, and the pausing itself, are the GUI's (see
+// gui::breakpoints).
+//
+// Conditions on a level - equals, unknown, floating - trip when the signal
+// comes to meet them, not on every tick it goes on meeting them, so resuming
+// from a hit runs on instead of stopping again on the next tick.
+
+use crate::sim::value::{Bit, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    // Every bit known and the whole value equal to this.
+    Equals(u64),
+    Changes,
+    // Bit 0 going from 0 to 1, as a clock pin sees an edge.
+    Rising,
+    // Invalid (a width mismatch) or any bit X.
+    Unknown,
+    // Floating, or any bit Z.
+    Floating,
+}
+
+impl Condition {
+    // One of each kind, an Equals holding 0.
+    pub const ALL: [Condition; 5] = [
+        Condition::Equals(0),
+        Condition::Changes,
+        Condition::Rising,
+        Condition::Unknown,
+        Condition::Floating,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Condition::Equals(_) => "equals",
+            Condition::Changes => "changes",
+            Condition::Rising => "rising edge",
+            Condition::Unknown => "is invalid / X",
+            Condition::Floating => "is floating / Z",
+        }
+    }
+
+    // Whether a tick that took the signal from `before` to `after` trips
+    // this condition.
+    pub fn hit(self, before: Value, after: Value) -> bool {
+        match self {
+            Condition::Changes => before != after,
+            Condition::Rising => before.bit(0) == Bit::Zero && after.bit(0) == Bit::One,
+            level => level.holds(after) && !level.holds(before),
+        }
+    }
+
+    fn holds(self, value: Value) -> bool {
+        let bits = |value: Value| {
+            let width = value.width().unwrap_or(0);
+            (0..width).map(move |i| value.bit(i))
+        };
+        match self {
+            Condition::Equals(n) => matches!(value, Value::Fixed { bits, .. } if bits == n),
+            Condition::Unknown => value == Value::Invalid || bits(value).any(|b| b == Bit::X),
+            Condition::Floating => value == Value::Floating || bits(value).any(|b| b == Bit::Z),
+            Condition::Changes | Condition::Rising => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_conditions_trip_on_arrival() {
+        let five = Value::new(5, 4);
+        assert!(Condition::Equals(5).hit(Value::new(4, 4), five));
+        assert!(!Condition::Equals(5).hit(five, five));
+        assert!(!Condition::Equals(5).hit(five, Value::new(6, 4)));
+
+        let partial = Value::from_masks(0b01, 0, 0b10, 2);
+        assert!(Condition::Unknown.hit(Value::new(1, 2), partial));
+        assert!(Condition::Unknown.hit(Value::new(1, 2), Value::Invalid));
+        assert!(!Condition::Unknown.hit(partial, Value::Invalid));
+        assert!(!Condition::Unknown.hit(Value::ZERO, Value::Floating));
+
+        let half_z = Value::from_masks(0b01, 0b10, 0, 2);
+        assert!(Condition::Floating.hit(Value::ZERO, Value::Floating));
+        assert!(Condition::Floating.hit(Value::new(1, 2), half_z));
+        assert!(!Condition::Floating.hit(Value::Floating, Value::Floating));
+    }
+
+    #[test]
+    fn test_edge_conditions() {
+        assert!(Condition::Rising.hit(Value::ZERO, Value::ONE));
+        assert!(!Condition::Rising.hit(Value::ONE, Value::ZERO));
+        assert!(!Condition::Rising.hit(Value::Floating, Value::ONE));
+        // A bus edges on bit 0 only.
+        assert!(Condition::Rising.hit(Value::new(0b10, 2), Value::new(0b01, 2)));
+        assert!(!Condition::Rising.hit(Value::new(0b01, 2), Value::new(0b11, 2)));
+
+        assert!(Condition::Changes.hit(Value::new(3, 8), Value::new(4, 8)));
+        assert!(Condition::Changes.hit(Value::ONE, Value::Floating));
+        assert!(!Condition::Changes.hit(Value::ONE, Value::ONE));
+    }
+}
@@ -1,8 +1,13 @@
-use crate::sim::component::{CompKey, Component, Input, Logic, LogicComb, LogicSeq, PinId};
-use crate::sim::net::{Net, NetKey};
+use crate::sim::component::{
+    CompKey, Component, Input, Logic, LogicComb, LogicSeq, PinId, SeqState,
+};
+use crate::sim::net::{Net, NetKey, Strength};
+use crate::sim::program::{strongly_connected, Program};
+use crate::sim::timeline::{Event, Timeline};
 use crate::sim::value::Value;
 
 use slotmap::{SecondaryMap, SlotMap};
+use std::cell::RefCell;
 use std::collections::{HashMap, VecDeque};
 
 /// Stable, app-assigned id for a `Tunnel`; survives remove + re-insert (ids never reused).
@@ -26,18 +31,41 @@ pub struct Tunnel {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SettleError {
-    Oscillation { net: NetKey, revisits: usize },
-    TunnelConflict { label: String },
+    // `components` is the combinational loop `net` sits on (see
+    // Circuit::combinational_loops), empty when no loop reaches it.
+    Oscillation {
+        net: NetKey,
+        revisits: usize,
+        components: Vec<CompKey>,
+    },
+    TunnelConflict {
+        label: String,
+    },
 }
 
 impl std::fmt::Display for SettleError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            SettleError::Oscillation { net, revisits } => write!(
+            SettleError::Oscillation {
+                net,
+                revisits,
+                components,
+            } if components.is_empty() => write!(
                 f,
                 "net {:?} did not converge after {} revisits (possible combinational oscillation)",
                 net, revisits
             ),
+            SettleError::Oscillation {
+                net,
+                revisits,
+                components,
+            } => write!(
+                f,
+                "net {:?} did not converge after {} revisits (combinational loop through {} components)",
+                net,
+                revisits,
+                components.len()
+            ),
             SettleError::TunnelConflict { label } => write!(
                 f,
                 "tunnel label {:?} has conflicting driven values from multiple Pull tunnels",
@@ -60,6 +88,12 @@ pub struct Circuit {
     // Monotonic, never reused - avoids ABA when undo re-inserts a deleted entity under its original key.
     next_comp: u64,
     next_tunnel: u64,
+    // Some while in timed mode (see set_timed): output changes of components
+    // with a delay wait here until their time comes.
+    pub(crate) timeline: Option<Timeline>,
+    // The compiled fast path for zero-delay settling (see Program), built on
+    // demand by settle() and dropped by every structural change.
+    pub(crate) program: Option<Program>,
 }
 
 impl Circuit {
@@ -76,6 +110,8 @@ impl Circuit {
         let key = CompKey(self.next_comp);
         self.next_comp += 1;
         self.components.insert(key, comp);
+        self.program = None;
+        self.join_timing(key);
         self.eval_component(key);
         key
     }
@@ -84,13 +120,29 @@ impl Circuit {
     /// `Reg`'s latched state returns intact.
     pub(crate) fn insert_component(&mut self, key: CompKey, comp: Component) {
         self.components.insert(key, comp);
+        self.program = None;
+        self.join_timing(key);
         self.eval_component(key);
     }
 
-    pub fn set_input(&mut self, comp: CompKey, bits: u32, width: u8) {
+    // Puts a subcircuit placed (or put back) part-way through a run in step
+    // with this circuit: the same mode and, when timed, the same time. Events
+    // it still had in flight from another time land first.
+    fn join_timing(&mut self, key: CompKey) {
+        let (timed, now) = (self.is_timed(), self.now());
+        if let Logic::Sub(sub) = &mut self.components.get_mut(&key).unwrap().logic {
+            let _ = sub.inner.set_timed_from(false, 0);
+            if timed {
+                let _ = sub.inner.set_timed_from(true, now);
+            }
+        }
+    }
+
+    pub fn set_input(&mut self, comp: CompKey, bits: u64, width: u8) {
         // TODO: Make this return a result
-        if let Logic::Comb(LogicComb::Input(Input { bits: b, width: w })) =
-            &mut self.components.get_mut(&comp).unwrap().logic
+        if let Logic::Comb(LogicComb::Input(Input {
+            bits: b, width: w, ..
+        })) = &mut self.components.get_mut(&comp).unwrap().logic
         {
             *b = bits;
             *w = width;
@@ -110,7 +162,7 @@ impl Circuit {
 
     /// Writes one word into a ROM's contents. Not undoable. No-op if `comp`
     /// isn't a ROM or `index` is out of range.
-    pub fn write_rom(&mut self, comp: CompKey, index: usize, value: u32) {
+    pub fn write_rom(&mut self, comp: CompKey, index: usize, value: u64) {
         // set_word takes &self (interior mutability); re-evaluate after the borrow ends to dirty the output net.
         let wrote = if let Logic::Comb(LogicComb::Rom(rom)) = &self.components[&comp].logic {
             if index < rom.len() {
@@ -131,7 +183,7 @@ impl Circuit {
     /// `write_rom`, this never changes `data_out` (a registered read,
     /// updated only by `tick_clock`). No-op if `comp` isn't a RAM or `index`
     /// is out of range.
-    pub fn write_ram(&mut self, comp: CompKey, index: usize, value: u32) {
+    pub fn write_ram(&mut self, comp: CompKey, index: usize, value: u64) {
         if let Logic::Seq(LogicSeq::Ram(ram)) = &self.components[&comp].logic {
             let contents = ram.contents();
             if index < contents.len() {
@@ -140,6 +192,35 @@ impl Circuit {
         }
     }
 
+    /// Replaces a ROM's or RAM's whole contents with `words`, zeros past
+    /// their end, each masked to the memory's width: an imported image (see
+    /// memory_image.rs). Not undoable, like `write_rom`/`write_ram`. No-op if
+    /// `comp` is neither.
+    pub fn load_memory(&mut self, comp: CompKey, words: &[u64]) {
+        let fill = |data: &RefCell<Vec<u64>>, data_width: u8| {
+            let mut data = data.borrow_mut();
+            data.fill(0);
+            for (word, &value) in data.iter_mut().zip(words) {
+                *word = value & Value::mask(data_width);
+            }
+        };
+        let rom = match &self.components[&comp].logic {
+            Logic::Comb(LogicComb::Rom(rom)) => {
+                fill(&rom.data, rom.data_width);
+                true
+            }
+            Logic::Seq(LogicSeq::Ram(ram)) => {
+                fill(&ram.contents().data, ram.contents().data_width);
+                false
+            }
+            _ => false,
+        };
+        // Only a ROM's output follows its contents straight away.
+        if rom {
+            self.eval_component(comp);
+        }
+    }
+
     /// The value on `comp`'s input if it is a single-input sink (Output or
     /// Probe), else `Value::Floating`.
     pub fn read_output(&self, comp: CompKey) -> Value {
@@ -168,6 +249,7 @@ impl Circuit {
         self.nets.clear();
         self.dirty.clear();
         self.queued.clear();
+        self.program = None;
 
         // Every input now reads Floating; re-evaluate each component so a later relink can't read back a stale output.
         let keys: Vec<CompKey> = self.components.keys().copied().collect();
@@ -192,6 +274,7 @@ impl Circuit {
     }
 
     fn attach(&mut self, net: NetKey, comp: CompKey, pin: PinId) {
+        self.program = None;
         match pin {
             PinId::In(i) => self.nets[net].sinks.push((comp, i)),
             PinId::Out(i) => self.nets[net].sources.push((comp, i)),
@@ -238,6 +321,7 @@ impl Circuit {
             return a;
         }
         let b_net = self.nets.remove(b).unwrap();
+        self.program = None;
 
         for (comp, i) in b_net.sinks {
             self.components
@@ -247,7 +331,7 @@ impl Circuit {
             self.nets[a].sinks.push((comp, i));
         }
 
-        // A may end up with two sources here; resolve_net reports that as Invalid rather than dropping one.
+        // A may end up with two sources here; resolve_net combines them rather than dropping one.
         for (comp, i) in b_net.sources {
             self.components
                 .get_mut(&comp)
@@ -292,6 +376,7 @@ impl Circuit {
         let old_net = self.tunnels[&tunnel].net;
         let label = self.tunnels[&tunnel].label.clone();
         self.tunnels.get_mut(&tunnel).unwrap().net = Some(net);
+        self.program = None;
         // The old net must also be re-resolved, or it keeps a stale tunnel-contributed value.
         if let Some(old) = old_net {
             if old != net {
@@ -307,6 +392,7 @@ impl Circuit {
     pub fn detach_tunnel(&mut self, tunnel: TunnelKey) {
         let label = self.tunnels[&tunnel].label.clone();
         if let Some(old) = self.tunnels.get_mut(&tunnel).unwrap().net.take() {
+            self.program = None;
             self.mark_dirty(old);
         }
         self.dirty_label_feed_nets(&label);
@@ -325,6 +411,7 @@ impl Circuit {
             }
         }
         if let Some(net) = net {
+            self.program = None;
             self.mark_dirty(net);
         }
         self.dirty_label_feed_nets(&label);
@@ -442,15 +529,282 @@ impl Circuit {
             .unwrap_or(Value::Floating)
     }
 
-    fn mark_dirty(&mut self, net: NetKey) {
+    pub(crate) fn mark_dirty(&mut self, net: NetKey) {
         if !self.queued.get(net).copied().unwrap_or(false) {
             self.queued.insert(net, true);
             self.dirty.push_back(net);
         }
     }
 
+    // Zero-delay by default: every change ripples through to a fixed point in
+    // one go, and a loop that never reaches one is an Oscillation. In timed
+    // mode this settles the current instant only - zero-delay parts catch up
+    // and any events due now apply - while later events wait for
+    // advance_time(), so a ring oscillator simply keeps running.
     pub fn settle(&mut self) -> Result<(), SettleError> {
         puffin::profile_function!();
+        let now = self.now();
+        self.run_until(now)?;
+        self.check_tunnels()
+    }
+
+    /// Switches between zero-delay settling and timed, event-driven
+    /// simulation, then settles in the new mode. Leaving timed mode lets every
+    /// pending event land at once - settling zero-delay then reports an
+    /// Oscillation for a circuit that only ran because of its delays. Every
+    /// subcircuit's inner circuit switches with it.
+    pub fn set_timed(&mut self, timed: bool) -> Result<(), SettleError> {
+        self.set_timed_from(timed, 0)
+    }
+
+    // set_timed, with a new timeline starting at `now` - the enclosing
+    // circuit's time, for a subcircuit's inner circuit.
+    fn set_timed_from(&mut self, timed: bool, now: u64) -> Result<(), SettleError> {
+        if timed == self.is_timed() {
+            return Ok(());
+        }
+        if timed {
+            self.timeline = Some(Timeline::starting_at(now));
+            self.program = None;
+        } else {
+            self.flush_timeline();
+            self.timeline = None;
+        }
+        for key in self.subcircuits() {
+            if let Logic::Sub(sub) = &mut self.components.get_mut(&key).unwrap().logic {
+                // Like SubCircuit::drive_and_settle, an error inside surfaces
+                // on this circuit's own settle.
+                let _ = sub.inner.set_timed_from(timed, now);
+            }
+            let values = self.components[&key].observe();
+            self.apply_output_values(key, values);
+        }
+        self.settle()
+    }
+
+    fn subcircuits(&self) -> Vec<CompKey> {
+        self.components
+            .iter()
+            .filter(|(_, c)| matches!(c.logic, Logic::Sub(_)))
+            .map(|(&key, _)| key)
+            .collect()
+    }
+
+    // Whether every output is a function of the current inputs alone: no
+    // sequential component here or inside any subcircuit. Gate feedback loops
+    // aren't considered, so a latch built from gates still counts.
+    pub fn is_combinational(&self) -> bool {
+        self.components.values().all(|c| match &c.logic {
+            Logic::Comb(_) => true,
+            Logic::Seq(_) => false,
+            Logic::Sub(sub) => sub.inner.is_combinational(),
+        })
+    }
+
+    // Every group of components whose outputs reach back to their own inputs
+    // without passing through a sequential part: the strongly connected
+    // components of the component graph, linked net by net and across tunnel
+    // labels, with a subcircuit standing in as one opaque component. Each
+    // group is sorted by key. A latch built from gates is a loop too - only
+    // the event loop can tell whether it settles.
+    pub fn combinational_loops(&self) -> Vec<Vec<CompKey>> {
+        let mut keys: Vec<CompKey> = self
+            .components
+            .iter()
+            .filter(|(_, c)| !matches!(c.logic, Logic::Seq(_)))
+            .map(|(&k, _)| k)
+            .collect();
+        keys.sort_by_key(|k| k.0);
+        let index: HashMap<CompKey, usize> =
+            keys.iter().enumerate().map(|(i, &k)| (k, i)).collect();
+
+        let succ: Vec<Vec<usize>> = keys
+            .iter()
+            .map(|k| {
+                let mut next = vec![];
+                for net in self.components[k].pins.outputs.iter().flatten() {
+                    for reached in self.nets_reached_from(*net) {
+                        next.extend(
+                            self.nets[reached]
+                                .sinks
+                                .iter()
+                                .filter_map(|(sink, _)| index.get(sink).copied()),
+                        );
+                    }
+                }
+                next.sort_unstable();
+                next.dedup();
+                next
+            })
+            .collect();
+
+        let mut loops: Vec<Vec<CompKey>> = strongly_connected(&succ)
+            .into_iter()
+            .filter(|scc| scc.len() > 1 || succ[scc[0]].contains(&scc[0]))
+            .map(|scc| {
+                let mut group: Vec<CompKey> = scc.into_iter().map(|i| keys[i]).collect();
+                group.sort_by_key(|k| k.0);
+                group
+            })
+            .collect();
+        loops.sort_by_key(|group| group[0].0);
+        loops
+    }
+
+    // The net itself, plus the Feed nets of any label it Pulls into.
+    fn nets_reached_from(&self, net: NetKey) -> Vec<NetKey> {
+        let mut nets = vec![net];
+        for t in self
+            .tunnels_on_net(net)
+            .filter(|t| t.role == TunnelRole::Pull)
+        {
+            for tk in &self.tunnel_labels[&t.label] {
+                let feed = &self.tunnels[tk];
+                if feed.role == TunnelRole::Feed {
+                    nets.extend(feed.net);
+                }
+            }
+        }
+        nets
+    }
+
+    // The combinational loop a non-converging net belongs to: the one holding
+    // a component that drives or reads it, else the first loop at all, since
+    // a net fed by an oscillating loop churns without being on it.
+    fn loop_through(&self, net: NetKey) -> Vec<CompKey> {
+        let mut loops = self.combinational_loops();
+        let on_net = |k: &CompKey| {
+            let n = &self.nets[net];
+            n.sources
+                .iter()
+                .map(|&(c, _)| c)
+                .chain(n.sinks.iter().map(|&(c, _)| c))
+                .any(|c| c == *k)
+        };
+        match loops.iter().position(|group| group.iter().any(on_net)) {
+            Some(i) => loops.swap_remove(i),
+            None => loops.into_iter().next().unwrap_or_default(),
+        }
+    }
+
+    pub fn is_timed(&self) -> bool {
+        self.timeline.is_some()
+    }
+
+    /// The current simulation time; always 0 outside timed mode.
+    pub fn now(&self) -> u64 {
+        self.timeline.as_ref().map_or(0, Timeline::now)
+    }
+
+    /// Runs the timed simulation `dt` time units forward, applying every event
+    /// due on the way. Outside timed mode there is no time to advance, and
+    /// this just settles.
+    pub fn advance_time(&mut self, dt: u64) -> Result<(), SettleError> {
+        puffin::profile_function!();
+        let until = self.now().saturating_add(dt);
+        self.run_until(until)?;
+        self.skip_to(until);
+        self.check_tunnels()
+    }
+
+    // Alternates zero-delay propagation with the next batch of due events,
+    // here or inside a subcircuit, until nothing is left due by `until`.
+    fn run_until(&mut self, until: u64) -> Result<(), SettleError> {
+        loop {
+            self.propagate()?;
+            let Some(time) = self.next_event_time().filter(|&t| t <= until) else {
+                return Ok(());
+            };
+            self.step_to(time)?;
+        }
+    }
+
+    // The earliest event still queued here or in any subcircuit's inner
+    // circuit, which keeps its own queue on the same clock.
+    fn next_event_time(&self) -> Option<u64> {
+        let own = self.timeline.as_ref()?.next_event_time();
+        self.components
+            .values()
+            .filter_map(|c| match &c.logic {
+                Logic::Sub(sub) => sub.inner.next_event_time(),
+                _ => None,
+            })
+            .chain(own)
+            .min()
+    }
+
+    // Moves time up to `time`, the earliest pending event anywhere, and
+    // applies this circuit's events due then. Each subcircuit runs its inner
+    // circuit up to it as well - keeping the two clocks together for when its
+    // inputs next change - and republishes its outputs. An inner circuit that
+    // fails to settle fails the step, as this circuit's own propagate would.
+    fn step_to(&mut self, time: u64) -> Result<(), SettleError> {
+        let Some(timeline) = &mut self.timeline else {
+            return Ok(());
+        };
+        let due = timeline.pop_due(time);
+        timeline.skip_to(time);
+        for event in due {
+            self.apply_event(event);
+        }
+        for key in self.subcircuits() {
+            if let Logic::Sub(sub) = &mut self.components.get_mut(&key).unwrap().logic {
+                let dt = time.saturating_sub(sub.inner.now());
+                sub.inner.advance_time(dt)?;
+            }
+            let values = self.components[&key].observe();
+            self.apply_output_values(key, values);
+        }
+        Ok(())
+    }
+
+    // Moves this circuit's clock and every inner circuit's to `time`, with
+    // nothing due on the way.
+    fn skip_to(&mut self, time: u64) {
+        if let Some(timeline) = &mut self.timeline {
+            timeline.skip_to(time);
+        }
+        for comp in self.components.values_mut() {
+            if let Logic::Sub(sub) = &mut comp.logic {
+                sub.inner.skip_to(time);
+            }
+        }
+    }
+
+    // Lands every pending event's final value immediately, leaving the queue
+    // empty. Components removed since their events were scheduled are skipped.
+    fn flush_timeline(&mut self) {
+        let Some(timeline) = &mut self.timeline else {
+            return;
+        };
+        for event in timeline.drain_final() {
+            self.apply_event(event);
+        }
+    }
+
+    fn apply_event(&mut self, event: Event) {
+        let Some(c) = self.components.get_mut(&event.comp) else {
+            return;
+        };
+        let Some(slot) = c.pins.out_cache.get_mut(event.pin) else {
+            return;
+        };
+        if *slot != event.value {
+            *slot = event.value;
+            if let Some(net) = c.pins.outputs[event.pin] {
+                self.mark_dirty(net);
+            }
+        }
+    }
+
+    // The zero-delay fixed-point loop behind settle(): resolves dirty nets and
+    // re-evaluates their sinks until nothing changes. Outside timed mode,
+    // sinks the program compiled wait for its sweep, which runs whenever the
+    // dirty queue drains and may hand nets back to it.
+    fn propagate(&mut self) -> Result<(), SettleError> {
+        if self.program.is_none() && !self.is_timed() {
+            self.program = Some(Program::compile(self));
+        }
         let mut revisits: SecondaryMap<NetKey, usize> = SecondaryMap::new();
         let iteration_budget = self
             .nets
@@ -459,54 +813,73 @@ impl Circuit {
             .max(1024);
         let mut total_iterations = 0;
 
-        while let Some(net) = self.dirty.pop_front() {
-            // Net may have been merged away and become stale.
-            if !self.nets.contains_key(net) {
-                continue;
-            }
-
-            // Clear the visited flag before eval so a loop can re-queue this net.
-            self.queued.insert(net, false);
-            let changed = self.resolve_net(net);
+        loop {
+            while let Some(net) = self.dirty.pop_front() {
+                // Net may have been merged away and become stale.
+                if !self.nets.contains_key(net) {
+                    continue;
+                }
 
-            if changed {
-                let revisit_count = revisits.get(net).copied().unwrap_or(0) + 1;
-                revisits.insert(net, revisit_count);
-                if revisit_count > Self::REVISIT_THRESHOLD {
+                // Clear the visited flag before eval so a loop can re-queue this net.
+                self.queued.insert(net, false);
+                let changed = self.resolve_net(net);
+
+                if changed {
+                    let revisit_count = revisits.get(net).copied().unwrap_or(0) + 1;
+                    revisits.insert(net, revisit_count);
+                    if revisit_count > Self::REVISIT_THRESHOLD {
+                        return Err(SettleError::Oscillation {
+                            net,
+                            revisits: revisit_count,
+                            components: self.loop_through(net),
+                        });
+                    }
+
+                    // Re-evaluate every sink, including sequential ones: an async reset pin
+                    // must take effect within this settle(), with no clock tick.
+                    // eval_component calls observe(), never tick(), so latched state never advances here.
+                    let sinks: Vec<_> = self.nets[net].sinks.to_vec();
+
+                    for (comp, _) in sinks {
+                        if !self.program.as_mut().is_some_and(|p| p.defer(comp)) {
+                            self.eval_component(comp);
+                        }
+                    }
+
+                    // If a Pull tunnel reads this net, its group's value may have changed; re-dirty sibling Feed nets.
+                    let pull_label: Option<String> = self
+                        .tunnels_on_net(net)
+                        .find(|t| t.role == TunnelRole::Pull)
+                        .map(|t| t.label.clone());
+                    if let Some(label) = pull_label {
+                        self.dirty_label_feed_nets(&label);
+                    }
+                }
+                total_iterations += 1;
+                if total_iterations > iteration_budget {
+                    // Defensive backstop; should be unreachable if the per-net revisit check above works.
                     return Err(SettleError::Oscillation {
                         net,
-                        revisits: revisit_count,
+                        revisits: revisits.get(net).copied().unwrap_or(0),
+                        components: self.loop_through(net),
                     });
                 }
-
-                // Re-evaluate every sink, including sequential ones: an async reset pin
-                // must take effect within this settle(), with no clock tick.
-                // eval_component calls observe(), never tick(), so latched state never advances here.
-                let sinks: Vec<_> = self.nets[net].sinks.to_vec();
-
-                for (comp, _) in sinks {
-                    self.eval_component(comp);
-                }
-
-                // If a Pull tunnel reads this net, its group's value may have changed; re-dirty sibling Feed nets.
-                let pull_label: Option<String> = self
-                    .tunnels_on_net(net)
-                    .find(|t| t.role == TunnelRole::Pull)
-                    .map(|t| t.label.clone());
-                if let Some(label) = pull_label {
-                    self.dirty_label_feed_nets(&label);
-                }
             }
-            total_iterations += 1;
-            if total_iterations > iteration_budget {
-                // Defensive backstop; should be unreachable if the per-net revisit check above works.
-                return Err(SettleError::Oscillation {
-                    net,
-                    revisits: revisits.get(net).copied().unwrap_or(0),
-                });
+            self.run_program();
+            if self.dirty.is_empty() {
+                return Ok(());
             }
         }
+    }
+
+    fn run_program(&mut self) {
+        if let Some(mut program) = self.program.take() {
+            program.run(self);
+            self.program = Some(program);
+        }
+    }
 
+    fn check_tunnels(&self) -> Result<(), SettleError> {
         // All nets have converged; any tunnel-group disagreement found now is genuine, not a mid-convergence artifact.
         let labels: Vec<String> = self.tunnel_labels.keys().cloned().collect();
         for label in &labels {
@@ -516,7 +889,7 @@ impl Circuit {
     }
 
     // True if attached pins declare conflicting widths, independent of the net's current Value.
-    fn net_width_conflict(&self, net: NetKey) -> bool {
+    pub(crate) fn net_width_conflict(&self, net: NetKey) -> bool {
         let n = &self.nets[net];
         let mut widths = n
             .sources
@@ -533,25 +906,42 @@ impl Circuit {
         widths.any(|w| w != first)
     }
 
-    // Two or more drivers is a conflict (a short); resolves to Value::Invalid, same as a width mismatch.
+    // Several drivers share a net bit by bit (see Value::resolve): a driver
+    // putting out Z on a bit, e.g. a disabled tri-state buffer, leaves it to the
+    // others, and only bits two drivers disagree on go X. Weak sources resolve
+    // the same way among themselves, then show through only where every strong
+    // one is Z. A width mismatch is still Value::Invalid.
     fn resolve_net(&mut self, net: NetKey) -> bool {
         puffin::profile_function!();
         let old = self.nets[net].value;
 
-        let new = if self.net_width_conflict(net) || self.nets[net].sources.len() > 1 {
+        let new = if self.net_width_conflict(net) {
             Value::Invalid
         } else {
-            match self.nets[net].sources.first() {
-                // Net takes value from pins.out_cache, which is updated in eval_component
-                Some(&(comp, i)) => self.components[&comp].pins.out_cache[i.0 as usize],
-                // A component driver always takes priority over a Feed tunnel's group value.
-                None => self.tunnel_feed_value(net),
-            }
+            // A component driver always takes priority over a Feed tunnel's group value.
+            let strong = self
+                .resolve_sources(net, Strength::Strong)
+                .unwrap_or_else(|| self.tunnel_feed_value(net));
+            strong.fill_z(
+                self.resolve_sources(net, Strength::Weak)
+                    .unwrap_or_default(),
+            )
         };
         self.nets[net].value = new;
         new != old
     }
 
+    // The combined value of the net's sources at `strength`, or None if it has none.
+    fn resolve_sources(&self, net: NetKey, strength: Strength) -> Option<Value> {
+        self.nets[net]
+            .sources
+            .iter()
+            .filter(|&&(comp, _)| self.components[&comp].strength() == strength)
+            // Net takes value from pins.out_cache, which is updated in eval_component
+            .map(|&(comp, i)| self.components[&comp].pins.out_cache[i.0 as usize])
+            .reduce(Value::resolve)
+    }
+
     fn eval_component(&mut self, comp: CompKey) {
         puffin::profile_function!();
         // A sequential component may apply async, level-sensitive effects here (e.g. an
@@ -562,10 +952,26 @@ impl Circuit {
             self.components.get_mut(&comp).unwrap().apply_async(&inputs);
         }
         let new_values = self.components[&comp].evaluate(&self.nets);
-        self.apply_output_values(comp, new_values);
+        self.drive_outputs(comp, new_values);
     }
 
-    // Shared by eval_component (combinational path) and tick_clock (sequential path).
+    // Sets a component's outputs as the result of evaluating it: at once, or
+    // in timed mode `delay` units from now.
+    fn drive_outputs(&mut self, comp: CompKey, new_values: Vec<Value>) {
+        let delay = self.components[&comp].delay;
+        let Some(timeline) = self.timeline.as_mut().filter(|_| delay > 0) else {
+            self.apply_output_values(comp, new_values);
+            return;
+        };
+        let current = &self.components[&comp].pins.out_cache;
+        for (pin, value) in new_values.into_iter().enumerate() {
+            timeline.schedule(comp, pin, current[pin], value, delay);
+        }
+    }
+
+    // Sets outputs immediately; drive_outputs defers to it outside timed mode.
+    // drive_input and reset_sequential call it directly - neither is a
+    // component reacting to its inputs, so neither waits out a delay.
     fn apply_output_values(&mut self, comp: CompKey, new_values: Vec<Value>) {
         puffin::profile_function!();
         let c = self.components.get_mut(&comp).unwrap();
@@ -587,12 +993,18 @@ impl Circuit {
     }
 
     // Generic over LogicSeq variants; a new sequential type needs no changes here.
+    // One step of the global clock: ticks clock sources and every stateful
+    // component without a wired clock pin. Components clocked from a net latch
+    // during the trailing settle, when their clock pin sees its edge - all sinks
+    // of the changed net evaluate before any other net resolves, so a chain of
+    // registers on one clock samples their inputs from before the edge. The
+    // transport, the CLI and test vectors step through step_clock_sources.
     pub fn tick_clock(&mut self) -> Result<(), SettleError> {
         puffin::profile_function!();
         let seq_comps: Vec<CompKey> = self
             .components
             .iter()
-            .filter(|(_, c)| c.is_stateful())
+            .filter(|(_, c)| c.follows_global_clock())
             .map(|(key, _)| *key)
             .collect();
 
@@ -606,7 +1018,43 @@ impl Circuit {
 
         for (key, inputs) in collected_inputs {
             let new_values = self.components.get_mut(&key).unwrap().tick(&inputs);
-            self.apply_output_values(key, new_values);
+            self.drive_outputs(key, new_values);
+        }
+
+        self.settle()
+    }
+
+    // The transport's step (see gui::clock::Clock::step): it drives the Clock
+    // sources rather than ticking every stateful part. Each source advances a
+    // phase and drives its new level, and a part clocked from one latches in
+    // the trailing settle, when its own pin sees its edge. A part with no
+    // source to follow - its clock pin unwired - stands on the transport's own
+    // clock, which edges every step, so it latches here, on inputs sampled
+    // before any source moved. Subcircuits step the sources inside them the
+    // same way.
+    pub fn step_clock_sources(&mut self) -> Result<(), SettleError> {
+        puffin::profile_function!();
+        let mut sources = Vec::new();
+        let mut unsourced = Vec::new();
+        for (&key, c) in &self.components {
+            match &c.logic {
+                Logic::Seq(LogicSeq::ClockSource(_)) => sources.push(key),
+                _ if c.follows_global_clock() => unsourced.push((key, c.read_inputs(&self.nets))),
+                _ => {}
+            }
+        }
+
+        for key in sources {
+            let level = self.components.get_mut(&key).unwrap().tick(&[]);
+            self.drive_outputs(key, level);
+        }
+        for (key, inputs) in unsourced {
+            let comp = self.components.get_mut(&key).unwrap();
+            let new_values = match &mut comp.logic {
+                Logic::Sub(sub) => sub.step_clock_sources(&inputs),
+                _ => comp.tick(&inputs),
+            };
+            self.drive_outputs(key, new_values);
         }
 
         self.settle()
@@ -622,13 +1070,77 @@ impl Circuit {
             .map(|(key, _)| *key)
             .collect();
 
-        for key in seq_comps {
+        // Power-on starts time over. Whatever was in flight lands first, so
+        // no combinational output is left waiting on an event that's gone.
+        if self.is_timed() {
+            self.flush_timeline();
+            self.timeline = Some(Timeline::default());
+        }
+
+        for &key in &seq_comps {
             self.components.get_mut(&key).unwrap().reset();
+        }
+        self.settle_latched(seq_comps)
+    }
+
+    // Puts back latched state that SeqLogic::snapshot() took, part by part,
+    // and settles around it like reset_sequential: restoring is not a clock
+    // edge. Drives the GUI's checkpoints (see checkpoint.rs), which restore a
+    // subcircuit's inside first, through its own inner Circuit.
+    pub(crate) fn restore_sequential(
+        &mut self,
+        states: &[(CompKey, &SeqState)],
+    ) -> Result<(), SettleError> {
+        puffin::profile_function!();
+        self.flush_timeline();
+        for &(key, state) in states {
+            if let Some(comp) = self.components.get_mut(&key) {
+                comp.restore(state, Some(Value::Floating));
+            }
+        }
+        let stateful = self
+            .components
+            .iter()
+            .filter(|(_, c)| c.is_stateful())
+            .map(|(key, _)| *key)
+            .collect();
+        self.settle_latched(stateful)
+    }
+
+    // Publishes each stateful part's latched outputs and settles. Clock pins
+    // pick up their net's level only afterwards: settling a clock source back
+    // to low is not an edge for the parts it drives.
+    fn settle_latched(&mut self, seq_comps: Vec<CompKey>) -> Result<(), SettleError> {
+        for &key in &seq_comps {
             let values = self.components[&key].observe();
             self.apply_output_values(key, values);
         }
+        let result = self.settle();
+        for key in seq_comps {
+            let inputs = self.components[&key].read_inputs(&self.nets);
+            self.components.get_mut(&key).unwrap().sync_clock(&inputs);
+        }
+        result
+    }
 
-        self.settle()
+    // Re-resolves every net from its drivers' cached outputs, evaluating
+    // nothing: sim::rewind puts back a settled moment whole, out_caches and
+    // all, and only the nets are left to catch up. A tunnel feeds one net from
+    // others, so this repeats until a pass changes nothing.
+    pub(crate) fn refresh_nets(&mut self) {
+        self.dirty.clear();
+        self.queued.clear();
+        self.program = None;
+        let nets: Vec<NetKey> = self.nets.keys().collect();
+        for _ in 0..=nets.len() {
+            let mut changed = false;
+            for &net in &nets {
+                changed |= self.resolve_net(net);
+            }
+            if !changed {
+                break;
+            }
+        }
     }
 
     pub fn remove_component(&mut self, key: CompKey) -> Option<Component> {
@@ -678,6 +1190,7 @@ impl Circuit {
         // Clear propagation state; the caller is expected to call settle() after
         self.dirty.clear();
         self.queued.clear();
+        self.program = None;
 
         // Hand the owned Component back for the undo entry (see insert_component); pins are
         // nulled so it holds no dangling NetKeys. A Reg's latched state rides along untouched.
@@ -710,7 +1223,8 @@ impl Circuit {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sim::component::{GateOp, RegConf};
+    use crate::sim::component::{ClockEdge, ClockSourceConf, GateOp, PullDirection, RegConf};
+    use test_case::test_case;
 
     // ---- Group 1: construction / basic wiring ----
 
@@ -777,7 +1291,7 @@ mod tests {
     }
 
     #[test]
-    fn test_link_second_source_yields_invalid() {
+    fn test_link_second_disagreeing_source_yields_unknown() {
         let mut c = Circuit::new();
         let i1 = c.add_component(Component::input(1, 1));
         let i2 = c.add_component(Component::input(0, 1));
@@ -789,11 +1303,12 @@ mod tests {
         c.link(i2, PinId::output(0), g2, PinId::input(0));
 
         c.link(g1, PinId::output(0), o, PinId::input(0));
-        // o already has a net driven by g1; adding g2 gives it two drivers -> Invalid, not silently picked.
+        // o already has a net driven by g1 (0); adding g2 (1) gives it two
+        // disagreeing drivers -> X, not silently picked.
         c.link(g2, PinId::output(0), o, PinId::input(0));
 
         c.settle().unwrap();
-        assert_eq!(c.read_output(o), Value::Invalid);
+        assert_eq!(c.read_output(o), Value::unknown(1));
     }
 
     #[test]
@@ -807,8 +1322,8 @@ mod tests {
     }
 
     #[test]
-    fn test_link_merge_two_drivers_yields_invalid() {
-        // Merging two driven nets folds both drivers onto the survivor -> Invalid, not silently kept.
+    fn test_link_merge_two_drivers_yields_unknown() {
+        // Merging two driven nets folds both drivers onto the survivor -> X where they disagree, not silently kept.
         let mut c = Circuit::new();
         let driver1 = c.add_component(Component::input(1, 1));
         let driver2 = c.add_component(Component::input(0, 1));
@@ -826,14 +1341,14 @@ mod tests {
         c.link(sink1, PinId::input(0), sink2, PinId::input(0));
 
         c.settle().unwrap();
-        // Both sinks now share one net driven by both driver1 and driver2 -> Invalid.
-        assert_eq!(c.read_output(sink1), Value::Invalid);
-        assert_eq!(c.read_output(sink2), Value::Invalid);
+        // Both sinks now share one net driven by both driver1 and driver2 -> X.
+        assert_eq!(c.read_output(sink1), Value::unknown(1));
+        assert_eq!(c.read_output(sink2), Value::unknown(1));
     }
 
     #[test]
     fn test_remove_one_of_two_drivers_clears_conflict() {
-        // A two-driver net is Invalid; removing one driver leaves it single-driver, not torn down.
+        // A conflicting two-driver net is X; removing one driver leaves it single-driver, not torn down.
         let mut c = Circuit::new();
         let d1 = c.add_component(Component::input(1, 1));
         let d2 = c.add_component(Component::input(0, 1));
@@ -842,13 +1357,87 @@ mod tests {
         c.link(d1, PinId::output(0), o, PinId::input(0));
         c.link(d2, PinId::output(0), o, PinId::input(0));
         c.settle().unwrap();
-        assert_eq!(c.read_output(o), Value::Invalid);
+        assert_eq!(c.read_output(o), Value::unknown(1));
 
         c.remove_component(d2);
         c.settle().unwrap();
         assert_eq!(c.read_output(o), Value::ONE);
     }
 
+    #[test_case(1, 0, Value::new(0xA, 4) ; "first buffer drives")]
+    #[test_case(0, 1, Value::new(0x5, 4) ; "second buffer drives")]
+    #[test_case(0, 0, Value::Floating ; "neither drives")]
+    #[test_case(1, 1, Value::from_masks(0, 0, 0xF, 4) ; "both drive, every bit disagrees")]
+    fn test_tri_state_buffers_share_a_bus(en1: u64, en2: u64, expected: Value) {
+        let mut c = Circuit::new();
+        let d1 = c.add_component(Component::input(0xA, 4));
+        let d2 = c.add_component(Component::input(0x5, 4));
+        let e1 = c.add_component(Component::input(en1, 1));
+        let e2 = c.add_component(Component::input(en2, 1));
+        let t1 = c.add_component(Component::tri_state(4));
+        let t2 = c.add_component(Component::tri_state(4));
+        let o = c.add_component(Component::output());
+
+        c.link(d1, PinId::output(0), t1, PinId::input(0));
+        c.link(e1, PinId::output(0), t1, PinId::input(1));
+        c.link(d2, PinId::output(0), t2, PinId::input(0));
+        c.link(e2, PinId::output(0), t2, PinId::input(1));
+        c.link(t1, PinId::output(0), o, PinId::input(0));
+        c.link(t2, PinId::output(0), o, PinId::input(0));
+
+        c.settle().unwrap();
+        assert_eq!(c.read_output(o), expected);
+    }
+
+    #[test]
+    fn test_agreeing_drivers_resolve_to_their_value() {
+        let mut c = Circuit::new();
+        let d1 = c.add_component(Component::input(1, 1));
+        let d2 = c.add_component(Component::input(1, 1));
+        let o = c.add_component(Component::output());
+
+        c.link(d1, PinId::output(0), o, PinId::input(0));
+        c.link(d2, PinId::output(0), o, PinId::input(0));
+        c.settle().unwrap();
+        assert_eq!(c.read_output(o), Value::ONE);
+    }
+
+    #[test_case(0, 0xF ; "buffer disabled, pull-up shows through")]
+    #[test_case(1, 0x3 ; "buffer enabled, drives over the pull-up")]
+    fn test_pull_up_yields_to_a_strong_driver(enable: u64, expected: u64) {
+        let mut c = Circuit::new();
+        let data = c.add_component(Component::input(0x3, 4));
+        let en = c.add_component(Component::input(enable, 1));
+        let buf = c.add_component(Component::tri_state(4));
+        let pull = c.add_component(Component::pull_resistor(PullDirection::Up, 4));
+        let o = c.add_component(Component::output());
+
+        c.link(data, PinId::output(0), buf, PinId::input(0));
+        c.link(en, PinId::output(0), buf, PinId::input(1));
+        c.link(buf, PinId::output(0), o, PinId::input(0));
+        c.link(pull, PinId::output(0), o, PinId::input(0));
+
+        c.settle().unwrap();
+        assert_eq!(c.read_output(o), Value::new(expected, 4));
+    }
+
+    #[test]
+    fn test_pull_resistors_alone_resolve_like_drivers() {
+        let mut c = Circuit::new();
+        let up = c.add_component(Component::pull_resistor(PullDirection::Up, 1));
+        let down = c.add_component(Component::pull_resistor(PullDirection::Down, 1));
+        let o = c.add_component(Component::output());
+
+        c.link(up, PinId::output(0), o, PinId::input(0));
+        c.settle().unwrap();
+        assert_eq!(c.read_output(o), Value::ONE);
+
+        // Two weak sources fighting is still a conflict.
+        c.link(down, PinId::output(0), o, PinId::input(0));
+        c.settle().unwrap();
+        assert_eq!(c.read_output(o), Value::unknown(1));
+    }
+
     #[test]
     fn test_link_merge_of_still_dirty_nets_removes_stale_key() {
         let mut c = Circuit::new();
@@ -924,9 +1513,9 @@ mod tests {
     }
 
     #[test]
-    fn test_settle_second_driver_into_loop_is_invalid_not_oscillation() {
+    fn test_settle_second_driver_into_loop_is_unknown_not_oscillation() {
         // Regression test: a NOT-gate ring used to oscillate when a second driver closed the
-        // loop. Two drivers now resolve to Invalid and stay there, so settle() converges instead.
+        // loop. The two disagreeing drivers resolve to X and stay there, so settle() converges instead.
         let mut c = Circuit::new();
         let seed = c.add_component(Component::input(0, 1));
         let n1 = c.add_component(Component::gate(GateOp::Not, 1, 1));
@@ -940,12 +1529,12 @@ mod tests {
 
         c.link(n3, PinId::output(0), n1, PinId::input(0));
         assert!(c.settle().is_ok());
-        // n1's input net is Invalid (two drivers); NOT reads Invalid as Floating,
-        // so n1's single-driver output net settles Floating.
+        // n1's input net is X (seed 0 vs n3 1); NOT of X is X, which is
+        // stable all the way round the ring.
         let o = c.add_component(Component::output());
         c.link(n1, PinId::output(0), o, PinId::input(0));
         c.settle().unwrap();
-        assert_eq!(c.read_output(o), Value::Floating);
+        assert_eq!(c.read_output(o), Value::unknown(1));
     }
 
     // ---- Group 3: register / clock behavior ----
@@ -955,7 +1544,7 @@ mod tests {
         let mut c = Circuit::new();
         let data = c.add_component(Component::input(0, 4));
         let we = c.add_component(Component::input(1, 1));
-        let reg = c.add_component(Component::reg(4));
+        let reg = c.add_component(Component::reg(4, ClockEdge::Rising));
         let out = c.add_component(Component::output());
         c.link(data, PinId::output(0), reg, PinId::input(0));
         c.link(we, PinId::output(0), reg, PinId::input(1));
@@ -974,7 +1563,7 @@ mod tests {
         let mut c = Circuit::new();
         let data = c.add_component(Component::input(0, 1));
         let we = c.add_component(Component::input(1, 1));
-        let reg = c.add_component(Component::reg(1));
+        let reg = c.add_component(Component::reg(1, ClockEdge::Rising));
         let not_g = c.add_component(Component::gate(GateOp::Not, 1, 1));
         let out = c.add_component(Component::output());
         c.link(data, PinId::output(0), reg, PinId::input(0));
@@ -997,7 +1586,7 @@ mod tests {
         let data = c.add_component(Component::input(9, 4));
         let we = c.add_component(Component::input(1, 1));
         let rst = c.add_component(Component::input(0, 1)); // deasserted
-        let reg = c.add_component(Component::reg(4));
+        let reg = c.add_component(Component::reg(4, ClockEdge::Rising));
         let out = c.add_component(Component::output());
         c.link(
             data,
@@ -1041,7 +1630,7 @@ mod tests {
         let mut c = Circuit::new();
         let toggle = c.add_component(Component::input(1, 1));
         let rst = c.add_component(Component::input(0, 1));
-        let ff = c.add_component(Component::t_flip_flop());
+        let ff = c.add_component(Component::t_flip_flop(ClockEdge::Rising));
         let out = c.add_component(Component::output());
         c.link(
             toggle,
@@ -1077,12 +1666,12 @@ mod tests {
         let mut c = Circuit::new();
         let data1 = c.add_component(Component::input(5, 4));
         let we1 = c.add_component(Component::input(1, 1));
-        let reg1 = c.add_component(Component::reg(4));
+        let reg1 = c.add_component(Component::reg(4, ClockEdge::Rising));
         let out1 = c.add_component(Component::output());
 
         let data2 = c.add_component(Component::input(9, 4));
         let we2 = c.add_component(Component::input(0, 1));
-        let reg2 = c.add_component(Component::reg(4));
+        let reg2 = c.add_component(Component::reg(4, ClockEdge::Rising));
         let out2 = c.add_component(Component::output());
 
         c.link(data1, PinId::output(0), reg1, PinId::input(0));
@@ -1105,8 +1694,8 @@ mod tests {
         let data = c.add_component(Component::input(5, 4));
         let we1 = c.add_component(Component::input(1, 1));
         let we2 = c.add_component(Component::input(1, 1));
-        let reg1 = c.add_component(Component::reg(4));
-        let reg2 = c.add_component(Component::reg(4));
+        let reg1 = c.add_component(Component::reg(4, ClockEdge::Rising));
+        let reg2 = c.add_component(Component::reg(4, ClockEdge::Rising));
         let out2 = c.add_component(Component::output());
 
         c.link(data, PinId::output(0), reg1, PinId::input(0));
@@ -1147,12 +1736,17 @@ mod tests {
         let mut c = Circuit::new();
         let data = c.add_component(Component::input(5, 4));
         let we = c.add_component(Component::input(1, 1));
-        let reg = c.add_component(Component::reg(4));
+        let reg = c.add_component(Component::reg(4, ClockEdge::Rising));
         let out_reg = c.add_component(Component::output());
 
         let load = c.add_component(Component::input(0, 1));
         let count = c.add_component(Component::input(1, 1));
-        let counter = c.add_component(Component::counter(4, 15, OverflowAction::Wrap));
+        let counter = c.add_component(Component::counter(
+            4,
+            15,
+            OverflowAction::Wrap,
+            ClockEdge::Rising,
+        ));
         let out_ctr = c.add_component(Component::output());
 
         c.link(data, PinId::output(0), reg, PinId::input(0));
@@ -1186,6 +1780,442 @@ mod tests {
         assert_eq!(c.read_output(o), Value::ONE);
     }
 
+    // A Reg holding constant data 5 with write-enable high, its clock pin fed
+    // from a clock source (which starts low). Returns (circuit, output).
+    fn clocked_reg(conf: ClockSourceConf, edge: ClockEdge) -> (Circuit, CompKey) {
+        let mut c = Circuit::new();
+        let data = c.add_component(Component::input(5, 4));
+        let we = c.add_component(Component::input(1, 1));
+        let clk = c.add_component(Component::clock_source(conf));
+        let reg = c.add_component(Component::reg(4, edge));
+        let out = c.add_component(Component::output());
+        c.link(
+            data,
+            PinId::output(0),
+            reg,
+            PinId::input(RegConf::DATA_PIN as u8),
+        );
+        c.link(
+            we,
+            PinId::output(0),
+            reg,
+            PinId::input(RegConf::WRITE_EN_PIN as u8),
+        );
+        c.link(
+            clk,
+            PinId::output(0),
+            reg,
+            PinId::input(RegConf::CLOCK_PIN as u8),
+        );
+        c.link(reg, PinId::output(0), out, PinId::input(0));
+        c.settle().unwrap();
+        (c, out)
+    }
+
+    #[test_case(ClockEdge::Rising, 5, 5 ; "rising latches as the clock goes high")]
+    #[test_case(ClockEdge::Falling, 0, 5 ; "falling latches as the clock goes low")]
+    fn test_clock_pin_latches_on_its_edge(edge: ClockEdge, after_high: u64, after_low: u64) {
+        let (mut c, out) = clocked_reg(ClockSourceConf::default(), edge);
+        c.tick_clock().unwrap(); // clock 0 -> 1
+        assert_eq!(c.read_output(out), Value::new(after_high, 4));
+        c.tick_clock().unwrap(); // clock 1 -> 0
+        assert_eq!(c.read_output(out), Value::new(after_low, 4));
+    }
+
+    #[test]
+    fn test_clock_pin_ignores_global_ticks_between_edges() {
+        let conf = ClockSourceConf {
+            high_ticks: 1,
+            low_ticks: 3,
+        };
+        let (mut c, out) = clocked_reg(conf, ClockEdge::Rising);
+        c.tick_clock().unwrap();
+        c.tick_clock().unwrap();
+        assert_eq!(c.read_output(out), Value::new(0, 4));
+        c.tick_clock().unwrap(); // third step: the clock finally rises
+        assert_eq!(c.read_output(out), Value::new(5, 4));
+    }
+
+    #[test]
+    fn test_reset_sequential_is_not_a_clock_edge() {
+        let (mut c, out) = clocked_reg(ClockSourceConf::default(), ClockEdge::Falling);
+        c.tick_clock().unwrap(); // clock high; a falling-edge reg holds
+
+        // Stop drops the clock back low, which must not latch.
+        c.reset_sequential().unwrap();
+        assert_eq!(c.read_output(out), Value::new(0, 4));
+        c.tick_clock().unwrap();
+        c.tick_clock().unwrap(); // a real falling edge
+        assert_eq!(c.read_output(out), Value::new(5, 4));
+    }
+
+    #[test]
+    fn test_clock_pin_chained_registers_sample_before_the_edge() {
+        let mut c = Circuit::new();
+        let data = c.add_component(Component::input(5, 4));
+        let clk = c.add_component(Component::clock_source(ClockSourceConf::default()));
+        let reg1 = c.add_component(Component::reg(4, ClockEdge::Rising));
+        let reg2 = c.add_component(Component::reg(4, ClockEdge::Rising));
+        let out2 = c.add_component(Component::output());
+        let clock_pin = PinId::input(RegConf::CLOCK_PIN as u8);
+        c.link(data, PinId::output(0), reg1, PinId::input(0));
+        c.link(reg1, PinId::output(0), reg2, PinId::input(0));
+        c.link(clk, PinId::output(0), reg1, clock_pin);
+        c.link(clk, PinId::output(0), reg2, clock_pin);
+        c.link(reg2, PinId::output(0), out2, PinId::input(0));
+        c.settle().unwrap();
+
+        // First rising edge: reg2 takes reg1's value from before it (0).
+        c.tick_clock().unwrap();
+        assert_eq!(c.read_output(out2), Value::new(0, 4));
+        c.tick_clock().unwrap();
+        c.tick_clock().unwrap();
+        assert_eq!(c.read_output(out2), Value::new(5, 4));
+    }
+
+    #[test]
+    fn test_two_clock_domains_advance_independently() {
+        use crate::sim::component::TFlipFlopConf as T;
+        let mut c = Circuit::new();
+        let toggle = c.add_component(Component::input(1, 1));
+        let fast = c.add_component(Component::clock_source(ClockSourceConf::default()));
+        let slow = c.add_component(Component::clock_source(ClockSourceConf {
+            high_ticks: 2,
+            low_ticks: 2,
+        }));
+        let mut outs = vec![];
+        for clk in [fast, slow] {
+            let ff = c.add_component(Component::t_flip_flop(ClockEdge::Rising));
+            let out = c.add_component(Component::output());
+            c.link(
+                toggle,
+                PinId::output(0),
+                ff,
+                PinId::input(T::TOGGLE_PIN as u8),
+            );
+            c.link(clk, PinId::output(0), ff, PinId::input(T::CLOCK_PIN as u8));
+            c.link(ff, PinId::output(0), out, PinId::input(0));
+            outs.push(out);
+        }
+        c.settle().unwrap();
+
+        for _ in 0..4 {
+            c.tick_clock().unwrap();
+        }
+        // Fast clock rose twice (back to 0), slow clock once.
+        assert_eq!(c.read_output(outs[0]), Value::ZERO);
+        assert_eq!(c.read_output(outs[1]), Value::ONE);
+    }
+
+    // A T flip-flop toggling on every edge its clock pin sees, which is left
+    // unwired unless `clk` is given. Returns its output.
+    fn toggler(c: &mut Circuit, clk: Option<CompKey>) -> CompKey {
+        use crate::sim::component::TFlipFlopConf as T;
+        let toggle = c.add_component(Component::input(1, 1));
+        let ff = c.add_component(Component::t_flip_flop(ClockEdge::Rising));
+        let out = c.add_component(Component::output());
+        c.link(
+            toggle,
+            PinId::output(0),
+            ff,
+            PinId::input(T::TOGGLE_PIN as u8),
+        );
+        if let Some(clk) = clk {
+            c.link(clk, PinId::output(0), ff, PinId::input(T::CLOCK_PIN as u8));
+        }
+        c.link(ff, PinId::output(0), out, PinId::input(0));
+        out
+    }
+
+    #[test]
+    fn test_step_clock_sources_latches_each_pin_on_its_own_edge() {
+        let conf = ClockSourceConf {
+            high_ticks: 1,
+            low_ticks: 2,
+        };
+        let (mut c, reg_out) = clocked_reg(conf, ClockEdge::Rising);
+        let unwired = toggler(&mut c, None);
+        c.settle().unwrap();
+
+        let mut seen = vec![];
+        for _ in 0..3 {
+            c.step_clock_sources().unwrap();
+            seen.push((c.read_output(reg_out), c.read_output(unwired)));
+        }
+        // The source rises on the second step; the unwired pin edges every step.
+        let (zero, five) = (Value::new(0, 4), Value::new(5, 4));
+        assert_eq!(
+            seen,
+            vec![(zero, Value::ONE), (five, Value::ZERO), (five, Value::ONE)]
+        );
+    }
+
+    #[test]
+    fn test_step_clock_sources_steps_sources_inside_a_subcircuit() {
+        let mut inner = Circuit::new();
+        let clk = inner.add_component(Component::clock_source(ClockSourceConf::default()));
+        let inner_out = toggler(&mut inner, Some(clk));
+        let mut c = Circuit::new();
+        let sub = c.add_component(Component::subcircuit(inner, vec![], vec![inner_out]));
+        let out = c.add_component(Component::output());
+        c.link(sub, PinId::output(0), out, PinId::input(0));
+        c.settle().unwrap();
+
+        let seen: Vec<Value> = (0..3)
+            .map(|_| {
+                c.step_clock_sources().unwrap();
+                c.read_output(out)
+            })
+            .collect();
+        assert_eq!(seen, vec![Value::ONE, Value::ONE, Value::ZERO]);
+    }
+
+    // ---- Timed mode ----
+
+    // NAND(en, ring) -> NOT -> NOT -> back into the NAND: a three-stage ring
+    // oscillator, held still while `en` is 0. A pull-up gives the loop a known
+    // starting level. Returns (circuit, en, ring).
+    fn ring_oscillator(timed: bool) -> (Circuit, CompKey, CompKey) {
+        let mut c = Circuit::new();
+        c.set_timed(timed).unwrap();
+        let en = c.add_component(Component::input(0, 1));
+        let nand = c.add_component(Component::gate(GateOp::Nand, 2, 1));
+        let not1 = c.add_component(Component::gate(GateOp::Not, 1, 1));
+        let not2 = c.add_component(Component::gate(GateOp::Not, 1, 1));
+        let ring = c.add_component(Component::output());
+        let pull = c.add_component(Component::pull_resistor(PullDirection::Up, 1));
+        c.link(pull, PinId::output(0), ring, PinId::input(0));
+        c.link(en, PinId::output(0), nand, PinId::input(0));
+        c.link(nand, PinId::output(0), not1, PinId::input(0));
+        c.link(not1, PinId::output(0), not2, PinId::input(0));
+        c.link(not2, PinId::output(0), nand, PinId::input(1));
+        c.link(not2, PinId::output(0), ring, PinId::input(0));
+        c.advance_time(10).unwrap();
+        (c, en, ring)
+    }
+
+    #[test]
+    fn test_ring_oscillator_is_an_oscillation_without_delays() {
+        let (mut c, en, ring) = ring_oscillator(false);
+        assert_eq!(c.read_output(ring), Value::ONE);
+        c.set_input(en, 1, 1);
+        assert!(matches!(c.settle(), Err(SettleError::Oscillation { .. })));
+    }
+
+    #[test]
+    fn test_oscillation_names_the_loop_it_is_on() {
+        let mut c = Circuit::new();
+        // A NOR latch that settles, beside a ring that doesn't.
+        let s = c.add_component(Component::input(0, 1));
+        let r = c.add_component(Component::input(1, 1));
+        let nor1 = c.add_component(Component::gate(GateOp::Nor, 2, 1));
+        let nor2 = c.add_component(Component::gate(GateOp::Nor, 2, 1));
+        c.link(r, PinId::output(0), nor1, PinId::input(0));
+        c.link(s, PinId::output(0), nor2, PinId::input(0));
+        c.link(nor1, PinId::output(0), nor2, PinId::input(1));
+        c.link(nor2, PinId::output(0), nor1, PinId::input(1));
+
+        let en = c.add_component(Component::input(0, 1));
+        let nand = c.add_component(Component::gate(GateOp::Nand, 2, 1));
+        let not1 = c.add_component(Component::gate(GateOp::Not, 1, 1));
+        let not2 = c.add_component(Component::gate(GateOp::Not, 1, 1));
+        let buf = c.add_component(Component::gate(GateOp::Not, 1, 1));
+        let out = c.add_component(Component::output());
+        c.link(en, PinId::output(0), nand, PinId::input(0));
+        c.link(nand, PinId::output(0), not1, PinId::input(0));
+        c.link(not1, PinId::output(0), not2, PinId::input(0));
+        c.link(not2, PinId::output(0), nand, PinId::input(1));
+        c.link(not2, PinId::output(0), buf, PinId::input(0));
+        c.link(buf, PinId::output(0), out, PinId::input(0));
+        // Gives the ring a level to start from, as in ring_oscillator.
+        let pull = c.add_component(Component::pull_resistor(PullDirection::Up, 1));
+        c.link(pull, PinId::output(0), buf, PinId::input(0));
+        c.settle().unwrap();
+
+        let mut latch = vec![nor1, nor2];
+        let mut ring = vec![nand, not1, not2];
+        latch.sort_by_key(|k| k.0);
+        ring.sort_by_key(|k| k.0);
+        assert_eq!(c.combinational_loops(), vec![latch, ring.clone()]);
+
+        c.set_input(en, 1, 1);
+        match c.settle() {
+            Err(SettleError::Oscillation { components, .. }) => assert_eq!(components, ring),
+            other => panic!("expected an oscillation, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_ring_oscillator_runs_in_timed_mode() {
+        let (mut c, en, ring) = ring_oscillator(true);
+        assert_eq!(c.read_output(ring), Value::ONE);
+        c.set_input(en, 1, 1);
+        c.settle().unwrap();
+
+        // Three unit delays around the ring: it flips every 3 units.
+        let mut seen = vec![];
+        for _ in 0..12 {
+            c.advance_time(1).unwrap();
+            seen.push(c.read_output(ring));
+        }
+        let expected: Vec<_> = [1, 1, 0, 0, 0, 1, 1, 1, 0, 0, 0, 1]
+            .map(|b| Value::new(b, 1))
+            .to_vec();
+        assert_eq!(seen, expected);
+        assert_eq!(c.now(), 22);
+    }
+
+    #[test]
+    fn test_unequal_path_delays_glitch_in_timed_mode() {
+        // a AND NOT(a), with the inverter slower than the AND gate.
+        let mut c = Circuit::new();
+        c.set_timed(true).unwrap();
+        let a = c.add_component(Component::input(0, 1));
+        let not = c.add_component(Component::gate(GateOp::Not, 1, 1).with_delay(2));
+        let and = c.add_component(Component::gate(GateOp::And, 2, 1));
+        let out = c.add_component(Component::output());
+        c.link(a, PinId::output(0), not, PinId::input(0));
+        c.link(a, PinId::output(0), and, PinId::input(0));
+        c.link(not, PinId::output(0), and, PinId::input(1));
+        c.link(and, PinId::output(0), out, PinId::input(0));
+        c.advance_time(10).unwrap();
+        assert_eq!(c.read_output(out), Value::ZERO);
+
+        c.set_input(a, 1, 1);
+        let mut seen = vec![];
+        for _ in 0..4 {
+            c.advance_time(1).unwrap();
+            seen.push(c.read_output(out));
+        }
+        assert_eq!(seen, [1, 1, 0, 0].map(|b| Value::new(b, 1)));
+    }
+
+    #[test]
+    fn test_leaving_timed_mode_lands_pending_events() {
+        let mut c = Circuit::new();
+        c.set_timed(true).unwrap();
+        let a = c.add_component(Component::input(0, 1));
+        let not = c.add_component(Component::gate(GateOp::Not, 1, 1).with_delay(5));
+        let out = c.add_component(Component::output());
+        c.link(a, PinId::output(0), not, PinId::input(0));
+        c.link(not, PinId::output(0), out, PinId::input(0));
+        c.advance_time(5).unwrap();
+        assert_eq!(c.read_output(out), Value::ONE);
+
+        c.set_input(a, 1, 1);
+        c.settle().unwrap(); // settles the current instant only
+        assert_eq!(c.read_output(out), Value::ONE);
+        assert_eq!(c.now(), 5);
+
+        c.set_timed(false).unwrap();
+        assert_eq!(c.read_output(out), Value::ZERO);
+        assert_eq!(c.now(), 0);
+    }
+
+    #[test]
+    fn test_reset_sequential_restarts_time() {
+        let (mut c, en, _) = ring_oscillator(true);
+        c.set_input(en, 1, 1);
+        c.advance_time(7).unwrap();
+        c.reset_sequential().unwrap();
+        assert_eq!(c.now(), 0);
+    }
+
+    // The ring oscillator above as a subcircuit instance in `c`, `en` in and
+    // the ring out. Returns (en, out).
+    fn ring_in_subcircuit(c: &mut Circuit) -> (CompKey, CompKey) {
+        let (inner, inner_en, inner_ring) = ring_oscillator(false);
+        let en = c.add_component(Component::input(0, 1));
+        let sub = c.add_component(Component::subcircuit(
+            inner,
+            vec![inner_en],
+            vec![inner_ring],
+        ));
+        let out = c.add_component(Component::output());
+        c.link(en, PinId::output(0), sub, PinId::input(0));
+        c.link(sub, PinId::output(0), out, PinId::input(0));
+        (en, out)
+    }
+
+    // Runs `c` forward one unit at a time, reading `out` after each.
+    fn trace(c: &mut Circuit, out: CompKey, steps: usize) -> Vec<Value> {
+        (0..steps)
+            .map(|_| {
+                c.advance_time(1).unwrap();
+                c.read_output(out)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ring_oscillator_inside_a_subcircuit_runs_in_timed_mode() {
+        let mut c = Circuit::new();
+        let (en, out) = ring_in_subcircuit(&mut c);
+        c.set_timed(true).unwrap();
+        assert_eq!(c.read_output(out), Value::ONE);
+        c.set_input(en, 1, 1);
+        c.settle().unwrap();
+
+        // The same three unit delays as the flat ring.
+        let expected = [1, 1, 0, 0, 0, 1, 1, 1, 0, 0, 0, 1].map(|b| Value::new(b, 1));
+        assert_eq!(trace(&mut c, out, 12), expected);
+        assert_eq!(c.now(), 12);
+
+        // Leaving timed mode leaves the instance zero-delay too.
+        c.set_timed(false).unwrap();
+        assert!(!c.components.values().any(|comp| match &comp.logic {
+            Logic::Sub(sub) => sub.inner.is_timed(),
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn test_subcircuit_placed_mid_run_joins_the_run_at_its_time() {
+        let mut c = Circuit::new();
+        c.set_timed(true).unwrap();
+        c.advance_time(5).unwrap();
+        let (en, out) = ring_in_subcircuit(&mut c);
+        c.set_input(en, 1, 1);
+        c.settle().unwrap();
+
+        let expected = [1, 1, 0, 0, 0, 1].map(|b| Value::new(b, 1));
+        assert_eq!(trace(&mut c, out, 6), expected);
+        assert_eq!(c.now(), 11);
+    }
+
+    #[test]
+    fn test_subcircuit_failing_to_settle_in_timed_mode_fails_the_run() {
+        // Inside: a slow inverter enabling a zero-delay ring, which can't
+        // settle once the inverter's output lands. A pull-up gives the ring a
+        // starting level.
+        let mut inner = Circuit::new();
+        let gate_en = inner.add_component(Component::input(1, 1));
+        let slow = inner.add_component(Component::gate(GateOp::Not, 1, 1).with_delay(2));
+        let nand = inner.add_component(Component::gate(GateOp::Nand, 2, 1).with_delay(0));
+        let not1 = inner.add_component(Component::gate(GateOp::Not, 1, 1).with_delay(0));
+        let not2 = inner.add_component(Component::gate(GateOp::Not, 1, 1).with_delay(0));
+        inner.link(gate_en, PinId::output(0), slow, PinId::input(0));
+        inner.link(slow, PinId::output(0), nand, PinId::input(0));
+        inner.link(nand, PinId::output(0), not1, PinId::input(0));
+        inner.link(not1, PinId::output(0), not2, PinId::input(0));
+        inner.link(not2, PinId::output(0), nand, PinId::input(1));
+        let pull = inner.add_component(Component::pull_resistor(PullDirection::Up, 1));
+        inner.link(pull, PinId::output(0), nand, PinId::input(1));
+        inner.settle().unwrap();
+        let mut c = Circuit::new();
+        let en = c.add_component(Component::input(1, 1));
+        let sub = c.add_component(Component::subcircuit(inner, vec![gate_en], vec![]));
+        c.link(en, PinId::output(0), sub, PinId::input(0));
+        c.set_timed(true).unwrap();
+
+        c.set_input(en, 0, 1);
+        c.settle().unwrap();
+        assert!(matches!(
+            c.advance_time(5),
+            Err(SettleError::Oscillation { .. })
+        ));
+    }
+
     // ---- Group 4: structural operations ----
 
     #[test]
@@ -1525,6 +2555,14 @@ mod tests {
         c.write_rom(rom_key, 5, 0x11);
         c.settle().unwrap();
         assert_eq!(c.read_output(out), Value::new(0xFF, 8));
+
+        // An image replaces every word, zeros past its end.
+        c.load_memory(rom_key, &[1, 2, 0x1FF]);
+        c.settle().unwrap();
+        assert_eq!(c.read_output(out), Value::new(0xFF, 8));
+        c.load_memory(rom_key, &[1]);
+        c.settle().unwrap();
+        assert_eq!(c.read_output(out), Value::new(0, 8));
     }
 
     #[test]
@@ -1563,7 +2601,7 @@ mod tests {
         let mut inner = Circuit::new();
         let d = inner.add_component(Component::input(0, 4));
         let we = inner.add_component(Component::input(0, 1));
-        let reg = inner.add_component(Component::reg(4));
+        let reg = inner.add_component(Component::reg(4, ClockEdge::Rising));
         let o = inner.add_component(Component::output());
         inner.link(d, PinId::output(0), reg, PinId::input(0));
         inner.link(we, PinId::output(0), reg, PinId::input(1));
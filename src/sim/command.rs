@@ -11,7 +11,7 @@ pub enum Command {
     AddComponent(Box<Component>),
     SetInput {
         comp: CompKey,
-        bits: u32,
+        bits: u64,
         width: u8,
     },
     ClearNets,
@@ -37,7 +37,11 @@ pub enum Command {
         new_label: String,
     },
     TickClock,
+    // The clock transport's step (see Circuit::step_clock_sources).
+    StepClockSources,
     ResetSequential,
+    // Runs timed mode forward this many time units (see Circuit::advance_time).
+    AdvanceTime(u64),
     RemoveComponent(CompKey),
 }
 
@@ -84,7 +88,9 @@ impl CommandOutput {
         }
     }
 
-    /// Panics unless this came from `Command::TickClock` or `Command::ResetSequential`.
+    /// Panics unless this came from `Command::TickClock`,
+    /// `Command::StepClockSources`, `Command::ResetSequential` or
+    /// `Command::AdvanceTime`.
     pub fn unwrap_settle(self) -> Result<(), SettleError> {
         match self {
             Self::Settle(r) => r,
@@ -112,7 +118,7 @@ pub enum UndoAction {
     InsertComponent(CompKey, Box<Component>),
     SetInput {
         comp: CompKey,
-        old_bits: u32,
+        old_bits: u64,
         old_width: u8,
     },
     RemoveTunnel(TunnelKey),
@@ -121,7 +127,8 @@ pub enum UndoAction {
         tunnel: TunnelKey,
         old_label: String,
     },
-    /// Never actually reached: `TickClock` undo is issued untracked (see `apply_undo`).
+    /// Never actually reached: `TickClock` and `StepClockSources` are issued
+    /// untracked (see `apply_undo`).
     RestoreSeqState {
         snapshots: Vec<(CompKey, SeqState)>,
     },
@@ -129,8 +136,10 @@ pub enum UndoAction {
 
 impl Circuit {
     /// Applies a `Command` and returns its output and the reversing
-    /// `UndoAction`. Does not call `settle()`, except `TickClock` which
-    /// settles internally.
+    /// `UndoAction`. Does not call `settle()`, except the simulation steps
+    /// (`TickClock`, `StepClockSources`, `ResetSequential`, `AdvanceTime`),
+    /// which settle
+    /// internally.
     pub fn apply(&mut self, command: Command) -> (CommandOutput, UndoAction) {
         puffin::profile_function!();
         match command {
@@ -140,7 +149,9 @@ impl Circuit {
             }
             Command::SetInput { comp, bits, width } => {
                 let old = match &self.components[&comp].logic {
-                    Logic::Comb(LogicComb::Input(Input { bits: b, width: w })) => Some((*b, *w)),
+                    Logic::Comb(LogicComb::Input(Input {
+                        bits: b, width: w, ..
+                    })) => Some((*b, *w)),
                     _ => None,
                 };
                 self.set_input(comp, bits, width);
@@ -196,7 +207,7 @@ impl Circuit {
                 };
                 (CommandOutput::None, undo)
             }
-            Command::TickClock => {
+            Command::TickClock | Command::StepClockSources => {
                 let snapshots: Vec<(CompKey, SeqState)> = self
                     .components
                     .iter()
@@ -205,7 +216,10 @@ impl Circuit {
                         _ => None,
                     })
                     .collect();
-                let result = self.tick_clock();
+                let result = match command {
+                    Command::TickClock => self.tick_clock(),
+                    _ => self.step_clock_sources(),
+                };
                 (
                     CommandOutput::Settle(result),
                     UndoAction::RestoreSeqState { snapshots },
@@ -217,6 +231,11 @@ impl Circuit {
                 CommandOutput::Settle(self.reset_sequential()),
                 UndoAction::NoOp,
             ),
+            // Also a simulation step, issued untracked.
+            Command::AdvanceTime(dt) => (
+                CommandOutput::Settle(self.advance_time(dt)),
+                UndoAction::NoOp,
+            ),
             Command::RemoveComponent(key) => {
                 let undo = match self.remove_component(key) {
                     Some(comp) => UndoAction::InsertComponent(key, Box::new(comp)),
@@ -257,7 +276,7 @@ impl Circuit {
                 // Capture the current value first so the returned inverse can
                 // restore it on redo.
                 let current = match &self.components[&comp].logic {
-                    Logic::Comb(LogicComb::Input(Input { bits, width })) => (*bits, *width),
+                    Logic::Comb(LogicComb::Input(Input { bits, width, .. })) => (*bits, *width),
                     _ => (old_bits, old_width),
                 };
                 self.set_input(comp, old_bits, old_width);
@@ -295,7 +314,7 @@ impl Circuit {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sim::component::GateOp;
+    use crate::sim::component::{ClockEdge, GateOp};
     use crate::sim::value::Value;
 
     // ---- Forward dispatch (Command -> Circuit mutation) ----
@@ -428,7 +447,7 @@ mod tests {
             b_pin: PinId::input(0),
         });
         c.settle().unwrap();
-        assert_eq!(c.read_output(o), Value::Invalid);
+        assert_eq!(c.read_output(o), Value::unknown(1));
 
         c.apply(Command::RemoveComponent(d2));
         c.settle().unwrap();
@@ -446,7 +465,10 @@ mod tests {
             .apply(Command::comp(Component::input(1, 1)))
             .0
             .unwrap_comp();
-        let reg = c.apply(Command::comp(Component::reg(1))).0.unwrap_comp();
+        let reg = c
+            .apply(Command::comp(Component::reg(1, ClockEdge::Rising)))
+            .0
+            .unwrap_comp();
         let out = c.apply(Command::comp(Component::output())).0.unwrap_comp();
         c.apply(Command::Link {
             a: data,
@@ -561,7 +583,10 @@ mod tests {
             .apply(Command::comp(Component::input(1, 1)))
             .0
             .unwrap_comp();
-        let reg = c.apply(Command::comp(Component::reg(1))).0.unwrap_comp();
+        let reg = c
+            .apply(Command::comp(Component::reg(1, ClockEdge::Rising)))
+            .0
+            .unwrap_comp();
         let out = c.apply(Command::comp(Component::output())).0.unwrap_comp();
         c.link(data, PinId::output(0), reg, PinId::input(0));
         c.link(we, PinId::output(0), reg, PinId::input(1));
@@ -758,7 +783,10 @@ mod tests {
             .apply(Command::comp(Component::input(1, 1)))
             .0
             .unwrap_comp();
-        let reg = c.apply(Command::comp(Component::reg(1))).0.unwrap_comp();
+        let reg = c
+            .apply(Command::comp(Component::reg(1, ClockEdge::Rising)))
+            .0
+            .unwrap_comp();
         let out = c.apply(Command::comp(Component::output())).0.unwrap_comp();
         c.apply(Command::Link {
             a: data,
@@ -809,7 +837,10 @@ mod tests {
             .apply(Command::comp(Component::input(1, 1)))
             .0
             .unwrap_comp();
-        let reg = c.apply(Command::comp(Component::reg(1))).0.unwrap_comp();
+        let reg = c
+            .apply(Command::comp(Component::reg(1, ClockEdge::Rising)))
+            .0
+            .unwrap_comp();
         c.link(data, PinId::output(0), reg, PinId::input(0));
         c.link(we, PinId::output(0), reg, PinId::input(1));
         c.settle().unwrap();
@@ -1,9 +1,10 @@
 use crate::sim::circuit::Circuit;
-use crate::sim::net::{Net, NetKey};
+use crate::sim::net::{Net, NetKey, Strength};
 use crate::sim::value::Value;
 use slotmap::{new_key_type, SlotMap};
 
 mod adder;
+mod clock;
 mod comparator;
 mod constant;
 mod counter;
@@ -16,6 +17,7 @@ mod input;
 mod jk_flip_flop;
 mod multiplier;
 mod mux;
+mod pull_resistor;
 mod ram;
 mod reg;
 mod rom;
@@ -24,8 +26,11 @@ mod splitter;
 mod sr_flip_flop;
 mod subtractor;
 mod t_flip_flop;
+mod tri_state;
 
 pub use adder::Adder;
+pub use clock::{ClockEdge, ClockSource, ClockSourceConf};
+use clock::{ClockPin, EdgeOnlyConf};
 pub use comparator::Comparator;
 pub use constant::Constant;
 pub use counter::{Counter, CounterConf, OverflowAction};
@@ -38,6 +43,7 @@ pub use input::Input;
 pub use jk_flip_flop::{JKFlipFlop, JKFlipFlopConf};
 pub use multiplier::Multiplier;
 pub use mux::Mux;
+pub use pull_resistor::{PullDirection, PullResistor};
 pub use ram::{Ram, RamCell, ReadBehavior};
 pub use reg::{Reg, RegConf};
 pub use rom::{Rom, MAX_ADDRESS_WIDTH};
@@ -46,6 +52,7 @@ pub use splitter::{FanDirection, Splitter};
 pub use sr_flip_flop::{SRFlipFlop, SRFlipFlopConf};
 pub use subtractor::Subtractor;
 pub use t_flip_flop::{TFlipFlop, TFlipFlopConf};
+pub use tri_state::TriState;
 
 /// Stable, app-assigned id for a `Component`; survives remove + re-insert (ids never reused).
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
@@ -61,31 +68,48 @@ new_key_type! {
 pub struct Component {
     pub pins: Pins,
     pub logic: Logic,
+    // Edge detector for a sequential component's clock pin, if it has one.
+    clock: Option<ClockPin>,
+    // Time units from an input change to the output change it causes, in the
+    // circuit's timed mode (see Circuit::set_timed). Ignored otherwise.
+    pub delay: u32,
 }
 
 impl Component {
     fn from_comb(logic: LogicComb) -> Self {
         let pins = Pins::new(logic.n_inputs(), logic.n_outputs());
+        let delay = logic.default_delay();
         Self {
             pins,
             logic: Logic::Comb(logic),
+            clock: None,
+            delay,
         }
     }
 
     fn from_seq(logic: LogicSeq) -> Self {
         let pins = Pins::new(logic.n_inputs(), logic.n_outputs());
+        let clock = logic
+            .clock_pin()
+            .map(|(pin, edge)| ClockPin::new(pin, edge));
+        let delay = logic.default_delay();
         Self {
             pins,
             logic: Logic::Seq(logic),
+            clock,
+            delay,
         }
     }
 
-    pub fn input(bits: u32, width: u8) -> Self {
-        Self::from_comb(LogicComb::Input(Input { bits, width }))
+    pub fn input(bits: u64, width: u8) -> Self {
+        Self::from_comb(LogicComb::Input(Input::new(bits, width)))
     }
-    pub fn constant(bits: u32, width: u8) -> Self {
+    pub fn constant(bits: u64, width: u8) -> Self {
         Self::from_comb(LogicComb::Constant(Constant { bits, width }))
     }
+    pub fn pull_resistor(direction: PullDirection, width: u8) -> Self {
+        Self::from_comb(LogicComb::PullResistor(PullResistor { direction, width }))
+    }
     pub fn output() -> Self {
         Self::from_comb(LogicComb::Output)
     }
@@ -101,6 +125,10 @@ impl Component {
         }))
     }
 
+    pub fn tri_state(data_width: u8) -> Self {
+        Self::from_comb(LogicComb::TriState(TriState { data_width }))
+    }
+
     pub fn mux(data_width: u8, sel_width: u8) -> Self {
         Self::from_comb(LogicComb::Mux(Mux {
             data_width,
@@ -115,42 +143,58 @@ impl Component {
         }))
     }
 
-    pub fn reg(data_width: u8) -> Self {
-        Self::from_seq(LogicSeq::Reg(Reg::new(data_width)))
+    pub fn reg(data_width: u8, clock_edge: ClockEdge) -> Self {
+        Self::from_seq(LogicSeq::Reg(Reg::new(data_width, clock_edge)))
     }
 
-    pub fn shift_reg(data_width: u8, num_stages: usize, parallel_load: bool) -> Self {
+    pub fn shift_reg(
+        data_width: u8,
+        num_stages: usize,
+        parallel_load: bool,
+        clock_edge: ClockEdge,
+    ) -> Self {
         Self::from_seq(LogicSeq::ShiftReg(ShiftReg::new(
             data_width,
             num_stages,
             parallel_load,
+            clock_edge,
         )))
     }
 
-    pub fn d_flip_flop() -> Self {
-        Self::from_seq(LogicSeq::DFlipFlop(DFlipFlop::new()))
+    pub fn d_flip_flop(clock_edge: ClockEdge) -> Self {
+        Self::from_seq(LogicSeq::DFlipFlop(DFlipFlop::new(clock_edge)))
     }
 
-    pub fn t_flip_flop() -> Self {
-        Self::from_seq(LogicSeq::TFlipFlop(TFlipFlop::new()))
+    pub fn t_flip_flop(clock_edge: ClockEdge) -> Self {
+        Self::from_seq(LogicSeq::TFlipFlop(TFlipFlop::new(clock_edge)))
     }
 
-    pub fn jk_flip_flop() -> Self {
-        Self::from_seq(LogicSeq::JKFlipFlop(JKFlipFlop::new()))
+    pub fn jk_flip_flop(clock_edge: ClockEdge) -> Self {
+        Self::from_seq(LogicSeq::JKFlipFlop(JKFlipFlop::new(clock_edge)))
     }
 
-    pub fn sr_flip_flop() -> Self {
-        Self::from_seq(LogicSeq::SRFlipFlop(SRFlipFlop::new()))
+    pub fn sr_flip_flop(clock_edge: ClockEdge) -> Self {
+        Self::from_seq(LogicSeq::SRFlipFlop(SRFlipFlop::new(clock_edge)))
     }
 
-    pub fn counter(data_width: u8, max_value: u32, overflow_action: OverflowAction) -> Self {
+    pub fn counter(
+        data_width: u8,
+        max_value: u64,
+        overflow_action: OverflowAction,
+        clock_edge: ClockEdge,
+    ) -> Self {
         Self::from_seq(LogicSeq::Counter(Counter::new(
             data_width,
             max_value,
             overflow_action,
+            clock_edge,
         )))
     }
 
+    pub fn clock_source(conf: ClockSourceConf) -> Self {
+        Self::from_seq(LogicSeq::ClockSource(ClockSource::new(conf)))
+    }
+
     pub fn splitter(arm_bits: Vec<Vec<u8>>, direction: FanDirection) -> Self {
         Self::from_comb(LogicComb::Splitter(Splitter::new(arm_bits, direction)))
     }
@@ -165,6 +209,10 @@ impl Component {
                 inputs,
                 outputs,
             })),
+            clock: None,
+            // The inner circuit's own parts carry the timing: it switches to
+            // timed mode with the enclosing circuit (see Circuit::set_timed).
+            delay: 0,
         }
     }
 
@@ -178,6 +226,8 @@ impl Component {
                 inputs: Vec::new(),
                 outputs: Vec::new(),
             })),
+            clock: None,
+            delay: 0,
         }
     }
 
@@ -214,6 +264,21 @@ impl Component {
         Self::from_seq(LogicSeq::Ram(RamCell::new(ram)))
     }
 
+    pub fn with_delay(mut self, delay: u32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    // A placed part's delay as saved with it (see io::ComponentEntry): None
+    // keeps this kind's default. A subcircuit's timing lives in its inner
+    // circuit, so it never takes one.
+    pub fn with_saved_delay(self, delay: Option<u32>) -> Self {
+        match delay {
+            Some(delay) if !matches!(self.logic, Logic::Sub(_)) => self.with_delay(delay),
+            _ => self,
+        }
+    }
+
     pub fn read_inputs(&self, nets: &SlotMap<NetKey, Net>) -> Vec<Value> {
         self.pins
             .inputs
@@ -238,9 +303,20 @@ impl Component {
 
     // Applies a sequential component's async, level-sensitive logic (e.g. an async reset) to its
     // latched state. No-op for combinational components. Must be idempotent (see SeqLogic::apply_async).
+    // A component with a clock pin also latches here, on that pin's edge: the
+    // edge is seen when settle() re-evaluates the component after its clock
+    // net changes.
     pub fn apply_async(&mut self, inputs: &[Value]) {
-        match &mut self.logic {
-            Logic::Seq(seq) => seq.apply_async(inputs),
+        let Self { logic, clock, .. } = self;
+        match logic {
+            Logic::Seq(seq) => {
+                seq.apply_async(inputs);
+                if let Some(clock) = clock {
+                    if clock.fires(inputs[clock.pin]) {
+                        seq.tick(inputs);
+                    }
+                }
+            }
             // Drives the boundary Inputs and settles the inner circuit; idempotent.
             Logic::Sub(sub) => sub.drive_and_settle(inputs),
             Logic::Comb(_) => {}
@@ -267,6 +343,9 @@ impl Component {
 
     // Restores latched sequential state to its power-on initial value. Only valid on Logic::Seq.
     pub fn reset(&mut self) {
+        if let Some(clock) = &mut self.clock {
+            clock.reset();
+        }
         match &mut self.logic {
             Logic::Comb(_) => unreachable!("reset() called on a combinational component"),
             Logic::Seq(seq) => seq.reset(),
@@ -274,6 +353,30 @@ impl Component {
         }
     }
 
+    // Re-reads the clock pin's level after a reset, so the next transition is
+    // judged against it rather than against Floating.
+    pub(crate) fn sync_clock(&mut self, inputs: &[Value]) {
+        if let Some(clock) = &mut self.clock {
+            clock.sync(inputs[clock.pin]);
+        }
+    }
+
+    // The level a sequential component's clock pin last saw, if it has one.
+    pub(crate) fn clock_level(&self) -> Option<Value> {
+        self.clock.as_ref().map(ClockPin::level)
+    }
+
+    // Puts back the latched state and clock-pin level a snapshot took (see
+    // sim::rewind). No-op on anything but a sequential component.
+    pub(crate) fn restore(&mut self, state: &SeqState, clock_level: Option<Value>) {
+        if let Logic::Seq(seq) = &mut self.logic {
+            seq.restore(state);
+        }
+        if let (Some(clock), Some(level)) = (&mut self.clock, clock_level) {
+            clock.sync(level);
+        }
+    }
+
     pub fn net_of(&self, pin: PinId) -> Option<NetKey> {
         match pin {
             // TODO: will panic on out of bounds, fix this
@@ -293,6 +396,25 @@ impl Component {
         matches!(self.logic, Logic::Seq(_) | Logic::Sub(_))
     }
 
+    // Whether Circuit::tick_clock() advances this component directly. A
+    // component whose clock pin is wired latches on that pin's edges instead;
+    // an unwired clock pin falls back to the global clock, so circuits drawn
+    // before clock pins existed keep working.
+    pub fn follows_global_clock(&self) -> bool {
+        match &self.clock {
+            Some(clock) => self.pins.inputs[clock.pin].is_none(),
+            None => self.is_stateful(),
+        }
+    }
+
+    // How hard this component's outputs hold a shared net (see Circuit::resolve_net).
+    pub fn strength(&self) -> Strength {
+        match self.logic {
+            Logic::Comb(LogicComb::PullResistor(_)) => Strength::Weak,
+            _ => Strength::Strong,
+        }
+    }
+
     pub fn input_width(&self, i: InIdx) -> Option<u8> {
         match &self.logic {
             Logic::Comb(c) => c.input_width(i.0 as usize),
@@ -319,6 +441,15 @@ impl Component {
     }
 }
 
+// The spec side of an Output: the live LogicComb::Output is a unit, so its
+// `label` (the name test vectors and the CLI know it by) lives only here.
+// Empty means unlabelled.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Output {
+    #[serde(default)]
+    pub label: String,
+}
+
 // A passive observer placed on a net: one input, no output, any width. Like an
 // Output, but it carries a user-set `name` and feeds the signal viewer. The name
 // lives only here (the live LogicComb::Probe is a unit, like Output).
@@ -333,9 +464,11 @@ pub struct Probe {
 pub enum ComponentSpec {
     Input(Input),
     Constant(Constant),
-    Output,
+    PullResistor(PullResistor),
+    Output(Output),
     Probe(Probe),
     Gate(Gate),
+    TriState(TriState),
     Mux(Mux),
     Demux(Demux),
     Reg(RegConf),
@@ -353,6 +486,7 @@ pub enum ComponentSpec {
     JKFlipFlop(JKFlipFlopConf),
     SRFlipFlop(SRFlipFlopConf),
     Counter(CounterConf),
+    ClockSource(ClockSourceConf),
     Splitter {
         // The trunk width edited in the properties panel; to_component() never reads it back.
         width: u8,
@@ -384,9 +518,11 @@ impl ComponentSpec {
         match self {
             Self::Input(_) => 0,
             Self::Constant(_) => 0,
-            Self::Output => 1,
+            Self::PullResistor(_) => 0,
+            Self::Output(_) => 1,
             Self::Probe(_) => 1,
             Self::Gate(g) => g.n_inputs(),
+            Self::TriState(t) => t.n_inputs(),
             Self::Mux(m) => m.n_inputs(),
             Self::Demux(d) => d.n_inputs(),
             Self::Reg(r) => r.n_inputs(),
@@ -404,6 +540,7 @@ impl ComponentSpec {
             Self::JKFlipFlop(ff) => ff.n_inputs(),
             Self::SRFlipFlop(ff) => ff.n_inputs(),
             Self::Counter(c) => c.n_inputs(),
+            Self::ClockSource(c) => c.n_inputs(),
             Self::Splitter {
                 arm_bits,
                 direction,
@@ -420,9 +557,11 @@ impl ComponentSpec {
         match self {
             Self::Input(_) => 1,
             Self::Constant(_) => 1,
-            Self::Output => 0,
+            Self::PullResistor(_) => 1,
+            Self::Output(_) => 0,
             Self::Probe(_) => 0,
             Self::Gate(g) => g.n_outputs(),
+            Self::TriState(t) => t.n_outputs(),
             Self::Mux(m) => m.n_outputs(),
             Self::Demux(d) => d.n_outputs(),
             Self::Reg(r) => r.n_outputs(),
@@ -440,6 +579,7 @@ impl ComponentSpec {
             Self::JKFlipFlop(ff) => ff.n_outputs(),
             Self::SRFlipFlop(ff) => ff.n_outputs(),
             Self::Counter(c) => c.n_outputs(),
+            Self::ClockSource(c) => c.n_outputs(),
             Self::Splitter {
                 arm_bits,
                 direction,
@@ -452,19 +592,36 @@ impl ComponentSpec {
         }
     }
 
+    // The name test vectors and the CLI address this component by: an Input's
+    // or Output's label, or a Probe's name. None for anything else, or an
+    // unlabelled Input/Output.
+    pub fn port_name(&self) -> Option<&str> {
+        let name = match self {
+            Self::Input(Input { label, .. }) | Self::Output(Output { label }) => label,
+            Self::Probe(Probe { name }) => name,
+            _ => return None,
+        };
+        (!name.is_empty()).then_some(name.as_str())
+    }
+
     pub(crate) fn to_component(&self) -> Component {
         match self {
             Self::Input(p) => Component::input(p.bits, p.width),
             Self::Constant(c) => Component::constant(c.bits, c.width),
-            Self::Output => Component::output(),
+            Self::PullResistor(p) => Component::pull_resistor(p.direction, p.width),
+            Self::Output(_) => Component::output(),
             Self::Probe(_) => Component::probe(),
             Self::Gate(g) => Component::gate(g.op, g.n_inputs, g.width),
+            Self::TriState(t) => Component::tri_state(t.data_width),
             Self::Mux(m) => Component::mux(m.data_width, m.sel_width),
             Self::Demux(d) => Component::demux(d.data_width, d.sel_width),
-            Self::Reg(r) => Component::reg(r.data_width),
-            Self::ShiftReg(sr) => {
-                Component::shift_reg(sr.data_width, sr.num_stages, sr.parallel_load)
-            }
+            Self::Reg(r) => Component::reg(r.data_width, r.clock_edge),
+            Self::ShiftReg(sr) => Component::shift_reg(
+                sr.data_width,
+                sr.num_stages,
+                sr.parallel_load,
+                sr.clock_edge,
+            ),
             Self::Encoder(e) => Component::priority_encoder(e.sel_width),
             Self::Adder(a) => Component::adder(a.data_width),
             Self::Subtractor(s) => Component::subtractor(s.data_width),
@@ -478,11 +635,14 @@ impl ComponentSpec {
             Self::Rom(r) => Component::rom(r.shared()),
             // Same aliasing as Rom above (see Ram's docs).
             Self::Ram(r) => Component::ram(r.shared()),
-            Self::DFlipFlop(_) => Component::d_flip_flop(),
-            Self::TFlipFlop(_) => Component::t_flip_flop(),
-            Self::JKFlipFlop(_) => Component::jk_flip_flop(),
-            Self::SRFlipFlop(_) => Component::sr_flip_flop(),
-            Self::Counter(c) => Component::counter(c.data_width, c.max_value, c.overflow_action),
+            Self::DFlipFlop(ff) => Component::d_flip_flop(ff.clock_edge),
+            Self::TFlipFlop(ff) => Component::t_flip_flop(ff.clock_edge),
+            Self::JKFlipFlop(ff) => Component::jk_flip_flop(ff.clock_edge),
+            Self::SRFlipFlop(ff) => Component::sr_flip_flop(ff.clock_edge),
+            Self::Counter(c) => {
+                Component::counter(c.data_width, c.max_value, c.overflow_action, c.clock_edge)
+            }
+            Self::ClockSource(c) => Component::clock_source(c.clone()),
             Self::Splitter {
                 arm_bits,
                 direction,
@@ -602,6 +762,13 @@ impl SubCircuit {
         self.observe()
     }
 
+    // tick() for the transport's step (see Circuit::step_clock_sources).
+    pub(crate) fn step_clock_sources(&mut self, inputs: &[Value]) -> Vec<Value> {
+        self.drive_inputs(inputs);
+        let _ = self.inner.step_clock_sources();
+        self.observe()
+    }
+
     fn reset(&mut self) {
         let _ = self.inner.reset_sequential();
     }
@@ -638,15 +805,29 @@ pub trait CombLogic {
     fn output_width(&self, i: usize) -> Option<u8>;
 }
 
+// Shared by the arithmetic components: true when every operand is at the
+// component's data width (the carry/borrow-in at its own width, or Floating,
+// which reads as 0) and at least one carries a Z/X bit. Their whole result
+// is then unknown (all X) rather than Floating.
+fn has_unknown_operand(operands: &[Value], width: u8, carry_in: Option<(Value, u8)>) -> bool {
+    let sized = |v: Value, w: u8| v.width() == Some(w);
+    let carry_ok = carry_in.is_none_or(|(v, w)| v == Value::Floating || sized(v, w));
+    let any_partial =
+        operands.iter().any(|v| v.is_partial()) || carry_in.is_some_and(|(v, _)| v.is_partial());
+    operands.iter().all(|&v| sized(v, width)) && carry_ok && any_partial
+}
+
 #[derive(Debug)]
 pub enum LogicComb {
     Input(Input),
     Constant(Constant),
+    PullResistor(PullResistor),
     Output,
     // A passive observer: like Output, but marked distinctly so the GUI can read
     // it for the signal viewer. Carries no state; its name lives in ComponentSpec.
     Probe,
     Gate(Gate),
+    TriState(TriState),
     Mux(Mux),
     Demux(Demux),
     Splitter(Splitter),
@@ -664,9 +845,11 @@ impl LogicComb {
         match self {
             Self::Input(p) => p.n_inputs(),
             Self::Constant(c) => c.n_inputs(),
+            Self::PullResistor(p) => p.n_inputs(),
             Self::Output => 1,
             Self::Probe => 1,
             Self::Gate(g) => g.n_inputs(),
+            Self::TriState(t) => t.n_inputs(),
             Self::Mux(m) => m.n_inputs(),
             Self::Demux(d) => d.n_inputs(),
             Self::Splitter(s) => s.n_inputs(),
@@ -684,9 +867,11 @@ impl LogicComb {
         match self {
             Self::Input(p) => p.n_outputs(),
             Self::Constant(c) => c.n_outputs(),
+            Self::PullResistor(p) => p.n_outputs(),
             Self::Output => 0,
             Self::Probe => 0,
             Self::Gate(g) => g.n_outputs(),
+            Self::TriState(t) => t.n_outputs(),
             Self::Mux(m) => m.n_outputs(),
             Self::Demux(d) => d.n_outputs(),
             Self::Splitter(s) => s.n_outputs(),
@@ -700,13 +885,29 @@ impl LogicComb {
         }
     }
 
+    // Sources, sinks and the purely structural splitter switch instantly;
+    // anything that computes takes one time unit.
+    pub fn default_delay(&self) -> u32 {
+        match self {
+            Self::Input(_)
+            | Self::Constant(_)
+            | Self::PullResistor(_)
+            | Self::Output
+            | Self::Probe
+            | Self::Splitter(_) => 0,
+            _ => 1,
+        }
+    }
+
     pub fn evaluate(&self, inputs: &[Value]) -> Vec<Value> {
         match self {
             Self::Input(p) => p.evaluate(inputs),
             Self::Constant(c) => c.evaluate(inputs),
+            Self::PullResistor(p) => p.evaluate(inputs),
             Self::Output => vec![],
             Self::Probe => vec![],
             Self::Gate(g) => g.evaluate(inputs),
+            Self::TriState(t) => t.evaluate(inputs),
             Self::Mux(m) => m.evaluate(inputs),
             Self::Demux(d) => d.evaluate(inputs),
             Self::Splitter(s) => s.evaluate(inputs),
@@ -724,9 +925,11 @@ impl LogicComb {
         match self {
             Self::Input(p) => p.input_width(i),
             Self::Constant(c) => c.input_width(i),
+            Self::PullResistor(p) => p.input_width(i),
             Self::Output => None,
             Self::Probe => None,
             Self::Gate(g) => g.input_width(i),
+            Self::TriState(t) => t.input_width(i),
             Self::Mux(m) => m.input_width(i),
             Self::Demux(d) => d.input_width(i),
             Self::Splitter(s) => s.input_width(i),
@@ -744,9 +947,11 @@ impl LogicComb {
         match self {
             Self::Input(p) => p.output_width(i),
             Self::Constant(c) => c.output_width(i),
+            Self::PullResistor(p) => p.output_width(i),
             Self::Output => None,
             Self::Probe => None,
             Self::Gate(g) => g.output_width(i),
+            Self::TriState(t) => t.output_width(i),
             Self::Mux(m) => m.output_width(i),
             Self::Demux(d) => d.output_width(i),
             Self::Splitter(s) => s.output_width(i),
@@ -779,10 +984,17 @@ pub trait SeqLogic {
     // latched state by apply_async(), so this stays a pure read.
     fn observe(&self) -> Vec<Value>;
     fn snapshot(&self) -> SeqState;
+    // Puts back latched state a snapshot() took; a snapshot of another kind
+    // of part is ignored. Drives the GUI's reverse stepping (see sim::rewind).
+    fn restore(&mut self, state: &SeqState);
     // Restores the latched state to its power-on initial value (what the
     // constructor sets), without touching construction params. Drives the
     // GUI's clock "Stop" (see Circuit::reset_sequential).
     fn reset(&mut self);
+    // The clock input pin and the edge that latches on it, if any. When that
+    // pin is wired, Component::apply_async() calls tick() on the edge and the
+    // global clock leaves the component alone (Component::follows_global_clock).
+    fn clock_pin(&self) -> Option<(usize, ClockEdge)>;
     // Expected bit width of pin `i`, from construction params (not any live
     // Value). None means any width (currently only Output). Used by
     // Circuit::resolve_net() to flag width-disagreeing nets.
@@ -803,18 +1015,21 @@ pub enum LogicSeq {
     SRFlipFlop(SRFlipFlop),
     Counter(Counter),
     Ram(RamCell),
+    ClockSource(ClockSource),
 }
 
 // Generic reflection of LogicSeq's persisted state - one arm per LogicSeq
 // variant, colocated here for the same "new variant -> matching arm"
-// locality the tick/observe dispatch above already relies on.
-#[derive(Debug, Clone)]
+// locality the tick/observe dispatch above already relies on. Serialized in
+// checkpoint files (see checkpoint.rs).
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum SeqState {
     Reg(Value),
     ShiftReg(Vec<Value>),
     FlipFlop(Value),
     Counter { value: Value, carry: Value },
     Ram(Value),
+    ClockSource(u32),
 }
 
 impl LogicSeq {
@@ -828,6 +1043,7 @@ impl LogicSeq {
             Self::SRFlipFlop(ff) => ff.n_inputs(),
             Self::Counter(c) => c.n_inputs(),
             Self::Ram(r) => r.n_inputs(),
+            Self::ClockSource(c) => c.n_inputs(),
         }
     }
 
@@ -841,6 +1057,15 @@ impl LogicSeq {
             Self::SRFlipFlop(ff) => ff.n_outputs(),
             Self::Counter(c) => c.n_outputs(),
             Self::Ram(r) => r.n_outputs(),
+            Self::ClockSource(c) => c.n_outputs(),
+        }
+    }
+
+    // A clock source is a stimulus, not logic: its edges land when ticked.
+    pub fn default_delay(&self) -> u32 {
+        match self {
+            Self::ClockSource(_) => 0,
+            _ => 1,
         }
     }
 
@@ -854,6 +1079,7 @@ impl LogicSeq {
             Self::SRFlipFlop(ff) => ff.tick(inputs),
             Self::Counter(c) => c.tick(inputs),
             Self::Ram(r) => r.tick(inputs),
+            Self::ClockSource(c) => c.tick(inputs),
         }
     }
 
@@ -867,6 +1093,7 @@ impl LogicSeq {
             Self::SRFlipFlop(ff) => ff.apply_async(inputs),
             Self::Counter(c) => c.apply_async(inputs),
             Self::Ram(r) => r.apply_async(inputs),
+            Self::ClockSource(c) => c.apply_async(inputs),
         }
     }
 
@@ -880,6 +1107,7 @@ impl LogicSeq {
             Self::SRFlipFlop(ff) => ff.observe(),
             Self::Counter(c) => c.observe(),
             Self::Ram(r) => r.observe(),
+            Self::ClockSource(c) => c.observe(),
         }
     }
 
@@ -893,6 +1121,7 @@ impl LogicSeq {
             Self::SRFlipFlop(ff) => ff.reset(),
             Self::Counter(c) => c.reset(),
             Self::Ram(r) => r.reset(),
+            Self::ClockSource(c) => c.reset(),
         }
     }
 
@@ -910,6 +1139,35 @@ impl LogicSeq {
             Self::SRFlipFlop(ff) => ff.snapshot(),
             Self::Counter(c) => c.snapshot(),
             Self::Ram(r) => r.snapshot(),
+            Self::ClockSource(c) => c.snapshot(),
+        }
+    }
+
+    pub(crate) fn restore(&mut self, state: &SeqState) {
+        match self {
+            Self::Reg(reg) => reg.restore(state),
+            Self::ShiftReg(sr) => sr.restore(state),
+            Self::DFlipFlop(ff) => ff.restore(state),
+            Self::TFlipFlop(ff) => ff.restore(state),
+            Self::JKFlipFlop(ff) => ff.restore(state),
+            Self::SRFlipFlop(ff) => ff.restore(state),
+            Self::Counter(c) => c.restore(state),
+            Self::Ram(r) => r.restore(state),
+            Self::ClockSource(c) => c.restore(state),
+        }
+    }
+
+    pub fn clock_pin(&self) -> Option<(usize, ClockEdge)> {
+        match self {
+            Self::Reg(reg) => reg.clock_pin(),
+            Self::ShiftReg(sr) => sr.clock_pin(),
+            Self::DFlipFlop(ff) => ff.clock_pin(),
+            Self::TFlipFlop(ff) => ff.clock_pin(),
+            Self::JKFlipFlop(ff) => ff.clock_pin(),
+            Self::SRFlipFlop(ff) => ff.clock_pin(),
+            Self::Counter(c) => c.clock_pin(),
+            Self::Ram(r) => r.clock_pin(),
+            Self::ClockSource(c) => c.clock_pin(),
         }
     }
 
@@ -923,6 +1181,7 @@ impl LogicSeq {
             Self::SRFlipFlop(ff) => ff.input_width(i),
             Self::Counter(c) => c.input_width(i),
             Self::Ram(r) => r.input_width(i),
+            Self::ClockSource(c) => c.input_width(i),
         }
     }
 
@@ -936,6 +1195,7 @@ impl LogicSeq {
             Self::SRFlipFlop(ff) => ff.output_width(i),
             Self::Counter(c) => c.output_width(i),
             Self::Ram(r) => r.output_width(i),
+            Self::ClockSource(c) => c.output_width(i),
         }
     }
 }
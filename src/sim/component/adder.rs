@@ -1,4 +1,4 @@
-use super::CombLogic;
+use super::{has_unknown_operand, CombLogic};
 use crate::sim::value::Value;
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -27,7 +27,7 @@ impl CombLogic for Adder {
         // A Floating carry-in behaves the same as a zero carry-in; anything else
         // non-Fixed, or a Fixed carry-in at the wrong width, falls through to Floating.
         let carry_in = match inputs[Self::CARRY_IN_PIN] {
-            Value::Floating => Some(0u32),
+            Value::Floating => Some(0u64),
             Value::Fixed { bits, width: 1 } => Some(bits),
             _ => None,
         };
@@ -41,17 +41,22 @@ impl CombLogic for Adder {
                 Value::Fixed { bits: b, width: bw },
                 Some(cin),
             ) if aw == self.data_width && bw == self.data_width => {
-                // Widen to u64 so a+b+cin can't overflow u32 (both addends can be up
-                // to Value::mask(32) = u32::MAX) before it's split back into sum/carry.
-                let sum_full = a as u64 + b as u64 + cin as u64;
-                let sum = (sum_full & Value::mask(self.data_width) as u64) as u32;
-                let carry = if self.data_width < 64 {
-                    ((sum_full >> self.data_width) & 1) as u32
-                } else {
-                    0
-                };
+                // Widen to u128 so a+b+cin can't overflow u64 (both addends can be up
+                // to Value::mask(64) = u64::MAX) before it's split back into sum/carry.
+                let sum_full = a as u128 + b as u128 + cin as u128;
+                let sum = (sum_full & Value::mask(self.data_width) as u128) as u64;
+                let carry = ((sum_full >> self.data_width) & 1) as u64;
                 vec![Value::new(sum, self.data_width), Value::new(carry, 1)]
             }
+            (a, b, _)
+                if has_unknown_operand(
+                    &[a, b],
+                    self.data_width,
+                    Some((inputs[Self::CARRY_IN_PIN], 1)),
+                ) =>
+            {
+                vec![Value::unknown(self.data_width), Value::unknown(1)]
+            }
             _ => vec![Value::Floating, Value::Floating],
         }
     }
@@ -129,17 +134,46 @@ mod tests {
 
     #[test]
     fn test_full_width_addend_does_not_panic() {
-        // Both addends at max u32 for a 32-bit adder: exercises the u64 widening
-        // that avoids an overflow panic on plain u32 addition.
+        // Both addends at max u32 for a 32-bit adder: the carry-out must come
+        // from bit 32 of the widened sum, not be lost when it's masked back down.
         let a = adder(32);
         // u32::MAX + u32::MAX + 1 = 2^33 - 1, which wraps to u32::MAX with carry-out set.
         assert_eq!(
             a.evaluate(&[
-                Value::new(u32::MAX, 32),
-                Value::new(u32::MAX, 32),
+                Value::new(u32::MAX as u64, 32),
+                Value::new(u32::MAX as u64, 32),
+                Value::ONE
+            ]),
+            vec![Value::new(u32::MAX as u64, 32), Value::ONE]
+        );
+    }
+
+    #[test]
+    fn test_full_width_addend_does_not_panic_at_64_bits() {
+        // Both addends at max u64 for a 64-bit adder: exercises the u128 widening
+        // that avoids an overflow panic on plain u64 addition.
+        let a = adder(64);
+        // u64::MAX + u64::MAX + 1 = 2^65 - 1, which wraps to u64::MAX with carry-out set.
+        assert_eq!(
+            a.evaluate(&[
+                Value::new(u64::MAX, 64),
+                Value::new(u64::MAX, 64),
                 Value::ONE
             ]),
-            vec![Value::new(u32::MAX, 32), Value::ONE]
+            vec![Value::new(u64::MAX, 64), Value::ONE]
+        );
+    }
+
+    #[test]
+    fn test_sum_wider_than_32_bits() {
+        let a = adder(40);
+        assert_eq!(
+            a.evaluate(&[
+                Value::new(0xFF_FFFF_FFFF, 40),
+                Value::new(1 << 32, 40),
+                Value::ZERO
+            ]),
+            vec![Value::new(0xFF_FFFF_FFFF & 0x00_FFFF_FFFF, 40), Value::ONE]
         );
     }
 
@@ -170,4 +204,17 @@ mod tests {
         assert_eq!(a.output_width(0), Some(4));
         assert_eq!(a.output_width(1), Some(1));
     }
+
+    #[test]
+    fn test_unknown_operand_bit_makes_outputs_unknown() {
+        let a = Adder { data_width: 4 };
+        assert_eq!(
+            a.evaluate(&[
+                Value::from_masks(0b0001, 0b1000, 0, 4),
+                Value::new(1, 4),
+                Value::Floating
+            ]),
+            vec![Value::unknown(4), Value::unknown(1)]
+        );
+    }
 }
@@ -0,0 +1,247 @@
+use super::{SeqLogic, SeqState};
+use crate::sim::value::Value;
+
+// Which transition of a clock pin latches a sequential component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum ClockEdge {
+    #[default]
+    Rising,
+    Falling,
+}
+
+// Edge detector for a sequential component's clock pin. Lives on the
+// Component rather than in each LogicSeq variant so the edge handling is
+// written once (see Component::apply_async). `last` starts, and resets to,
+// Floating, so the first level seen after power-on or Stop is never an edge;
+// Circuit::reset_sequential re-syncs it once the reset has settled.
+#[derive(Debug)]
+pub(crate) struct ClockPin {
+    pub(crate) pin: usize,
+    edge: ClockEdge,
+    last: Value,
+}
+
+impl ClockPin {
+    pub(crate) fn new(pin: usize, edge: ClockEdge) -> Self {
+        Self {
+            pin,
+            edge,
+            last: Value::Floating,
+        }
+    }
+
+    // Records `level` and reports whether moving to it from the previous level
+    // is this pin's edge. Only a clean 0 -> 1 (or 1 -> 0) transition counts, so
+    // a repeat call with the same level never fires twice - which keeps
+    // apply_async idempotent.
+    pub(crate) fn fires(&mut self, level: Value) -> bool {
+        let prev = std::mem::replace(&mut self.last, level);
+        match self.edge {
+            ClockEdge::Rising => prev == Value::ZERO && level == Value::ONE,
+            ClockEdge::Falling => prev == Value::ONE && level == Value::ZERO,
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.last = Value::Floating;
+    }
+
+    // Records `level` as the current one without looking for an edge.
+    pub(crate) fn sync(&mut self, level: Value) {
+        self.last = level;
+    }
+
+    pub(crate) fn level(&self) -> Value {
+        self.last
+    }
+}
+
+// The flip-flop confs were unit structs, saved as `null`, before they gained a
+// clock edge. They deserialize through this so both that and the current
+// `{"clock_edge": ...}` form load.
+#[derive(serde::Deserialize)]
+pub struct EdgeOnlyConf {
+    #[serde(default)]
+    clock_edge: ClockEdge,
+}
+
+impl EdgeOnlyConf {
+    pub(super) fn edge(conf: Option<Self>) -> ClockEdge {
+        conf.map(|c| c.clock_edge).unwrap_or_default()
+    }
+}
+
+// A clock source: low for `low_ticks` steps of the global clock, then high for
+// `high_ticks`, repeating. Both are at least 1 (the properties panel enforces
+// it; a 0 here reads as 1). Two sources with different durations give two
+// clock domains.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ClockSourceConf {
+    pub high_ticks: u32,
+    pub low_ticks: u32,
+}
+
+impl Default for ClockSourceConf {
+    fn default() -> Self {
+        Self {
+            high_ticks: 1,
+            low_ticks: 1,
+        }
+    }
+}
+
+impl ClockSourceConf {
+    pub fn n_inputs(&self) -> usize {
+        0
+    }
+
+    pub fn n_outputs(&self) -> usize {
+        1
+    }
+
+    pub fn input_width(&self, _i: usize) -> Option<u8> {
+        None
+    }
+
+    pub fn output_width(&self, i: usize) -> Option<u8> {
+        match i {
+            0 => Some(1),
+            _ => None,
+        }
+    }
+
+    fn low(&self) -> u32 {
+        self.low_ticks.max(1)
+    }
+
+    fn period(&self) -> u32 {
+        self.low() + self.high_ticks.max(1)
+    }
+}
+
+// Sequential so it resets on Stop like any other state. The transport steps
+// it directly (see Circuit::step_clock_sources), as does Circuit::tick_clock;
+// `phase` counts those steps into the current period.
+#[derive(Debug)]
+pub struct ClockSource {
+    conf: ClockSourceConf,
+    phase: u32,
+}
+
+impl ClockSource {
+    pub fn new(conf: ClockSourceConf) -> Self {
+        Self { conf, phase: 0 }
+    }
+
+    fn level(&self) -> Value {
+        if self.phase < self.conf.low() {
+            Value::ZERO
+        } else {
+            Value::ONE
+        }
+    }
+}
+
+impl SeqLogic for ClockSource {
+    fn n_inputs(&self) -> usize {
+        self.conf.n_inputs()
+    }
+
+    fn n_outputs(&self) -> usize {
+        self.conf.n_outputs()
+    }
+
+    fn tick(&mut self, _inputs: &[Value]) -> Vec<Value> {
+        self.phase = (self.phase + 1) % self.conf.period();
+        vec![self.level()]
+    }
+
+    fn apply_async(&mut self, _inputs: &[Value]) {}
+
+    fn observe(&self) -> Vec<Value> {
+        vec![self.level()]
+    }
+
+    fn reset(&mut self) {
+        self.phase = 0;
+    }
+
+    fn snapshot(&self) -> SeqState {
+        SeqState::ClockSource(self.phase)
+    }
+
+    fn restore(&mut self, state: &SeqState) {
+        if let SeqState::ClockSource(phase) = state {
+            self.phase = *phase;
+        }
+    }
+
+    // A clock source is what drives clock pins; it has none of its own.
+    fn clock_pin(&self) -> Option<(usize, ClockEdge)> {
+        None
+    }
+
+    fn input_width(&self, i: usize) -> Option<u8> {
+        self.conf.input_width(i)
+    }
+
+    fn output_width(&self, i: usize) -> Option<u8> {
+        self.conf.output_width(i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn levels(conf: ClockSourceConf, steps: usize) -> Vec<u64> {
+        let mut clk = ClockSource::new(conf);
+        let mut out = vec![];
+        for _ in 0..steps {
+            match clk.tick(&[])[0] {
+                Value::Fixed { bits, .. } => out.push(bits),
+                v => panic!("clock drove {v:?}"),
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_starts_low_and_toggles_every_step_by_default() {
+        let clk = ClockSource::new(ClockSourceConf::default());
+        assert_eq!(clk.observe(), vec![Value::ZERO]);
+        assert_eq!(levels(ClockSourceConf::default(), 4), vec![1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_uneven_duty_cycle() {
+        let conf = ClockSourceConf {
+            high_ticks: 1,
+            low_ticks: 3,
+        };
+        assert_eq!(levels(conf, 8), vec![0, 0, 1, 0, 0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_reset_returns_to_low_at_phase_zero() {
+        let mut clk = ClockSource::new(ClockSourceConf::default());
+        clk.tick(&[]);
+        assert_eq!(clk.observe(), vec![Value::ONE]);
+        clk.reset();
+        assert_eq!(clk.observe(), vec![Value::ZERO]);
+    }
+
+    #[test_case(ClockEdge::Rising, Value::ZERO, Value::ONE, true ; "rising fires on 0 to 1")]
+    #[test_case(ClockEdge::Rising, Value::ONE, Value::ZERO, false ; "rising ignores 1 to 0")]
+    #[test_case(ClockEdge::Falling, Value::ONE, Value::ZERO, true ; "falling fires on 1 to 0")]
+    #[test_case(ClockEdge::Falling, Value::ZERO, Value::ONE, false ; "falling ignores 0 to 1")]
+    #[test_case(ClockEdge::Rising, Value::Floating, Value::ONE, false ; "floating to 1 is not an edge")]
+    fn test_clock_pin_edges(edge: ClockEdge, from: Value, to: Value, fires: bool) {
+        let mut pin = ClockPin::new(0, edge);
+        pin.fires(from);
+        assert_eq!(pin.fires(to), fires);
+        // Same level again is never a second edge.
+        assert!(!pin.fires(to));
+    }
+}
@@ -1,4 +1,4 @@
-use super::CombLogic;
+use super::{has_unknown_operand, CombLogic};
 use crate::sim::value::Value;
 use std::cmp::Ordering;
 
@@ -37,6 +37,7 @@ impl CombLogic for Comparator {
                 };
                 vec![Value::new(gt, 1), Value::new(eq, 1), Value::new(lt, 1)]
             }
+            (a, b) if has_unknown_operand(&[a, b], width, None) => vec![Value::unknown(1); 3],
             _ => vec![Value::Floating, Value::Floating, Value::Floating],
         }
     }
@@ -8,7 +8,7 @@ use crate::sim::value::Value;
 // subcircuit). See gui::app::build_doc_circuit.
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Constant {
-    pub bits: u32,
+    pub bits: u64,
     pub width: u8,
 }
 
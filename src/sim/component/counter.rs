@@ -1,4 +1,4 @@
-use super::{SeqLogic, SeqState};
+use super::{ClockEdge, SeqLogic, SeqState};
 use crate::sim::value::Value;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
@@ -13,14 +13,17 @@ pub enum OverflowAction {
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct CounterConf {
     pub data_width: u8,
-    pub max_value: u32,
+    pub max_value: u64,
     pub overflow_action: OverflowAction,
+    #[serde(default)]
+    pub clock_edge: ClockEdge,
 }
 
 impl CounterConf {
     pub const DATA_PIN: usize = 0;
     pub const LOAD_PIN: usize = 1;
     pub const COUNT_PIN: usize = 2;
+    pub const CLOCK_PIN: usize = 3;
 
     pub const Q_PIN: usize = 0;
     pub const CARRY_PIN: usize = 1;
@@ -28,7 +31,7 @@ impl CounterConf {
 
 impl CounterConf {
     pub fn n_inputs(&self) -> usize {
-        3
+        4
     }
 
     pub fn n_outputs(&self) -> usize {
@@ -40,6 +43,7 @@ impl CounterConf {
             CounterConf::DATA_PIN => Some(self.data_width),
             CounterConf::LOAD_PIN => Some(1),
             CounterConf::COUNT_PIN => Some(1),
+            CounterConf::CLOCK_PIN => Some(1),
             _ => None,
         }
     }
@@ -64,12 +68,18 @@ pub struct Counter {
 }
 
 impl Counter {
-    pub fn new(data_width: u8, max_value: u32, overflow_action: OverflowAction) -> Self {
+    pub fn new(
+        data_width: u8,
+        max_value: u64,
+        overflow_action: OverflowAction,
+        clock_edge: ClockEdge,
+    ) -> Self {
         Self {
             conf: CounterConf {
                 data_width,
                 max_value,
                 overflow_action,
+                clock_edge,
             },
             value: Value::new(0, data_width),
             carry: Value::ZERO,
@@ -79,7 +89,7 @@ impl Counter {
     // Bits of `v` if it's a Fixed value of exactly `width` bits, else None -
     // an unexpected width (or Floating/Invalid) makes the step's result
     // Floating, mirroring how CombLogic ops treat width-mismatched operands.
-    fn bits_of(v: Value, width: u8) -> Option<u32> {
+    fn bits_of(v: Value, width: u8) -> Option<u64> {
         match v {
             Value::Fixed { bits, width: w } if w == width => Some(bits),
             _ => None,
@@ -186,6 +196,17 @@ impl SeqLogic for Counter {
         }
     }
 
+    fn restore(&mut self, state: &SeqState) {
+        if let SeqState::Counter { value, carry } = state {
+            self.value = *value;
+            self.carry = *carry;
+        }
+    }
+
+    fn clock_pin(&self) -> Option<(usize, ClockEdge)> {
+        Some((CounterConf::CLOCK_PIN, self.conf.clock_edge))
+    }
+
     fn input_width(&self, i: usize) -> Option<u8> {
         self.conf.input_width(i)
     }
@@ -201,8 +222,13 @@ mod tests {
     use crate::sim::component::LogicSeq;
     use test_case::test_case;
 
-    fn new_counter(data_width: u8, max_value: u32, overflow_action: OverflowAction) -> LogicSeq {
-        LogicSeq::Counter(Counter::new(data_width, max_value, overflow_action))
+    fn new_counter(data_width: u8, max_value: u64, overflow_action: OverflowAction) -> LogicSeq {
+        LogicSeq::Counter(Counter::new(
+            data_width,
+            max_value,
+            overflow_action,
+            ClockEdge::Rising,
+        ))
     }
 
     fn tick(seq: &mut LogicSeq, data: Value, load: Value, count: Value) -> Vec<Value> {
@@ -256,7 +282,7 @@ mod tests {
     #[test_case(OverflowAction::Wrap, 0 ; "wrap goes to 0")]
     #[test_case(OverflowAction::StayMax, 9 ; "stay_max saturates at max")]
     #[test_case(OverflowAction::PassMax, 10 ; "pass_max keeps counting past max")]
-    fn test_increment_overflow(action: OverflowAction, expect: u32) {
+    fn test_increment_overflow(action: OverflowAction, expect: u64) {
         let mut c = new_counter(4, 9, action);
         tick(&mut c, Value::new(9, 4), Value::ONE, Value::ZERO); // load max (9)
         assert_eq!(
@@ -290,7 +316,7 @@ mod tests {
     #[test_case(OverflowAction::Wrap, 9 ; "wrap goes to max")]
     #[test_case(OverflowAction::StayMax, 0 ; "stay_max saturates at 0")]
     #[test_case(OverflowAction::PassMax, 15 ; "pass_max wraps via natural bit width")]
-    fn test_decrement_underflow(action: OverflowAction, expect: u32) {
+    fn test_decrement_underflow(action: OverflowAction, expect: u64) {
         let mut c = new_counter(4, 9, action);
         tick(&mut c, Value::new(0, 4), Value::ONE, Value::ZERO);
         assert_eq!(
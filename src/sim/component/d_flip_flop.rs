@@ -1,9 +1,20 @@
-use super::{SeqLogic, SeqState};
+use super::{ClockEdge, EdgeOnlyConf, SeqLogic, SeqState};
 use crate::sim::value::Value;
 
 /// A D flip-flop is essentially a single-bit register.
-#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
-pub struct DFlipFlopConf;
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "Option<EdgeOnlyConf>")]
+pub struct DFlipFlopConf {
+    pub clock_edge: ClockEdge,
+}
+
+impl From<Option<EdgeOnlyConf>> for DFlipFlopConf {
+    fn from(conf: Option<EdgeOnlyConf>) -> Self {
+        Self {
+            clock_edge: EdgeOnlyConf::edge(conf),
+        }
+    }
+}
 
 impl DFlipFlopConf {
     pub const DATA_PIN: usize = 0;
@@ -12,11 +23,12 @@ impl DFlipFlopConf {
     // observe), and clears the latched value on the next tick so the reset
     // sticks. Active only on exactly Value::ONE.
     pub const RESET_PIN: usize = 2;
+    pub const CLOCK_PIN: usize = 3;
 }
 
 impl DFlipFlopConf {
     pub fn n_inputs(&self) -> usize {
-        3
+        4
     }
 
     pub fn n_outputs(&self) -> usize {
@@ -28,6 +40,7 @@ impl DFlipFlopConf {
             DFlipFlopConf::DATA_PIN => Some(1),
             DFlipFlopConf::WRITE_EN_PIN => Some(1),
             DFlipFlopConf::RESET_PIN => Some(1),
+            DFlipFlopConf::CLOCK_PIN => Some(1),
             _ => None,
         }
     }
@@ -47,9 +60,9 @@ pub struct DFlipFlop {
 }
 
 impl DFlipFlop {
-    pub fn new() -> Self {
+    pub fn new(clock_edge: ClockEdge) -> Self {
         Self {
-            conf: DFlipFlopConf,
+            conf: DFlipFlopConf { clock_edge },
             value: Value::ZERO,
         }
     }
@@ -57,7 +70,7 @@ impl DFlipFlop {
 
 impl Default for DFlipFlop {
     fn default() -> Self {
-        Self::new()
+        Self::new(ClockEdge::default())
     }
 }
 
@@ -106,6 +119,16 @@ impl SeqLogic for DFlipFlop {
         SeqState::FlipFlop(self.value)
     }
 
+    fn restore(&mut self, state: &SeqState) {
+        if let SeqState::FlipFlop(value) = state {
+            self.value = *value;
+        }
+    }
+
+    fn clock_pin(&self) -> Option<(usize, ClockEdge)> {
+        Some((DFlipFlopConf::CLOCK_PIN, self.conf.clock_edge))
+    }
+
     fn input_width(&self, i: usize) -> Option<u8> {
         self.conf.input_width(i)
     }
@@ -123,7 +146,7 @@ mod tests {
     use test_case::test_case;
 
     fn new_d_flip_flop() -> LogicSeq {
-        LogicSeq::DFlipFlop(DFlipFlop::new())
+        LogicSeq::DFlipFlop(DFlipFlop::new(ClockEdge::Rising))
     }
 
     // No async reset asserted (the common case in these tests).
@@ -25,7 +25,7 @@ impl CombLogic for Demux {
             Value::Fixed { bits: sel, width } if width == self.sel_width => {
                 let data = inputs[Self::DATA_PIN];
 
-                if let Value::Fixed { width, .. } = data {
+                if let Some(width) = data.width() {
                     if width != self.data_width {
                         return vec![Value::Floating; branches];
                     }
@@ -34,6 +34,10 @@ impl CombLogic for Demux {
                 values[sel as usize] = data;
                 values
             }
+            // Any branch might be the selected one.
+            Value::Partial { width, .. } if width == self.sel_width => {
+                vec![Value::unknown(self.data_width); branches]
+            }
             _ => vec![Value::Floating; branches],
         }
     }
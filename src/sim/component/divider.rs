@@ -1,4 +1,4 @@
-use super::CombLogic;
+use super::{has_unknown_operand, CombLogic};
 use crate::sim::value::Value;
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -29,8 +29,8 @@ impl CombLogic for Divider {
         // A Floating carry-in behaves the same as a zero carry-in; anything else
         // non-Fixed, or a Fixed carry-in at the wrong width, falls through to Floating.
         let carry_in = match inputs[Self::CARRY_IN_PIN] {
-            Value::Floating => Some(0u64),
-            Value::Fixed { bits, width: cw } if cw == width => Some(bits as u64),
+            Value::Floating => Some(0u128),
+            Value::Fixed { bits, width: cw } if cw == width => Some(bits as u128),
             _ => None,
         };
         match (
@@ -50,19 +50,24 @@ impl CombLogic for Divider {
                 Some(cin),
             ) if dw == width && sw == width => {
                 // A zero divisor is treated as 1 rather than dividing by zero.
-                let divisor = if divisor == 0 { 1 } else { divisor } as u64;
-                // Widen to u64: the carry-in occupies the upper `width` bits, the
+                let divisor = if divisor == 0 { 1 } else { divisor } as u128;
+                // Widen to u128: the carry-in occupies the upper `width` bits, the
                 // dividend the lower `width` bits, so the full dividend always fits
-                // within 2*width <= 64 bits.
-                let full_dividend = if width < 64 {
-                    (cin << width) | dividend as u64
-                } else {
-                    dividend as u64
-                };
-                let quotient = ((full_dividend / divisor) & Value::mask(width) as u64) as u32;
-                let remainder = ((full_dividend % divisor) & Value::mask(width) as u64) as u32;
+                // within 2*width <= 128 bits.
+                let full_dividend = (cin << width) | dividend as u128;
+                let quotient = ((full_dividend / divisor) & Value::mask(width) as u128) as u64;
+                let remainder = ((full_dividend % divisor) & Value::mask(width) as u128) as u64;
                 vec![Value::new(quotient, width), Value::new(remainder, width)]
             }
+            (a, b, _)
+                if has_unknown_operand(
+                    &[a, b],
+                    width,
+                    Some((inputs[Self::CARRY_IN_PIN], width)),
+                ) =>
+            {
+                vec![Value::unknown(width), Value::unknown(width)]
+            }
             _ => vec![Value::Floating, Value::Floating],
         }
     }
@@ -149,18 +154,18 @@ mod tests {
 
     #[test]
     fn test_full_width_operands_do_not_panic() {
-        // Max dividend/carry-in for a 32-bit divider: exercises the u64 widening
-        // that avoids an overflow panic on the carry-in shift.
+        // Max dividend/carry-in for a 32-bit divider: the carry-in must shift
+        // into the upper 32 bits of the full dividend.
         let d = divider(32);
         let full_dividend = ((u32::MAX as u64) << 32) | u32::MAX as u64;
         let divisor = 3u64;
-        let expected_quotient = (full_dividend / divisor) as u32;
-        let expected_remainder = (full_dividend % divisor) as u32;
+        let expected_quotient = (full_dividend / divisor) & Value::mask(32);
+        let expected_remainder = full_dividend % divisor;
         assert_eq!(
             d.evaluate(&[
-                Value::new(u32::MAX, 32),
+                Value::new(u32::MAX as u64, 32),
                 Value::new(3, 32),
-                Value::new(u32::MAX, 32)
+                Value::new(u32::MAX as u64, 32)
             ]),
             vec![
                 Value::new(expected_quotient, 32),
@@ -169,6 +174,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_full_width_operands_do_not_panic_at_64_bits() {
+        // Max dividend/carry-in for a 64-bit divider: exercises the u128 widening
+        // that avoids an overflow panic on the carry-in shift.
+        let d = divider(64);
+        let full_dividend = ((u64::MAX as u128) << 64) | u64::MAX as u128;
+        let divisor = 3u128;
+        let expected_quotient = (full_dividend / divisor) as u64;
+        let expected_remainder = (full_dividend % divisor) as u64;
+        assert_eq!(
+            d.evaluate(&[
+                Value::new(u64::MAX, 64),
+                Value::new(3, 64),
+                Value::new(u64::MAX, 64)
+            ]),
+            vec![
+                Value::new(expected_quotient, 64),
+                Value::new(expected_remainder, 64)
+            ]
+        );
+    }
+
     #[test]
     fn test_mismatched_operand_width_yields_floating() {
         let d = divider(4);
@@ -48,7 +48,7 @@ impl CombLogic for Encoder {
                     .rposition(|v| v == Value::ONE);
 
                 if let Some(i) = highest_set {
-                    sel = Value::new(i as u32, self.sel_width);
+                    sel = Value::new(i as u64, self.sel_width);
                     en_out = Value::ZERO;
                     grp_out = Value::ONE;
                 } else {
@@ -77,7 +77,7 @@ mod tests {
     use test_case::test_case;
 
     // [enable, arm0, arm1, ...], with each arm set to bit i of `mask`.
-    fn make_inputs(sel_width: u8, enable: Value, mask: u32) -> Vec<Value> {
+    fn make_inputs(sel_width: u8, enable: Value, mask: u64) -> Vec<Value> {
         let n_arms = 1usize << sel_width;
         let mut inputs = vec![enable];
         inputs.extend((0..n_arms).map(|i| Value::new((mask >> i) & 1, 1)));
@@ -93,10 +93,10 @@ mod tests {
     #[test_case(0b1010, Some(3), 0, 1 ; "arms 1 and 3 set, highest wins")]
     #[test_case(0b1111, Some(3), 0, 1 ; "all arms set, highest wins")]
     fn test_truth_table_priority_selects_highest_set_arm(
-        mask: u32,
-        expected_sel: Option<u32>,
-        expected_en: u32,
-        expected_grp: u32,
+        mask: u64,
+        expected_sel: Option<u64>,
+        expected_en: u64,
+        expected_grp: u64,
     ) {
         let enc = Encoder { sel_width: 2 };
         let expected_sel_value = match expected_sel {
@@ -116,7 +116,7 @@ mod tests {
     #[test_case(0b0000 ; "disabled, no arms set")]
     #[test_case(0b0001 ; "disabled, arm 0 set")]
     #[test_case(0b1111 ; "disabled, all arms set")]
-    fn test_disabled_forces_floating_selector_and_zero_flags(mask: u32) {
+    fn test_disabled_forces_floating_selector_and_zero_flags(mask: u64) {
         let enc = Encoder { sel_width: 2 };
         assert_eq!(
             enc.evaluate(&make_inputs(2, Value::ZERO, mask)),
@@ -217,9 +217,9 @@ mod tests {
     #[test_case(1, 0b0000, 0b0010, false, true  ; "enc1 empty, enc2 fires")]
     #[test_case(1, 0b0000, 0b0000, false, false ; "enc1 empty, enc2 empty, both quiet but chain enabled")]
     fn test_chain_group_priority_and_activation(
-        top_enable: u32,
-        arms1_mask: u32,
-        arms2_mask: u32,
+        top_enable: u64,
+        arms1_mask: u64,
+        arms2_mask: u64,
         expect_enc1_fires: bool,
         expect_enc2_fires: bool,
     ) {
@@ -237,20 +237,20 @@ mod tests {
         let out2 = enc2.evaluate(&make_inputs(sel_width, en1_out, arms2_mask));
         let (sel2, en2_out, grp2_out) = (out2[0], out2[1], out2[2]);
 
-        assert_eq!(grp1_out, Value::new(expect_enc1_fires as u32, 1));
-        assert_eq!(grp2_out, Value::new(expect_enc2_fires as u32, 1));
+        assert_eq!(grp1_out, Value::new(expect_enc1_fires as u64, 1));
+        assert_eq!(grp2_out, Value::new(expect_enc2_fires as u64, 1));
         // The two encoders never both claim to have fired.
         assert!(!(expect_enc1_fires && expect_enc2_fires));
 
         if expect_enc1_fires {
-            let expected_i = 31 - arms1_mask.leading_zeros(); // highest set bit index
+            let expected_i = (63 - arms1_mask.leading_zeros()) as u64; // highest set bit index
             assert_eq!(sel1, Value::new(expected_i, sel_width));
             assert_eq!(en1_out, Value::ZERO);
             // enc2's enable_in was never asserted, so it stays quiet.
             assert_eq!(sel2, Value::Floating);
             assert_eq!(en2_out, Value::ZERO);
         } else if expect_enc2_fires {
-            let expected_i = 31 - arms2_mask.leading_zeros();
+            let expected_i = (63 - arms2_mask.leading_zeros()) as u64;
             assert_eq!(sel1, Value::Floating);
             assert_eq!(en1_out, Value::ONE);
             assert_eq!(sel2, Value::new(expected_i, sel_width));
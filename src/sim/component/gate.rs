@@ -66,7 +66,7 @@ impl CombLogic for Gate {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum GateOp {
     And,
     Or,
@@ -106,7 +106,7 @@ mod tests {
     #[test_case(GateOp::Nor,  0, 1, 0 ; "nor 0 1")]
     #[test_case(GateOp::Nor,  1, 0, 0 ; "nor 1 0")]
     #[test_case(GateOp::Nor,  1, 1, 0 ; "nor 1 1")]
-    fn test_binary_truth_table(op: GateOp, av: u32, bv: u32, expected: u32) {
+    fn test_binary_truth_table(op: GateOp, av: u64, bv: u64, expected: u64) {
         let gate = Gate {
             op,
             n_inputs: 2,
@@ -120,7 +120,7 @@ mod tests {
 
     #[test_case(0, 1 ; "not 0")]
     #[test_case(1, 0 ; "not 1")]
-    fn test_not_truth_table(av: u32, expected: u32) {
+    fn test_not_truth_table(av: u64, expected: u64) {
         let gate = Gate {
             op: GateOp::Not,
             n_inputs: 1,
@@ -3,8 +3,22 @@ use crate::sim::value::Value;
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Input {
-    pub bits: u32,
+    pub bits: u64,
     pub width: u8,
+    // Names the Input to test vectors (see sim::test_vector) and the CLI; the
+    // simulation never reads it. Empty means unlabelled.
+    #[serde(default)]
+    pub label: String,
+}
+
+impl Input {
+    pub fn new(bits: u64, width: u8) -> Self {
+        Self {
+            bits,
+            width,
+            label: String::new(),
+        }
+    }
 }
 
 impl CombLogic for Input {
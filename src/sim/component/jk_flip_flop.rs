@@ -1,9 +1,20 @@
-use super::{SeqLogic, SeqState};
+use super::{ClockEdge, EdgeOnlyConf, SeqLogic, SeqState};
 use crate::sim::value::Value;
 
 /// A J-K flip-flop: J=K=0 holds, J=1/K=0 sets, J=0/K=1 resets, J=K=1 toggles.
-#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
-pub struct JKFlipFlopConf;
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "Option<EdgeOnlyConf>")]
+pub struct JKFlipFlopConf {
+    pub clock_edge: ClockEdge,
+}
+
+impl From<Option<EdgeOnlyConf>> for JKFlipFlopConf {
+    fn from(conf: Option<EdgeOnlyConf>) -> Self {
+        Self {
+            clock_edge: EdgeOnlyConf::edge(conf),
+        }
+    }
+}
 
 impl JKFlipFlopConf {
     pub const J_PIN: usize = 0;
@@ -13,11 +24,12 @@ impl JKFlipFlopConf {
     // observe), and clears the latched value on the next tick so the reset
     // sticks. Active only on exactly Value::ONE.
     pub const RESET_PIN: usize = 3;
+    pub const CLOCK_PIN: usize = 4;
 }
 
 impl JKFlipFlopConf {
     pub fn n_inputs(&self) -> usize {
-        4
+        5
     }
 
     pub fn n_outputs(&self) -> usize {
@@ -30,6 +42,7 @@ impl JKFlipFlopConf {
             JKFlipFlopConf::K_PIN => Some(1),
             JKFlipFlopConf::WRITE_EN_PIN => Some(1),
             JKFlipFlopConf::RESET_PIN => Some(1),
+            JKFlipFlopConf::CLOCK_PIN => Some(1),
             _ => None,
         }
     }
@@ -49,9 +62,9 @@ pub struct JKFlipFlop {
 }
 
 impl JKFlipFlop {
-    pub fn new() -> Self {
+    pub fn new(clock_edge: ClockEdge) -> Self {
         Self {
-            conf: JKFlipFlopConf,
+            conf: JKFlipFlopConf { clock_edge },
             value: Value::ZERO,
         }
     }
@@ -59,7 +72,7 @@ impl JKFlipFlop {
 
 impl Default for JKFlipFlop {
     fn default() -> Self {
-        Self::new()
+        Self::new(ClockEdge::default())
     }
 }
 
@@ -116,6 +129,16 @@ impl SeqLogic for JKFlipFlop {
         SeqState::FlipFlop(self.value)
     }
 
+    fn restore(&mut self, state: &SeqState) {
+        if let SeqState::FlipFlop(value) = state {
+            self.value = *value;
+        }
+    }
+
+    fn clock_pin(&self) -> Option<(usize, ClockEdge)> {
+        Some((JKFlipFlopConf::CLOCK_PIN, self.conf.clock_edge))
+    }
+
     fn input_width(&self, i: usize) -> Option<u8> {
         self.conf.input_width(i)
     }
@@ -133,7 +156,7 @@ mod tests {
     use test_case::test_case;
 
     fn new_jk_flip_flop() -> LogicSeq {
-        LogicSeq::JKFlipFlop(JKFlipFlop::new())
+        LogicSeq::JKFlipFlop(JKFlipFlop::new(ClockEdge::Rising))
     }
 
     // No async reset asserted (the common case in these tests).
@@ -1,4 +1,4 @@
-use super::CombLogic;
+use super::{has_unknown_operand, CombLogic};
 use crate::sim::value::Value;
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -29,8 +29,8 @@ impl CombLogic for Multiplier {
         // A Floating carry-in behaves the same as a zero carry-in; anything else
         // non-Fixed, or a Fixed carry-in at the wrong width, falls through to Floating.
         let carry_in = match inputs[Self::CARRY_IN_PIN] {
-            Value::Floating => Some(0u64),
-            Value::Fixed { bits, width: cw } if cw == width => Some(bits as u64),
+            Value::Floating => Some(0u128),
+            Value::Fixed { bits, width: cw } if cw == width => Some(bits as u128),
             _ => None,
         };
         match (
@@ -43,17 +43,22 @@ impl CombLogic for Multiplier {
                 Value::Fixed { bits: b, width: bw },
                 Some(cin),
             ) if aw == width && bw == width => {
-                // Widen to u64: the product of two `width`-bit values plus a
-                // `width`-bit carry-in always fits within 2*width <= 64 bits.
-                let full = a as u64 * b as u64 + cin;
-                let product = (full & Value::mask(width) as u64) as u32;
-                let carry_out = if width < 64 {
-                    ((full >> width) & Value::mask(width) as u64) as u32
-                } else {
-                    0
-                };
+                // Widen to u128: the product of two `width`-bit values plus a
+                // `width`-bit carry-in always fits within 2*width <= 128 bits.
+                let full = a as u128 * b as u128 + cin;
+                let product = (full & Value::mask(width) as u128) as u64;
+                let carry_out = ((full >> width) & Value::mask(width) as u128) as u64;
                 vec![Value::new(product, width), Value::new(carry_out, width)]
             }
+            (a, b, _)
+                if has_unknown_operand(
+                    &[a, b],
+                    width,
+                    Some((inputs[Self::CARRY_IN_PIN], width)),
+                ) =>
+            {
+                vec![Value::unknown(width), Value::unknown(width)]
+            }
             _ => vec![Value::Floating, Value::Floating],
         }
     }
@@ -134,17 +139,16 @@ mod tests {
     #[test]
     fn test_full_width_operands_do_not_panic() {
         // Both operands at max u32 plus a max carry-in for a 32-bit multiplier:
-        // exercises the u64 widening that avoids an overflow panic on plain u32
-        // multiplication.
+        // the upper half of the product must land in the carry-out.
         let m = multiplier(32);
         let full = u32::MAX as u64 * u32::MAX as u64 + u32::MAX as u64;
-        let expected_product = (full & Value::mask(32) as u64) as u32;
-        let expected_carry = (full >> 32) as u32;
+        let expected_product = full & Value::mask(32);
+        let expected_carry = full >> 32;
         assert_eq!(
             m.evaluate(&[
-                Value::new(u32::MAX, 32),
-                Value::new(u32::MAX, 32),
-                Value::new(u32::MAX, 32)
+                Value::new(u32::MAX as u64, 32),
+                Value::new(u32::MAX as u64, 32),
+                Value::new(u32::MAX as u64, 32)
             ]),
             vec![
                 Value::new(expected_product, 32),
@@ -153,6 +157,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_full_width_operands_do_not_panic_at_64_bits() {
+        // Both operands at max u64 plus a max carry-in for a 64-bit multiplier:
+        // exercises the u128 widening that avoids an overflow panic on plain u64
+        // multiplication.
+        let m = multiplier(64);
+        let full = u64::MAX as u128 * u64::MAX as u128 + u64::MAX as u128;
+        let expected_product = (full & Value::mask(64) as u128) as u64;
+        let expected_carry = (full >> 64) as u64;
+        assert_eq!(
+            m.evaluate(&[
+                Value::new(u64::MAX, 64),
+                Value::new(u64::MAX, 64),
+                Value::new(u64::MAX, 64)
+            ]),
+            vec![
+                Value::new(expected_product, 64),
+                Value::new(expected_carry, 64)
+            ]
+        );
+    }
+
     #[test]
     fn test_mismatched_operand_width_yields_floating() {
         let m = multiplier(4);
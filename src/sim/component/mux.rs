@@ -28,6 +28,14 @@ impl CombLogic for Mux {
                     vec![Value::Floating]
                 }
             }
+            // Can't tell which branch is selected, so no output bit is known.
+            Value::Partial { width, .. } => {
+                if self.sel_width == width {
+                    vec![Value::unknown(self.data_width)]
+                } else {
+                    vec![Value::Floating]
+                }
+            }
         }
     }
     fn input_width(&self, i: usize) -> Option<u8> {
@@ -59,7 +67,7 @@ mod tests {
             Value::new(0, 2),
         ];
         for (sel, expected) in branches.iter().enumerate() {
-            let mut inputs = vec![Value::new(sel as u32, 2)];
+            let mut inputs = vec![Value::new(sel as u64, 2)];
             inputs.extend_from_slice(&branches);
             assert_eq!(mux.evaluate(&inputs), vec![*expected]);
         }
@@ -106,4 +114,20 @@ mod tests {
             vec![Value::Floating]
         );
     }
+
+    #[test]
+    fn test_partial_selector_yields_unknown_output() {
+        let mux = Mux {
+            data_width: 2,
+            sel_width: 2,
+        };
+        let inputs = [
+            Value::from_masks(0b01, 0b10, 0, 2),
+            Value::new(0, 2),
+            Value::new(1, 2),
+            Value::new(2, 2),
+            Value::new(3, 2),
+        ];
+        assert_eq!(mux.evaluate(&inputs), vec![Value::unknown(2)]);
+    }
 }
@@ -0,0 +1,54 @@
+use super::CombLogic;
+use crate::sim::value::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum PullDirection {
+    #[default]
+    Down,
+    Up,
+}
+
+// A pull-up/pull-down resistor: a weak source (see net::Strength) that
+// supplies its level only on bits every strong driver of the net leaves Z.
+// On its own it's just a constant all-0s/all-1s output.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PullResistor {
+    pub direction: PullDirection,
+    pub width: u8,
+}
+
+impl CombLogic for PullResistor {
+    fn n_inputs(&self) -> usize {
+        0
+    }
+    fn n_outputs(&self) -> usize {
+        1
+    }
+    fn evaluate(&self, _inputs: &[Value]) -> Vec<Value> {
+        let bits = match self.direction {
+            PullDirection::Down => 0,
+            PullDirection::Up => Value::mask(self.width),
+        };
+        vec![Value::new(bits, self.width)]
+    }
+    fn input_width(&self, _i: usize) -> Option<u8> {
+        unreachable!("PullResistor has no input pins")
+    }
+    fn output_width(&self, _i: usize) -> Option<u8> {
+        Some(self.width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(PullDirection::Down, 4, 0x0 ; "pull down")]
+    #[test_case(PullDirection::Up, 4, 0xF ; "pull up")]
+    #[test_case(PullDirection::Up, 64, u64::MAX ; "pull up full width")]
+    fn test_drives_its_rail_level(direction: PullDirection, width: u8, bits: u64) {
+        let p = PullResistor { direction, width };
+        assert_eq!(p.evaluate(&[]), vec![Value::new(bits, width)]);
+    }
+}
@@ -1,20 +1,27 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use super::{SeqLogic, SeqState};
+use super::{ClockEdge, SeqLogic, SeqState};
 use crate::sim::value::Value;
 
-// Structurally like Rom (shared Rc<RefCell<Vec<u32>>>), but sequential:
+// Structurally like Rom (shared Rc<RefCell<Vec<u64>>>), but sequential:
 // data_out is a registered read updated only by tick(), not by every
 // settle(). Unlike Rom, contents are never persisted or deep-copied — a
-// fresh or cloned Ram always zero-fills, since RAM contents are ephemeral
-// debug state. `Ram::resized` is the one op that preserves the buffer.
+// fresh or cloned Ram starts from its power-on image (`initial`, zeros by
+// default), since RAM contents are run state. `Ram::resized` is the one op
+// that preserves the buffer.
 #[derive(Debug, PartialEq)]
 pub struct Ram {
     pub data_width: u8,
     pub address_width: u8,
     pub read_behavior: ReadBehavior,
-    pub data: Rc<RefCell<Vec<u32>>>,
+    pub clock_edge: ClockEdge,
+    pub data: Rc<RefCell<Vec<u64>>>,
+    // The power-on image from address 0, zeros past its end: a program or
+    // data a CPU lab starts from. Saved in the project, unlike `data`, and
+    // loaded again by every reset (Stop) unless `keep_contents`.
+    pub initial: Vec<u64>,
+    pub keep_contents: bool,
 }
 
 // Resolves data_out when write_enable and load_enable are both asserted for
@@ -27,10 +34,16 @@ pub enum ReadBehavior {
 }
 
 impl Clone for Ram {
-    // Always a fresh, zero-filled buffer — never shares Ram::shared's Rc,
-    // and never copies current contents (that's Ram::resized).
+    // Always a fresh buffer holding the power-on image — never shares
+    // Ram::shared's Rc, and never copies current contents (that's
+    // Ram::resized).
     fn clone(&self) -> Self {
-        Self::new(self.data_width, self.address_width, self.read_behavior)
+        Self {
+            clock_edge: self.clock_edge,
+            keep_contents: self.keep_contents,
+            ..Self::new(self.data_width, self.address_width, self.read_behavior)
+        }
+        .with_initial(self.initial.clone())
     }
 }
 
@@ -39,6 +52,7 @@ impl Ram {
     pub const WE_PIN: usize = 1;
     pub const LE_PIN: usize = 2;
     pub const DATA_IN_PIN: usize = 3;
+    pub const CLOCK_PIN: usize = 4;
     pub const DATA_OUT_PIN: usize = 0;
 
     pub fn new(data_width: u8, address_width: u8, read_behavior: ReadBehavior) -> Self {
@@ -47,23 +61,57 @@ impl Ram {
             data_width,
             address_width,
             read_behavior,
+            clock_edge: ClockEdge::default(),
             data: Rc::new(RefCell::new(vec![0; len])),
+            initial: Vec::new(),
+            keep_contents: false,
         }
     }
 
+    // Sets the power-on image, fitted to the RAM (see `image`), and loads it.
+    pub fn with_initial(mut self, image: Vec<u64>) -> Self {
+        self.initial = self.image(image);
+        self.load_initial();
+        self
+    }
+
     // Rc handle sharing the same buffer — the one exception to Clone's
-    // zero-fill rule, so to_component() aliases the live component's data.
+    // fresh-buffer rule, so to_component() aliases the live component's data.
     pub fn shared(&self) -> Self {
         Self {
             data_width: self.data_width,
             address_width: self.address_width,
             read_behavior: self.read_behavior,
+            clock_edge: self.clock_edge,
             data: Rc::clone(&self.data),
+            initial: self.initial.clone(),
+            keep_contents: self.keep_contents,
         }
     }
 
+    // `words` fitted to this RAM as a power-on image: cut to its length,
+    // masked to its width, and without trailing zeros, so a mostly empty
+    // image stays small on disk.
+    pub fn image(&self, mut words: Vec<u64>) -> Vec<u64> {
+        words.truncate(self.len());
+        for w in &mut words {
+            *w &= Value::mask(self.data_width);
+        }
+        let used = words.iter().rposition(|&w| w != 0).map_or(0, |i| i + 1);
+        words.truncate(used);
+        words
+    }
+
+    // Puts the power-on image back over the current contents.
+    pub fn load_initial(&self) {
+        let mut data = self.data.borrow_mut();
+        data.fill(0);
+        let n = self.initial.len().min(data.len());
+        data[..n].copy_from_slice(&self.initial[..n]);
+    }
+
     pub fn n_inputs(&self) -> usize {
-        4
+        5
     }
 
     pub fn n_outputs(&self) -> usize {
@@ -76,6 +124,7 @@ impl Ram {
             Self::WE_PIN => Some(1),
             Self::LE_PIN => Some(1),
             Self::DATA_IN_PIN => Some(self.data_width),
+            Self::CLOCK_PIN => Some(1),
             _ => None,
         }
     }
@@ -96,13 +145,13 @@ impl Ram {
     }
 
     // The stored word at `index` (0 if out of range), already masked.
-    pub fn word(&self, index: usize) -> u32 {
+    pub fn word(&self, index: usize) -> u64 {
         self.data.borrow().get(index).copied().unwrap_or(0)
     }
 
     // &self (interior mutability): a write via either handle is visible to
     // both. No-op if out of range.
-    pub fn set_word(&self, index: usize, value: u32) {
+    pub fn set_word(&self, index: usize, value: u64) {
         let mut data = self.data.borrow_mut();
         if index < data.len() {
             data[index] = value & Value::mask(self.data_width);
@@ -122,24 +171,41 @@ impl Ram {
                 *w &= m;
             }
         }
-        Self {
+        let mut resized = Self {
             data_width: new_data_width,
             address_width: new_address_width,
             read_behavior: self.read_behavior,
+            clock_edge: self.clock_edge,
             data: Rc::new(RefCell::new(data)),
-        }
+            initial: Vec::new(),
+            keep_contents: self.keep_contents,
+        };
+        resized.initial = resized.image(self.initial.clone());
+        resized
     }
 }
 
-// Hand-written: only the three config fields persist. `data` never appears
-// on disk — reload always zero-fills via Ram::new.
+// Hand-written: only the config fields and the power-on image persist.
+// `data` never appears on disk — reload always starts from the image.
 impl serde::Serialize for Ram {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         use serde::ser::SerializeStruct;
-        let mut s = serializer.serialize_struct("Ram", 3)?;
+        let mut s = serializer.serialize_struct("Ram", 6)?;
         s.serialize_field("data_width", &self.data_width)?;
         s.serialize_field("address_width", &self.address_width)?;
         s.serialize_field("read_behavior", &self.read_behavior)?;
+        s.serialize_field("clock_edge", &self.clock_edge)?;
+        // Omitted at their defaults, so files without them read the same.
+        if self.initial.is_empty() {
+            s.skip_field("initial")?;
+        } else {
+            s.serialize_field("initial", &self.initial)?;
+        }
+        if self.keep_contents {
+            s.serialize_field("keep_contents", &self.keep_contents)?;
+        } else {
+            s.skip_field("keep_contents")?;
+        }
         s.end()
     }
 }
@@ -151,9 +217,20 @@ impl<'de> serde::Deserialize<'de> for Ram {
             data_width: u8,
             address_width: u8,
             read_behavior: ReadBehavior,
+            #[serde(default)]
+            clock_edge: ClockEdge,
+            #[serde(default)]
+            initial: Vec<u64>,
+            #[serde(default)]
+            keep_contents: bool,
         }
         let f = RamFields::deserialize(deserializer)?;
-        Ok(Ram::new(f.data_width, f.address_width, f.read_behavior))
+        Ok(Ram {
+            clock_edge: f.clock_edge,
+            keep_contents: f.keep_contents,
+            ..Ram::new(f.data_width, f.address_width, f.read_behavior)
+        }
+        .with_initial(f.initial))
     }
 }
 
@@ -253,16 +330,29 @@ impl SeqLogic for RamCell {
         vec![self.output]
     }
 
-    // Leaves conf.data untouched — unlike other sequential components, RAM
-    // survives a play/pause/step/stop cycle instead of being wiped on Stop.
+    // Back to the power-on image, or with keep_contents, leaves conf.data
+    // untouched so a RAM survives a play/pause/step/stop cycle.
     fn reset(&mut self) {
         self.output = Value::new(0, self.conf.data_width);
+        if !self.conf.keep_contents {
+            self.conf.load_initial();
+        }
     }
 
     fn snapshot(&self) -> SeqState {
         SeqState::Ram(self.output)
     }
 
+    fn restore(&mut self, state: &SeqState) {
+        if let SeqState::Ram(output) = state {
+            self.output = *output;
+        }
+    }
+
+    fn clock_pin(&self) -> Option<(usize, ClockEdge)> {
+        Some((Ram::CLOCK_PIN, self.conf.clock_edge))
+    }
+
     fn input_width(&self, i: usize) -> Option<u8> {
         self.conf.input_width(i)
     }
@@ -412,8 +502,32 @@ mod tests {
     }
 
     #[test]
-    fn test_reset_clears_output_but_preserves_memory() {
-        let mut ram = new_ram(8, 4, ReadBehavior::ReadAfterWrite);
+    fn test_reset_reloads_the_initial_image() {
+        let conf = Ram::new(8, 4, ReadBehavior::ReadAfterWrite).with_initial(vec![0x11, 0x22]);
+        let mut ram = LogicSeq::Ram(RamCell::new(conf));
+        assert_eq!(
+            ram.tick(&ins(Value::new(1, 4), NO_WE, LE, Value::Floating)),
+            vec![Value::new(0x22, 8)]
+        );
+        ram.tick(&ins(Value::new(1, 4), WE, NO_LE, Value::new(0x99, 8)));
+        ram.tick(&ins(Value::new(2, 4), WE, NO_LE, Value::new(0x33, 8)));
+
+        ram.reset();
+        assert_eq!(ram.observe(), vec![Value::new(0, 8)]);
+        let LogicSeq::Ram(cell) = &ram else {
+            unreachable!()
+        };
+        assert_eq!(cell.contents().word(1), 0x22);
+        assert_eq!(cell.contents().word(2), 0);
+    }
+
+    #[test]
+    fn test_reset_clears_output_but_keeps_contents_when_asked() {
+        let conf = Ram {
+            keep_contents: true,
+            ..Ram::new(8, 4, ReadBehavior::ReadAfterWrite)
+        };
+        let mut ram = LogicSeq::Ram(RamCell::new(conf));
         ram.tick(&ins(Value::new(0, 4), WE, LE, Value::new(0x42, 8)));
         assert_eq!(ram.observe(), vec![Value::new(0x42, 8)]);
 
@@ -444,6 +558,20 @@ mod tests {
         assert_eq!(cloned.word(2), 0);
     }
 
+    #[test]
+    fn test_clone_and_resize_carry_the_initial_image() {
+        let ram =
+            Ram::new(8, 2, ReadBehavior::ReadAfterWrite).with_initial(vec![1, 0x1FF, 3, 0, 0]);
+        // Cut to 4 words, masked to 8 bits.
+        assert_eq!(ram.initial, vec![1, 0xFF, 3]);
+        ram.set_word(0, 0x42);
+        assert_eq!(ram.clone().word(0), 1);
+
+        let narrow = ram.resized(1, 1);
+        assert_eq!(narrow.initial, vec![1, 1]);
+        assert_eq!(narrow.word(0), 0);
+    }
+
     #[test]
     fn test_shared_aliases_the_same_buffer() {
         let a = Ram::new(8, 2, ReadBehavior::ReadAfterWrite);
@@ -473,5 +601,21 @@ mod tests {
         assert_eq!(reloaded.address_width, 4);
         assert_eq!(reloaded.read_behavior, ReadBehavior::WriteAfterRead);
         assert_eq!(reloaded.word(3), 0); // zero-filled, not the original 0xAB
+        assert!(!json.contains("initial") && !json.contains("keep_contents"));
+    }
+
+    #[test]
+    fn test_serde_round_trip_loads_the_initial_image() {
+        let ram = Ram {
+            keep_contents: true,
+            ..Ram::new(8, 4, ReadBehavior::ReadAfterWrite).with_initial(vec![0, 7])
+        };
+        ram.set_word(3, 0xAB);
+        let json = serde_json::to_string(&ram).unwrap();
+        let reloaded: Ram = serde_json::from_str(&json).unwrap();
+        assert_eq!(reloaded.initial, vec![0, 7]);
+        assert!(reloaded.keep_contents);
+        assert_eq!(reloaded.word(1), 7);
+        assert_eq!(reloaded.word(3), 0);
     }
 }
@@ -1,10 +1,12 @@
-use super::{SeqLogic, SeqState};
+use super::{ClockEdge, SeqLogic, SeqState};
 use crate::sim::value::Value;
 
 // Construction record only; the latched runtime value lives in LogicSeq::Reg::value.
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct RegConf {
     pub data_width: u8,
+    #[serde(default)]
+    pub clock_edge: ClockEdge,
 }
 
 impl RegConf {
@@ -13,11 +15,12 @@ impl RegConf {
     // Forces the output to zero the instant it's held. Active only on
     // exactly Value::ONE.
     pub const RESET_PIN: usize = 2;
+    pub const CLOCK_PIN: usize = 3;
 }
 
 impl RegConf {
     pub fn n_inputs(&self) -> usize {
-        3
+        4
     }
 
     pub fn n_outputs(&self) -> usize {
@@ -29,6 +32,7 @@ impl RegConf {
             RegConf::DATA_PIN => Some(self.data_width),
             RegConf::WRITE_EN_PIN => Some(1),
             RegConf::RESET_PIN => Some(1),
+            RegConf::CLOCK_PIN => Some(1),
             _ => None,
         }
     }
@@ -48,9 +52,12 @@ pub struct Reg {
 }
 
 impl Reg {
-    pub fn new(data_width: u8) -> Self {
+    pub fn new(data_width: u8, clock_edge: ClockEdge) -> Self {
         Self {
-            conf: RegConf { data_width },
+            conf: RegConf {
+                data_width,
+                clock_edge,
+            },
             value: Value::new(0, data_width),
         }
     }
@@ -99,6 +106,16 @@ impl SeqLogic for Reg {
         SeqState::Reg(self.value)
     }
 
+    fn restore(&mut self, state: &SeqState) {
+        if let SeqState::Reg(value) = state {
+            self.value = *value;
+        }
+    }
+
+    fn clock_pin(&self) -> Option<(usize, ClockEdge)> {
+        Some((RegConf::CLOCK_PIN, self.conf.clock_edge))
+    }
+
     fn input_width(&self, i: usize) -> Option<u8> {
         self.conf.input_width(i)
     }
@@ -116,7 +133,7 @@ mod tests {
     use test_case::test_case;
 
     fn new_reg(data_width: u8) -> LogicSeq {
-        LogicSeq::Reg(Reg::new(data_width))
+        LogicSeq::Reg(Reg::new(data_width, ClockEdge::Rising))
     }
 
     const NO_RST: Value = Value::ZERO;
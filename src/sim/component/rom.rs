@@ -7,17 +7,17 @@ use crate::sim::value::Value;
 // A ROM carries bulk state (`data`) but stays combinational: evaluate() is a
 // pure read, and contents only change through an explicit GUI edit
 // (Circuit::write_rom). `data` is an Rc<RefCell<..>> so the placed spec and
-// the live component can share one buffer without duplicating up to 64 MiB
+// the live component can share one buffer without duplicating up to 128 MiB
 // (see Rom::shared). Clone stays a deep, independent copy, though — paste,
 // undo, and save all rely on cloning a spec producing an independent record.
 #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Rom {
     pub data_width: u8,
     pub address_width: u8,
-    pub data: Rc<RefCell<Vec<u32>>>,
+    pub data: Rc<RefCell<Vec<u64>>>,
 }
 
-// 2^24 words (64 MiB of u32) — the ceiling the GUI clamps address_width to.
+// 2^24 words (128 MiB of u64) — the ceiling the GUI clamps address_width to.
 pub const MAX_ADDRESS_WIDTH: u8 = 24;
 
 impl Clone for Rom {
@@ -51,8 +51,8 @@ impl Rom {
         }
     }
 
-    pub fn mask(&self) -> u32 {
-        mask_for(self.data_width)
+    pub fn mask(&self) -> u64 {
+        Value::mask(self.data_width)
     }
 
     pub fn len(&self) -> usize {
@@ -63,13 +63,13 @@ impl Rom {
         self.data.borrow().is_empty()
     }
 
-    pub fn word(&self, index: usize) -> u32 {
+    pub fn word(&self, index: usize) -> u64 {
         self.data.borrow().get(index).copied().unwrap_or(0)
     }
 
     // &self (interior mutability): a write via either handle is visible to
     // both. No-op if out of range.
-    pub fn set_word(&self, index: usize, value: u32) {
+    pub fn set_word(&self, index: usize, value: u64) {
         let mut data = self.data.borrow_mut();
         if index < data.len() {
             data[index] = value & self.mask();
@@ -83,7 +83,7 @@ impl Rom {
         let mut data = self.data.borrow().clone();
         data.resize(new_len, 0);
         if new_data_width < self.data_width {
-            let m = mask_for(new_data_width);
+            let m = Value::mask(new_data_width);
             for w in &mut data {
                 *w &= m;
             }
@@ -96,14 +96,6 @@ impl Rom {
     }
 }
 
-fn mask_for(width: u8) -> u32 {
-    if width >= 32 {
-        u32::MAX
-    } else {
-        (1u32 << width) - 1
-    }
-}
-
 impl CombLogic for Rom {
     fn n_inputs(&self) -> usize {
         1
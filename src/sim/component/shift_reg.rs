@@ -1,4 +1,4 @@
-use super::{SeqLogic, SeqState};
+use super::{ClockEdge, SeqLogic, SeqState};
 use crate::sim::value::Value;
 
 // Pin layout depends on `parallel_load`/`num_stages`, so pin indices are
@@ -9,6 +9,8 @@ pub struct ShiftRegConf {
     pub data_width: u8,
     pub num_stages: usize,
     pub parallel_load: bool,
+    #[serde(default)]
+    pub clock_edge: ClockEdge,
 }
 
 impl ShiftRegConf {
@@ -37,14 +39,19 @@ impl ShiftRegConf {
             2
         }
     }
+
+    // Always last, after reset.
+    pub fn clock_pin(&self) -> usize {
+        self.reset_pin() + 1
+    }
 }
 
 impl ShiftRegConf {
     pub fn n_inputs(&self) -> usize {
         if self.parallel_load {
-            4 + self.num_stages // data, load, shift, one per stage, reset
+            5 + self.num_stages // data, load, shift, one per stage, reset, clock
         } else {
-            3 // data, shift, reset
+            4 // data, shift, reset, clock
         }
     }
 
@@ -60,7 +67,7 @@ impl ShiftRegConf {
         if i == Self::DATA_PIN {
             return Some(self.data_width);
         }
-        if i == self.shift_pin() || i == self.reset_pin() {
+        if i == self.shift_pin() || i == self.reset_pin() || i == self.clock_pin() {
             return Some(1);
         }
         if self.parallel_load {
@@ -91,13 +98,19 @@ pub struct ShiftReg {
 }
 
 impl ShiftReg {
-    pub fn new(data_width: u8, num_stages: usize, parallel_load: bool) -> Self {
+    pub fn new(
+        data_width: u8,
+        num_stages: usize,
+        parallel_load: bool,
+        clock_edge: ClockEdge,
+    ) -> Self {
         let num_stages = num_stages.max(1);
         Self {
             conf: ShiftRegConf {
                 data_width,
                 num_stages,
                 parallel_load,
+                clock_edge,
             },
             stages: vec![Value::new(0, data_width); num_stages],
         }
@@ -174,6 +187,16 @@ impl SeqLogic for ShiftReg {
         SeqState::ShiftReg(self.stages.clone())
     }
 
+    fn restore(&mut self, state: &SeqState) {
+        if let SeqState::ShiftReg(stages) = state {
+            self.stages.clone_from(stages);
+        }
+    }
+
+    fn clock_pin(&self) -> Option<(usize, ClockEdge)> {
+        Some((self.conf.clock_pin(), self.conf.clock_edge))
+    }
+
     fn input_width(&self, i: usize) -> Option<u8> {
         self.conf.input_width(i)
     }
@@ -189,7 +212,12 @@ mod tests {
     use crate::sim::component::LogicSeq;
 
     fn new_shift_reg(data_width: u8, num_stages: usize, parallel_load: bool) -> LogicSeq {
-        LogicSeq::ShiftReg(ShiftReg::new(data_width, num_stages, parallel_load))
+        LogicSeq::ShiftReg(ShiftReg::new(
+            data_width,
+            num_stages,
+            parallel_load,
+            ClockEdge::Rising,
+        ))
     }
 
     // ── Serial mode (parallel_load = false): inputs = [data, shift, reset] ──────
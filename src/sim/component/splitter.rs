@@ -1,5 +1,5 @@
 use super::CombLogic;
-use crate::sim::value::Value;
+use crate::sim::value::{Bit, Value};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum FanDirection {
@@ -67,7 +67,6 @@ impl Splitter {
     // Inverts new(). Round-trips to an identical routing/arm_width, though not
     // necessarily the same input (a bit claimed by two arms only shows here
     // under the winning arm).
-    #[allow(dead_code)]
     pub(crate) fn arm_bits(&self) -> Vec<Vec<u8>> {
         let mut arm_bits: Vec<Vec<u8>> = self
             .arm_width
@@ -83,6 +82,29 @@ impl Splitter {
     }
 }
 
+// Accumulates a Value bit by bit as evaluate() routes it; bits never set stay 0.
+#[derive(Clone, Default)]
+struct BitPlanes {
+    bits: u64,
+    z: u64,
+    x: u64,
+}
+
+impl BitPlanes {
+    fn set(&mut self, i: u8, bit: Bit) {
+        match bit {
+            Bit::Zero => {}
+            Bit::One => self.bits |= 1 << i,
+            Bit::Z => self.z |= 1 << i,
+            Bit::X => self.x |= 1 << i,
+        }
+    }
+
+    fn into_value(self, width: u8) -> Value {
+        Value::from_masks(self.bits, self.z, self.x, width)
+    }
+}
+
 impl CombLogic for Splitter {
     fn n_inputs(&self) -> usize {
         match self.direction {
@@ -99,42 +121,47 @@ impl CombLogic for Splitter {
     fn evaluate(&self, inputs: &[Value]) -> Vec<Value> {
         match self.direction {
             FanDirection::Right => match inputs[0] {
-                Value::Fixed { bits, .. } => {
-                    let mut arm_bits_out = vec![0u32; self.arms as usize];
+                // Routed bit by bit, so a Z/X trunk bit lands only on its own arm.
+                trunk @ (Value::Fixed { .. } | Value::Partial { .. }) => {
+                    let mut arm_planes = vec![BitPlanes::default(); self.arms as usize];
                     for (data_i, route) in self.routing.iter().enumerate() {
                         if let Some((arm, slot)) = *route {
-                            if bits & (1 << data_i) != 0 {
-                                arm_bits_out[arm as usize] |= 1 << slot;
-                            }
+                            arm_planes[arm as usize].set(slot, trunk.bit(data_i as u8));
                         }
                     }
-                    (0..self.arms as usize)
-                        .map(|arm| Value::new(arm_bits_out[arm], self.arm_width[arm]))
+                    arm_planes
+                        .into_iter()
+                        .zip(&self.arm_width)
+                        .map(|(planes, &width)| planes.into_value(width))
                         .collect()
                 }
                 Value::Floating | Value::Invalid => vec![Value::Floating; self.arms as usize],
             },
             FanDirection::Left => {
                 let arm_vals: Vec<Value> = (0..self.arms as usize).map(|i| inputs[i]).collect();
-                // Floating or a wrong-width arm poisons the whole merged output.
+                // A wrong-width arm poisons the whole merged output; a Floating
+                // one just leaves its own bits Z.
                 let widths_ok = (0..self.arms as usize).all(|arm| {
                     self.arm_width[arm] == 0
-                        || matches!(arm_vals[arm], Value::Fixed { width, .. } if width == self.arm_width[arm])
+                        || arm_vals[arm]
+                            .width()
+                            .is_none_or(|width| width == self.arm_width[arm])
                 });
                 if !widths_ok {
                     vec![Value::Floating]
                 } else {
-                    let mut out_bits = 0u32;
+                    let mut out = BitPlanes::default();
                     for (data_i, route) in self.routing.iter().enumerate() {
                         if let Some((arm, slot)) = *route {
-                            if let Value::Fixed { bits, .. } = arm_vals[arm as usize] {
-                                if bits & (1 << slot) != 0 {
-                                    out_bits |= 1 << data_i;
-                                }
-                            }
+                            let bit = match arm_vals[arm as usize] {
+                                // Invalid never propagates past its own net.
+                                Value::Invalid => Bit::Z,
+                                v => v.bit(slot),
+                            };
+                            out.set(data_i as u8, bit);
                         }
                     }
-                    vec![Value::new(out_bits, self.data_width())]
+                    vec![out.into_value(self.data_width())]
                 }
             }
         }
@@ -305,14 +332,27 @@ mod tests {
     }
 
     #[test]
-    fn test_combine_floating_arm_propagates() {
+    fn test_combine_floating_arm_leaves_only_its_bits_z() {
         let s = Splitter::new(vec![vec![0], vec![1]], FanDirection::Left);
         assert_eq!(
             s.evaluate(&[Value::new(0b1, 1), Value::Floating]),
+            vec![Value::from_masks(0b01, 0b10, 0, 2)]
+        );
+        assert_eq!(
+            s.evaluate(&[Value::Floating, Value::Floating]),
             vec![Value::Floating]
         );
     }
 
+    #[test]
+    fn test_partial_trunk_routes_z_and_x_per_arm() {
+        let s = Splitter::new(vec![vec![0, 1], vec![2], vec![3]], FanDirection::Right);
+        assert_eq!(
+            s.evaluate(&[Value::from_masks(0b0001, 0b0100, 0b1000, 4)]),
+            vec![Value::new(0b01, 2), Value::Floating, Value::unknown(1)]
+        );
+    }
+
     #[test]
     fn test_combine_width_mismatch_yields_floating() {
         let s = Splitter::new(vec![vec![0, 1], vec![2]], FanDirection::Left);
@@ -1,9 +1,20 @@
-use super::{SeqLogic, SeqState};
+use super::{ClockEdge, EdgeOnlyConf, SeqLogic, SeqState};
 use crate::sim::value::Value;
 
 /// An S-R flip-flop: S=R=0 holds, S=1/R=0 sets, S=0/R=1 resets, S=R=1 is forbidden (floats).
-#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
-pub struct SRFlipFlopConf;
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "Option<EdgeOnlyConf>")]
+pub struct SRFlipFlopConf {
+    pub clock_edge: ClockEdge,
+}
+
+impl From<Option<EdgeOnlyConf>> for SRFlipFlopConf {
+    fn from(conf: Option<EdgeOnlyConf>) -> Self {
+        Self {
+            clock_edge: EdgeOnlyConf::edge(conf),
+        }
+    }
+}
 
 impl SRFlipFlopConf {
     pub const S_PIN: usize = 0;
@@ -14,11 +25,12 @@ impl SRFlipFlopConf {
     // sticks. Distinct from the clocked R input; active only on exactly
     // Value::ONE.
     pub const RESET_PIN: usize = 3;
+    pub const CLOCK_PIN: usize = 4;
 }
 
 impl SRFlipFlopConf {
     pub fn n_inputs(&self) -> usize {
-        4
+        5
     }
 
     pub fn n_outputs(&self) -> usize {
@@ -31,6 +43,7 @@ impl SRFlipFlopConf {
             SRFlipFlopConf::R_PIN => Some(1),
             SRFlipFlopConf::WRITE_EN_PIN => Some(1),
             SRFlipFlopConf::RESET_PIN => Some(1),
+            SRFlipFlopConf::CLOCK_PIN => Some(1),
             _ => None,
         }
     }
@@ -50,9 +63,9 @@ pub struct SRFlipFlop {
 }
 
 impl SRFlipFlop {
-    pub fn new() -> Self {
+    pub fn new(clock_edge: ClockEdge) -> Self {
         Self {
-            conf: SRFlipFlopConf,
+            conf: SRFlipFlopConf { clock_edge },
             value: Value::ZERO,
         }
     }
@@ -60,7 +73,7 @@ impl SRFlipFlop {
 
 impl Default for SRFlipFlop {
     fn default() -> Self {
-        Self::new()
+        Self::new(ClockEdge::default())
     }
 }
 
@@ -117,6 +130,16 @@ impl SeqLogic for SRFlipFlop {
         SeqState::FlipFlop(self.value)
     }
 
+    fn restore(&mut self, state: &SeqState) {
+        if let SeqState::FlipFlop(value) = state {
+            self.value = *value;
+        }
+    }
+
+    fn clock_pin(&self) -> Option<(usize, ClockEdge)> {
+        Some((SRFlipFlopConf::CLOCK_PIN, self.conf.clock_edge))
+    }
+
     fn input_width(&self, i: usize) -> Option<u8> {
         self.conf.input_width(i)
     }
@@ -134,7 +157,7 @@ mod tests {
     use test_case::test_case;
 
     fn new_sr_flip_flop() -> LogicSeq {
-        LogicSeq::SRFlipFlop(SRFlipFlop::new())
+        LogicSeq::SRFlipFlop(SRFlipFlop::new(ClockEdge::Rising))
     }
 
     // No async reset asserted (the common case in these tests).
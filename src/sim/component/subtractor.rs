@@ -1,4 +1,4 @@
-use super::CombLogic;
+use super::{has_unknown_operand, CombLogic};
 use crate::sim::value::Value;
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -28,7 +28,7 @@ impl CombLogic for Subtractor {
         // A Floating borrow-in behaves the same as a zero borrow-in; anything else
         // non-Fixed, or a Fixed borrow-in at the wrong width, falls through to Floating.
         let borrow_in = match inputs[Self::BORROW_IN_PIN] {
-            Value::Floating => Some(0u32),
+            Value::Floating => Some(0u64),
             Value::Fixed { bits, width: 1 } => Some(bits),
             _ => None,
         };
@@ -42,13 +42,18 @@ impl CombLogic for Subtractor {
                 Value::Fixed { bits: b, width: bw },
                 Some(bin),
             ) if aw == width && bw == width => {
-                // Wraps mod 2^32 then masks to `width` bits, which equals mod
-                // 2^width since 2^width divides 2^32 - no signed/widened
+                // Wraps mod 2^64 then masks to `width` bits, which equals mod
+                // 2^width since 2^width divides 2^64 - no signed/widened
                 // intermediate needed, unlike the borrow-out check below.
                 let diff = a.wrapping_sub(b).wrapping_sub(bin) & Value::mask(width);
-                let borrow = ((a as u64) < (b as u64 + bin as u64)) as u32;
+                let borrow = ((a as u128) < (b as u128 + bin as u128)) as u64;
                 vec![Value::new(diff, width), Value::new(borrow, 1)]
             }
+            (a, b, _)
+                if has_unknown_operand(&[a, b], width, Some((inputs[Self::BORROW_IN_PIN], 1))) =>
+            {
+                vec![Value::unknown(width), Value::unknown(1)]
+            }
             _ => vec![Value::Floating, Value::Floating],
         }
     }
@@ -126,16 +131,31 @@ mod tests {
 
     #[test]
     fn test_full_width_subtrahend_does_not_panic() {
-        // Subtrahend at max u32 plus a borrow-in for a 32-bit subtractor: exercises
-        // the u64 widening in the borrow-out check that avoids an overflow panic
-        // on plain u32 addition (b + bin).
+        // Subtrahend at max u32 plus a borrow-in for a 32-bit subtractor: the
+        // borrow-out check must compare the widened b + bin, not a wrapped one.
         let s = subtractor(32);
         assert_eq!(
-            s.evaluate(&[Value::new(0, 32), Value::new(u32::MAX, 32), Value::ONE]),
+            s.evaluate(&[
+                Value::new(0, 32),
+                Value::new(u32::MAX as u64, 32),
+                Value::ONE
+            ]),
             vec![Value::new(0, 32), Value::ONE]
         );
     }
 
+    #[test]
+    fn test_full_width_subtrahend_does_not_panic_at_64_bits() {
+        // Subtrahend at max u64 plus a borrow-in for a 64-bit subtractor: exercises
+        // the u128 widening in the borrow-out check that avoids an overflow panic
+        // on plain u64 addition (b + bin).
+        let s = subtractor(64);
+        assert_eq!(
+            s.evaluate(&[Value::new(0, 64), Value::new(u64::MAX, 64), Value::ONE]),
+            vec![Value::new(0, 64), Value::ONE]
+        );
+    }
+
     #[test]
     fn test_mismatched_operand_width_yields_floating() {
         let s = subtractor(4);
@@ -1,9 +1,20 @@
-use super::{SeqLogic, SeqState};
+use super::{ClockEdge, EdgeOnlyConf, SeqLogic, SeqState};
 use crate::sim::value::Value;
 
 /// A T flip-flop toggles its stored bit each time the toggle input is high on write.
-#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
-pub struct TFlipFlopConf;
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "Option<EdgeOnlyConf>")]
+pub struct TFlipFlopConf {
+    pub clock_edge: ClockEdge,
+}
+
+impl From<Option<EdgeOnlyConf>> for TFlipFlopConf {
+    fn from(conf: Option<EdgeOnlyConf>) -> Self {
+        Self {
+            clock_edge: EdgeOnlyConf::edge(conf),
+        }
+    }
+}
 
 impl TFlipFlopConf {
     pub const TOGGLE_PIN: usize = 0;
@@ -12,11 +23,12 @@ impl TFlipFlopConf {
     // observe), and clears the latched value on the next tick so the reset
     // sticks. Active only on exactly Value::ONE.
     pub const RESET_PIN: usize = 2;
+    pub const CLOCK_PIN: usize = 3;
 }
 
 impl TFlipFlopConf {
     pub fn n_inputs(&self) -> usize {
-        3
+        4
     }
 
     pub fn n_outputs(&self) -> usize {
@@ -28,6 +40,7 @@ impl TFlipFlopConf {
             TFlipFlopConf::TOGGLE_PIN => Some(1),
             TFlipFlopConf::WRITE_EN_PIN => Some(1),
             TFlipFlopConf::RESET_PIN => Some(1),
+            TFlipFlopConf::CLOCK_PIN => Some(1),
             _ => None,
         }
     }
@@ -47,9 +60,9 @@ pub struct TFlipFlop {
 }
 
 impl TFlipFlop {
-    pub fn new() -> Self {
+    pub fn new(clock_edge: ClockEdge) -> Self {
         Self {
-            conf: TFlipFlopConf,
+            conf: TFlipFlopConf { clock_edge },
             value: Value::ZERO,
         }
     }
@@ -57,7 +70,7 @@ impl TFlipFlop {
 
 impl Default for TFlipFlop {
     fn default() -> Self {
-        Self::new()
+        Self::new(ClockEdge::default())
     }
 }
 
@@ -108,6 +121,16 @@ impl SeqLogic for TFlipFlop {
         SeqState::FlipFlop(self.value)
     }
 
+    fn restore(&mut self, state: &SeqState) {
+        if let SeqState::FlipFlop(value) = state {
+            self.value = *value;
+        }
+    }
+
+    fn clock_pin(&self) -> Option<(usize, ClockEdge)> {
+        Some((TFlipFlopConf::CLOCK_PIN, self.conf.clock_edge))
+    }
+
     fn input_width(&self, i: usize) -> Option<u8> {
         self.conf.input_width(i)
     }
@@ -125,7 +148,7 @@ mod tests {
     use test_case::test_case;
 
     fn new_t_flip_flop() -> LogicSeq {
-        LogicSeq::TFlipFlop(TFlipFlop::new())
+        LogicSeq::TFlipFlop(TFlipFlop::new(ClockEdge::Rising))
     }
 
     // No async reset asserted (the common case in these tests).
@@ -0,0 +1,106 @@
+use super::CombLogic;
+use crate::sim::value::Value;
+
+// A controlled buffer: passes its data through while enabled and lets go of
+// the net (Floating, all Z) otherwise, so several can share one bus - see
+// Circuit::resolve_net for how their outputs combine.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TriState {
+    pub data_width: u8,
+}
+
+impl TriState {
+    const DATA_PIN: usize = 0;
+    const ENABLE_PIN: usize = 1;
+}
+
+impl CombLogic for TriState {
+    fn n_inputs(&self) -> usize {
+        2
+    }
+
+    fn n_outputs(&self) -> usize {
+        1
+    }
+
+    fn evaluate(&self, inputs: &[Value]) -> Vec<Value> {
+        let data = inputs[Self::DATA_PIN];
+        let out = match inputs[Self::ENABLE_PIN] {
+            Value::ZERO => Value::Floating,
+            // A Floating enable counts as enabled, like Reg's write-enable.
+            Value::ONE | Value::Floating if data.width() == Some(self.data_width) => data,
+            Value::ONE | Value::Floating => Value::Floating,
+            // An X/Z enable might or might not be driving: every bit is unknown.
+            Value::Partial { width: 1, .. } => Value::unknown(self.data_width),
+            // Malformed enable (wrong width): drive nothing.
+            _ => Value::Floating,
+        };
+        vec![out]
+    }
+
+    fn input_width(&self, i: usize) -> Option<u8> {
+        match i {
+            Self::DATA_PIN => Some(self.data_width),
+            Self::ENABLE_PIN => Some(1),
+            _ => None,
+        }
+    }
+
+    fn output_width(&self, i: usize) -> Option<u8> {
+        match i {
+            0 => Some(self.data_width),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(Value::ONE ; "enable high")]
+    #[test_case(Value::Floating ; "enable floating")]
+    fn test_enabled_passes_data_through(enable: Value) {
+        let t = TriState { data_width: 4 };
+        assert_eq!(
+            t.evaluate(&[Value::new(0b1010, 4), enable]),
+            vec![Value::new(0b1010, 4)]
+        );
+    }
+
+    #[test_case(Value::ZERO ; "enable low")]
+    #[test_case(Value::new(1, 2) ; "enable wrong width")]
+    fn test_disabled_drives_floating(enable: Value) {
+        let t = TriState { data_width: 4 };
+        assert_eq!(
+            t.evaluate(&[Value::new(0b1010, 4), enable]),
+            vec![Value::Floating]
+        );
+    }
+
+    #[test]
+    fn test_partial_data_passes_through_bit_for_bit() {
+        let t = TriState { data_width: 4 };
+        let data = Value::from_masks(0b0001, 0b0110, 0b1000, 4);
+        assert_eq!(t.evaluate(&[data, Value::ONE]), vec![data]);
+    }
+
+    #[test]
+    fn test_unknown_enable_makes_output_unknown() {
+        let t = TriState { data_width: 4 };
+        assert_eq!(
+            t.evaluate(&[Value::new(0b1010, 4), Value::unknown(1)]),
+            vec![Value::unknown(4)]
+        );
+    }
+
+    #[test]
+    fn test_wrong_width_data_yields_floating() {
+        let t = TriState { data_width: 4 };
+        assert_eq!(
+            t.evaluate(&[Value::new(1, 3), Value::ONE]),
+            vec![Value::Floating]
+        );
+    }
+}
@@ -0,0 +1,466 @@
+// Two-level logic minimization: each Output bit of a truth table (see
+// truth_table.rs) as a minimal sum of products, by Quine-McCluskey. Prime
+// implicants come from repeatedly merging terms that differ in one variable;
+// the cover takes the essential primes, then searches the rest (branch and
+// bound, seeded with a greedy cover, within a node budget) for the fewest
+// terms, then literals.
+//
+// Variables are input bits, most significant first: a 2-bit Input `a` and a
+// 1-bit `b` give variables a[1], a[0], b, and a truth table's row number is
+// the minterm. An Output bit that is z or x on a row is a don't-care there.
+
+use std::collections::HashSet;
+
+use crate::sim::truth_table::TruthTable;
+use crate::sim::value::{Bit, Value};
+
+// Prime implicants can number up to 3^n / n, so larger tables are only
+// tabulated, not minimized.
+pub const MAX_MINIMIZE_VARS: usize = 12;
+
+// Bounds the exact cover search; past it the best cover found so far stands.
+const SEARCH_BUDGET: usize = 100_000;
+
+// A product term over n variables: it covers minterm `m` when `m` agrees with
+// `value` everywhere outside `dashes`. Bit n-1 is the first variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Implicant {
+    pub value: u32,
+    pub dashes: u32,
+}
+
+impl Implicant {
+    pub fn covers(self, m: u32) -> bool {
+        m & !self.dashes == self.value
+    }
+
+    pub fn literals(self, n_vars: usize) -> u32 {
+        n_vars as u32 - self.dashes.count_ones()
+    }
+
+    // `a·b'` style, over `names` (first variable first); `1` with no literals.
+    pub fn to_text(self, names: &[String]) -> String {
+        let n = names.len();
+        let literals: Vec<String> = names
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| {
+                let bit = 1 << (n - 1 - i);
+                if self.dashes & bit != 0 {
+                    None
+                } else if self.value & bit != 0 {
+                    Some(name.clone())
+                } else {
+                    Some(format!("{name}'"))
+                }
+            })
+            .collect();
+        if literals.is_empty() {
+            "1".to_string()
+        } else {
+            literals.join("·")
+        }
+    }
+}
+
+// One Output bit as a function of the input bits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitFunction {
+    // The Output's name, with `[i]` for a bit of a wider one.
+    pub name: String,
+    pub ones: Vec<u32>,
+    pub dont_cares: Vec<u32>,
+}
+
+impl BitFunction {
+    // What the function is at minterm `m`, for display: 1, 0, or None for a
+    // don't-care.
+    pub fn at(&self, m: u32) -> Option<bool> {
+        if self.ones.binary_search(&m).is_ok() {
+            Some(true)
+        } else if self.dont_cares.binary_search(&m).is_ok() {
+            None
+        } else {
+            Some(false)
+        }
+    }
+}
+
+// A function and its minimized sum of products. No terms is constant 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expression {
+    pub function: BitFunction,
+    pub terms: Vec<Implicant>,
+}
+
+impl Expression {
+    pub fn to_text(&self, names: &[String]) -> String {
+        let sum = if self.terms.is_empty() {
+            "0".to_string()
+        } else {
+            let terms: Vec<String> = self.terms.iter().map(|t| t.to_text(names)).collect();
+            terms.join(" + ")
+        };
+        format!("{} = {sum}", self.function.name)
+    }
+}
+
+// The variable names of a truth table: one per input bit, MSB first.
+pub fn variables(table: &TruthTable) -> Vec<String> {
+    table
+        .inputs
+        .iter()
+        .flat_map(|p| {
+            let width = p.width.unwrap_or(1);
+            (0..width).rev().map(move |i| bit_name(&p.name, i, width))
+        })
+        .collect()
+}
+
+// Every Output bit of `table` as a function of its input bits, MSB first per
+// Output. An Output's width is the widest value it takes on any row.
+pub fn bit_functions(table: &TruthTable) -> Vec<BitFunction> {
+    let mut functions = Vec::new();
+    for (o, port) in table.outputs.iter().enumerate() {
+        let width = table
+            .rows
+            .iter()
+            .filter_map(|r| r.outputs[o].width())
+            .max()
+            .unwrap_or(1);
+        for bit in (0..width).rev() {
+            let mut f = BitFunction {
+                name: bit_name(&port.name, bit, width),
+                ones: Vec::new(),
+                dont_cares: Vec::new(),
+            };
+            for (m, row) in table.rows.iter().enumerate() {
+                match value_bit(row.outputs[o], bit) {
+                    Bit::One => f.ones.push(m as u32),
+                    Bit::Zero => {}
+                    Bit::Z | Bit::X => f.dont_cares.push(m as u32),
+                }
+            }
+            functions.push(f);
+        }
+    }
+    functions
+}
+
+// Minimizes every Output bit of `table`. None past MAX_MINIMIZE_VARS.
+pub fn expressions(table: &TruthTable) -> Option<Vec<Expression>> {
+    let n_vars = variables(table).len();
+    if n_vars > MAX_MINIMIZE_VARS {
+        return None;
+    }
+    let expressions = bit_functions(table)
+        .into_iter()
+        .map(|function| Expression {
+            terms: minimize(n_vars, &function.ones, &function.dont_cares),
+            function,
+        })
+        .collect();
+    Some(expressions)
+}
+
+fn bit_name(name: &str, bit: u8, width: u8) -> String {
+    if width == 1 {
+        name.to_string()
+    } else {
+        format!("{name}[{bit}]")
+    }
+}
+
+// An Invalid or Floating value has no width; read them as all-X / all-Z.
+fn value_bit(v: Value, bit: u8) -> Bit {
+    match v.width() {
+        Some(_) => v.bit(bit),
+        None => v.bit(0),
+    }
+}
+
+// A minimal sum of products over `n_vars` variables that is 1 on every minterm
+// in `ones` and 0 everywhere outside `ones` and `dont_cares`. Terms are sorted.
+pub fn minimize(n_vars: usize, ones: &[u32], dont_cares: &[u32]) -> Vec<Implicant> {
+    if ones.is_empty() {
+        return Vec::new();
+    }
+    let primes: Vec<Implicant> = prime_implicants(n_vars, ones, dont_cares)
+        .into_iter()
+        .filter(|p| ones.iter().any(|&m| p.covers(m)))
+        .collect();
+
+    // Essential primes: the only cover of some 1.
+    let mut chosen: Vec<Implicant> = Vec::new();
+    for &m in ones {
+        let mut covering = primes.iter().filter(|p| p.covers(m));
+        if let (Some(&only), None) = (covering.next(), covering.next()) {
+            if !chosen.contains(&only) {
+                chosen.push(only);
+            }
+        }
+    }
+    let rest: Vec<u32> = ones
+        .iter()
+        .copied()
+        .filter(|&m| !chosen.iter().any(|p| p.covers(m)))
+        .collect();
+    let candidates: Vec<Implicant> = primes
+        .iter()
+        .copied()
+        .filter(|p| !chosen.contains(p) && rest.iter().any(|&m| p.covers(m)))
+        .collect();
+    chosen.extend(cover(n_vars, &rest, &candidates));
+    chosen.sort();
+    chosen
+}
+
+// Every prime implicant of the function with these 1s and don't-cares.
+fn prime_implicants(n_vars: usize, ones: &[u32], dont_cares: &[u32]) -> Vec<Implicant> {
+    let mut primes = Vec::new();
+    let mut current: HashSet<Implicant> = ones
+        .iter()
+        .chain(dont_cares)
+        .map(|&value| Implicant { value, dashes: 0 })
+        .collect();
+    while !current.is_empty() {
+        let mut merged: HashSet<Implicant> = HashSet::new();
+        let mut absorbed: HashSet<Implicant> = HashSet::new();
+        for &imp in &current {
+            for v in 0..n_vars {
+                let bit = 1 << v;
+                if imp.dashes & bit != 0 || imp.value & bit == 0 {
+                    continue;
+                }
+                let partner = Implicant {
+                    value: imp.value & !bit,
+                    dashes: imp.dashes,
+                };
+                if current.contains(&partner) {
+                    merged.insert(Implicant {
+                        value: partner.value,
+                        dashes: imp.dashes | bit,
+                    });
+                    absorbed.insert(imp);
+                    absorbed.insert(partner);
+                }
+            }
+        }
+        primes.extend(current.difference(&absorbed).copied());
+        current = merged;
+    }
+    // Hash order varies run to run; the cover shouldn't.
+    primes.sort();
+    primes
+}
+
+// The cheapest subset of `candidates` covering all of `ones`: fewest terms,
+// then fewest literals.
+fn cover(n_vars: usize, ones: &[u32], candidates: &[Implicant]) -> Vec<Implicant> {
+    if ones.is_empty() {
+        return Vec::new();
+    }
+    // Which 1s (by index) each candidate covers, and the reverse.
+    let covers: Vec<Vec<usize>> = candidates
+        .iter()
+        .map(|p| (0..ones.len()).filter(|&i| p.covers(ones[i])).collect())
+        .collect();
+    let mut options: Vec<Vec<usize>> = vec![Vec::new(); ones.len()];
+    for (j, covered) in covers.iter().enumerate() {
+        for &i in covered {
+            options[i].push(j);
+        }
+    }
+    let literals: Vec<u32> = candidates.iter().map(|p| p.literals(n_vars)).collect();
+
+    // Greedy: repeatedly take the term covering the most still-uncovered 1s.
+    let mut covered = vec![false; ones.len()];
+    let mut greedy = Vec::new();
+    while covered.contains(&false) {
+        let next = (0..candidates.len())
+            .max_by_key(|&j| {
+                let n = covers[j].iter().filter(|&&i| !covered[i]).count();
+                (n, std::cmp::Reverse(literals[j]))
+            })
+            .expect("primes cover every 1");
+        for &i in &covers[next] {
+            covered[i] = true;
+        }
+        greedy.push(next);
+    }
+
+    let mut search = Search {
+        covers: &covers,
+        options: &options,
+        literals: &literals,
+        times_covered: vec![0; ones.len()],
+        best_cost: (greedy.len(), greedy.iter().map(|&j| literals[j]).sum()),
+        best: greedy,
+        budget: SEARCH_BUDGET,
+    };
+    search.run(&mut Vec::new());
+    search.best.iter().map(|&j| candidates[j]).collect()
+}
+
+// Branch and bound over candidate indices.
+struct Search<'a> {
+    covers: &'a [Vec<usize>],
+    options: &'a [Vec<usize>],
+    literals: &'a [u32],
+    // Per 1, how many chosen terms cover it.
+    times_covered: Vec<u32>,
+    best: Vec<usize>,
+    best_cost: (usize, u32),
+    budget: usize,
+}
+
+impl Search<'_> {
+    // Branches on each way to cover the uncovered 1 with the fewest options.
+    fn run(&mut self, chosen: &mut Vec<usize>) {
+        if self.budget == 0 {
+            return;
+        }
+        self.budget -= 1;
+        let uncovered = (0..self.times_covered.len())
+            .filter(|&i| self.times_covered[i] == 0)
+            .min_by_key(|&i| self.options[i].len());
+        let Some(i) = uncovered else {
+            let cost = (chosen.len(), chosen.iter().map(|&j| self.literals[j]).sum());
+            if cost < self.best_cost {
+                self.best_cost = cost;
+                self.best = chosen.clone();
+            }
+            return;
+        };
+        // Another term can't beat the best on term count.
+        if chosen.len() + 1 > self.best_cost.0 {
+            return;
+        }
+        let (options, covers) = (self.options, self.covers);
+        for &j in &options[i] {
+            for &k in &covers[j] {
+                self.times_covered[k] += 1;
+            }
+            chosen.push(j);
+            self.run(chosen);
+            chosen.pop();
+            for &k in &covers[j] {
+                self.times_covered[k] -= 1;
+            }
+        }
+    }
+}
+
+// ── Karnaugh maps ─────────────────────────────────────────────────────────────
+
+// K-maps are drawn for up to this many variables, as one Gray-coded grid.
+pub const MAX_KMAP_VARS: usize = 6;
+
+// The reflected binary (Gray) code of `i`: neighbours differ in one bit.
+pub fn gray(i: u32) -> u32 {
+    i ^ (i >> 1)
+}
+
+// How a K-map over `n_vars` splits them: the first half (rounded down) label
+// the rows, the rest the columns.
+pub fn kmap_shape(n_vars: usize) -> (usize, usize) {
+    let row_vars = n_vars / 2;
+    (row_vars, n_vars - row_vars)
+}
+
+// The minterm in K-map cell (row, col).
+pub fn kmap_minterm(n_vars: usize, row: u32, col: u32) -> u32 {
+    let (_, col_vars) = kmap_shape(n_vars);
+    (gray(row) << col_vars) | gray(col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::circuit::Circuit;
+    use crate::sim::component::{Component, GateOp, PinId};
+    use crate::sim::test_vector::Port;
+
+    fn names(n: &[&str]) -> Vec<String> {
+        n.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn text(n_vars: usize, ones: &[u32], dc: &[u32]) -> String {
+        let vars = names(&["a", "b", "c", "d"][..n_vars]);
+        let terms: Vec<String> = minimize(n_vars, ones, dc)
+            .iter()
+            .map(|t| t.to_text(&vars))
+            .collect();
+        terms.join(" + ")
+    }
+
+    #[test]
+    fn test_minimize_classic_examples() {
+        assert_eq!(text(2, &[], &[]), "");
+        assert_eq!(text(2, &[0, 1, 2, 3], &[]), "1");
+        assert_eq!(text(2, &[1, 2], &[]), "a'·b + a·b'");
+        // Majority of three.
+        assert_eq!(text(3, &[3, 5, 6, 7], &[]), "b·c + a·c + a·b");
+        // f(a,b,c,d) = sum m(4,8,10,11,12,15) + d(9,14): a·b' + a·c + b·c'·d'.
+        let f = minimize(4, &[4, 8, 10, 11, 12, 15], &[9, 14]);
+        assert_eq!(f.len(), 3);
+        for m in [4, 8, 10, 11, 12, 15] {
+            assert!(f.iter().any(|t| t.covers(m)), "misses {m}");
+        }
+        for m in [0, 1, 2, 3, 5, 6, 7, 13] {
+            assert!(!f.iter().any(|t| t.covers(m)), "covers {m}");
+        }
+    }
+
+    #[test]
+    fn test_minimize_cyclic_cover_is_minimal() {
+        // No essential primes; six primes, and three of them suffice.
+        let f = minimize(3, &[0, 1, 2, 5, 6, 7], &[]);
+        assert_eq!(f.len(), 3);
+        assert_eq!(f.iter().map(|t| t.literals(3)).sum::<u32>(), 6);
+    }
+
+    #[test]
+    fn test_expressions_from_truth_table() {
+        // y = a & b, all two bits wide: one function per bit of y.
+        let mut c = Circuit::new();
+        let a = c.add_component(Component::input(0, 2));
+        let b = c.add_component(Component::input(0, 2));
+        let and = c.add_component(Component::gate(GateOp::And, 2, 2));
+        let y = c.add_component(Component::output());
+        c.link(a, PinId::output(0), and, PinId::input(0));
+        c.link(b, PinId::output(0), and, PinId::input(1));
+        c.link(and, PinId::output(0), y, PinId::input(0));
+        let port = |name: &str, key, width| Port {
+            name: name.to_string(),
+            key,
+            width,
+        };
+        let table = TruthTable::analyze(
+            &mut c,
+            vec![port("a", a, Some(2)), port("b", b, Some(2))],
+            vec![port("y", y, None)],
+        )
+        .unwrap();
+        let vars = variables(&table);
+        assert_eq!(vars, names(&["a[1]", "a[0]", "b[1]", "b[0]"]));
+        let text: Vec<String> = expressions(&table)
+            .unwrap()
+            .iter()
+            .map(|e| e.to_text(&vars))
+            .collect();
+        assert_eq!(text, vec!["y[1] = a[1]·b[1]", "y[0] = a[0]·b[0]"]);
+    }
+
+    #[test]
+    fn test_kmap_cells_are_gray_coded() {
+        assert_eq!(kmap_shape(4), (2, 2));
+        assert_eq!(kmap_shape(5), (2, 3));
+        // Row 2 is 11, column 3 is 10.
+        assert_eq!(kmap_minterm(4, 2, 3), 0b1110);
+        for row in 0..3 {
+            let step = kmap_minterm(4, row, 0) ^ kmap_minterm(4, row + 1, 0);
+            assert_eq!(step.count_ones(), 1);
+        }
+    }
+}
@@ -6,12 +6,21 @@ new_key_type! {
     pub struct NetKey;
 }
 
+// How hard a source holds a net. Strong drivers resolve among themselves
+// first; weak ones (pull resistors) only fill in the bits they all leave Z.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strength {
+    Weak,
+    Strong,
+}
+
 #[derive(Debug, Default)]
 pub struct Net {
     pub value: Value,
-    // Every output pin currently driving this net. A well-formed net has at
-    // most one; two or more is a driver conflict (a short) that resolve_net
-    // flags as Value::Invalid, mirroring the width-conflict handling.
+    // Every output pin currently driving this net. Two or more share it bit by
+    // bit: resolve_net lets a Z bit yield to the other drivers and marks a bit
+    // X only where two of them drive different levels. A Strength::Weak source
+    // counts only on bits no strong source drives.
     pub sources: Vec<(CompKey, OutIdx)>,
     pub sinks: Vec<(CompKey, InIdx)>,
 }
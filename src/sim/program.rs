@@ -0,0 +1,407 @@
+use crate::sim::circuit::Circuit;
+use crate::sim::component::{CompKey, Logic};
+use crate::sim::net::NetKey;
+use crate::sim::value::Value;
+
+use slotmap::SecondaryMap;
+use std::collections::{HashMap, HashSet};
+
+// Operand slot for a pin with no net; it reads Floating.
+const UNWIRED: u32 = u32::MAX;
+
+// One compiled combinational component. Its operands are `n_in` input nets
+// followed by `n_out` output nets, starting at `operands[at]`.
+#[derive(Debug)]
+struct Step {
+    comp: CompKey,
+    at: usize,
+    n_in: usize,
+    n_out: usize,
+}
+
+// The compiled fast path behind Circuit::settle(). Every combinational
+// component outside a feedback loop becomes a step, and the steps sit in
+// topological order over dense net indices, so one forward sweep settles them
+// all: a step only ever wakes steps after it. Everything else - sequential
+// parts, subcircuits, tunnels, gate loops and nets with several drivers -
+// stays on the event loop, which hands changes into the sweep by marking
+// steps pending and takes back any net the sweep can't resolve on its own.
+// Any structural change to the circuit drops the program; the next settle
+// compiles a fresh one.
+#[derive(Debug)]
+pub(crate) struct Program {
+    steps: Vec<Step>,
+    operands: Vec<u32>,
+    step_of: HashMap<CompKey, usize>,
+    // Dense net table; every other per-net array is indexed the same way.
+    nets: Vec<NetKey>,
+    // Whether the sweep resolves the net itself: a single step drives it, no
+    // tunnel sits on it, and nothing but steps, Outputs and Probes reads it.
+    // Its value is then just that step's output.
+    direct: Vec<bool>,
+    // The steps reading each net, as ranges into `readers`.
+    reader_start: Vec<usize>,
+    readers: Vec<usize>,
+    pending: Vec<bool>,
+    first_pending: usize,
+    // Scratch reused across sweeps, so evaluating a step allocates nothing
+    // beyond its result.
+    inputs: Vec<Value>,
+    requeue: Vec<NetKey>,
+}
+
+impl Program {
+    pub(crate) fn compile(circuit: &Circuit) -> Self {
+        puffin::profile_function!();
+        let mut index: SecondaryMap<NetKey, u32> = SecondaryMap::new();
+        let nets: Vec<NetKey> = circuit.nets.keys().collect();
+        for (i, &net) in nets.iter().enumerate() {
+            index.insert(net, i as u32);
+        }
+
+        let candidates: Vec<CompKey> = circuit
+            .components
+            .iter()
+            .filter(|(_, c)| match &c.logic {
+                Logic::Comb(logic) => logic.n_inputs() > 0 && logic.n_outputs() > 0,
+                _ => false,
+            })
+            .map(|(&key, _)| key)
+            .collect();
+        let candidate: HashMap<CompKey, usize> = candidates
+            .iter()
+            .enumerate()
+            .map(|(i, &key)| (key, i))
+            .collect();
+
+        // Edges run from a component to every candidate reading one of its
+        // outputs, whatever else shares the net.
+        let succ: Vec<Vec<usize>> = candidates
+            .iter()
+            .map(|key| {
+                let mut next: Vec<usize> = circuit.components[key]
+                    .pins
+                    .outputs
+                    .iter()
+                    .flatten()
+                    .flat_map(|&net| &circuit.nets[net].sinks)
+                    .filter_map(|(sink, _)| candidate.get(sink).copied())
+                    .collect();
+                next.sort_unstable();
+                next.dedup();
+                next
+            })
+            .collect();
+
+        // Tarjan emits components sinks-first, so the singletons reversed are
+        // a topological order; anything on a loop is left out.
+        let mut order: Vec<usize> = strongly_connected(&succ)
+            .into_iter()
+            .filter(|scc| scc.len() == 1 && !succ[scc[0]].contains(&scc[0]))
+            .map(|scc| scc[0])
+            .collect();
+        order.reverse();
+
+        let mut steps = Vec::with_capacity(order.len());
+        let mut operands = Vec::new();
+        let mut step_of = HashMap::with_capacity(order.len());
+        let dense = |net: &Option<NetKey>| net.map_or(UNWIRED, |net| index[net]);
+        for i in order {
+            let key = candidates[i];
+            let pins = &circuit.components[&key].pins;
+            step_of.insert(key, steps.len());
+            steps.push(Step {
+                comp: key,
+                at: operands.len(),
+                n_in: pins.inputs.len(),
+                n_out: pins.outputs.len(),
+            });
+            operands.extend(pins.inputs.iter().map(dense));
+            operands.extend(pins.outputs.iter().map(dense));
+        }
+
+        let mut readers_of: Vec<Vec<usize>> = vec![Vec::new(); nets.len()];
+        for (i, step) in steps.iter().enumerate() {
+            for &net in &operands[step.at..step.at + step.n_in] {
+                if net != UNWIRED && readers_of[net as usize].last() != Some(&i) {
+                    readers_of[net as usize].push(i);
+                }
+            }
+        }
+        let mut reader_start = Vec::with_capacity(nets.len() + 1);
+        let mut readers = Vec::new();
+        for r in readers_of {
+            reader_start.push(readers.len());
+            readers.extend(r);
+        }
+        reader_start.push(readers.len());
+
+        let tunnelled: HashSet<NetKey> = circuit.tunnels.values().filter_map(|t| t.net).collect();
+        let direct = nets
+            .iter()
+            .map(|&key| {
+                let net = &circuit.nets[key];
+                let passive = |comp: &CompKey| {
+                    matches!(&circuit.components[comp].logic, Logic::Comb(c) if c.n_outputs() == 0)
+                };
+                matches!(net.sources.as_slice(), [(comp, _)] if step_of.contains_key(comp))
+                    && net
+                        .sinks
+                        .iter()
+                        .all(|(comp, _)| step_of.contains_key(comp) || passive(comp))
+                    && !tunnelled.contains(&key)
+                    && !circuit.net_width_conflict(key)
+            })
+            .collect();
+
+        let n = steps.len();
+        Self {
+            steps,
+            operands,
+            step_of,
+            nets,
+            direct,
+            reader_start,
+            readers,
+            pending: vec![false; n],
+            first_pending: n,
+            inputs: Vec::new(),
+            requeue: Vec::new(),
+        }
+    }
+
+    // Takes a sink the event loop would evaluate and queues it for the next
+    // sweep instead. False if `comp` isn't a step.
+    pub(crate) fn defer(&mut self, comp: CompKey) -> bool {
+        let Some(&i) = self.step_of.get(&comp) else {
+            return false;
+        };
+        self.pending[i] = true;
+        self.first_pending = self.first_pending.min(i);
+        true
+    }
+
+    // Evaluates every pending step in order. A changed direct net wakes its
+    // readers further down; any other changed net goes back on the circuit's
+    // dirty queue for the event loop.
+    pub(crate) fn run(&mut self, circuit: &mut Circuit) {
+        puffin::profile_function!();
+        for i in self.first_pending..self.steps.len() {
+            if !std::mem::take(&mut self.pending[i]) {
+                continue;
+            }
+            let step = &self.steps[i];
+            let operands = &self.operands[step.at..step.at + step.n_in + step.n_out];
+            let (ins, outs) = operands.split_at(step.n_in);
+
+            self.inputs.clear();
+            self.inputs.extend(ins.iter().map(|&net| match net {
+                UNWIRED => Value::Floating,
+                net => circuit.nets[self.nets[net as usize]].value,
+            }));
+            let comp = circuit.components.get_mut(&step.comp).unwrap();
+            let Logic::Comb(logic) = &comp.logic else {
+                unreachable!("only combinational components compile to steps");
+            };
+            let values = logic.evaluate(&self.inputs);
+
+            for (pin, value) in values.into_iter().enumerate() {
+                let slot = &mut comp.pins.out_cache[pin];
+                if *slot == value {
+                    continue;
+                }
+                *slot = value;
+                let net = outs[pin];
+                if net == UNWIRED {
+                    continue;
+                }
+                let key = self.nets[net as usize];
+                if !self.direct[net as usize] {
+                    self.requeue.push(key);
+                    continue;
+                }
+                let net = net as usize;
+                if circuit.nets[key].value != value {
+                    circuit.nets[key].value = value;
+                    for &r in &self.readers[self.reader_start[net]..self.reader_start[net + 1]] {
+                        self.pending[r] = true;
+                    }
+                }
+            }
+            for net in self.requeue.drain(..) {
+                circuit.mark_dirty(net);
+            }
+        }
+        self.first_pending = self.steps.len();
+    }
+}
+
+// The strongly connected components of a graph given as successor lists, in
+// Tarjan's order: every component comes after all the ones it reaches.
+// Iterative, so a chain thousands of nodes long doesn't overflow the stack.
+pub(crate) fn strongly_connected(succ: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    const UNSEEN: usize = usize::MAX;
+    let n = succ.len();
+    let mut index = vec![UNSEEN; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut sccs = Vec::new();
+    let mut next = 0;
+    // (node, position in its successor list) for each frame of the DFS.
+    let mut frames: Vec<(usize, usize)> = Vec::new();
+
+    for root in 0..n {
+        if index[root] != UNSEEN {
+            continue;
+        }
+        frames.push((root, 0));
+        while let Some(&(v, i)) = frames.last() {
+            if index[v] == UNSEEN {
+                index[v] = next;
+                low[v] = next;
+                next += 1;
+                stack.push(v);
+                on_stack[v] = true;
+            }
+            if let Some(&w) = succ[v].get(i) {
+                frames.last_mut().unwrap().1 += 1;
+                if index[w] == UNSEEN {
+                    frames.push((w, 0));
+                } else if on_stack[w] {
+                    low[v] = low[v].min(index[w]);
+                }
+                continue;
+            }
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if low[v] == index[v] {
+                let mut scc = Vec::new();
+                loop {
+                    let w = stack.pop().unwrap();
+                    on_stack[w] = false;
+                    scc.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                sccs.push(scc);
+            }
+        }
+    }
+    sccs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::component::{Component, GateOp, PinId};
+
+    #[test]
+    fn test_strongly_connected_puts_components_after_what_they_reach() {
+        // 0 -> 1 <-> 2 -> 3, and 3 loops on itself.
+        let succ = vec![vec![1], vec![2], vec![1, 3], vec![3]];
+        let sccs: Vec<Vec<usize>> = strongly_connected(&succ)
+            .into_iter()
+            .map(|mut scc| {
+                scc.sort();
+                scc
+            })
+            .collect();
+        assert_eq!(sccs, vec![vec![3], vec![1, 2], vec![0]]);
+    }
+
+    // A few hundred random gates over eight inputs, settled once through the
+    // program and once on the plain event loop (timed mode with every delay
+    // zero); every output must agree after each input change.
+    #[test]
+    fn test_random_gate_network_matches_the_event_loop() {
+        let build = |timed: bool| {
+            let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+            let mut next = move |n: usize| {
+                seed = seed
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                (seed >> 33) as usize % n
+            };
+            let mut c = Circuit::new();
+            let inputs: Vec<CompKey> = (0..8)
+                .map(|_| c.add_component(Component::input(0, 1)))
+                .collect();
+            let mut drivers = inputs.clone();
+            let ops = [
+                GateOp::And,
+                GateOp::Or,
+                GateOp::Xor,
+                GateOp::Nand,
+                GateOp::Not,
+            ];
+            for _ in 0..300 {
+                let op = ops[next(ops.len())];
+                let n = if op == GateOp::Not { 1 } else { 2 };
+                let g = c.add_component(Component::gate(op, n, 1).with_delay(0));
+                for i in 0..n {
+                    let from = drivers[next(drivers.len())];
+                    c.link(from, PinId::output(0), g, PinId::input(i as u8));
+                }
+                drivers.push(g);
+            }
+            let outputs: Vec<CompKey> = drivers[drivers.len() - 32..]
+                .iter()
+                .map(|&g| {
+                    let o = c.add_component(Component::output());
+                    c.link(g, PinId::output(0), o, PinId::input(0));
+                    o
+                })
+                .collect();
+            c.set_timed(timed).unwrap();
+            c.settle().unwrap();
+            (c, inputs, outputs)
+        };
+        let (mut fast, inputs, outputs) = build(false);
+        let (mut slow, _, _) = build(true);
+        assert_eq!(fast.program.as_ref().unwrap().steps.len(), 300);
+        assert!(slow.program.is_none());
+
+        for round in 0..40u64 {
+            let bits = round.wrapping_mul(0x9e37_79b9) >> 3;
+            for (i, &input) in inputs.iter().enumerate() {
+                fast.set_input(input, (bits >> i) & 1, 1);
+                slow.set_input(input, (bits >> i) & 1, 1);
+            }
+            fast.settle().unwrap();
+            slow.settle().unwrap();
+            for &o in &outputs {
+                assert_eq!(fast.read_output(o), slow.read_output(o), "round {round}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_gate_loop_stays_on_the_event_loop() {
+        // An SR latch of two cross-coupled NORs, Q inverted into an Output.
+        let mut c = Circuit::new();
+        let s = c.add_component(Component::input(1, 1));
+        let r = c.add_component(Component::input(0, 1));
+        let q = c.add_component(Component::gate(GateOp::Nor, 2, 1));
+        let q_n = c.add_component(Component::gate(GateOp::Nor, 2, 1));
+        let not = c.add_component(Component::gate(GateOp::Not, 1, 1));
+        let o = c.add_component(Component::output());
+        c.link(r, PinId::output(0), q, PinId::input(0));
+        c.link(q_n, PinId::output(0), q, PinId::input(1));
+        c.link(s, PinId::output(0), q_n, PinId::input(0));
+        c.link(q, PinId::output(0), q_n, PinId::input(1));
+        c.link(q, PinId::output(0), not, PinId::input(0));
+        c.link(not, PinId::output(0), o, PinId::input(0));
+
+        c.settle().unwrap();
+        let program = c.program.as_ref().unwrap();
+        assert_eq!(program.steps.len(), 1);
+        assert!(program.step_of.contains_key(&not));
+        // Gates pass Floating through, so the latch never leaves it - the
+        // same fixed point the event loop alone reaches.
+        assert_eq!(c.read_output(o), Value::Floating);
+    }
+}
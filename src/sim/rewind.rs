@@ -0,0 +1,434 @@
+// Reverse stepping through a clocked run: a Rewind keeps one frame per tick
+// and can put the circuit back as it stood after any of them, which is what
+// the GUI's Step Back and the signal viewer's scrubber drive.
+//
+// A frame holds each component's cached outputs and its state - an Input's
+// bits, a sequential part's latch and clock-pin level, a subcircuit's whole
+// inner frame - plus the timed mode's pending events. RAM contents are too
+// big to copy every tick, so a frame carries only the words its tick wrote,
+// old and new, and seeking replays those one way or the other. Net values are
+// left out: a frame is a settled moment, and they follow from the outputs
+// (see Circuit::refresh_nets).
+//
+// Frames go by CompKey. A Paused Input poke replaces its component under a
+// new key, so the caller passes that on to rekey() before amend().
+
+use crate::sim::circuit::Circuit;
+use crate::sim::component::{CompKey, Logic, LogicComb, LogicSeq, Ram, SeqState};
+use crate::sim::timeline::Timeline;
+use crate::sim::value::Value;
+
+use std::collections::{HashMap, VecDeque};
+use std::ops::RangeInclusive;
+
+// Frames kept; past this the oldest go first.
+pub const MAX_FRAMES: usize = 10_000;
+
+#[derive(Debug)]
+struct Snapshot {
+    parts: HashMap<CompKey, Part>,
+    timeline: Option<Timeline>,
+}
+
+#[derive(Debug)]
+struct Part {
+    outputs: Vec<Value>,
+    state: PartState,
+}
+
+#[derive(Debug)]
+enum PartState {
+    Stateless,
+    Input(u64),
+    Seq {
+        state: SeqState,
+        clock: Option<Value>,
+    },
+    Sub(Box<Snapshot>),
+}
+
+// One word a tick wrote to a RAM, found by the keys leading down to it
+// through any subcircuits.
+#[derive(Debug)]
+struct RamWrite {
+    path: Vec<CompKey>,
+    index: usize,
+    old: u64,
+    new: u64,
+}
+
+#[derive(Debug)]
+struct Frame {
+    snapshot: Snapshot,
+    // The RAM writes that led here from the frame before.
+    writes: Vec<RamWrite>,
+}
+
+#[derive(Debug, Default)]
+pub struct Rewind {
+    frames: VecDeque<Frame>,
+    // The tick of frames[0].
+    first: usize,
+    // The frame the circuit was last put at.
+    cursor: usize,
+    // Every RAM's contents as of that frame, for the next tick to diff against.
+    rams: HashMap<Vec<CompKey>, Vec<u64>>,
+}
+
+impl Rewind {
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    // The ticks there are frames for, None before start().
+    pub fn ticks(&self) -> Option<RangeInclusive<usize>> {
+        let last = self.frames.len().checked_sub(1)?;
+        Some(self.first..=self.first + last)
+    }
+
+    // The tick the circuit stands at.
+    pub fn tick(&self) -> usize {
+        self.first + self.cursor
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    // Starts over with `circuit` as it stands at `tick`.
+    pub fn start(&mut self, circuit: &Circuit, tick: usize) {
+        self.clear();
+        self.first = tick;
+        self.rams = rams(circuit)
+            .into_iter()
+            .map(|(path, ram)| (path, ram.data.borrow().clone()))
+            .collect();
+        self.frames.push_back(Frame {
+            snapshot: Snapshot::take(circuit),
+            writes: Vec::new(),
+        });
+    }
+
+    // Adds the frame one tick on from the current one. Any frames past the
+    // current one - a future stepped back from - go, as this tick replaces
+    // them.
+    pub fn record(&mut self, circuit: &Circuit) {
+        if self.frames.is_empty() {
+            return;
+        }
+        self.frames.truncate(self.cursor + 1);
+        let writes = self.diff_rams(circuit);
+        self.frames.push_back(Frame {
+            snapshot: Snapshot::take(circuit),
+            writes,
+        });
+        if self.frames.len() > MAX_FRAMES {
+            self.frames.pop_front();
+            self.first += 1;
+        }
+        self.cursor = self.frames.len() - 1;
+    }
+
+    // Folds a poke between ticks - an Input's bits, a RAM word - into the
+    // current frame, so seeking away and back keeps it. Frames past the
+    // current one go, as with record().
+    pub fn amend(&mut self, circuit: &Circuit) {
+        if self.frames.is_empty() {
+            return;
+        }
+        self.frames.truncate(self.cursor + 1);
+        let writes = self.diff_rams(circuit);
+        let frame = &mut self.frames[self.cursor];
+        frame.snapshot = Snapshot::take(circuit);
+        frame.writes.extend(writes);
+    }
+
+    // Follows a top-level component replaced under a new key.
+    pub fn rekey(&mut self, old: CompKey, new: CompKey) {
+        let rekey_path = |path: &mut Vec<CompKey>| {
+            if path.first() == Some(&old) {
+                path[0] = new;
+            }
+        };
+        for frame in &mut self.frames {
+            if let Some(part) = frame.snapshot.parts.remove(&old) {
+                frame.snapshot.parts.insert(new, part);
+            }
+            for write in &mut frame.writes {
+                rekey_path(&mut write.path);
+            }
+        }
+        self.rams = std::mem::take(&mut self.rams)
+            .into_iter()
+            .map(|(mut path, data)| {
+                rekey_path(&mut path);
+                (path, data)
+            })
+            .collect();
+    }
+
+    // Puts `circuit` back as it stood at `tick`, clamped to the recorded
+    // ticks. Nothing is dropped, so it can seek forward again.
+    pub fn seek(&mut self, circuit: &mut Circuit, tick: usize) {
+        let Some(ticks) = self.ticks() else {
+            return;
+        };
+        let target = tick.clamp(*ticks.start(), *ticks.end()) - self.first;
+        while self.cursor > target {
+            for write in self.frames[self.cursor].writes.iter().rev() {
+                put_word(circuit, &mut self.rams, &write.path, write.index, write.old);
+            }
+            self.cursor -= 1;
+        }
+        while self.cursor < target {
+            self.cursor += 1;
+            for write in &self.frames[self.cursor].writes {
+                put_word(circuit, &mut self.rams, &write.path, write.index, write.new);
+            }
+        }
+        self.frames[self.cursor].snapshot.restore(circuit);
+    }
+
+    // The words every RAM changed since the copy in `rams`, which catches up.
+    fn diff_rams(&mut self, circuit: &Circuit) -> Vec<RamWrite> {
+        let mut writes = Vec::new();
+        for (path, ram) in rams(circuit) {
+            let data = ram.data.borrow();
+            let copy = self
+                .rams
+                .entry(path.clone())
+                .or_insert_with(|| vec![0; data.len()]);
+            for (index, (old, &new)) in copy.iter_mut().zip(data.iter()).enumerate() {
+                if *old != new {
+                    writes.push(RamWrite {
+                        path: path.clone(),
+                        index,
+                        old: *old,
+                        new,
+                    });
+                    *old = new;
+                }
+            }
+        }
+        writes
+    }
+}
+
+impl Snapshot {
+    fn take(circuit: &Circuit) -> Self {
+        let parts = circuit
+            .components
+            .iter()
+            .map(|(&key, comp)| {
+                let state = match &comp.logic {
+                    Logic::Comb(LogicComb::Input(input)) => PartState::Input(input.bits),
+                    Logic::Comb(_) => PartState::Stateless,
+                    Logic::Seq(seq) => PartState::Seq {
+                        state: seq.snapshot(),
+                        clock: comp.clock_level(),
+                    },
+                    Logic::Sub(sub) => PartState::Sub(Box::new(Snapshot::take(&sub.inner))),
+                };
+                let outputs = comp.pins.out_cache.clone();
+                (key, Part { outputs, state })
+            })
+            .collect();
+        Self {
+            parts,
+            timeline: circuit.timeline.clone(),
+        }
+    }
+
+    // A component added since the snapshot keeps what it has; one whose
+    // outputs no longer line up keeps its outputs.
+    fn restore(&self, circuit: &mut Circuit) {
+        for (key, part) in &self.parts {
+            let Some(comp) = circuit.components.get_mut(key) else {
+                continue;
+            };
+            if comp.pins.out_cache.len() == part.outputs.len() {
+                comp.pins.out_cache.clone_from(&part.outputs);
+            }
+            match &part.state {
+                PartState::Stateless => {}
+                PartState::Input(bits) => {
+                    if let Logic::Comb(LogicComb::Input(input)) = &mut comp.logic {
+                        input.bits = *bits;
+                    }
+                }
+                PartState::Seq { state, clock } => comp.restore(state, *clock),
+                PartState::Sub(inner) => {
+                    if let Logic::Sub(sub) = &mut comp.logic {
+                        inner.restore(&mut sub.inner);
+                    }
+                }
+            }
+        }
+        if circuit.is_timed() == self.timeline.is_some() {
+            circuit.timeline.clone_from(&self.timeline);
+        }
+        circuit.refresh_nets();
+    }
+}
+
+// Every RAM in `circuit` and its subcircuits, by path.
+fn rams(circuit: &Circuit) -> Vec<(Vec<CompKey>, &Ram)> {
+    fn visit<'a>(
+        circuit: &'a Circuit,
+        path: &mut Vec<CompKey>,
+        found: &mut Vec<(Vec<CompKey>, &'a Ram)>,
+    ) {
+        for (&key, comp) in &circuit.components {
+            path.push(key);
+            match &comp.logic {
+                Logic::Seq(LogicSeq::Ram(ram)) => found.push((path.clone(), ram.contents())),
+                Logic::Sub(sub) => visit(&sub.inner, path, found),
+                _ => {}
+            }
+            path.pop();
+        }
+    }
+    let mut found = Vec::new();
+    visit(circuit, &mut Vec::new(), &mut found);
+    found
+}
+
+fn ram_at<'a>(circuit: &'a Circuit, path: &[CompKey]) -> Option<&'a Ram> {
+    let (key, rest) = path.split_first()?;
+    match &circuit.components.get(key)?.logic {
+        Logic::Seq(LogicSeq::Ram(ram)) if rest.is_empty() => Some(ram.contents()),
+        Logic::Sub(sub) => ram_at(&sub.inner, rest),
+        _ => None,
+    }
+}
+
+fn put_word(
+    circuit: &Circuit,
+    rams: &mut HashMap<Vec<CompKey>, Vec<u64>>,
+    path: &[CompKey],
+    index: usize,
+    value: u64,
+) {
+    if let Some(ram) = ram_at(circuit, path) {
+        ram.set_word(index, value);
+    }
+    if let Some(word) = rams.get_mut(path).and_then(|copy| copy.get_mut(index)) {
+        *word = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::component::{ClockEdge, Component, OverflowAction, PinId, ReadBehavior};
+
+    // A 4-bit counter counting up every tick, and the Output reading it.
+    fn counting() -> (Circuit, CompKey) {
+        let mut c = Circuit::new();
+        let load = c.add_component(Component::input(0, 1));
+        let count = c.add_component(Component::input(1, 1));
+        let counter = c.add_component(Component::counter(
+            4,
+            15,
+            OverflowAction::Wrap,
+            ClockEdge::Rising,
+        ));
+        let out = c.add_component(Component::output());
+        c.link(load, PinId::output(0), counter, PinId::input(1));
+        c.link(count, PinId::output(0), counter, PinId::input(2));
+        c.link(counter, PinId::output(0), out, PinId::input(0));
+        c.settle().unwrap();
+        (c, out)
+    }
+
+    #[test]
+    fn test_seek_steps_back_and_forward() {
+        let (mut c, out) = counting();
+        let mut rewind = Rewind::default();
+        rewind.start(&c, 0);
+        for _ in 0..5 {
+            c.tick_clock().unwrap();
+            rewind.record(&c);
+        }
+        assert_eq!(rewind.ticks(), Some(0..=5));
+
+        rewind.seek(&mut c, 2);
+        assert_eq!(c.read_output(out), Value::new(2, 4));
+        rewind.seek(&mut c, 5);
+        assert_eq!(c.read_output(out), Value::new(5, 4));
+
+        // Ticking on from a rewound frame runs from its state and drops the
+        // frames after it.
+        rewind.seek(&mut c, 1);
+        c.tick_clock().unwrap();
+        rewind.record(&c);
+        assert_eq!(c.read_output(out), Value::new(2, 4));
+        assert_eq!(rewind.ticks(), Some(0..=2));
+        assert_eq!(rewind.tick(), 2);
+    }
+
+    #[test]
+    fn test_seek_reverts_ram_writes() {
+        let mut c = Circuit::new();
+        let addr = c.add_component(Component::input(3, 2));
+        let data = c.add_component(Component::input(7, 4));
+        let ram = c.add_component(Component::ram(Ram::new(4, 2, ReadBehavior::ReadAfterWrite)));
+        let out = c.add_component(Component::output());
+        c.link(
+            addr,
+            PinId::output(0),
+            ram,
+            PinId::input(Ram::ADDR_PIN as u8),
+        );
+        c.link(
+            data,
+            PinId::output(0),
+            ram,
+            PinId::input(Ram::DATA_IN_PIN as u8),
+        );
+        c.link(
+            ram,
+            PinId::output(Ram::DATA_OUT_PIN as u8),
+            out,
+            PinId::input(0),
+        );
+        c.settle().unwrap();
+        let word = |c: &Circuit| ram_at(c, &[ram]).unwrap().word(3);
+
+        let mut rewind = Rewind::default();
+        rewind.start(&c, 0);
+        c.tick_clock().unwrap();
+        rewind.record(&c);
+        assert_eq!(word(&c), 7);
+        assert_eq!(c.read_output(out), Value::new(7, 4));
+
+        rewind.seek(&mut c, 0);
+        assert_eq!(word(&c), 0);
+        assert_eq!(c.read_output(out), Value::new(0, 4));
+        rewind.seek(&mut c, 1);
+        assert_eq!(word(&c), 7);
+        assert_eq!(c.read_output(out), Value::new(7, 4));
+    }
+
+    #[test]
+    fn test_amend_keeps_a_poke_between_ticks() {
+        let mut c = Circuit::new();
+        let input = c.add_component(Component::input(5, 4));
+        let out = c.add_component(Component::output());
+        c.link(input, PinId::output(0), out, PinId::input(0));
+        c.settle().unwrap();
+
+        let mut rewind = Rewind::default();
+        rewind.start(&c, 0);
+        c.tick_clock().unwrap();
+        rewind.record(&c);
+        c.set_input(input, 9, 4);
+        c.settle().unwrap();
+        rewind.amend(&c);
+
+        rewind.seek(&mut c, 0);
+        assert_eq!(c.read_output(out), Value::new(5, 4));
+        rewind.seek(&mut c, 1);
+        assert_eq!(c.read_output(out), Value::new(9, 4));
+    }
+}
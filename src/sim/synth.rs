@@ -0,0 +1,763 @@
+// Synthesis, the reverse of truth_table.rs: a behaviour typed as Boolean
+// expressions (`Y = A & !B | C`, one Output per line) or as a truth table is
+// turned into a gate netlist. Every Output bit is minimized to a sum of
+// products (see minimize.rs) and realized as two levels of logic in one of
+// three styles: AND/OR with NOT gates, NAND only, or NOR only (a product of
+// sums, from the minimized 0s).
+//
+// Everything is 1 bit wide. Variables keep the order they first appear in, the
+// first being the most significant bit of a minterm, as in an Analyze table.
+// Gates never take more than MAX_FAN_IN inputs; wider ones become a tree.
+
+use std::collections::HashMap;
+
+use crate::sim::component::GateOp;
+use crate::sim::minimize::{self, BitFunction, Implicant, MAX_MINIMIZE_VARS};
+
+// The widest gate the properties panel offers.
+pub const MAX_FAN_IN: usize = 8;
+
+// Past this a generated schematic is too big to be of use.
+pub const MAX_GATES: usize = 400;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SynthError {
+    // 1-based; 0 when the error is about the text as a whole.
+    pub line: usize,
+    pub msg: String,
+}
+
+impl SynthError {
+    fn whole(msg: String) -> Self {
+        Self { line: 0, msg }
+    }
+}
+
+impl std::fmt::Display for SynthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.msg)
+        } else {
+            write!(f, "line {}: {}", self.line, self.msg)
+        }
+    }
+}
+
+impl std::error::Error for SynthError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    #[default]
+    AndOrNot,
+    NandOnly,
+    NorOnly,
+}
+
+impl Style {
+    pub const ALL: [Style; 3] = [Style::AndOrNot, Style::NandOnly, Style::NorOnly];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Style::AndOrNot => "AND/OR/NOT",
+            Style::NandOnly => "NAND only",
+            Style::NorOnly => "NOR only",
+        }
+    }
+}
+
+// What to build: named 1-bit inputs, and each output as a function of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spec {
+    pub inputs: Vec<String>,
+    pub outputs: Vec<BitFunction>,
+}
+
+impl Spec {
+    // One `Name = expression` per line; blank lines and `#` comments are
+    // skipped. Operators, loosest first: `|` or `+`, `^`, `&` `*` or `·`, then
+    // prefix `!` or `~` and postfix `'` for NOT. `0` and `1` are constants. An
+    // expression may use an Output defined on an earlier line.
+    pub fn from_expressions(text: &str) -> Result<Self, SynthError> {
+        let mut inputs: Vec<String> = Vec::new();
+        let mut outputs: Vec<(String, Expr)> = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let err = |msg: String| SynthError { line: line_no, msg };
+            let (name, body) = line
+                .split_once('=')
+                .ok_or_else(|| err("expected `Name = expression`".to_string()))?;
+            let name = name.trim();
+            if !is_identifier(name) {
+                return Err(err(format!("{name:?} is not a valid name")));
+            }
+            if inputs.iter().any(|n| n == name) {
+                return Err(err(format!("{name} is already used as an input")));
+            }
+            if outputs.iter().any(|(n, _)| n == name) {
+                return Err(err(format!("{name} is defined twice")));
+            }
+            let tokens = tokenize(body).map_err(err)?;
+            let mut parser = Parser {
+                tokens: &tokens,
+                pos: 0,
+                inputs: &mut inputs,
+                outputs: &outputs,
+            };
+            let expr = parser.parse().map_err(err)?;
+            outputs.push((name.to_string(), expr));
+        }
+        if outputs.is_empty() {
+            return Err(SynthError::whole("no expressions".to_string()));
+        }
+        check_inputs(inputs.len())?;
+
+        let n = inputs.len();
+        let exprs: Vec<&Expr> = outputs.iter().map(|(_, e)| e).collect();
+        let outputs = outputs
+            .iter()
+            .map(|(name, expr)| BitFunction {
+                name: name.clone(),
+                ones: (0..1u32 << n)
+                    .filter(|&m| expr.eval(n, m, &exprs))
+                    .collect(),
+                dont_cares: Vec::new(),
+            })
+            .collect();
+        Ok(Self { inputs, outputs })
+    }
+
+    // A header of input names, `|`, then output names; then one row per line
+    // in the same layout, each cell 0, 1 or `-`. A `-` input stands for both
+    // values, and a `-` output is a don't-care. Combinations no row lists
+    // are 0. Cells need no spaces between them: `01-|1` is a row.
+    pub fn from_table(text: &str) -> Result<Self, SynthError> {
+        let mut lines = text.lines().enumerate().filter_map(|(i, line)| {
+            let line = line.split('#').next().unwrap_or("").trim();
+            (!line.is_empty()).then_some((i + 1, line))
+        });
+        let (header_line, header) = lines
+            .next()
+            .ok_or_else(|| SynthError::whole("no header row".to_string()))?;
+        let err = |line: usize, msg: String| SynthError { line, msg };
+        let (ins, outs) = header
+            .split_once('|')
+            .ok_or_else(|| err(header_line, "expected `inputs | outputs`".to_string()))?;
+        let names = |side: &str| -> Vec<String> {
+            side.split([' ', '\t', ','])
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        let (inputs, output_names) = (names(ins), names(outs));
+        if output_names.is_empty() {
+            return Err(err(header_line, "no outputs".to_string()));
+        }
+        for (k, name) in inputs.iter().chain(&output_names).enumerate() {
+            if !is_identifier(name) {
+                return Err(err(header_line, format!("{name:?} is not a valid name")));
+            }
+            if inputs
+                .iter()
+                .chain(&output_names)
+                .take(k)
+                .any(|n| n == name)
+            {
+                return Err(err(header_line, format!("{name} appears twice")));
+            }
+        }
+        check_inputs(inputs.len())?;
+
+        let n = inputs.len();
+        // Per output and minterm: None unlisted, Some(None) don't-care.
+        let mut cells: Vec<Vec<Option<Option<bool>>>> =
+            vec![vec![None; 1 << n]; output_names.len()];
+        for (line, row) in lines {
+            let (ins, outs) = row
+                .split_once('|')
+                .ok_or_else(|| err(line, "expected `inputs | outputs`".to_string()))?;
+            let ins = row_cells(ins, n).map_err(|msg| err(line, msg))?;
+            let outs = row_cells(outs, output_names.len()).map_err(|msg| err(line, msg))?;
+            let dashes = ins
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| c.is_none())
+                .fold(0u32, |acc, (i, _)| acc | 1 << (n - 1 - i));
+            let value = ins
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| **c == Some(true))
+                .fold(0u32, |acc, (i, _)| acc | 1 << (n - 1 - i));
+            let covered = Implicant { value, dashes };
+            for m in (0..1u32 << n).filter(|&m| covered.covers(m)) {
+                for (column, &out) in cells.iter_mut().zip(&outs) {
+                    let cell = &mut column[m as usize];
+                    match (*cell, out) {
+                        (Some(Some(was)), Some(now)) if was != now => {
+                            return Err(err(
+                                line,
+                                format!("contradicts an earlier row at {m:0n$b}"),
+                            ));
+                        }
+                        // A listed value stands; a don't-care never overrides.
+                        (Some(Some(_)), _) | (Some(None), None) => {}
+                        _ => *cell = Some(out),
+                    }
+                }
+            }
+        }
+
+        let outputs = output_names
+            .into_iter()
+            .zip(cells)
+            .map(|(name, column)| {
+                let with = |want: Option<bool>| {
+                    (0..1u32 << n)
+                        .filter(|&m| column[m as usize] == Some(want))
+                        .collect()
+                };
+                BitFunction {
+                    name,
+                    ones: with(Some(true)),
+                    dont_cares: with(None),
+                }
+            })
+            .collect();
+        Ok(Self { inputs, outputs })
+    }
+}
+
+fn check_inputs(n: usize) -> Result<(), SynthError> {
+    if n > MAX_MINIMIZE_VARS {
+        return Err(SynthError::whole(format!(
+            "{n} inputs; at most {MAX_MINIMIZE_VARS} can be synthesized"
+        )));
+    }
+    Ok(())
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// One side of a table row: exactly `n` cells of 0, 1 or -, in any spacing.
+// `-` reads as None.
+fn row_cells(side: &str, n: usize) -> Result<Vec<Option<bool>>, String> {
+    let cells: Vec<char> = side
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ',')
+        .collect();
+    if cells.len() != n {
+        return Err(format!("expected {n} cells, got {}", cells.len()));
+    }
+    cells
+        .into_iter()
+        .map(|c| match c {
+            '0' => Ok(Some(false)),
+            '1' => Ok(Some(true)),
+            '-' | 'x' | 'X' => Ok(None),
+            _ => Err(format!("bad cell {c:?}")),
+        })
+        .collect()
+}
+
+// ── Expressions ───────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Const(bool),
+    Not,
+    Prime,
+    And,
+    Or,
+    Xor,
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '!' | '~' => Token::Not,
+            '\'' => Token::Prime,
+            '&' | '*' | '·' => Token::And,
+            '|' | '+' => Token::Or,
+            '^' => Token::Xor,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '0' => Token::Const(false),
+            '1' => Token::Const(true),
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut name = c.to_string();
+                while let Some(&c) = chars
+                    .peek()
+                    .filter(|c| c.is_ascii_alphanumeric() || **c == '_')
+                {
+                    name.push(c);
+                    chars.next();
+                }
+                Token::Ident(name)
+            }
+            _ => return Err(format!("unexpected {c:?}")),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Const(bool),
+    Input(usize),
+    // An Output defined on an earlier line.
+    Output(usize),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Xor(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    // The value at minterm `m` of `n` inputs; `outputs` are every line's.
+    fn eval(&self, n: usize, m: u32, outputs: &[&Expr]) -> bool {
+        match self {
+            Expr::Const(b) => *b,
+            Expr::Input(i) => m >> (n - 1 - i) & 1 == 1,
+            Expr::Output(j) => outputs[*j].eval(n, m, outputs),
+            Expr::Not(a) => !a.eval(n, m, outputs),
+            Expr::And(a, b) => a.eval(n, m, outputs) && b.eval(n, m, outputs),
+            Expr::Or(a, b) => a.eval(n, m, outputs) || b.eval(n, m, outputs),
+            Expr::Xor(a, b) => a.eval(n, m, outputs) != b.eval(n, m, outputs),
+        }
+    }
+}
+
+// Recursive descent over one line's tokens; names that aren't earlier
+// Outputs become inputs as they're met.
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    inputs: &'a mut Vec<String>,
+    outputs: &'a [(String, Expr)],
+}
+
+impl Parser<'_> {
+    fn parse(&mut self) -> Result<Expr, String> {
+        let expr = self.or()?;
+        match self.tokens.get(self.pos) {
+            None => Ok(expr),
+            Some(t) => Err(format!("unexpected {}", describe(t))),
+        }
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.tokens.get(self.pos) == Some(token);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.xor()?;
+        while self.eat(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.xor()?));
+        }
+        Ok(expr)
+    }
+
+    fn xor(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while self.eat(&Token::Xor) {
+            expr = Expr::Xor(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.unary()?;
+        while self.eat(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        let mut expr = self.primary()?;
+        while self.eat(&Token::Prime) {
+            expr = Expr::Not(Box::new(expr));
+        }
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let Some(token) = self.tokens.get(self.pos) else {
+            return Err("unexpected end of expression".to_string());
+        };
+        self.pos += 1;
+        match token {
+            Token::Const(b) => Ok(Expr::Const(*b)),
+            Token::Ident(name) => {
+                if let Some(j) = self.outputs.iter().position(|(n, _)| n == name) {
+                    return Ok(Expr::Output(j));
+                }
+                let i = match self.inputs.iter().position(|n| n == name) {
+                    Some(i) => i,
+                    None => {
+                        self.inputs.push(name.clone());
+                        self.inputs.len() - 1
+                    }
+                };
+                Ok(Expr::Input(i))
+            }
+            Token::Open => {
+                let expr = self.or()?;
+                if !self.eat(&Token::Close) {
+                    return Err("missing `)`".to_string());
+                }
+                Ok(expr)
+            }
+            t => Err(format!("unexpected {}", describe(t))),
+        }
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Ident(name) => name.clone(),
+        Token::Const(b) => format!("`{}`", *b as u8),
+        Token::Not => "`!`".to_string(),
+        Token::Prime => "`'`".to_string(),
+        Token::And => "`&`".to_string(),
+        Token::Or => "`|`".to_string(),
+        Token::Xor => "`^`".to_string(),
+        Token::Open => "`(`".to_string(),
+        Token::Close => "`)`".to_string(),
+    }
+}
+
+// ── Netlists ──────────────────────────────────────────────────────────────────
+
+// A gate's input or an Output's driver. Ordered so a gate's inputs list the
+// spec's inputs first, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Signal {
+    Const(bool),
+    Input(usize),
+    Gate(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gate {
+    pub op: GateOp,
+    // 1 for Not, else 2..=MAX_FAN_IN; a NAND or NOR standing in for a NOT
+    // takes its one signal on both inputs.
+    pub inputs: Vec<Signal>,
+}
+
+// Gates only read inputs and earlier gates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Netlist {
+    pub inputs: Vec<String>,
+    pub gates: Vec<Gate>,
+    pub outputs: Vec<(String, Signal)>,
+}
+
+impl Netlist {
+    // Every output's value at minterm `m`.
+    pub fn eval(&self, m: u32) -> Vec<bool> {
+        let n = self.inputs.len();
+        let mut values: Vec<bool> = Vec::with_capacity(self.gates.len());
+        let read = |values: &[bool], s: Signal| match s {
+            Signal::Const(b) => b,
+            Signal::Input(i) => m >> (n - 1 - i) & 1 == 1,
+            Signal::Gate(g) => values[g],
+        };
+        for gate in &self.gates {
+            let mut ins = gate.inputs.iter().map(|&s| read(&values, s));
+            let v = match gate.op {
+                GateOp::And => ins.all(|b| b),
+                GateOp::Nand => !ins.all(|b| b),
+                GateOp::Or => ins.any(|b| b),
+                GateOp::Nor => !ins.any(|b| b),
+                GateOp::Xor => ins.fold(false, |a, b| a != b),
+                GateOp::Xnor => !ins.fold(false, |a, b| a != b),
+                GateOp::Not => !ins.all(|b| b),
+            };
+            values.push(v);
+        }
+        self.outputs
+            .iter()
+            .map(|&(_, s)| read(&values, s))
+            .collect()
+    }
+}
+
+// Minimizes every output of `spec` and builds it in `style`. Identical gates
+// are built once, so outputs share product terms and inverted inputs.
+pub fn synthesize(spec: &Spec, style: Style) -> Result<Netlist, SynthError> {
+    let n = spec.inputs.len();
+    let mut builder = Builder {
+        style,
+        gates: Vec::new(),
+        index: HashMap::new(),
+    };
+    let mut outputs = Vec::with_capacity(spec.outputs.len());
+    for f in &spec.outputs {
+        let zeros: Vec<u32> = (0..1u32 << n).filter(|m| f.at(*m) == Some(false)).collect();
+        let signal = if f.ones.is_empty() {
+            Signal::Const(false)
+        } else if zeros.is_empty() {
+            Signal::Const(true)
+        } else if style == Style::NorOnly {
+            // A product of sums: f = NOR over the 0s' terms of NOR over each
+            // term's literals, inverted.
+            let sums: Vec<Signal> = minimize::minimize(n, &zeros, &f.dont_cares)
+                .into_iter()
+                .map(|t| {
+                    let literals = builder.literals(n, t, false);
+                    builder.nor(literals)
+                })
+                .collect();
+            builder.nor(sums)
+        } else {
+            let terms = minimize::minimize(n, &f.ones, &f.dont_cares);
+            if style == Style::NandOnly {
+                let products: Vec<Signal> = terms
+                    .into_iter()
+                    .map(|t| {
+                        let literals = builder.literals(n, t, true);
+                        builder.nand(literals)
+                    })
+                    .collect();
+                builder.nand(products)
+            } else {
+                let products: Vec<Signal> = terms
+                    .into_iter()
+                    .map(|t| {
+                        let literals = builder.literals(n, t, true);
+                        builder.wide(GateOp::And, literals)
+                    })
+                    .collect();
+                builder.wide(GateOp::Or, products)
+            }
+        };
+        if builder.gates.len() > MAX_GATES {
+            return Err(SynthError::whole(format!(
+                "this needs more than {MAX_GATES} gates"
+            )));
+        }
+        outputs.push((f.name.clone(), signal));
+    }
+    Ok(Netlist {
+        inputs: spec.inputs.clone(),
+        gates: builder.gates,
+        outputs,
+    })
+}
+
+struct Builder {
+    style: Style,
+    gates: Vec<Gate>,
+    index: HashMap<(GateOp, Vec<Signal>), usize>,
+}
+
+impl Builder {
+    // Inputs are sorted first: every op here is commutative, and a gate met
+    // again with its inputs shuffled is still the same gate.
+    fn gate(&mut self, op: GateOp, mut inputs: Vec<Signal>) -> Signal {
+        inputs.sort();
+        let key = (op, inputs);
+        if let Some(&g) = self.index.get(&key) {
+            return Signal::Gate(g);
+        }
+        self.gates.push(Gate {
+            op,
+            inputs: key.1.clone(),
+        });
+        self.index.insert(key, self.gates.len() - 1);
+        Signal::Gate(self.gates.len() - 1)
+    }
+
+    // A NOT gate, or in the single-gate styles that gate with its inputs
+    // tied. Inverting an inverter gives back its input.
+    fn not(&mut self, s: Signal) -> Signal {
+        let op = match self.style {
+            Style::AndOrNot => GateOp::Not,
+            Style::NandOnly => GateOp::Nand,
+            Style::NorOnly => GateOp::Nor,
+        };
+        if let Signal::Gate(g) = s {
+            let gate = &self.gates[g];
+            if gate.op == op && gate.inputs.iter().all(|&i| i == gate.inputs[0]) {
+                return gate.inputs[0];
+            }
+        }
+        let inputs = if op == GateOp::Not {
+            vec![s]
+        } else {
+            vec![s, s]
+        };
+        self.gate(op, inputs)
+    }
+
+    // The literals of `term`, or with `positive` false each one inverted.
+    fn literals(&mut self, n: usize, term: Implicant, positive: bool) -> Vec<Signal> {
+        (0..n)
+            .filter(|i| term.dashes >> (n - 1 - i) & 1 == 0)
+            .map(|i| {
+                let one = term.value >> (n - 1 - i) & 1 == 1;
+                if one == positive {
+                    Signal::Input(i)
+                } else {
+                    self.not(Signal::Input(i))
+                }
+            })
+            .collect()
+    }
+
+    // AND or OR of any number of signals, one alone being itself.
+    fn wide(&mut self, op: GateOp, signals: Vec<Signal>) -> Signal {
+        match signals.len() {
+            1 => signals[0],
+            n if n <= MAX_FAN_IN => self.gate(op, signals),
+            _ => {
+                let parts = signals
+                    .chunks(MAX_FAN_IN)
+                    .map(|c| self.wide(op, c.to_vec()))
+                    .collect();
+                self.wide(op, parts)
+            }
+        }
+    }
+
+    // NAND of any number of signals; past MAX_FAN_IN, of inverted NANDs
+    // (ANDs) of each group.
+    fn nand(&mut self, signals: Vec<Signal>) -> Signal {
+        self.inverting(GateOp::Nand, signals)
+    }
+
+    fn nor(&mut self, signals: Vec<Signal>) -> Signal {
+        self.inverting(GateOp::Nor, signals)
+    }
+
+    fn inverting(&mut self, op: GateOp, signals: Vec<Signal>) -> Signal {
+        match signals.len() {
+            1 => self.not(signals[0]),
+            n if n <= MAX_FAN_IN => self.gate(op, signals),
+            _ => {
+                let parts = signals
+                    .chunks(MAX_FAN_IN)
+                    .map(|c| {
+                        let part = self.inverting(op, c.to_vec());
+                        self.not(part)
+                    })
+                    .collect();
+                self.inverting(op, parts)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every style computes every listed row of `spec`.
+    fn assert_realizes(spec: &Spec) {
+        let n = spec.inputs.len();
+        for style in Style::ALL {
+            let net = synthesize(spec, style).unwrap();
+            for m in 0..1u32 << n {
+                let got = net.eval(m);
+                for (f, &y) in spec.outputs.iter().zip(&got) {
+                    if let Some(want) = f.at(m) {
+                        assert_eq!(y, want, "{} at {m:b} in {style:?}", f.name);
+                    }
+                }
+            }
+            for gate in &net.gates {
+                assert!(gate.inputs.len() <= MAX_FAN_IN);
+                match style {
+                    Style::AndOrNot => {
+                        assert!(matches!(gate.op, GateOp::And | GateOp::Or | GateOp::Not))
+                    }
+                    Style::NandOnly => assert_eq!(gate.op, GateOp::Nand),
+                    Style::NorOnly => assert_eq!(gate.op, GateOp::Nor),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_expressions_parse_with_precedence() {
+        let spec = Spec::from_expressions("Y = A & !B | C\n# note\n\nZ = (A ^ B)' + Y·0").unwrap();
+        assert_eq!(spec.inputs, vec!["A", "B", "C"]);
+        assert_eq!(spec.outputs[0].name, "Y");
+        // A·B' + C over A B C.
+        assert_eq!(spec.outputs[0].ones, vec![1, 3, 4, 5, 7]);
+        // XNOR of A and B.
+        assert_eq!(spec.outputs[1].ones, vec![0, 1, 6, 7]);
+        assert_realizes(&spec);
+
+        let net = synthesize(&spec, Style::AndOrNot).unwrap();
+        // NOT B, A·B' and the OR; then for Z, NOT A, two products and the OR.
+        assert_eq!(net.gates.len(), 7);
+
+        let err = |text: &str| Spec::from_expressions(text).unwrap_err();
+        assert_eq!(err("Y = A &\n").msg, "unexpected end of expression");
+        assert_eq!(err("Y = A\nA = B").line, 2);
+        assert_eq!(err("Y = (A | B").msg, "missing `)`");
+        assert_eq!(err("Y = A B").msg, "unexpected B");
+        assert_eq!(err("Y = A $ B").msg, "unexpected '$'");
+    }
+
+    #[test]
+    fn test_table_rows_expand_dashes_and_dont_cares() {
+        let spec = Spec::from_table(
+            "a b c | y z\n\
+             1 - - | 1 0\n\
+             010   | 0 -\n\
+             0,1,1 | 1 1\n",
+        )
+        .unwrap();
+        assert_eq!(spec.outputs[0].ones, vec![3, 4, 5, 6, 7]);
+        assert_eq!(spec.outputs[1].ones, vec![3]);
+        assert_eq!(spec.outputs[1].dont_cares, vec![2]);
+        assert_realizes(&spec);
+
+        let err = Spec::from_table("a b | y\n1 - | 1\n1 1 | 0").unwrap_err();
+        assert_eq!(err.line, 3);
+        assert_eq!(Spec::from_table("a b | y\n0 1 1 | 1").unwrap_err().line, 2);
+        assert_eq!(
+            Spec::from_table("a a | y").unwrap_err().msg,
+            "a appears twice"
+        );
+    }
+
+    #[test]
+    fn test_wide_functions_stay_within_fan_in() {
+        // 5-input parity: 16 products of 5 literals each.
+        let spec = Spec::from_expressions(
+            "p = a ^ b ^ c ^ d ^ e\nq = a & b & c & d & e & f & g & h & i\nk = 1",
+        )
+        .unwrap();
+        assert_realizes(&spec);
+        let net = synthesize(&spec, Style::NandOnly).unwrap();
+        assert_eq!(net.outputs[2].1, Signal::Const(true));
+    }
+}
@@ -0,0 +1,519 @@
+// Test vectors: a table of input values and expected outputs, run against a
+// circuit row by row. Ports are named by Input/Output labels and Probe names
+// (see ComponentSpec::port_name). The text format:
+//
+//     # a 2-bit counter with an enable
+//     en | q
+//     0  | 0
+//     1  | -      <- `-`: leave an input as it is / don't check an output
+//     tick 3      <- tick the clock 3 times (`tick` alone ticks once)
+//     -  | 3
+//
+// The header names the inputs left of `|` and the checked outputs right of it.
+// Each row drives its inputs, settles, then compares the outputs. Values are
+// decimal, 0x hex or 0b binary. `#` starts a comment.
+//
+// A run with no ticks on a combinational circuit the batch simulator takes
+// (see sim::batch) evaluates LANES rows per pass instead.
+
+use crate::sim::batch::{Batch, LANES};
+use crate::sim::circuit::{Circuit, SettleError};
+use crate::sim::component::{CompKey, ComponentSpec, Logic, LogicComb};
+use crate::sim::value::Value;
+
+// A parse or binding error, with the 1-based line it's on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VectorError {
+    pub line: usize,
+    pub msg: String,
+}
+
+impl std::fmt::Display for VectorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.msg)
+    }
+}
+
+impl std::error::Error for VectorError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    // Per column, None for `-`.
+    Row {
+        line: usize,
+        inputs: Vec<Option<u64>>,
+        expected: Vec<Option<u64>>,
+    },
+    Tick {
+        line: usize,
+        count: usize,
+    },
+}
+
+impl Step {
+    pub fn line(&self) -> usize {
+        match self {
+            Step::Row { line, .. } | Step::Tick { line, .. } => *line,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestVectors {
+    // The header's line, where an unknown port name is reported.
+    pub header_line: usize,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    pub steps: Vec<Step>,
+}
+
+// One named port of the circuit under test. `width` is an Input's width, used
+// to reject values that don't fit; None for Outputs and Probes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Port {
+    pub name: String,
+    pub key: CompKey,
+    pub width: Option<u8>,
+}
+
+// The named ports among a circuit's components: labelled Inputs and Outputs,
+// and Probes. Unlabelled Inputs/Outputs can't be addressed, so are left out.
+pub fn ports<'a>(components: impl Iterator<Item = (&'a ComponentSpec, CompKey)>) -> Vec<Port> {
+    components
+        .filter_map(|(spec, key)| {
+            let name = spec.port_name()?.to_string();
+            let width = match spec {
+                ComponentSpec::Input(input) => Some(input.width),
+                _ => None,
+            };
+            Some(Port { name, key, width })
+        })
+        .collect()
+}
+
+// An output that didn't hold its expected value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub port: String,
+    pub key: CompKey,
+    pub expected: u64,
+    pub actual: Value,
+}
+
+// The outcome of one step. A Tick only fails by not settling.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepResult {
+    pub line: usize,
+    pub mismatches: Vec<Mismatch>,
+    pub error: Option<SettleError>,
+}
+
+impl StepResult {
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty() && self.error.is_none()
+    }
+}
+
+// "0x1F", "0b101" or "31".
+pub fn parse_value(text: &str) -> Option<u64> {
+    if let Some(hex) = text.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16).ok()
+    } else if let Some(bin) = text.strip_prefix("0b") {
+        u64::from_str_radix(bin, 2).ok()
+    } else {
+        text.parse().ok()
+    }
+}
+
+impl TestVectors {
+    pub fn parse(text: &str) -> Result<Self, VectorError> {
+        let mut lines = text.lines().enumerate().filter_map(|(i, line)| {
+            let line = line.split('#').next().unwrap_or("").trim();
+            (!line.is_empty()).then_some((i + 1, line))
+        });
+        let (header_line, header) = lines.next().ok_or(VectorError {
+            line: 1,
+            msg: "no header line".to_string(),
+        })?;
+        let (inputs, outputs) = split_columns(header_line, header)?;
+        let inputs: Vec<String> = inputs.iter().map(|s| s.to_string()).collect();
+        let outputs: Vec<String> = outputs.iter().map(|s| s.to_string()).collect();
+
+        let mut steps = Vec::new();
+        for (line, text) in lines {
+            let err = |msg: String| VectorError { line, msg };
+            if let Some(rest) = text.strip_prefix("tick") {
+                if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+                    let count = match rest.trim() {
+                        "" => 1,
+                        n => n
+                            .parse()
+                            .map_err(|_| err(format!("bad tick count {n:?}")))?,
+                    };
+                    steps.push(Step::Tick { line, count });
+                    continue;
+                }
+            }
+            let (ins, outs) = split_columns(line, text)?;
+            if ins.len() != inputs.len() || outs.len() != outputs.len() {
+                return Err(err(format!(
+                    "expected {} inputs and {} outputs, got {} and {}",
+                    inputs.len(),
+                    outputs.len(),
+                    ins.len(),
+                    outs.len()
+                )));
+            }
+            let cells = |cells: &[&str]| -> Result<Vec<Option<u64>>, VectorError> {
+                cells
+                    .iter()
+                    .map(|&cell| match cell {
+                        "-" => Ok(None),
+                        _ => parse_value(cell)
+                            .map(Some)
+                            .ok_or_else(|| err(format!("bad value {cell:?}"))),
+                    })
+                    .collect()
+            };
+            steps.push(Step::Row {
+                line,
+                inputs: cells(&ins)?,
+                expected: cells(&outs)?,
+            });
+        }
+        Ok(Self {
+            header_line,
+            inputs,
+            outputs,
+            steps,
+        })
+    }
+
+    // Runs every step against `circuit`, which is left in its final state.
+    // Ticks mirror the GUI's Step (Circuit::step_clock_sources, plus `time_per_tick` units of
+    // time in timed mode); in timed mode a row also runs `time_per_tick` units
+    // before its outputs are read. A step that fails to settle is recorded and
+    // the run carries on.
+    pub fn run(
+        &self,
+        circuit: &mut Circuit,
+        ports: &[Port],
+        time_per_tick: u64,
+    ) -> Result<Vec<StepResult>, VectorError> {
+        let find = |name: &str, want_input: bool| {
+            ports
+                .iter()
+                .find(|p| p.name == name && p.width.is_some() == want_input)
+                .ok_or_else(|| VectorError {
+                    line: self.header_line,
+                    msg: format!(
+                        "circuit has no {} named {name:?}",
+                        if want_input { "input" } else { "output" }
+                    ),
+                })
+        };
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|name| find(name, true))
+            .collect::<Result<Vec<_>, _>>()?;
+        let outputs = self
+            .outputs
+            .iter()
+            .map(|name| find(name, false))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(results) = self.run_batched(circuit, &inputs, &outputs)? {
+            return Ok(results);
+        }
+
+        let advance = |circuit: &mut Circuit| {
+            if circuit.is_timed() {
+                circuit.advance_time(time_per_tick)
+            } else {
+                Ok(())
+            }
+        };
+        let mut results = Vec::new();
+        for step in &self.steps {
+            match step {
+                Step::Tick { line, count } => {
+                    let mut error = None;
+                    for _ in 0..*count {
+                        if let Err(e) = circuit.step_clock_sources().and_then(|_| advance(circuit))
+                        {
+                            error = Some(e);
+                            break;
+                        }
+                    }
+                    results.push(StepResult {
+                        line: *line,
+                        mismatches: Vec::new(),
+                        error,
+                    });
+                }
+                Step::Row {
+                    line,
+                    inputs: values,
+                    expected,
+                } => {
+                    for (port, value) in inputs.iter().zip(values) {
+                        let (Some(bits), Some(width)) = (*value, port.width) else {
+                            continue;
+                        };
+                        check_fits(*line, port, bits)?;
+                        circuit.set_input(port.key, bits, width);
+                    }
+                    let error = circuit.settle().and_then(|_| advance(circuit)).err();
+                    let actual: Vec<Value> =
+                        outputs.iter().map(|p| circuit.read_output(p.key)).collect();
+                    let mismatches = mismatches(&outputs, expected, &actual);
+                    results.push(StepResult {
+                        line: *line,
+                        mismatches,
+                        error,
+                    });
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    // The whole run LANES rows at a time, when there are no ticks, the
+    // circuit is combinational and zero-delay, and the batch simulator takes
+    // it; None otherwise. Like the row-by-row run, it leaves the circuit
+    // settled on the last row.
+    fn run_batched(
+        &self,
+        circuit: &mut Circuit,
+        inputs: &[&Port],
+        outputs: &[&Port],
+    ) -> Result<Option<Vec<StepResult>>, VectorError> {
+        let ticks = self.steps.iter().any(|s| matches!(s, Step::Tick { .. }));
+        if ticks || circuit.is_timed() || !circuit.is_combinational() {
+            return Ok(None);
+        }
+        let input_keys: Vec<CompKey> = inputs.iter().map(|p| p.key).collect();
+        let output_keys: Vec<CompKey> = outputs.iter().map(|p| p.key).collect();
+        let Ok(mut batch) = Batch::compile(circuit, &input_keys, &output_keys) else {
+            return Ok(None);
+        };
+
+        // `-` keeps an input's value from the row before, starting from the
+        // value it has now.
+        let mut current: Vec<u64> = inputs
+            .iter()
+            .map(|p| match &circuit.components[&p.key].logic {
+                Logic::Comb(LogicComb::Input(input)) => input.bits,
+                _ => 0,
+            })
+            .collect();
+        let mut rows = Vec::with_capacity(self.steps.len());
+        for step in &self.steps {
+            let Step::Row {
+                line,
+                inputs: values,
+                expected,
+            } = step
+            else {
+                continue;
+            };
+            for ((port, value), slot) in inputs.iter().zip(values).zip(&mut current) {
+                if let Some(bits) = *value {
+                    check_fits(*line, port, bits)?;
+                    *slot = bits;
+                }
+            }
+            rows.push((*line, current.clone(), expected));
+        }
+
+        let mut results = Vec::with_capacity(rows.len());
+        for chunk in rows.chunks(LANES) {
+            let vectors: Vec<Vec<u64>> = chunk.iter().map(|(_, v, _)| v.clone()).collect();
+            for ((line, _, expected), actual) in chunk.iter().zip(batch.run(&vectors)) {
+                results.push(StepResult {
+                    line: *line,
+                    mismatches: mismatches(outputs, expected, &actual),
+                    error: None,
+                });
+            }
+        }
+        if let Some((_, last, _)) = rows.last() {
+            for (port, &bits) in inputs.iter().zip(last) {
+                circuit.set_input(port.key, bits, port.width.unwrap_or(1));
+            }
+            if let Err(e) = circuit.settle() {
+                results.last_mut().unwrap().error = Some(e);
+            }
+        }
+        Ok(Some(results))
+    }
+}
+
+fn check_fits(line: usize, port: &Port, bits: u64) -> Result<(), VectorError> {
+    let width = port.width.unwrap_or(Value::MAX_WIDTH);
+    if bits & !Value::mask(width) != 0 {
+        return Err(VectorError {
+            line,
+            msg: format!("{bits} doesn't fit {width}-bit input {}", port.name),
+        });
+    }
+    Ok(())
+}
+
+// The checked outputs that didn't read back as their expected value.
+fn mismatches(outputs: &[&Port], expected: &[Option<u64>], actual: &[Value]) -> Vec<Mismatch> {
+    outputs
+        .iter()
+        .zip(expected)
+        .zip(actual)
+        .filter_map(|((port, expected), &actual)| {
+            let expected = (*expected)?;
+            let matches = matches!(actual, Value::Fixed { bits, .. } if bits == expected);
+            (!matches).then(|| Mismatch {
+                port: port.name.clone(),
+                key: port.key,
+                expected,
+                actual,
+            })
+        })
+        .collect()
+}
+
+// Splits "a b | c d" into its input and output columns.
+fn split_columns(line: usize, text: &str) -> Result<(Vec<&str>, Vec<&str>), VectorError> {
+    let (ins, outs) = text.split_once('|').ok_or_else(|| VectorError {
+        line,
+        msg: "expected `inputs | outputs`".to_string(),
+    })?;
+    Ok((
+        ins.split_whitespace().collect(),
+        outs.split_whitespace().collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::component::{ClockEdge, Component, GateOp, PinId, Probe, TFlipFlopConf};
+
+    fn port(name: &str, key: CompKey, width: Option<u8>) -> Port {
+        Port {
+            name: name.to_string(),
+            key,
+            width,
+        }
+    }
+
+    // a, b -> AND -> y.
+    fn and_bench() -> (Circuit, Vec<Port>) {
+        let mut c = Circuit::new();
+        let a = c.add_component(Component::input(0, 1));
+        let b = c.add_component(Component::input(0, 1));
+        let and = c.add_component(Component::gate(GateOp::And, 2, 1));
+        let y = c.add_component(Component::output());
+        c.link(a, PinId::output(0), and, PinId::input(0));
+        c.link(b, PinId::output(0), and, PinId::input(1));
+        c.link(and, PinId::output(0), y, PinId::input(0));
+        c.settle().unwrap();
+        let ports = vec![
+            port("a", a, Some(1)),
+            port("b", b, Some(1)),
+            port("y", y, None),
+        ];
+        (c, ports)
+    }
+
+    #[test]
+    fn test_parse_rows_ticks_and_dont_cares() {
+        let tv =
+            TestVectors::parse("# comment\na b | y\n\n1 0x1 | -\ntick\n- 0b0 | 0 # end\ntick 3\n")
+                .unwrap();
+        assert_eq!(tv.header_line, 2);
+        assert_eq!(tv.inputs, vec!["a", "b"]);
+        assert_eq!(tv.outputs, vec!["y"]);
+        assert_eq!(
+            tv.steps,
+            vec![
+                Step::Row {
+                    line: 4,
+                    inputs: vec![Some(1), Some(1)],
+                    expected: vec![None],
+                },
+                Step::Tick { line: 5, count: 1 },
+                Step::Row {
+                    line: 6,
+                    inputs: vec![None, Some(0)],
+                    expected: vec![Some(0)],
+                },
+                Step::Tick { line: 7, count: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_carry_their_line() {
+        let err = |text| TestVectors::parse(text).unwrap_err();
+        assert_eq!(err("a | y\n1 1 | 0").line, 2);
+        assert_eq!(err("a | y\n\n1 | zz").line, 3);
+        assert_eq!(err("a | y\ntick x").line, 2);
+        assert_eq!(err("a y").line, 1);
+    }
+
+    #[test]
+    fn test_run_reports_failing_rows() {
+        let (mut c, ports) = and_bench();
+        let tv = TestVectors::parse("a b | y\n0 0 | 0\n1 1 | 1\n1 0 | 1\n- - | -").unwrap();
+        let results = tv.run(&mut c, &ports, 0).unwrap();
+        let passed: Vec<bool> = results.iter().map(StepResult::passed).collect();
+        assert_eq!(passed, vec![true, true, false, true]);
+        // The last row's `-`s kept 1 and 0.
+        assert_eq!(c.read_output(ports[2].key), Value::ZERO);
+        assert_eq!(
+            results[2].mismatches,
+            vec![Mismatch {
+                port: "y".to_string(),
+                key: ports[2].key,
+                expected: 1,
+                actual: Value::ZERO,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_run_rejects_unknown_ports_and_oversized_values() {
+        let (mut c, ports) = and_bench();
+        let tv = TestVectors::parse("# header next\na y | y").unwrap();
+        let err = tv.run(&mut c, &ports, 0).unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(err.msg.contains("input named \"y\""));
+        let tv = TestVectors::parse("a b | y\n2 0 | -").unwrap();
+        assert_eq!(tv.run(&mut c, &ports, 0).unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn test_run_ticks_sequential_circuits() {
+        // t -> T flip-flop -> probe q.
+        let mut c = Circuit::new();
+        let t = c.add_component(Component::input(0, 1));
+        let ff = c.add_component(Component::t_flip_flop(ClockEdge::Rising));
+        let q = c.add_component(Component::probe());
+        c.link(
+            t,
+            PinId::output(0),
+            ff,
+            PinId::input(TFlipFlopConf::TOGGLE_PIN as u8),
+        );
+        c.link(ff, PinId::output(0), q, PinId::input(0));
+        c.settle().unwrap();
+        let spec = ComponentSpec::Probe(Probe {
+            name: "q".to_string(),
+        });
+        let mut ports = ports(std::iter::once((&spec, q)));
+        ports.push(port("t", t, Some(1)));
+
+        let tv = TestVectors::parse("t | q\n1 | 0\ntick\n- | 1\ntick 2\n- | 1\n0 | -\ntick\n- | 1")
+            .unwrap();
+        let results = tv.run(&mut c, &ports, 0).unwrap();
+        assert!(results.iter().all(StepResult::passed), "{results:?}");
+    }
+}
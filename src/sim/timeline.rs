@@ -0,0 +1,211 @@
+use crate::sim::component::CompKey;
+use crate::sim::value::Value;
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
+
+// A change to one output pin, due at `time`. `seq` keeps events due at the
+// same time in the order they were scheduled.
+#[derive(Debug, Clone)]
+pub(crate) struct Event {
+    pub(crate) time: u64,
+    seq: u64,
+    pub(crate) comp: CompKey,
+    pub(crate) pin: usize,
+    pub(crate) value: Value,
+}
+
+impl PartialEq for Event {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Event {}
+
+impl PartialOrd for Event {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Event {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.time, self.seq).cmp(&(other.time, other.seq))
+    }
+}
+
+// The time-ordered event queue behind Circuit's timed mode (see
+// Circuit::set_timed). Time is in abstract units; a component's output lands
+// `Component::delay` units after the input change that caused it. Delays are
+// transport delays: every scheduled change happens, so a pulse shorter than a
+// gate's delay still shows up (as a glitch) on its output.
+#[derive(Debug, Default, Clone)]
+pub struct Timeline {
+    now: u64,
+    next_seq: u64,
+    queue: BinaryHeap<Reverse<Event>>,
+    // Per output pin with events in flight: the value its last scheduled event
+    // sets, and how many of its events are still queued. A re-evaluation that
+    // projects the same value again schedules nothing.
+    projected: HashMap<(CompKey, usize), (Value, usize)>,
+}
+
+impl Timeline {
+    // An empty queue whose clock already reads `now`: a subcircuit's inner
+    // circuit joining the enclosing circuit's run part-way through.
+    pub(crate) fn starting_at(now: u64) -> Self {
+        Self {
+            now,
+            ..Self::default()
+        }
+    }
+
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    // The time of the earliest queued event, if any.
+    pub fn next_event_time(&self) -> Option<u64> {
+        self.queue.peek().map(|Reverse(e)| e.time)
+    }
+
+    // Schedules output `pin` of `comp` to become `value` `delay` units from
+    // now, unless that's already where the pin is headed - `current` is its
+    // value right now, used when nothing is in flight for it.
+    pub(crate) fn schedule(
+        &mut self,
+        comp: CompKey,
+        pin: usize,
+        current: Value,
+        value: Value,
+        delay: u32,
+    ) {
+        let slot = self.projected.entry((comp, pin)).or_insert((current, 0));
+        if slot.0 == value {
+            if slot.1 == 0 {
+                self.projected.remove(&(comp, pin));
+            }
+            return;
+        }
+        *slot = (value, slot.1 + 1);
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.queue.push(Reverse(Event {
+            time: self.now + delay as u64,
+            seq,
+            comp,
+            pin,
+            value,
+        }));
+    }
+
+    // Removes every event due at the earliest queued time, provided that time
+    // is no later than `until`, and moves `now` up to it. Empty once nothing is
+    // due by `until`.
+    pub(crate) fn pop_due(&mut self, until: u64) -> Vec<Event> {
+        let Some(time) = self.next_event_time().filter(|&t| t <= until) else {
+            return Vec::new();
+        };
+        self.now = time;
+        let mut due = Vec::new();
+        while let Some(Reverse(e)) = self.queue.peek() {
+            if e.time != time {
+                break;
+            }
+            let Reverse(e) = self.queue.pop().expect("peeked");
+            self.settle_projection(e.comp, e.pin);
+            due.push(e);
+        }
+        due
+    }
+
+    // Moves `now` forward to `time` with nothing happening in between - the
+    // caller has already run every event due by then.
+    pub(crate) fn skip_to(&mut self, time: u64) {
+        debug_assert!(self.next_event_time().is_none_or(|t| t > time));
+        self.now = self.now.max(time);
+    }
+
+    // Empties the queue, returning only the final value scheduled for each pin
+    // - what the circuit would reach if time ran on with no further changes.
+    pub(crate) fn drain_final(&mut self) -> Vec<Event> {
+        self.queue.clear();
+        self.projected
+            .drain()
+            .map(|((comp, pin), (value, _))| Event {
+                time: self.now,
+                seq: 0,
+                comp,
+                pin,
+                value,
+            })
+            .collect()
+    }
+
+    fn settle_projection(&mut self, comp: CompKey, pin: usize) {
+        if let Some(slot) = self.projected.get_mut(&(comp, pin)) {
+            slot.1 -= 1;
+            if slot.1 == 0 {
+                self.projected.remove(&(comp, pin));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: CompKey = CompKey(0);
+    const B: CompKey = CompKey(1);
+
+    #[test]
+    fn test_events_pop_in_time_then_schedule_order() {
+        let mut t = Timeline::default();
+        t.schedule(A, 0, Value::ZERO, Value::ONE, 3);
+        t.schedule(B, 0, Value::ZERO, Value::ONE, 1);
+        t.schedule(B, 1, Value::ZERO, Value::ONE, 1);
+
+        let first: Vec<_> = t.pop_due(10).iter().map(|e| (e.comp, e.pin)).collect();
+        assert_eq!(first, vec![(B, 0), (B, 1)]);
+        assert_eq!(t.now(), 1);
+        assert_eq!(t.pop_due(10).len(), 1);
+        assert_eq!(t.now(), 3);
+        assert!(t.pop_due(10).is_empty());
+    }
+
+    #[test]
+    fn test_nothing_pops_past_until() {
+        let mut t = Timeline::default();
+        t.schedule(A, 0, Value::ZERO, Value::ONE, 5);
+        assert!(t.pop_due(4).is_empty());
+        assert_eq!(t.now(), 0);
+        assert_eq!(t.pending(), 1);
+    }
+
+    #[test]
+    fn test_rescheduling_the_projected_value_is_a_no_op() {
+        let mut t = Timeline::default();
+        t.schedule(A, 0, Value::ZERO, Value::ZERO, 1); // already there
+        assert_eq!(t.pending(), 0);
+        t.schedule(A, 0, Value::ZERO, Value::ONE, 1);
+        t.schedule(A, 0, Value::ZERO, Value::ONE, 1); // already headed there
+        assert_eq!(t.pending(), 1);
+    }
+
+    #[test]
+    fn test_a_short_pulse_survives_as_two_events() {
+        let mut t = Timeline::default();
+        t.schedule(A, 0, Value::ZERO, Value::ONE, 2);
+        t.schedule(A, 0, Value::ZERO, Value::ZERO, 2); // back again: a glitch
+        assert_eq!(t.pending(), 2);
+        let finals = t.drain_final();
+        assert_eq!(finals.len(), 1);
+        assert_eq!(finals[0].value, Value::ZERO);
+    }
+}
@@ -0,0 +1,437 @@
+// Static timing: the longest combinational path from every start point - an
+// Input, or a sequential part's outputs - to every end point it reaches - an
+// Output, or a sequential part's inputs. Lengths count either gate levels
+// (each part that computes is one level; wiring, splitters and ports are
+// free) or each part's timed-mode delay. The parts at the two ends add
+// nothing: a path measures only the logic between them.
+//
+// Subcircuits are looked through, not treated as one part, so a ripple-carry
+// adder and a carry-lookahead one built the same size show their different
+// depths. A component inside one is named by its key chain from the
+// top-level circuit down.
+
+use crate::sim::circuit::Circuit;
+use crate::sim::component::{CompKey, Logic, LogicComb};
+use crate::sim::net::NetKey;
+
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    #[default]
+    Levels,
+    // Component::delay, the per-part delay timed mode runs with: the kind's
+    // default unless one was saved with the part (see io::ComponentEntry).
+    Delays,
+}
+
+// The longest path between one start point and one end point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    pub length: u64,
+    // Start point, the logic in order, then the end point; each a chain of
+    // keys from the top-level circuit down through subcircuits.
+    pub components: Vec<Vec<CompKey>>,
+}
+
+// With a combinational loop there is no longest path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CombinationalLoop;
+
+impl std::fmt::Display for CombinationalLoop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the circuit has a combinational loop, so its paths have no length"
+        )
+    }
+}
+
+impl std::error::Error for CombinationalLoop {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timing {
+    // One per start/end pair joined by any path, longest first.
+    pub paths: Vec<Path>,
+}
+
+impl Timing {
+    pub fn analyze(circuit: &Circuit, metric: Metric) -> Result<Self, CombinationalLoop> {
+        let mut graph = Graph {
+            metric,
+            ..Graph::default()
+        };
+        graph.add_scope(circuit, &[], None);
+        let order = graph.topological_order().ok_or(CombinationalLoop)?;
+        let mut paths = vec![];
+        for (at, &start) in order.iter().enumerate() {
+            if matches!(graph.nodes[start].kind, Kind::Start) {
+                graph.paths_from(&order[at..], &mut paths);
+            }
+        }
+        paths.sort_by_key(|p| std::cmp::Reverse(p.length));
+        Ok(Timing { paths })
+    }
+
+    pub fn worst(&self) -> Option<&Path> {
+        self.paths.first()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Start,
+    End,
+    // A part the path runs through, with its length.
+    Logic(u64),
+    // A subcircuit's boundary Input or Output, joining an outer net to an
+    // inner one; free and left out of the component list.
+    Port,
+    Net,
+}
+
+#[derive(Debug)]
+struct Node {
+    kind: Kind,
+    // Empty for a net.
+    chain: Vec<CompKey>,
+}
+
+// The boundary nets of a subcircuit being added: the outer net on each of its
+// pins, by the inner Input or Output that pin maps to.
+struct Boundary {
+    inputs: HashMap<CompKey, usize>,
+    outputs: HashMap<CompKey, usize>,
+}
+
+// Parts and nets of the whole hierarchy, flattened into one DAG where every
+// edge runs from a net to a part reading it or from a part to a net it drives.
+#[derive(Debug, Default)]
+struct Graph {
+    metric: Metric,
+    nodes: Vec<Node>,
+    succ: Vec<Vec<usize>>,
+    // Nets by scope; nets sharing a tunnel label within one scope share a node.
+    nets: HashMap<(usize, NetKey), usize>,
+    labels: HashMap<(usize, String), usize>,
+    scopes: usize,
+}
+
+impl Graph {
+    fn node(&mut self, kind: Kind, chain: Vec<CompKey>) -> usize {
+        self.nodes.push(Node { kind, chain });
+        self.succ.push(vec![]);
+        self.nodes.len() - 1
+    }
+
+    fn edge(&mut self, from: usize, to: usize) {
+        self.succ[from].push(to);
+    }
+
+    fn add_scope(&mut self, circuit: &Circuit, chain: &[CompKey], boundary: Option<Boundary>) {
+        let scope = self.scopes;
+        self.scopes += 1;
+        for tunnel in circuit.tunnels.values() {
+            if let Some(net) = tunnel.net {
+                let len = self.nodes.len();
+                let node = *self
+                    .labels
+                    .entry((scope, tunnel.label.clone()))
+                    .or_insert(len);
+                if node == len {
+                    self.node(Kind::Net, vec![]);
+                }
+                self.nets.insert((scope, net), node);
+            }
+        }
+        let net_node = |graph: &mut Self, net: NetKey| match graph.nets.get(&(scope, net)) {
+            Some(&node) => node,
+            None => {
+                let node = graph.node(Kind::Net, vec![]);
+                graph.nets.insert((scope, net), node);
+                node
+            }
+        };
+
+        let mut keys: Vec<CompKey> = circuit.components.keys().copied().collect();
+        keys.sort_by_key(|k| k.0);
+        for key in keys {
+            let comp = &circuit.components[&key];
+            let mut here = chain.to_vec();
+            here.push(key);
+            let inputs: Vec<usize> = comp
+                .pins
+                .inputs
+                .iter()
+                .flatten()
+                .map(|&net| net_node(self, net))
+                .collect();
+            let outputs: Vec<usize> = comp
+                .pins
+                .outputs
+                .iter()
+                .flatten()
+                .map(|&net| net_node(self, net))
+                .collect();
+            match &comp.logic {
+                Logic::Sub(sub) => {
+                    let pin_nets =
+                        |nets: &[Option<NetKey>], ports: &[CompKey], graph: &mut Self| {
+                            ports
+                                .iter()
+                                .zip(nets)
+                                .filter_map(|(&port, net)| Some((port, net_node(graph, (*net)?))))
+                                .collect()
+                        };
+                    let boundary = Boundary {
+                        inputs: pin_nets(&comp.pins.inputs, &sub.inputs, self),
+                        outputs: pin_nets(&comp.pins.outputs, &sub.outputs, self),
+                    };
+                    self.add_scope(&sub.inner, &here, Some(boundary));
+                }
+                Logic::Seq(_) => {
+                    let end = self.node(Kind::End, here.clone());
+                    for net in inputs {
+                        self.edge(net, end);
+                    }
+                    let start = self.node(Kind::Start, here);
+                    for net in outputs {
+                        self.edge(start, net);
+                    }
+                }
+                Logic::Comb(logic) => {
+                    let (kind, inputs, outputs) = match logic {
+                        LogicComb::Input(_) => match &boundary {
+                            None => (Kind::Start, inputs, outputs),
+                            Some(b) => match b.inputs.get(&key).copied() {
+                                Some(outer) => (Kind::Port, vec![outer], outputs),
+                                // Unreachable from outside: a constant.
+                                None => continue,
+                            },
+                        },
+                        LogicComb::Output => match &boundary {
+                            None => (Kind::End, inputs, outputs),
+                            Some(b) => match b.outputs.get(&key).copied() {
+                                Some(outer) => (Kind::Port, inputs, vec![outer]),
+                                None => continue,
+                            },
+                        },
+                        _ => {
+                            let length = match self.metric {
+                                Metric::Levels => logic.default_delay(),
+                                Metric::Delays => comp.delay,
+                            };
+                            (Kind::Logic(u64::from(length)), inputs, outputs)
+                        }
+                    };
+                    let node = self.node(kind, here);
+                    for net in inputs {
+                        self.edge(net, node);
+                    }
+                    for net in outputs {
+                        self.edge(node, net);
+                    }
+                }
+            }
+        }
+    }
+
+    // Kahn's algorithm; None if a loop keeps some node from ever being ready.
+    fn topological_order(&self) -> Option<Vec<usize>> {
+        let mut indegree = vec![0usize; self.nodes.len()];
+        for next in self.succ.iter().flatten() {
+            indegree[*next] += 1;
+        }
+        let mut order: Vec<usize> = (0..self.nodes.len())
+            .filter(|&n| indegree[n] == 0)
+            .collect();
+        let mut at = 0;
+        while at < order.len() {
+            for &next in &self.succ[order[at]] {
+                indegree[next] -= 1;
+                if indegree[next] == 0 {
+                    order.push(next);
+                }
+            }
+            at += 1;
+        }
+        (order.len() == self.nodes.len()).then_some(order)
+    }
+
+    // The longest path from `order[0]`, a start point, to each end point
+    // after it in `order`.
+    fn paths_from(&self, order: &[usize], paths: &mut Vec<Path>) {
+        let mut arrival: Vec<Option<u64>> = vec![None; self.nodes.len()];
+        let mut pred = vec![usize::MAX; self.nodes.len()];
+        arrival[order[0]] = Some(0);
+        for &node in order {
+            let Some(at) = arrival[node] else {
+                continue;
+            };
+            for &next in &self.succ[node] {
+                let length = match self.nodes[next].kind {
+                    Kind::Logic(length) => at + length,
+                    _ => at,
+                };
+                if arrival[next].is_none_or(|a| length > a) {
+                    arrival[next] = Some(length);
+                    pred[next] = node;
+                }
+            }
+            if matches!(self.nodes[node].kind, Kind::End) {
+                let mut components = vec![];
+                let mut walk = node;
+                loop {
+                    if matches!(
+                        self.nodes[walk].kind,
+                        Kind::Start | Kind::End | Kind::Logic(_)
+                    ) {
+                        components.push(self.nodes[walk].chain.clone());
+                    }
+                    if walk == order[0] {
+                        break;
+                    }
+                    walk = pred[walk];
+                }
+                components.reverse();
+                paths.push(Path {
+                    length: at,
+                    components,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::component::{ClockEdge, Component, GateOp, PinId};
+
+    fn chain(keys: &[CompKey]) -> Vec<Vec<CompKey>> {
+        keys.iter().map(|&k| vec![k]).collect()
+    }
+
+    #[test]
+    fn test_longest_path_between_each_pair_of_ports() {
+        let mut c = Circuit::new();
+        let a = c.add_component(Component::input(0, 1));
+        let b = c.add_component(Component::input(0, 1));
+        let not1 = c.add_component(Component::gate(GateOp::Not, 1, 1));
+        let not2 = c.add_component(Component::gate(GateOp::Not, 1, 1));
+        let and = c.add_component(Component::gate(GateOp::And, 2, 1));
+        let out = c.add_component(Component::output());
+        c.link(a, PinId::output(0), not1, PinId::input(0));
+        c.link(not1, PinId::output(0), not2, PinId::input(0));
+        c.link(not2, PinId::output(0), and, PinId::input(0));
+        c.link(b, PinId::output(0), and, PinId::input(1));
+        c.link(and, PinId::output(0), out, PinId::input(0));
+
+        let timing = Timing::analyze(&c, Metric::Levels).unwrap();
+        assert_eq!(
+            timing.paths,
+            vec![
+                Path {
+                    length: 3,
+                    components: chain(&[a, not1, not2, and, out]),
+                },
+                Path {
+                    length: 1,
+                    components: chain(&[b, and, out]),
+                },
+            ]
+        );
+
+        // By delay, the short branch can be the slow one.
+        c.components.get_mut(&and).unwrap().delay = 2;
+        c.components.get_mut(&not1).unwrap().delay = 0;
+        let timing = Timing::analyze(&c, Metric::Delays).unwrap();
+        assert_eq!(timing.worst().unwrap().length, 3);
+        assert_eq!(timing.paths[1].length, 2);
+    }
+
+    #[test]
+    fn test_registers_end_one_path_and_start_the_next() {
+        let mut c = Circuit::new();
+        let d = c.add_component(Component::input(0, 4));
+        let r1 = c.add_component(Component::reg(4, ClockEdge::Rising));
+        let r2 = c.add_component(Component::reg(4, ClockEdge::Rising));
+        let add = c.add_component(Component::adder(4));
+        let out = c.add_component(Component::output());
+        c.link(d, PinId::output(0), r1, PinId::input(0));
+        c.link(r1, PinId::output(0), add, PinId::input(0));
+        c.link(r2, PinId::output(0), add, PinId::input(1));
+        c.link(add, PinId::output(0), r2, PinId::input(0));
+        c.link(r2, PinId::output(0), out, PinId::input(0));
+
+        let timing = Timing::analyze(&c, Metric::Levels).unwrap();
+        let mut pairs: Vec<_> = timing
+            .paths
+            .iter()
+            .map(|p| {
+                (
+                    p.components[0][0],
+                    p.components.last().unwrap()[0],
+                    p.length,
+                )
+            })
+            .collect();
+        pairs.sort_by_key(|&(s, e, _)| (s.0, e.0));
+        assert_eq!(
+            pairs,
+            vec![(d, r1, 0), (r1, r2, 1), (r2, r2, 1), (r2, out, 0)]
+        );
+    }
+
+    #[test]
+    fn test_paths_run_through_subcircuits() {
+        // Two NOTs in a row, wrapped.
+        let buffer = || {
+            let mut inner = Circuit::new();
+            let i = inner.add_component(Component::input(0, 1));
+            let n1 = inner.add_component(Component::gate(GateOp::Not, 1, 1));
+            let n2 = inner.add_component(Component::gate(GateOp::Not, 1, 1));
+            let o = inner.add_component(Component::output());
+            inner.link(i, PinId::output(0), n1, PinId::input(0));
+            inner.link(n1, PinId::output(0), n2, PinId::input(0));
+            inner.link(n2, PinId::output(0), o, PinId::input(0));
+            (Component::subcircuit(inner, vec![i], vec![o]), [n1, n2])
+        };
+        let mut c = Circuit::new();
+        let a = c.add_component(Component::input(0, 1));
+        let (first, first_nots) = buffer();
+        let (second, second_nots) = buffer();
+        let first = c.add_component(first);
+        let second = c.add_component(second);
+        let out = c.add_component(Component::output());
+        c.link(a, PinId::output(0), first, PinId::input(0));
+        c.link(first, PinId::output(0), second, PinId::input(0));
+        c.link(second, PinId::output(0), out, PinId::input(0));
+
+        let timing = Timing::analyze(&c, Metric::Levels).unwrap();
+        assert_eq!(
+            timing.paths,
+            vec![Path {
+                length: 4,
+                components: vec![
+                    vec![a],
+                    vec![first, first_nots[0]],
+                    vec![first, first_nots[1]],
+                    vec![second, second_nots[0]],
+                    vec![second, second_nots[1]],
+                    vec![out],
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_loops_have_no_timing() {
+        let mut c = Circuit::new();
+        let nor1 = c.add_component(Component::gate(GateOp::Nor, 2, 1));
+        let nor2 = c.add_component(Component::gate(GateOp::Nor, 2, 1));
+        c.link(nor1, PinId::output(0), nor2, PinId::input(0));
+        c.link(nor2, PinId::output(0), nor1, PinId::input(0));
+        assert_eq!(Timing::analyze(&c, Metric::Levels), Err(CombinationalLoop));
+    }
+}
@@ -0,0 +1,317 @@
+// Exhaustive truth tables for combinational circuits: every combination of the
+// Inputs' values is applied and settled in turn, and the Outputs read back.
+// Rows count up from all-zero, the first Input being the most significant
+// column. Rows grow as 2^(total input bits), so that total is capped at
+// MAX_INPUT_BITS.
+//
+// Only components are checked for state (see Circuit::is_combinational): a
+// latch built from gate feedback reads as whatever the previous row left.
+// Circuits the batch simulator takes (see sim::batch) run LANES rows a pass.
+
+use crate::sim::batch::{Batch, LANES};
+use crate::sim::circuit::{Circuit, SettleError};
+use crate::sim::test_vector::Port;
+use crate::sim::value::Value;
+
+// 65536 rows: still quick to settle and to scroll through. A wider circuit
+// is refused outright rather than given a truncated table: the expressions
+// (see sim::minimize) and exports are read off the whole table, and would
+// be wrong for the rows left out.
+pub const MAX_INPUT_BITS: u32 = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnalyzeError {
+    // A sequential component, or a subcircuit holding one.
+    Stateful,
+    NoInputs,
+    NoOutputs,
+    TooWide { bits: u32 },
+    // Settling failed on the row with these input values.
+    Settle { inputs: Vec<u64>, err: SettleError },
+}
+
+impl std::fmt::Display for AnalyzeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnalyzeError::Stateful => write!(
+                f,
+                "the circuit has sequential components, so its outputs depend on more than its inputs"
+            ),
+            AnalyzeError::NoInputs => write!(f, "the circuit has no Inputs"),
+            AnalyzeError::NoOutputs => write!(f, "the circuit has no Outputs"),
+            AnalyzeError::TooWide { bits } => write!(
+                f,
+                "{bits} input bits would take 2^{bits} rows; at most {MAX_INPUT_BITS} bits are enumerated, as a partial table would give wrong expressions"
+            ),
+            AnalyzeError::Settle { inputs, err } => {
+                write!(f, "inputs {inputs:?} did not settle: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AnalyzeError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub inputs: Vec<u64>,
+    pub outputs: Vec<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TruthTable {
+    // Each carries its width.
+    pub inputs: Vec<Port>,
+    pub outputs: Vec<Port>,
+    pub rows: Vec<Row>,
+}
+
+impl TruthTable {
+    // Enumerates `inputs` on `circuit`, which is left holding the last row.
+    pub fn analyze(
+        circuit: &mut Circuit,
+        inputs: Vec<Port>,
+        outputs: Vec<Port>,
+    ) -> Result<Self, AnalyzeError> {
+        if !circuit.is_combinational() {
+            return Err(AnalyzeError::Stateful);
+        }
+        if inputs.is_empty() {
+            return Err(AnalyzeError::NoInputs);
+        }
+        if outputs.is_empty() {
+            return Err(AnalyzeError::NoOutputs);
+        }
+        let widths: Vec<u8> = inputs
+            .iter()
+            .map(|p| p.width.expect("Input ports carry their width"))
+            .collect();
+        let bits: u32 = widths.iter().map(|&w| w as u32).sum();
+        if bits > MAX_INPUT_BITS {
+            return Err(AnalyzeError::TooWide { bits });
+        }
+
+        let row_inputs = |n: u64| {
+            let mut values = vec![0; widths.len()];
+            let mut rest = n;
+            for (value, &width) in values.iter_mut().zip(&widths).rev() {
+                *value = rest & Value::mask(width);
+                rest >>= width;
+            }
+            values
+        };
+        let settle_row = |circuit: &mut Circuit, values: &[u64]| {
+            for ((port, &value), &width) in inputs.iter().zip(values).zip(&widths) {
+                circuit.set_input(port.key, value, width);
+            }
+            circuit.settle().map_err(|err| AnalyzeError::Settle {
+                inputs: values.to_vec(),
+                err,
+            })
+        };
+
+        let input_keys: Vec<_> = inputs.iter().map(|p| p.key).collect();
+        let output_keys: Vec<_> = outputs.iter().map(|p| p.key).collect();
+        let mut rows = Vec::with_capacity(1 << bits);
+        if let Ok(mut batch) = Batch::compile(circuit, &input_keys, &output_keys) {
+            let all: Vec<Vec<u64>> = (0..1u64 << bits).map(row_inputs).collect();
+            for chunk in all.chunks(LANES) {
+                for (values, outputs) in chunk.iter().zip(batch.run(chunk)) {
+                    rows.push(Row {
+                        inputs: values.clone(),
+                        outputs,
+                    });
+                }
+            }
+            // Leave the circuit on the last row, as the slow path does.
+            settle_row(circuit, &all[all.len() - 1])?;
+        } else {
+            for n in 0..1u64 << bits {
+                let values = row_inputs(n);
+                settle_row(circuit, &values)?;
+                let outputs = outputs.iter().map(|p| circuit.read_output(p.key)).collect();
+                rows.push(Row {
+                    inputs: values,
+                    outputs,
+                });
+            }
+        }
+        Ok(Self {
+            inputs,
+            outputs,
+            rows,
+        })
+    }
+
+    // Column names: inputs, then outputs.
+    pub fn header(&self) -> Vec<&str> {
+        self.inputs
+            .iter()
+            .chain(&self.outputs)
+            .map(|p| p.name.as_str())
+            .collect()
+    }
+
+    // A row's cells, in binary: inputs zero-padded to their width, outputs
+    // per bit with `z`/`x` for undriven/unknown bits.
+    pub fn cells(&self, row: &Row) -> Vec<String> {
+        let inputs = self
+            .inputs
+            .iter()
+            .zip(&row.inputs)
+            .map(|(p, v)| format!("{v:0w$b}", w = p.width.unwrap_or(1) as usize));
+        let outputs = row.outputs.iter().map(|&v| match v.width() {
+            Some(width) => (0..width).rev().map(|i| v.bit(i).as_char()).collect(),
+            None => v.bit(0).as_char().to_string(),
+        });
+        inputs.chain(outputs).collect()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let line = |cells: &[&str]| {
+            let quoted: Vec<String> = cells.iter().map(|c| csv_field(c)).collect();
+            quoted.join(",") + "\n"
+        };
+        let mut out = line(&self.header());
+        for row in &self.rows {
+            let cells = self.cells(row);
+            out += &line(&cells.iter().map(String::as_str).collect::<Vec<_>>());
+        }
+        out
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let line = |cells: &[&str]| {
+            let escaped: Vec<String> = cells.iter().map(|c| c.replace('|', "\\|")).collect();
+            format!("| {} |\n", escaped.join(" | "))
+        };
+        let header = self.header();
+        let mut out = line(&header);
+        out += &line(&vec!["---"; header.len()]);
+        for row in &self.rows {
+            let cells = self.cells(row);
+            out += &line(&cells.iter().map(String::as_str).collect::<Vec<_>>());
+        }
+        out
+    }
+}
+
+// Quotes a field holding a comma, quote or newline, doubling its quotes.
+pub(crate) fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::component::{ClockEdge, CompKey, Component, GateOp, PinId};
+
+    fn port(name: &str, key: CompKey, width: Option<u8>) -> Port {
+        Port {
+            name: name.to_string(),
+            key,
+            width,
+        }
+    }
+
+    // a & b -> y and ~b -> q, all two bits wide.
+    fn bench() -> (Circuit, Vec<Port>, Vec<Port>) {
+        let mut c = Circuit::new();
+        let a = c.add_component(Component::input(0, 2));
+        let b = c.add_component(Component::input(0, 2));
+        let and = c.add_component(Component::gate(GateOp::And, 2, 2));
+        let not = c.add_component(Component::gate(GateOp::Not, 1, 2));
+        let y = c.add_component(Component::output());
+        let q = c.add_component(Component::output());
+        c.link(a, PinId::output(0), and, PinId::input(0));
+        c.link(b, PinId::output(0), and, PinId::input(1));
+        c.link(b, PinId::output(0), not, PinId::input(0));
+        c.link(and, PinId::output(0), y, PinId::input(0));
+        c.link(not, PinId::output(0), q, PinId::input(0));
+        c.settle().unwrap();
+        (
+            c,
+            vec![port("a", a, Some(2)), port("b", b, Some(2))],
+            vec![port("y", y, None), port("q", q, None)],
+        )
+    }
+
+    #[test]
+    fn test_analyze_enumerates_every_row() {
+        let (mut c, inputs, outputs) = bench();
+        let table = TruthTable::analyze(&mut c, inputs, outputs.clone()).unwrap();
+        assert_eq!(table.header(), vec!["a", "b", "y", "q"]);
+        assert_eq!(table.rows.len(), 16);
+        // a is the high half of the row number.
+        assert_eq!(table.rows[7].inputs, vec![1, 3]);
+        assert_eq!(
+            table.rows[7].outputs,
+            vec![Value::new(1, 2), Value::new(0, 2)]
+        );
+        assert_eq!(table.cells(&table.rows[7]), vec!["01", "11", "01", "00"]);
+        // Left holding the last row.
+        assert_eq!(c.read_output(outputs[0].key), Value::new(3, 2));
+
+        let csv = table.to_csv();
+        assert!(csv.starts_with("a,b,y,q\n00,00,00,11\n"));
+        assert_eq!(csv.lines().count(), 17);
+        let md = table.to_markdown();
+        assert!(
+            md.starts_with("| a | b | y | q |\n| --- | --- | --- | --- |\n| 00 | 00 | 00 | 11 |\n")
+        );
+    }
+
+    #[test]
+    fn test_analyze_rejects_state_and_wide_inputs() {
+        let (mut c, inputs, outputs) = bench();
+        let wide = vec![port("w", inputs[0].key, Some(17))];
+        assert_eq!(
+            TruthTable::analyze(&mut c, wide, outputs.clone()),
+            Err(AnalyzeError::TooWide { bits: 17 })
+        );
+        assert_eq!(
+            TruthTable::analyze(&mut c, Vec::new(), outputs.clone()),
+            Err(AnalyzeError::NoInputs)
+        );
+
+        c.add_component(Component::reg(1, ClockEdge::Rising));
+        assert!(!c.is_combinational());
+        assert_eq!(
+            TruthTable::analyze(&mut c, inputs, outputs),
+            Err(AnalyzeError::Stateful)
+        );
+    }
+
+    #[test]
+    fn test_analyze_settles_rows_the_batch_cant_take() {
+        // An adder has no bit-parallel form, so each row settles in turn.
+        let mut c = Circuit::new();
+        let a = c.add_component(Component::input(0, 2));
+        let b = c.add_component(Component::input(0, 2));
+        let add = c.add_component(Component::adder(2));
+        let sum = c.add_component(Component::output());
+        c.link(a, PinId::output(0), add, PinId::input(0));
+        c.link(b, PinId::output(0), add, PinId::input(1));
+        c.link(add, PinId::output(0), sum, PinId::input(0));
+        c.settle().unwrap();
+        let table = TruthTable::analyze(
+            &mut c,
+            vec![port("a", a, Some(2)), port("b", b, Some(2))],
+            vec![port("sum", sum, None)],
+        )
+        .unwrap();
+        assert_eq!(table.rows[7].outputs, vec![Value::new(0, 2)]);
+        assert_eq!(c.read_output(sum), Value::new(2, 2));
+    }
+
+    #[test]
+    fn test_csv_quotes_awkward_names() {
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("plain"), "plain");
+    }
+}
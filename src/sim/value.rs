@@ -1,66 +1,221 @@
 use std::ops::{Add, BitAnd, BitOr, BitXor, Not, Sub};
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum Value {
     #[default]
     Floating,
     Fixed {
-        bits: u32,
+        bits: u64,
         width: u8,
     },
-    // Wiring itself is wrong (e.g. a short, or a width mismatch) - distinct
-    // from Floating ("no value yet"). Set only by Circuit::resolve_net();
+    // A bus with some bits undriven (`z`) or unknown (`x`), the rest known in
+    // `bits`. The masks are disjoint and `bits` is clear under both. Never
+    // all-known (that's Fixed) or all-Z (that's Floating) - build it with
+    // Value::from_masks(), which normalizes to those two.
+    Partial {
+        bits: u64,
+        z: u64,
+        x: u64,
+        width: u8,
+    },
+    // Wiring itself is wrong (a width mismatch) - distinct from Floating ("no
+    // value yet"). Drivers that merely disagree resolve per bit to X instead;
+    // see Value::resolve(). Set only by Circuit::resolve_net();
     // never produced by CombLogic::evaluate(), and falls through the same
     // catch-all arms as any non-Fixed value, so it never propagates past the
     // one net where it's flagged.
     Invalid,
 }
 
+/// One bit of a `Value`, in four-state (IEEE 1364) terms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bit {
+    Zero,
+    One,
+    Z,
+    X,
+}
+
+impl Bit {
+    pub fn as_char(self) -> char {
+        match self {
+            Bit::Zero => '0',
+            Bit::One => '1',
+            Bit::Z => 'z',
+            Bit::X => 'x',
+        }
+    }
+}
+
 impl Value {
+    // The widest bus a pin may declare: one bit per `bits` position.
+    pub const MAX_WIDTH: u8 = 64;
     pub const ZERO: Value = Value::Fixed { bits: 0, width: 1 };
     pub const ONE: Value = Value::Fixed { bits: 1, width: 1 };
 
-    pub fn new(bits: u32, width: u8) -> Self {
+    pub fn new(bits: u64, width: u8) -> Self {
         Value::Fixed { bits, width }
     }
-    pub fn mask(width: u8) -> u32 {
-        if width >= 32 {
-            u32::MAX
+    pub fn mask(width: u8) -> u64 {
+        if width >= 64 {
+            u64::MAX
         } else {
             (1 << width) - 1
         }
     }
-}
 
-impl BitAnd for Value {
-    type Output = Self;
+    // Builds a per-bit value from its planes. X wins over Z on a bit flagged
+    // as both; a value with no Z/X bits is Fixed, an all-Z one is Floating.
+    pub fn from_masks(bits: u64, z: u64, x: u64, width: u8) -> Self {
+        let mask = Self::mask(width);
+        let x = x & mask;
+        let z = z & mask & !x;
+        let bits = bits & !(z | x);
+        if z | x == 0 {
+            Value::Fixed { bits, width }
+        } else if z == mask {
+            Value::Floating
+        } else {
+            Value::Partial { bits, z, x, width }
+        }
+    }
 
-    fn bitand(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Self::Fixed { bits: a, width: n }, Self::Fixed { bits: b, width: m }) if n == m => {
-                Self::Fixed {
-                    bits: a & b,
-                    width: n,
+    // Every bit X: what a component outputs when a Z/X input bit leaves its
+    // whole result unknown (e.g. an adder).
+    pub fn unknown(width: u8) -> Self {
+        Self::from_masks(0, 0, u64::MAX, width)
+    }
+
+    // Floating has no width of its own, and Invalid's is in dispute.
+    pub fn width(self) -> Option<u8> {
+        match self {
+            Value::Fixed { width, .. } | Value::Partial { width, .. } => Some(width),
+            Value::Floating | Value::Invalid => None,
+        }
+    }
+
+    pub fn is_partial(self) -> bool {
+        matches!(self, Value::Partial { .. })
+    }
+
+    // Bit `i`, counted from the LSB. A Floating value reads Z at every
+    // index and an Invalid one X.
+    pub fn bit(self, i: u8) -> Bit {
+        let at = |m: u64| i < 64 && m & (1 << i) != 0;
+        match self {
+            Value::Floating => Bit::Z,
+            Value::Invalid => Bit::X,
+            Value::Fixed { bits, .. } => {
+                if at(bits) {
+                    Bit::One
+                } else {
+                    Bit::Zero
                 }
             }
+            Value::Partial { bits, z, x, .. } => {
+                if at(x) {
+                    Bit::X
+                } else if at(z) {
+                    Bit::Z
+                } else if at(bits) {
+                    Bit::One
+                } else {
+                    Bit::Zero
+                }
+            }
+        }
+    }
+
+    // (known-one, known-zero, width) planes for the bitwise ops; a Z or X
+    // bit sits in neither plane. None for Floating/Invalid.
+    fn levels(self) -> Option<(u64, u64, u8)> {
+        match self {
+            Value::Fixed { bits, width } => Some((bits, !bits & Self::mask(width), width)),
+            Value::Partial { bits, z, x, width } => {
+                Some((bits, !(bits | z | x) & Self::mask(width), width))
+            }
+            Value::Floating | Value::Invalid => None,
+        }
+    }
+
+    // (z, x) planes; both empty for a Fixed value.
+    fn unknowns(self) -> (u64, u64) {
+        match self {
+            Value::Partial { z, x, .. } => (z, x),
+            _ => (0, 0),
+        }
+    }
+
+    // Combines two drivers of one net, bit by bit: a Z bit yields to the other
+    // driver, two equal levels stand, and two disagreeing levels (or an X on
+    // either side) are a conflict, X. Floating (all Z) is the identity, so
+    // folding a net's sources from Floating gives its resolved value. Drivers
+    // of different widths can't be lined up bit for bit, so that's Invalid.
+    pub fn resolve(self, other: Self) -> Self {
+        match (self, other) {
+            (Value::Floating, v) | (v, Value::Floating) => v,
+            (Value::Invalid, _) | (_, Value::Invalid) => Value::Invalid,
+            (a, b) => match (a.levels(), b.levels()) {
+                (Some((a1, a0, n)), Some((b1, b0, m))) if n == m => {
+                    let ((az, ax), (bz, bx)) = (a.unknowns(), b.unknowns());
+                    let x = ax | bx | (a1 & b0) | (a0 & b1);
+                    Self::from_masks(a1 | b1, az & bz, x, n)
+                }
+                _ => Value::Invalid,
+            },
+        }
+    }
+
+    // Fills this value's Z bits from `weak`, leaving every driven (0/1/X) bit
+    // as is: how a pull resistor's level shows through an undriven bus.
+    pub fn fill_z(self, weak: Self) -> Self {
+        match (self, weak) {
+            (Value::Floating, v) | (v, Value::Floating) => v,
+            (Value::Invalid, _) | (_, Value::Invalid) => Value::Invalid,
+            (Value::Fixed { .. }, _) => self,
+            (Value::Partial { bits, z, x, width }, w) if w.width() == Some(width) => {
+                let (wz, wx) = w.unknowns();
+                let w_bits = w.levels().map_or(0, |(ones, _, _)| ones);
+                Self::from_masks(bits | (w_bits & z), z & wz, x | (wx & z), width)
+            }
+            _ => Value::Invalid,
+        }
+    }
+
+    // Inverse of levels(): a bit in neither plane is X. A gate never drives Z.
+    fn from_levels(ones: u64, zeros: u64, width: u8) -> Self {
+        Self::from_masks(ones, 0, !(ones | zeros), width)
+    }
+
+    // Shared body of the bitwise operators: `op` maps the operands' (ones,
+    // zeros) planes to the result's. Mismatched widths or a Floating/Invalid
+    // operand give Floating.
+    fn bitwise(self, rhs: Self, op: impl Fn((u64, u64), (u64, u64)) -> (u64, u64)) -> Self {
+        match (self.levels(), rhs.levels()) {
+            (Some((a1, a0, n)), Some((b1, b0, m))) if n == m => {
+                let (ones, zeros) = op((a1, a0), (b1, b0));
+                Self::from_levels(ones, zeros, n)
+            }
             _ => Self::Floating,
         }
     }
 }
 
+impl BitAnd for Value {
+    type Output = Self;
+
+    // A known 0 on either side decides the bit, even against a Z/X.
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.bitwise(rhs, |(a1, a0), (b1, b0)| (a1 & b1, a0 | b0))
+    }
+}
+
 impl BitOr for Value {
     type Output = Self;
 
+    // A known 1 on either side decides the bit, even against a Z/X.
     fn bitor(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Self::Fixed { bits: a, width: n }, Self::Fixed { bits: b, width: m }) if n == m => {
-                Self::Fixed {
-                    bits: a | b,
-                    width: n,
-                }
-            }
-            _ => Self::Floating,
-        }
+        self.bitwise(rhs, |(a1, a0), (b1, b0)| (a1 | b1, a0 & b0))
     }
 }
 
@@ -68,18 +223,14 @@ impl BitXor for Value {
     type Output = Self;
 
     fn bitxor(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Self::Fixed { bits: a, width: n }, Self::Fixed { bits: b, width: m }) if n == m => {
-                Self::Fixed {
-                    bits: a ^ b,
-                    width: n,
-                }
-            }
-            _ => Self::Floating,
-        }
+        self.bitwise(rhs, |(a1, a0), (b1, b0)| {
+            ((a1 & b0) | (a0 & b1), (a1 & b1) | (a0 & b0))
+        })
     }
 }
 
+// Arithmetic has no per-bit answer: any Z/X bit in an operand makes the
+// whole result unknown.
 impl Add for Value {
     type Output = Self;
 
@@ -91,6 +242,9 @@ impl Add for Value {
                     width: n,
                 }
             }
+            (a, b) if (a.is_partial() || b.is_partial()) && a.width() == b.width() => {
+                Self::unknown(a.width().unwrap_or(0))
+            }
             _ => Self::Floating,
         }
     }
@@ -107,6 +261,9 @@ impl Sub for Value {
                     width: n,
                 }
             }
+            (a, b) if (a.is_partial() || b.is_partial()) && a.width() == b.width() => {
+                Self::unknown(a.width().unwrap_or(0))
+            }
             _ => Self::Floating,
         }
     }
@@ -121,16 +278,122 @@ impl Not for Value {
                 bits: !bits & Self::mask(width),
                 width,
             },
+            Self::Partial { .. } => {
+                let (ones, zeros, width) = self.levels().unwrap();
+                Self::from_levels(zeros, ones, width)
+            }
             Self::Floating => Self::Floating,
             Self::Invalid => Self::Floating,
         }
     }
 }
 
+// The number base a value is shown in, by the signal viewer and the CLI.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum Radix {
+    #[default]
+    Hex,
+    Binary,
+    Unsigned,
+    Signed,
+}
+
+impl Radix {
+    pub fn label(self) -> &'static str {
+        match self {
+            Radix::Hex => "hex",
+            Radix::Binary => "bin",
+            Radix::Unsigned => "uint",
+            Radix::Signed => "int",
+        }
+    }
+
+    pub const ALL: [Radix; 4] = [Radix::Hex, Radix::Binary, Radix::Unsigned, Radix::Signed];
+}
+
+// A value's text in the given radix. `Floating` shows as `Z`, `Invalid` as `X`.
+// A partially-known bus has no single number, so it always shows per bit:
+// binary digits with `z`/`x`, or in hex a `Z` for an all-Z nibble and an `X`
+// for any other nibble with an unknown bit.
+pub fn format_value(v: Value, radix: Radix) -> String {
+    match v {
+        Value::Floating => "Z".to_string(),
+        Value::Invalid => "X".to_string(),
+        Value::Fixed { bits, width } => match radix {
+            Radix::Hex => format!("0x{:X}", bits),
+            Radix::Binary => format!("{:0width$b}", bits, width = width as usize),
+            Radix::Unsigned => bits.to_string(),
+            Radix::Signed => sign_extend(bits, width).to_string(),
+        },
+        Value::Partial { width, .. } => match radix {
+            Radix::Hex => {
+                let digits: String = (0..width.div_ceil(4))
+                    .rev()
+                    .map(|nibble| hex_digit(v, nibble * 4, width))
+                    .collect();
+                format!("0x{}", digits)
+            }
+            _ => (0..width).rev().map(|i| v.bit(i).as_char()).collect(),
+        },
+    }
+}
+
+// The hex digit for bits `lo..lo+4` of a partially-known `v`, clipped to `width`.
+fn hex_digit(v: Value, lo: u8, width: u8) -> char {
+    let bits: Vec<Bit> = (lo..(lo + 4).min(width)).map(|i| v.bit(i)).collect();
+    if bits.iter().all(|&b| b == Bit::Z) {
+        'Z'
+    } else if bits.iter().any(|&b| matches!(b, Bit::Z | Bit::X)) {
+        'X'
+    } else {
+        let n = bits
+            .iter()
+            .rev()
+            .fold(0, |acc, &b| acc << 1 | (b == Bit::One) as u32);
+        char::from_digit(n, 16).unwrap().to_ascii_uppercase()
+    }
+}
+
+// Reads `bits` as a two's-complement value of `width` bits.
+fn sign_extend(bits: u64, width: u8) -> i64 {
+    let w = width as u32;
+    if w == 0 || w >= 64 {
+        return bits as i64;
+    }
+    if bits & (1 << (w - 1)) != 0 {
+        (bits | !Value::mask(width)) as i64
+    } else {
+        bits as i64
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_value_radices() {
+        let v = Value::new(0xB, 4);
+        assert_eq!(format_value(v, Radix::Hex), "0xB");
+        assert_eq!(format_value(v, Radix::Binary), "1011");
+        assert_eq!(format_value(v, Radix::Unsigned), "11");
+        // 0b1011 as a signed 4-bit value is -5.
+        assert_eq!(format_value(v, Radix::Signed), "-5");
+        assert_eq!(format_value(Value::Floating, Radix::Hex), "Z");
+        assert_eq!(format_value(Value::Invalid, Radix::Hex), "X");
+    }
+
+    #[test]
+    fn partial_value_formats_per_bit() {
+        // Bits 7..0 = z z z z 1 x 0 1
+        let v = Value::from_masks(0b0000_1001, 0b1111_0000, 0b0000_0100, 8);
+        assert_eq!(format_value(v, Radix::Binary), "zzzz1x01");
+        assert_eq!(format_value(v, Radix::Hex), "0xZX");
+        assert_eq!(format_value(v, Radix::Unsigned), "zzzz1x01");
+        let v = Value::from_masks(0b0101_0000, 0b0000_1111, 0, 8);
+        assert_eq!(format_value(v, Radix::Hex), "0x5Z");
+    }
+
     #[test]
     fn test_or() {
         assert_eq!(
@@ -202,8 +465,110 @@ mod tests {
         assert_eq!(Value::mask(0), 0);
         assert_eq!(Value::mask(1), 0b1);
         assert_eq!(Value::mask(3), 0b111);
-        assert_eq!(Value::mask(31), u32::MAX >> 1);
-        assert_eq!(Value::mask(32), u32::MAX);
-        assert_eq!(Value::mask(33), u32::MAX);
+        assert_eq!(Value::mask(31), u32::MAX as u64 >> 1);
+        assert_eq!(Value::mask(32), u32::MAX as u64);
+        assert_eq!(Value::mask(63), u64::MAX >> 1);
+        assert_eq!(Value::mask(64), u64::MAX);
+        assert_eq!(Value::mask(65), u64::MAX);
+    }
+
+    #[test]
+    fn test_from_masks_normalizes() {
+        assert_eq!(Value::from_masks(0b101, 0, 0, 3), Value::new(0b101, 3));
+        assert_eq!(Value::from_masks(0, 0b111, 0, 3), Value::Floating);
+        assert_eq!(
+            Value::from_masks(0b111, 0b010, 0b010, 3),
+            Value::Partial {
+                bits: 0b101,
+                z: 0,
+                x: 0b010,
+                width: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_bit() {
+        let v = Value::from_masks(0b0001, 0b0100, 0b1000, 4);
+        assert_eq!(v.bit(0), Bit::One);
+        assert_eq!(v.bit(1), Bit::Zero);
+        assert_eq!(v.bit(2), Bit::Z);
+        assert_eq!(v.bit(3), Bit::X);
+        assert_eq!(Value::Floating.bit(0), Bit::Z);
+        assert_eq!(Value::Invalid.bit(0), Bit::X);
+    }
+
+    #[test]
+    fn test_and_known_zero_dominates_unknown() {
+        // bit0: 1&Z -> X, bit1: 0&Z -> 0, bit2: 1&1 -> 1
+        let a = Value::from_masks(0b100, 0b011, 0, 3);
+        let b = Value::new(0b101, 3);
+        assert_eq!(a & b, Value::from_masks(0b100, 0, 0b001, 3));
+    }
+
+    #[test]
+    fn test_or_known_one_dominates_unknown() {
+        // bit0: 1|X -> 1, bit1: 0|X -> X
+        let a = Value::from_masks(0, 0, 0b11, 2);
+        let b = Value::new(0b01, 2);
+        assert_eq!(a | b, Value::from_masks(0b01, 0, 0b10, 2));
+    }
+
+    #[test]
+    fn test_xor_and_not_turn_z_into_x() {
+        let a = Value::from_masks(0b01, 0b10, 0, 2);
+        assert_eq!(a ^ Value::new(0b11, 2), Value::from_masks(0b00, 0, 0b10, 2));
+        assert_eq!(!a, Value::from_masks(0b00, 0, 0b10, 2));
+    }
+
+    #[test]
+    fn test_arithmetic_with_unknown_bit_is_all_x() {
+        let a = Value::from_masks(0b01, 0b10, 0, 2);
+        assert_eq!(a + Value::new(1, 2), Value::unknown(2));
+        assert_eq!(Value::new(1, 2) - a, Value::unknown(2));
+        assert_eq!(a + Value::new(1, 3), Value::Floating);
+    }
+
+    #[test]
+    fn test_resolve_z_bits_yield_to_the_other_driver() {
+        // Two drivers each own one nibble of a byte and leave the other Z.
+        let lo = Value::from_masks(0x05, 0xF0, 0, 8);
+        let hi = Value::from_masks(0xA0, 0x0F, 0, 8);
+        assert_eq!(lo.resolve(hi), Value::new(0xA5, 8));
+        assert_eq!(Value::Floating.resolve(lo), lo);
+        assert_eq!(Value::Floating.resolve(Value::Floating), Value::Floating);
+    }
+
+    #[test]
+    fn test_resolve_disagreement_is_x_only_where_drivers_differ() {
+        // bit0 agrees (1/1), bit1 disagrees (0/1), bit2 is Z on one side.
+        let a = Value::from_masks(0b001, 0, 0, 3);
+        let b = Value::from_masks(0b011, 0b100, 0, 3);
+        assert_eq!(a.resolve(b), Value::from_masks(0b001, 0, 0b010, 3));
+        assert_eq!(Value::ONE.resolve(Value::ZERO), Value::unknown(1));
+        assert_eq!(Value::ONE.resolve(Value::ONE), Value::ONE);
+    }
+
+    #[test]
+    fn test_resolve_x_wins_and_width_mismatch_is_invalid() {
+        assert_eq!(
+            Value::unknown(2).resolve(Value::new(1, 2)),
+            Value::unknown(2)
+        );
+        assert_eq!(Value::new(1, 2).resolve(Value::new(1, 3)), Value::Invalid);
+        assert_eq!(Value::Invalid.resolve(Value::Floating), Value::Invalid);
+    }
+
+    #[test]
+    fn test_fill_z_only_touches_undriven_bits() {
+        // bit0 driven 1, bit1 driven 0, bit2 Z, bit3 X; pulled up to 1111.
+        let driven = Value::from_masks(0b0001, 0b0100, 0b1000, 4);
+        assert_eq!(
+            driven.fill_z(Value::new(0xF, 4)),
+            Value::from_masks(0b0101, 0, 0b1000, 4)
+        );
+        assert_eq!(Value::Floating.fill_z(Value::ZERO), Value::ZERO);
+        assert_eq!(Value::ONE.fill_z(Value::ZERO), Value::ONE);
+        assert_eq!(Value::ONE.fill_z(Value::Floating), Value::ONE);
     }
 }
@@ -0,0 +1,490 @@
+// Circuit statistics as a bill of materials: every part of one circuit of a
+// project, counted by kind, variant (a gate's op and input count, a mux's
+// ways, a memory's words, a subcircuit's circuit) and width. Subcircuits are
+// counted through, once per instance, so a circuit placed four times brings
+// four times its parts. Their boundary Inputs and Outputs are pins, not
+// parts, and only the top circuit's count. Headless.
+//
+// The optional transistor column is a rough static-CMOS estimate per part -
+// a full adder of 28, an edge-triggered flip-flop of 24, a 6T RAM cell - good
+// for comparing two designs, not for sizing a chip. NAND2 equivalents divide
+// it by the NAND2's four transistors, the usual area measure.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::io::ProjectFile;
+use crate::sim::component::{ComponentSpec, FanDirection, GateOp, PullDirection};
+use crate::sim::truth_table::csv_field;
+
+// One line of the bill: a kind of part and how it's configured.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Part {
+    pub kind: &'static str,
+    // Empty where the kind has no variants.
+    pub variant: String,
+    // None for parts with no single width (an Output, a subcircuit).
+    pub width: Option<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub part: Part,
+    pub count: u64,
+    // Estimated transistors in one of the part.
+    pub transistors: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bom {
+    // Sorted by part.
+    pub rows: Vec<Row>,
+    // Subcircuits left uncounted because they would contain themselves.
+    pub recursive: Vec<String>,
+}
+
+impl Bom {
+    // The bill for circuit `index` of a validated project.
+    pub fn of(file: &ProjectFile, index: usize) -> Self {
+        let mut counts = BTreeMap::new();
+        let mut recursive = vec![];
+        count(
+            file,
+            index,
+            1,
+            true,
+            &mut vec![index],
+            &mut counts,
+            &mut recursive,
+        );
+        let rows = counts
+            .into_iter()
+            .map(|(part, (count, transistors))| Row {
+                part,
+                count,
+                transistors,
+            })
+            .collect();
+        recursive.sort();
+        recursive.dedup();
+        Bom { rows, recursive }
+    }
+
+    // Part counts per kind, in kind order.
+    pub fn by_kind(&self) -> Vec<(&'static str, u64)> {
+        let mut kinds: BTreeMap<&'static str, u64> = BTreeMap::new();
+        for row in &self.rows {
+            *kinds.entry(row.part.kind).or_default() += row.count;
+        }
+        kinds.into_iter().collect()
+    }
+
+    // Part counts per width, widthless parts first.
+    pub fn by_width(&self) -> Vec<(Option<u8>, u64)> {
+        let mut widths: BTreeMap<Option<u8>, u64> = BTreeMap::new();
+        for row in &self.rows {
+            *widths.entry(row.part.width).or_default() += row.count;
+        }
+        widths.into_iter().collect()
+    }
+
+    pub fn transistors(&self) -> u64 {
+        self.rows.iter().map(|r| r.count * r.transistors).sum()
+    }
+
+    pub fn nand2_equivalents(&self) -> u64 {
+        self.transistors().div_ceil(4)
+    }
+
+    // One line per row, with the estimate's columns only if asked for.
+    pub fn to_csv(&self, estimate: bool) -> String {
+        let mut out = String::from("kind,variant,width,count");
+        if estimate {
+            out += ",transistors_each,transistors,nand2_equivalents";
+        }
+        out.push('\n');
+        for row in &self.rows {
+            let width = row.part.width.map_or(String::new(), |w| w.to_string());
+            out += &format!(
+                "{},{},{width},{}",
+                row.part.kind,
+                csv_field(&row.part.variant),
+                row.count
+            );
+            if estimate {
+                let total = row.count * row.transistors;
+                out += &format!(",{},{total},{}", row.transistors, total.div_ceil(4));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+// Adds circuit `index`'s parts, `times` over, to `counts` (part -> (count,
+// transistors each)). `stack` holds the circuits being counted, outermost
+// first, so a reference back into one of them is caught.
+fn count(
+    file: &ProjectFile,
+    index: usize,
+    times: u64,
+    top: bool,
+    stack: &mut Vec<usize>,
+    counts: &mut BTreeMap<Part, (u64, u64)>,
+    recursive: &mut Vec<String>,
+) {
+    let entry = &file.circuits[index];
+    let targets: HashMap<usize, usize> = entry
+        .subcircuits
+        .iter()
+        .map(|s| (s.component, s.circuit))
+        .collect();
+    for (i, record) in entry.snapshot.components.iter().enumerate() {
+        let spec = &record.spec;
+        if !top && matches!(spec, ComponentSpec::Input(_) | ComponentSpec::Output(_)) {
+            continue;
+        }
+        let (part, transistors) = describe(spec);
+        counts.entry(part).or_insert((0, transistors)).0 += times;
+        let Some(&target) = targets.get(&i) else {
+            continue;
+        };
+        if stack.contains(&target) {
+            recursive.push(file.circuits[target].name.clone());
+            continue;
+        }
+        stack.push(target);
+        count(file, target, times, false, stack, counts, recursive);
+        stack.pop();
+    }
+}
+
+// A spec's line on the bill, and its transistor estimate.
+fn describe(spec: &ComponentSpec) -> (Part, u64) {
+    let part = |kind, variant: String, width| Part {
+        kind,
+        variant,
+        width,
+    };
+    let words = |address_width: u8| 1u64 << address_width;
+    match spec {
+        ComponentSpec::Input(i) => (part("Input", String::new(), Some(i.width)), 0),
+        ComponentSpec::Constant(c) => (part("Constant", String::new(), Some(c.width)), 0),
+        ComponentSpec::PullResistor(p) => {
+            let direction = match p.direction {
+                PullDirection::Up => "up",
+                PullDirection::Down => "down",
+            };
+            (part("Pull Resistor", direction.into(), Some(p.width)), 0)
+        }
+        ComponentSpec::Output(_) => (part("Output", String::new(), None), 0),
+        ComponentSpec::Probe(_) => (part("Probe", String::new(), None), 0),
+        ComponentSpec::ClockSource(_) => (part("Clock", String::new(), Some(1)), 0),
+        ComponentSpec::Gate(g) => {
+            let n = g.n_inputs as u64;
+            let per_bit = match g.op {
+                GateOp::Not => 2,
+                GateOp::Nand | GateOp::Nor => 2 * n,
+                GateOp::And | GateOp::Or => 2 * n + 2,
+                GateOp::Xor | GateOp::Xnor => 12 * n.saturating_sub(1),
+            };
+            let op = match g.op {
+                GateOp::And => "AND",
+                GateOp::Or => "OR",
+                GateOp::Xor => "XOR",
+                GateOp::Nand => "NAND",
+                GateOp::Nor => "NOR",
+                GateOp::Xnor => "XNOR",
+                GateOp::Not => "NOT",
+            };
+            let variant = match g.op {
+                GateOp::Not => op.to_string(),
+                _ => format!("{op} {}-input", g.n_inputs),
+            };
+            (
+                part("Gate", variant, Some(g.width)),
+                per_bit * u64::from(g.width),
+            )
+        }
+        ComponentSpec::TriState(t) => (
+            part("Controlled Buffer", String::new(), Some(t.data_width)),
+            6 * u64::from(t.data_width),
+        ),
+        // A tree of 2:1 muxes, 14 transistors each per bit.
+        ComponentSpec::Mux(m) => (
+            part(
+                "Mux",
+                format!("{}:1", words(m.sel_width)),
+                Some(m.data_width),
+            ),
+            14 * (words(m.sel_width) - 1) * u64::from(m.data_width),
+        ),
+        // A decoder, then an AND per output bit.
+        ComponentSpec::Demux(d) => (
+            part(
+                "Demux",
+                format!("1:{}", words(d.sel_width)),
+                Some(d.data_width),
+            ),
+            words(d.sel_width) * (6 * u64::from(d.data_width) + 2 * u64::from(d.sel_width)),
+        ),
+        ComponentSpec::Encoder(e) => (
+            part(
+                "Priority Encoder",
+                format!("{}:{}", words(e.sel_width), e.sel_width),
+                Some(e.sel_width),
+            ),
+            6 * words(e.sel_width) * u64::from(e.sel_width),
+        ),
+        // A flip-flop and a load-enable mux per bit.
+        ComponentSpec::Reg(r) => (
+            part("Register", String::new(), Some(r.data_width)),
+            38 * u64::from(r.data_width),
+        ),
+        ComponentSpec::ShiftReg(s) => {
+            let mut variant = format!("{} stages", s.num_stages);
+            if s.parallel_load {
+                variant += ", parallel load";
+            }
+            let bits = s.num_stages as u64 * u64::from(s.data_width);
+            let per_bit = if s.parallel_load { 38 } else { 24 };
+            (
+                part("Shift Register", variant, Some(s.data_width)),
+                per_bit * bits,
+            )
+        }
+        // A flip-flop and a half adder per bit.
+        ComponentSpec::Counter(c) => (
+            part("Counter", String::new(), Some(c.data_width)),
+            42 * u64::from(c.data_width),
+        ),
+        ComponentSpec::Adder(a) => (
+            part("Adder", String::new(), Some(a.data_width)),
+            28 * u64::from(a.data_width),
+        ),
+        ComponentSpec::Subtractor(s) => (
+            part("Subtractor", String::new(), Some(s.data_width)),
+            30 * u64::from(s.data_width),
+        ),
+        // An array multiplier: an AND per partial-product bit, a full adder
+        // per reduction step.
+        ComponentSpec::Multiplier(m) => {
+            let w = u64::from(m.data_width);
+            (
+                part("Multiplier", String::new(), Some(m.data_width)),
+                6 * w * w + 28 * w * w.saturating_sub(1),
+            )
+        }
+        // An array divider: a full subtractor and a restore mux per cell.
+        ComponentSpec::Divider(d) => {
+            let w = u64::from(d.data_width);
+            (
+                part("Divider", String::new(), Some(d.data_width)),
+                44 * w * w,
+            )
+        }
+        ComponentSpec::Comparator(c) => (
+            part("Comparator", String::new(), Some(c.data_width)),
+            24 * u64::from(c.data_width),
+        ),
+        // One transistor per stored bit, plus the address decoder.
+        ComponentSpec::Rom(r) => (
+            part(
+                "ROM",
+                format!("{} words", words(r.address_width)),
+                Some(r.data_width),
+            ),
+            words(r.address_width) * (u64::from(r.data_width) + 6),
+        ),
+        // Six per stored bit, plus the address decoder.
+        ComponentSpec::Ram(r) => (
+            part(
+                "RAM",
+                format!("{} words", words(r.address_width)),
+                Some(r.data_width),
+            ),
+            words(r.address_width) * (6 * u64::from(r.data_width) + 6),
+        ),
+        ComponentSpec::DFlipFlop(_) => (part("D Flip-Flop", String::new(), Some(1)), 24),
+        ComponentSpec::TFlipFlop(_) => (part("T Flip-Flop", String::new(), Some(1)), 36),
+        ComponentSpec::JKFlipFlop(_) => (part("JK Flip-Flop", String::new(), Some(1)), 38),
+        ComponentSpec::SRFlipFlop(_) => (part("SR Flip-Flop", String::new(), Some(1)), 28),
+        ComponentSpec::Splitter {
+            width,
+            arm_bits,
+            direction,
+        } => {
+            let kind = match direction {
+                FanDirection::Right => "Splitter",
+                FanDirection::Left => "Combiner",
+            };
+            (
+                part(kind, format!("{} arms", arm_bits.len()), Some(*width)),
+                0,
+            )
+        }
+        // Its parts are counted where it's placed.
+        ComponentSpec::Subcircuit { name, .. } => (part("Subcircuit", name.clone(), None), 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::geometry::GridPos;
+    use crate::io::{CircuitEntry, CircuitSnapshot, ComponentEntry, SubcircuitRef};
+    use crate::sim::component::{Adder, ComponentSpec, DocId, Gate, Input, Output};
+
+    fn entry(
+        name: &str,
+        specs: Vec<ComponentSpec>,
+        subcircuits: Vec<SubcircuitRef>,
+    ) -> CircuitEntry {
+        CircuitEntry {
+            name: name.to_string(),
+            snapshot: CircuitSnapshot {
+                components: specs
+                    .into_iter()
+                    .map(|spec| ComponentEntry {
+                        spec,
+                        grid_pos: GridPos::new(0, 0),
+                        delay: None,
+                    })
+                    .collect(),
+                ..CircuitSnapshot::default()
+            },
+            subcircuits,
+        }
+    }
+
+    fn gate(op: GateOp, n_inputs: usize, width: u8) -> ComponentSpec {
+        ComponentSpec::Gate(Gate {
+            op,
+            n_inputs,
+            width,
+        })
+    }
+
+    fn sub(name: &str) -> ComponentSpec {
+        ComponentSpec::Subcircuit {
+            doc: DocId::default(),
+            name: name.to_string(),
+            input_widths: vec![1, 1],
+            output_widths: vec![1],
+        }
+    }
+
+    // Top places `half` twice; each half places `xor` once beside an AND.
+    fn project() -> ProjectFile {
+        let xor = entry(
+            "xor",
+            vec![
+                ComponentSpec::Input(Input::new(0, 1)),
+                ComponentSpec::Input(Input::new(0, 1)),
+                gate(GateOp::Xor, 2, 1),
+                ComponentSpec::Output(Output::default()),
+            ],
+            vec![],
+        );
+        let half = entry(
+            "half",
+            vec![
+                ComponentSpec::Input(Input::new(0, 1)),
+                sub("xor"),
+                gate(GateOp::And, 2, 1),
+            ],
+            vec![SubcircuitRef {
+                component: 1,
+                circuit: 2,
+            }],
+        );
+        let top = entry(
+            "top",
+            vec![
+                ComponentSpec::Input(Input::new(0, 8)),
+                sub("half"),
+                sub("half"),
+                ComponentSpec::Adder(Adder { data_width: 8 }),
+                gate(GateOp::And, 3, 8),
+            ],
+            vec![
+                SubcircuitRef {
+                    component: 1,
+                    circuit: 1,
+                },
+                SubcircuitRef {
+                    component: 2,
+                    circuit: 1,
+                },
+            ],
+        );
+        ProjectFile::new(0, vec![top, half, xor])
+    }
+
+    #[test]
+    fn test_counts_multiply_through_the_hierarchy() {
+        let bom = Bom::of(&project(), 0);
+        let counts: Vec<(&str, &str, Option<u8>, u64)> = bom
+            .rows
+            .iter()
+            .map(|r| (r.part.kind, r.part.variant.as_str(), r.part.width, r.count))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("Adder", "", Some(8), 1),
+                ("Gate", "AND 2-input", Some(1), 2),
+                ("Gate", "AND 3-input", Some(8), 1),
+                ("Gate", "XOR 2-input", Some(1), 2),
+                ("Input", "", Some(8), 1),
+                ("Subcircuit", "half", None, 2),
+                ("Subcircuit", "xor", None, 2),
+            ]
+        );
+        assert_eq!(
+            bom.by_kind(),
+            vec![("Adder", 1), ("Gate", 5), ("Input", 1), ("Subcircuit", 4)]
+        );
+        assert_eq!(bom.by_width(), vec![(None, 4), (Some(1), 4), (Some(8), 3)]);
+        // Adder 28*8, AND3 8*8, two AND2 at 6, two XOR2 at 12.
+        assert_eq!(bom.transistors(), 224 + 64 + 12 + 24);
+        assert_eq!(bom.nand2_equivalents(), 81);
+        assert!(bom.recursive.is_empty());
+    }
+
+    #[test]
+    fn test_csv_lists_each_row_with_an_optional_estimate() {
+        let csv = Bom::of(&project(), 1).to_csv(true);
+        assert_eq!(
+            csv,
+            "kind,variant,width,count,transistors_each,transistors,nand2_equivalents\n\
+             Gate,AND 2-input,1,1,6,6,2\n\
+             Gate,XOR 2-input,1,1,12,12,3\n\
+             Input,,1,1,0,0,0\n\
+             Subcircuit,xor,,1,0,0,0\n"
+        );
+        let plain = Bom::of(&project(), 2).to_csv(false);
+        assert!(plain.starts_with("kind,variant,width,count\n"));
+        assert!(plain.contains("Output,,,1\n"));
+    }
+
+    #[test]
+    fn test_a_subcircuit_containing_itself_is_counted_once() {
+        let mut file = project();
+        // `xor` now places `half`, which places it.
+        file.circuits[2].snapshot.components.push(ComponentEntry {
+            spec: sub("half"),
+            grid_pos: GridPos::new(0, 0),
+            delay: None,
+        });
+        file.circuits[2].subcircuits.push(SubcircuitRef {
+            component: 4,
+            circuit: 1,
+        });
+        let bom = Bom::of(&file, 1);
+        assert_eq!(bom.recursive, vec!["half".to_string()]);
+        assert_eq!(
+            bom.by_kind(),
+            vec![("Gate", 2), ("Input", 1), ("Subcircuit", 2)]
+        );
+    }
+}
@@ -0,0 +1,274 @@
+// Recorded stimulus for replay: one value per named signal per clock tick,
+// read from a CSV table or sampled out of a VCD dump (see vcd::read). Signals
+// are matched to labelled Inputs by name (see test_vector::ports) and applied
+// as Command::SetInput, one row before each tick. Headless; the GUI's replay
+// lives in Document::replay_stimulus.
+
+use crate::sim::command::Command;
+use crate::sim::component::CompKey;
+use crate::sim::test_vector::{parse_value, Port};
+use crate::sim::value::Value;
+use crate::vcd;
+
+// File types the GUI's Replay Stimulus picker offers.
+pub const STIMULUS_FILE_EXTS: &[&str] = &["vcd", "csv"];
+
+// A VCD sampled at too fine a period would otherwise ask for millions of ticks.
+pub const MAX_ROWS: usize = 100_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StimulusError {
+    // 1-based; 0 when the error isn't tied to a line.
+    pub line: usize,
+    pub msg: String,
+}
+
+impl std::fmt::Display for StimulusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            0 => write!(f, "{}", self.msg),
+            line => write!(f, "line {line}: {}", self.msg),
+        }
+    }
+}
+
+impl std::error::Error for StimulusError {}
+
+impl From<vcd::VcdError> for StimulusError {
+    fn from(e: vcd::VcdError) -> Self {
+        Self {
+            line: e.line,
+            msg: e.msg,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stimulus {
+    pub signals: Vec<String>,
+    // One per tick, one entry per signal. None leaves the Input as it was.
+    pub rows: Vec<Vec<Option<u64>>>,
+}
+
+// A stimulus column bound to the Input it drives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    pub column: usize,
+    pub name: String,
+    pub key: CompKey,
+    pub width: u8,
+}
+
+impl Stimulus {
+    // A header row of signal names, then one row of values per tick. Values
+    // take the test-vector forms (decimal, 0x…, 0b…); an empty cell, `-`, `x`
+    // or `z` holds the previous value. Blank lines and `#` comments are skipped.
+    pub fn from_csv(text: &str) -> Result<Self, StimulusError> {
+        let mut lines = text.lines().enumerate().filter_map(|(i, line)| {
+            let line = line.split('#').next().unwrap_or("").trim();
+            (!line.is_empty()).then_some((i + 1, line))
+        });
+        let (_, header) = lines.next().ok_or(StimulusError {
+            line: 1,
+            msg: "no header row".to_string(),
+        })?;
+        let signals: Vec<String> = header.split(',').map(|s| s.trim().to_string()).collect();
+
+        let mut rows = Vec::new();
+        for (line, text) in lines {
+            let cells: Vec<&str> = text.split(',').map(str::trim).collect();
+            if cells.len() != signals.len() {
+                return Err(StimulusError {
+                    line,
+                    msg: format!("expected {} values, got {}", signals.len(), cells.len()),
+                });
+            }
+            let row = cells
+                .iter()
+                .map(|&cell| match cell.to_ascii_lowercase().as_str() {
+                    "" | "-" | "x" | "z" => Ok(None),
+                    _ => parse_value(cell).map(Some).ok_or_else(|| StimulusError {
+                        line,
+                        msg: format!("bad value {cell:?}"),
+                    }),
+                })
+                .collect::<Result<_, _>>()?;
+            rows.push(row);
+        }
+        Ok(Self { signals, rows })
+    }
+
+    // Samples a dump every `period` time units from 0 through its last change,
+    // one row per sample. Bits that are x or z hold the previous value, like an
+    // empty CSV cell. A name declared in several scopes keeps its first `$var`.
+    pub fn from_vcd(text: &str, period: u64) -> Result<Self, StimulusError> {
+        let period = period.max(1);
+        let mut dumped = vcd::read(text)?;
+        let mut seen = std::collections::HashSet::new();
+        dumped.retain(|s| seen.insert(s.name.clone()));
+
+        let end = dumped
+            .iter()
+            .filter_map(|s| s.changes.last().map(|&(t, _)| t))
+            .max()
+            .unwrap_or(0);
+        let n_rows = (end / period) as usize + 1;
+        if n_rows > MAX_ROWS {
+            return Err(StimulusError {
+                line: 0,
+                msg: format!(
+                    "sampling every {period} gives {n_rows} ticks (at most {MAX_ROWS}); \
+                     use a longer period"
+                ),
+            });
+        }
+        let rows = (0..n_rows as u64)
+            .map(|k| {
+                dumped
+                    .iter()
+                    .map(|s| match s.value_at(k * period) {
+                        Value::Fixed { bits, .. } => Some(bits),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(Self {
+            signals: dumped.into_iter().map(|s| s.name).collect(),
+            rows,
+        })
+    }
+
+    // Matches signals to the Inputs among `ports` by name. Signals with no
+    // Input are ignored - a dump usually covers the outputs too - but at least
+    // one must match.
+    pub fn bind(&self, ports: &[Port]) -> Result<Vec<Binding>, StimulusError> {
+        let bindings: Vec<Binding> = self
+            .signals
+            .iter()
+            .enumerate()
+            .filter_map(|(column, name)| {
+                // Only Inputs have a width.
+                let (key, width) = ports
+                    .iter()
+                    .find_map(|p| (&p.name == name).then_some((p.key, p.width?)))?;
+                Some(Binding {
+                    column,
+                    name: name.clone(),
+                    key,
+                    width,
+                })
+            })
+            .collect();
+        if bindings.is_empty() {
+            return Err(StimulusError {
+                line: 0,
+                msg: format!(
+                    "no signal matches a labelled Input (signals: {})",
+                    self.signals.join(", ")
+                ),
+            });
+        }
+        Ok(bindings)
+    }
+
+    // The SetInputs that apply row `row`, truncating values to each Input's
+    // width.
+    pub fn commands<'a>(
+        &'a self,
+        row: usize,
+        bindings: &'a [Binding],
+    ) -> impl Iterator<Item = Command> + 'a {
+        bindings.iter().filter_map(move |b| {
+            let bits = self.rows[row][b.column]?;
+            Some(Command::SetInput {
+                comp: b.key,
+                bits: bits & Value::mask(b.width),
+                width: b.width,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::circuit::Circuit;
+    use crate::sim::component::{Component, PinId};
+
+    #[test]
+    fn test_from_csv() {
+        let csv = "a, b\n# reset\n0, 0x3\n1,\n0b1, z\n";
+        let stim = Stimulus::from_csv(csv).unwrap();
+        assert_eq!(stim.signals, vec!["a", "b"]);
+        assert_eq!(
+            stim.rows,
+            vec![
+                vec![Some(0), Some(3)],
+                vec![Some(1), None],
+                vec![Some(1), None]
+            ]
+        );
+        let err = Stimulus::from_csv("a,b\n1\n").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(Stimulus::from_csv("a\n2q\n").unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn test_from_vcd_samples_each_period() {
+        let vcd = "$var wire 1 ! en $end
+$var wire 4 \" d $end
+$var wire 1 # en $end
+#0
+0!
+bx \"
+#10
+1!
+b101 \"
+#25
+0!
+";
+        let stim = Stimulus::from_vcd(vcd, 10).unwrap();
+        assert_eq!(stim.signals, vec!["en", "d"]);
+        assert_eq!(
+            stim.rows,
+            vec![
+                vec![Some(0), None],
+                vec![Some(1), Some(5)],
+                vec![Some(1), Some(5)]
+            ]
+        );
+        assert!(Stimulus::from_vcd("$var wire 1 ! a $end\n#1000000\n1!\n", 1).is_err());
+    }
+
+    #[test]
+    fn test_bind_and_apply() {
+        let mut c = Circuit::new();
+        let a = c.add_component(Component::input(0, 2));
+        let y = c.add_component(Component::output());
+        c.link(a, PinId::output(0), y, PinId::input(0));
+        c.settle().unwrap();
+        let port = |name: &str, key, width| Port {
+            name: name.to_string(),
+            key,
+            width,
+        };
+        // An Output sharing the name can't be driven.
+        let ports = vec![port("a", y, None), port("a", a, Some(2))];
+
+        let stim = Stimulus::from_csv("a,nope\n7,1\n-,1\n").unwrap();
+        let bindings = stim.bind(&ports).unwrap();
+        assert_eq!(bindings.len(), 1);
+        assert_eq!((bindings[0].key, bindings[0].width), (a, 2));
+
+        for command in stim.commands(0, &bindings) {
+            c.apply(command);
+        }
+        c.settle().unwrap();
+        assert_eq!(c.read_output(y), Value::new(3, 2));
+        assert_eq!(stim.commands(1, &bindings).count(), 0);
+
+        let unmatched = Stimulus::from_csv("b\n1\n").unwrap();
+        assert!(unmatched.bind(&ports).is_err());
+    }
+}
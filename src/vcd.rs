@@ -0,0 +1,418 @@
+// IEEE 1364 Value Change Dump: `write` renders recorded probe traces (see
+// gui::signal_viewer::SignalLog) for GTKWave and friends; `read` parses a dump
+// back into per-signal change lists, e.g. an HDL testbench's waveform to
+// replay into Inputs (see stimulus.rs). Headless.
+
+use std::collections::HashMap;
+
+use crate::sim::value::Value;
+
+// Osmilog time is in abstract units; VCD needs a real unit, so one unit is
+// written as 1 ns.
+const TIMESCALE: &str = "1ns";
+
+// One signal's samples, index = clock tick.
+pub struct Trace<'a> {
+    pub name: &'a str,
+    pub samples: &'a [Value],
+}
+
+// Renders `traces` as a VCD document under one module scope named `scope`.
+// Tick `k` lands at time `k * tick_period`. Each variable is as wide as the
+// widest sample in its trace (1 bit if it never held a sized value); Floating
+// and unknown bits are written as `z` and `x`. A trace shorter than the others
+// just holds its last value.
+pub fn write(scope: &str, traces: &[Trace], tick_period: u64) -> String {
+    let widths: Vec<u8> = traces
+        .iter()
+        .map(|t| {
+            t.samples
+                .iter()
+                .filter_map(|v| v.width())
+                .max()
+                .unwrap_or(1)
+        })
+        .collect();
+    let ids: Vec<String> = (0..traces.len()).map(identifier).collect();
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "$version osmilog {} $end\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+    out.push_str(&format!("$timescale {TIMESCALE} $end\n"));
+    out.push_str(&format!("$scope module {} $end\n", reference(scope, "top")));
+    for ((trace, width), id) in traces.iter().zip(&widths).zip(&ids) {
+        out.push_str(&format!(
+            "$var wire {width} {id} {} $end\n",
+            reference(trace.name, "probe")
+        ));
+    }
+    out.push_str("$upscope $end\n$enddefinitions $end\n");
+
+    let ticks = traces.iter().map(|t| t.samples.len()).max().unwrap_or(0);
+    for tick in 0..ticks {
+        let changes: Vec<String> = traces
+            .iter()
+            .zip(&widths)
+            .zip(&ids)
+            .filter_map(|((trace, &width), id)| {
+                let value = *trace.samples.get(tick)?;
+                let changed = tick == 0 || trace.samples[tick - 1] != value;
+                changed.then(|| value_change(value, width, id))
+            })
+            .collect();
+        if tick == 0 {
+            out.push_str("#0\n$dumpvars\n");
+            changes.iter().for_each(|c| out.push_str(&format!("{c}\n")));
+            out.push_str("$end\n");
+        } else if !changes.is_empty() {
+            out.push_str(&format!("#{}\n", tick as u64 * tick_period));
+            changes.iter().for_each(|c| out.push_str(&format!("{c}\n")));
+        }
+    }
+    // Closes the last sample's interval so viewers draw it for a full tick.
+    if ticks > 0 {
+        out.push_str(&format!("#{}\n", ticks as u64 * tick_period));
+    }
+    out
+}
+
+// A parse error, with the 1-based line it's on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VcdError {
+    pub line: usize,
+    pub msg: String,
+}
+
+impl std::fmt::Display for VcdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.msg)
+    }
+}
+
+impl std::error::Error for VcdError {}
+
+// One `$var` of a dump and every value it took, in time order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signal {
+    // The reference name alone, without its scope or any bit-select.
+    pub name: String,
+    pub width: u8,
+    pub changes: Vec<(u64, Value)>,
+}
+
+impl Signal {
+    // The value at `time`: the last change at or before it, Floating before
+    // the first.
+    pub fn value_at(&self, time: u64) -> Value {
+        let after = self.changes.partition_point(|&(t, _)| t <= time);
+        after
+            .checked_sub(1)
+            .map_or(Value::Floating, |i| self.changes[i].1)
+    }
+}
+
+// Parses a dump's `$var`s and value changes. Scopes, comments and the other
+// header sections are skipped, as are real-valued changes. Variables wider
+// than Value::MAX_WIDTH are rejected.
+pub fn read(text: &str) -> Result<Vec<Signal>, VcdError> {
+    let mut tokens = text
+        .lines()
+        .enumerate()
+        .flat_map(|(i, line)| line.split_whitespace().map(move |t| (i + 1, t)));
+    let mut signals: Vec<Signal> = Vec::new();
+    // Several $vars may share one identifier code.
+    let mut by_id: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut time = 0;
+
+    while let Some((line, token)) = tokens.next() {
+        let err = |msg: String| VcdError { line, msg };
+        match token {
+            "$var" => {
+                let mut field = |what: &str| {
+                    tokens
+                        .next()
+                        .map(|(_, t)| t)
+                        .ok_or_else(|| err(format!("$var is missing its {what}")))
+                };
+                let _kind = field("type")?;
+                let size = field("size")?;
+                let id = field("identifier")?;
+                let name = field("name")?;
+                let width = size
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|w| (1..=Value::MAX_WIDTH).contains(w))
+                    .ok_or_else(|| err(format!("unsupported $var size {size:?}")))?;
+                skip_to_end(&mut tokens);
+                by_id.entry(id).or_default().push(signals.len());
+                signals.push(Signal {
+                    name: name.to_string(),
+                    width,
+                    changes: Vec::new(),
+                });
+            }
+            // Markers around the initial values: the changes inside them are
+            // read like any others.
+            "$dumpvars" | "$dumpall" | "$dumpon" | "$dumpoff" | "$end" => {}
+            t if t.starts_with('$') => skip_to_end(&mut tokens),
+            t if t.starts_with('#') => {
+                time = t[1..]
+                    .parse()
+                    .map_err(|_| err(format!("bad timestamp {t:?}")))?;
+            }
+            t if t.starts_with(['b', 'B']) => {
+                let (_, id) = tokens
+                    .next()
+                    .ok_or_else(|| err("vector change is missing its identifier".into()))?;
+                let digits = &t[1..];
+                for &i in by_id.get(id).into_iter().flatten() {
+                    let value = parse_vector(digits, signals[i].width)
+                        .ok_or_else(|| err(format!("bad vector value {t:?}")))?;
+                    signals[i].changes.push((time, value));
+                }
+            }
+            t if t.starts_with(['r', 'R']) => {
+                tokens.next();
+            }
+            t => {
+                // Split after the first character, not byte: a stray
+                // non-ASCII token is an error, not a bad char boundary.
+                let (digit, id) = t.split_at(t.chars().next().map_or(0, char::len_utf8));
+                let value =
+                    parse_vector(digit, 1).ok_or_else(|| err(format!("unexpected token {t:?}")))?;
+                for &i in by_id.get(id).into_iter().flatten() {
+                    signals[i].changes.push((time, value));
+                }
+            }
+        }
+    }
+    Ok(signals)
+}
+
+// Consumes tokens through the next `$end`.
+fn skip_to_end<'a>(tokens: &mut impl Iterator<Item = (usize, &'a str)>) {
+    for (_, t) in tokens.by_ref() {
+        if t == "$end" {
+            return;
+        }
+    }
+}
+
+// A vector's digits, MSB first, as a `width`-bit Value. Fewer digits than the
+// width extend to the left as VCD specifies: with 0 after a leading 0 or 1,
+// else with the leading x or z.
+fn parse_vector(digits: &str, width: u8) -> Option<Value> {
+    let digits = digits.as_bytes();
+    if digits.is_empty() || digits.len() > width as usize {
+        return None;
+    }
+    let fill = match digits[0].to_ascii_lowercase() {
+        b'0' | b'1' => b'0',
+        other => other,
+    };
+    let (mut bits, mut z, mut x) = (0u64, 0u64, 0u64);
+    let padded =
+        std::iter::repeat_n(fill, width as usize - digits.len()).chain(digits.iter().copied());
+    for (i, d) in padded.enumerate() {
+        let bit = 1u64 << (width as usize - 1 - i);
+        match d.to_ascii_lowercase() {
+            b'0' => {}
+            b'1' => bits |= bit,
+            b'z' => z |= bit,
+            b'x' => x |= bit,
+            _ => return None,
+        }
+    }
+    Some(Value::from_masks(bits, z, x, width))
+}
+
+// "0!" for a scalar, "b0z1x !" for a vector, MSB first.
+fn value_change(value: Value, width: u8, id: &str) -> String {
+    let bits: String = (0..width).rev().map(|i| value.bit(i).as_char()).collect();
+    if width == 1 {
+        format!("{bits}{id}")
+    } else {
+        format!("b{bits} {id}")
+    }
+}
+
+// The n-th short identifier code, drawn from the printable ASCII range VCD
+// allows: "!", "\"", ... "~", then "!!", "\"!", ...
+fn identifier(mut n: usize) -> String {
+    const FIRST: u8 = b'!';
+    const COUNT: usize = (b'~' - b'!' + 1) as usize;
+    let mut id = String::new();
+    loop {
+        id.push((FIRST + (n % COUNT) as u8) as char);
+        n /= COUNT;
+        if n == 0 {
+            return id;
+        }
+        n -= 1;
+    }
+}
+
+// A name as a VCD reference: no whitespace, never empty.
+fn reference(name: &str, fallback: &str) -> String {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect();
+    if name.is_empty() {
+        fallback.to_string()
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_dumps_changes_only() {
+        let clk = [Value::ZERO, Value::ONE, Value::ONE, Value::ZERO];
+        let bus = [
+            Value::Floating,
+            Value::new(0b10, 2),
+            Value::from_masks(0b01, 0b10, 0, 2),
+            Value::Invalid,
+        ];
+        let vcd = write(
+            "Main",
+            &[
+                Trace {
+                    name: "clk",
+                    samples: &clk,
+                },
+                Trace {
+                    name: "data bus",
+                    samples: &bus,
+                },
+            ],
+            10,
+        );
+        let body = vcd.split_once("$timescale").unwrap().1;
+        assert_eq!(
+            body,
+            " 1ns $end
+$scope module Main $end
+$var wire 1 ! clk $end
+$var wire 2 \" data_bus $end
+$upscope $end
+$enddefinitions $end
+#0
+$dumpvars
+0!
+bzz \"
+$end
+#10
+1!
+b10 \"
+#20
+bz1 \"
+#30
+0!
+bxx \"
+#40
+"
+        );
+    }
+
+    #[test]
+    fn test_read_round_trips_write() {
+        let clk = [Value::ZERO, Value::ONE, Value::ZERO];
+        let bus = [
+            Value::new(0b1010, 4),
+            Value::from_masks(0b0001, 0b0110, 0, 4),
+            Value::unknown(4),
+        ];
+        let vcd = write(
+            "Main",
+            &[
+                Trace {
+                    name: "clk",
+                    samples: &clk,
+                },
+                Trace {
+                    name: "bus",
+                    samples: &bus,
+                },
+            ],
+            5,
+        );
+        let signals = read(&vcd).unwrap();
+        assert_eq!(signals.len(), 2);
+        assert_eq!((signals[0].name.as_str(), signals[0].width), ("clk", 1));
+        assert_eq!((signals[1].name.as_str(), signals[1].width), ("bus", 4));
+        for (tick, (&c, &b)) in clk.iter().zip(&bus).enumerate() {
+            let t = tick as u64 * 5;
+            assert_eq!(signals[0].value_at(t), c);
+            assert_eq!(signals[1].value_at(t + 2), b);
+        }
+    }
+
+    #[test]
+    fn test_read_foreign_dump() {
+        // Scoped, aliased, bit-selected and left-extended, as HDL tools write.
+        let vcd = "$date today $end
+$timescale 1ps $end
+$scope module tb $end
+$var wire 8 # data [7:0] $end
+$scope module dut $end
+$var reg 1 % en $end
+$var wire 8 # d $end
+$upscope $end
+$upscope $end
+$enddefinitions $end
+#0
+$dumpvars
+bx #
+0%
+$end
+#100
+b101 #
+1%
+#150
+bz1 #
+";
+        let signals = read(vcd).unwrap();
+        let names: Vec<&str> = signals.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["data", "en", "d"]);
+        assert_eq!(signals[0].value_at(50), Value::unknown(8));
+        assert_eq!(signals[0].value_at(120), Value::new(0b101, 8));
+        assert_eq!(signals[2].value_at(120), Value::new(0b101, 8));
+        assert_eq!(
+            signals[0].value_at(150),
+            Value::from_masks(0b1, 0b1111_1110, 0, 8)
+        );
+        assert_eq!(signals[1].value_at(100), Value::ONE);
+
+        assert_eq!(read("$var wire 2 ! a $end\n#0\nb2 !").unwrap_err().line, 3);
+        assert_eq!(read("$var wire 65 ! w $end").unwrap_err().line, 1);
+    }
+
+    #[test]
+    fn test_read_rejects_a_non_ascii_token() {
+        let err = read("$var wire 1 ! a $end\n#0\n\u{e9}!").unwrap_err();
+        assert_eq!(err.line, 3);
+        assert!(err.to_string().contains("unexpected token"));
+    }
+
+    #[test]
+    fn test_identifiers_are_unique_and_printable() {
+        let ids: Vec<String> = (0..200).map(identifier).collect();
+        assert_eq!(ids[0], "!");
+        assert_eq!(ids[93], "~");
+        assert_eq!(ids[94], "!!");
+        let unique: std::collections::HashSet<_> = ids.iter().collect();
+        assert_eq!(unique.len(), ids.len());
+        assert!(ids
+            .iter()
+            .flat_map(|id| id.chars())
+            .all(|c| c.is_ascii_graphic()));
+    }
+}
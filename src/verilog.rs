@@ -0,0 +1,1314 @@
+// Synthesizable structural Verilog (IEEE 1364-2005) for taking a design on to
+// FPGA tools: `export` renders every circuit of a project as one module.
+// Boundary Inputs and Outputs become its ports, in subcircuit pin order and
+// named the way the CLI names them; wire groups become wires, named after a
+// tunnel label where they carry one, with same-label tunnels joined into one
+// net; combinational parts become continuous assigns, sequential parts
+// always-blocks, and placed subcircuits instances of their circuit's module.
+// Headless.
+//
+// Osmilog's global clock has no wire of its own, so every module with a part
+// that follows it - a sequential part whose clock pin is unwired, a clock
+// source, or an instance of such a module - gets an explicit `clk` input, and
+// "one global tick" becomes "one rising edge of clk". A wired clock pin
+// clocks its part on that net's configured edge instead.
+//
+// What Verilog can't say the way the sim does is approximated: an unwired
+// input reads as its Floating meaning where the part defines one (an enable as
+// on, a carry-in or reset as off), else as `z`; the state starts at zero
+// through register initializers, and a RAM at its power-on image; and a
+// subcircuit that would contain itself is left out with a comment.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::io::ProjectFile;
+use crate::project::{boundary_pins, snapshot_groups};
+use crate::sim::component::{
+    ClockEdge, ComponentSpec, CounterConf, DFlipFlopConf, FanDirection, GateOp, InIdx,
+    JKFlipFlopConf, OutIdx, OverflowAction, PinId, PullDirection, Ram, ReadBehavior, RegConf,
+    SRFlipFlopConf, Splitter, TFlipFlopConf,
+};
+use crate::sim::value::Value;
+
+// Reserved words of IEEE 1364-2005, which no identifier may be.
+const KEYWORDS: &str = "\
+always and assign automatic begin buf bufif0 bufif1 case casex casez cell cmos config deassign \
+default defparam design disable edge else end endcase endconfig endfunction endgenerate \
+endmodule endprimitive endspecify endtable endtask event for force forever fork function \
+generate genvar highz0 highz1 if ifnone incdir include initial inout input instance integer \
+join large liblist library localparam macromodule medium module nand negedge nmos nor \
+noshowcancelled not notif0 notif1 or output parameter pmos posedge primitive pull0 pull1 \
+pulldown pullup pulsestyle_ondetect pulsestyle_onevent rcmos real realtime reg release repeat \
+rnmos rpmos rtran rtranif0 rtranif1 scalared showcancelled signed small specify specparam \
+strong0 strong1 supply0 supply1 table task time tran tranif0 tranif1 tri tri0 tri1 triand \
+trior trireg unsigned use uwire vectored wait wand weak0 weak1 while wire wor xnor xor";
+
+// The port every module that follows the global clock gets.
+const CLOCK_PORT: &str = "clk";
+
+// Renders the whole project, one module per circuit in project order.
+pub fn export(file: &ProjectFile) -> String {
+    let nets: Vec<Nets> = (0..file.circuits.len())
+        .map(|i| Nets::of(file, i))
+        .collect();
+    let mut modules = Names::default();
+    let mut interfaces: Vec<Interface> = nets
+        .iter()
+        .enumerate()
+        .map(|(i, nets)| Interface::of(file, i, nets, &mut modules))
+        .collect();
+    let targets: Vec<HashMap<usize, usize>> = (0..file.circuits.len())
+        .map(|i| instance_targets(file, i, &interfaces))
+        .collect();
+    let mut clocked = vec![None; file.circuits.len()];
+    for (i, iface) in interfaces.iter_mut().enumerate() {
+        iface.clocked = needs_clock(file, i, &nets, &targets, &mut clocked);
+    }
+
+    let mut out = String::from("// Exported from osmilog. `clk` carries the global clock.\n");
+    for (i, nets) in nets.iter().enumerate() {
+        out.push('\n');
+        out.push_str(&Module::new(file, i, nets, &interfaces, &targets[i]).render());
+    }
+    out
+}
+
+// Hands out identifiers within one scope: each a legal identifier, and
+// distinct from every one handed out before it.
+#[derive(Default, Clone)]
+struct Names {
+    taken: HashSet<String>,
+}
+
+impl Names {
+    fn claim(&mut self, wanted: &str) -> String {
+        let base = identifier(wanted);
+        let mut name = base.clone();
+        let mut n = 2;
+        while !self.taken.insert(name.clone()) {
+            name = format!("{base}_{n}");
+            n += 1;
+        }
+        name
+    }
+}
+
+// `text` made a legal identifier: any other character becomes `_`, and one
+// that would start with a digit, or be a keyword, gains a `_`.
+fn identifier(text: &str) -> String {
+    let mut id: String = text
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if id.is_empty() || id.starts_with(|c: char| c.is_ascii_digit()) {
+        id.insert(0, '_');
+    }
+    if KEYWORDS.split_whitespace().any(|k| k == id) {
+        id.push('_');
+    }
+    id
+}
+
+// The nets of one circuit, by index: its wire groups, merged where tunnels
+// share a label, plus one net for each output pin wired to nothing (so every
+// part has somewhere to drive).
+struct Nets {
+    // Per component record, the net of each input pin (None if unwired) and
+    // of each output pin.
+    inputs: Vec<Vec<Option<usize>>>,
+    outputs: Vec<Vec<usize>>,
+    widths: Vec<u8>,
+    labels: Vec<Option<String>>,
+    pulls: Vec<Option<PullDirection>>,
+}
+
+impl Nets {
+    fn of(file: &ProjectFile, index: usize) -> Self {
+        let snapshot = &file.circuits[index].snapshot;
+        let groups = snapshot_groups(file, index);
+
+        let mut parent: Vec<usize> = (0..groups.len()).collect();
+        fn find(parent: &[usize], mut g: usize) -> usize {
+            while parent[g] != g {
+                g = parent[g];
+            }
+            g
+        }
+        let mut by_label: HashMap<&str, usize> = HashMap::new();
+        for (g, group) in groups.iter().enumerate() {
+            for &t in &group.tunnels {
+                let first = *by_label
+                    .entry(snapshot.tunnels[t].label.as_str())
+                    .or_insert(g);
+                let (a, b) = (find(&parent, first), find(&parent, g));
+                parent[b] = a;
+            }
+        }
+        let class: Vec<usize> = (0..groups.len()).map(|g| find(&parent, g)).collect();
+        let mut pin_count = vec![0; groups.len()];
+        for (g, group) in groups.iter().enumerate() {
+            pin_count[class[g]] += group.pins.len();
+        }
+
+        let mut inputs: Vec<Vec<Option<usize>>> = snapshot
+            .components
+            .iter()
+            .map(|c| vec![None; c.spec.n_inputs()])
+            .collect();
+        let mut outputs: Vec<Vec<Option<usize>>> = snapshot
+            .components
+            .iter()
+            .map(|c| vec![None; c.spec.n_outputs()])
+            .collect();
+        let mut labels: Vec<Option<String>> = Vec::new();
+        let mut net_of_class: HashMap<usize, usize> = HashMap::new();
+        for (g, group) in groups.iter().enumerate() {
+            // A pin alone on its net is as good as unwired, as in the sim.
+            if pin_count[class[g]] < 2 {
+                continue;
+            }
+            let net = *net_of_class.entry(class[g]).or_insert_with(|| {
+                labels.push(None);
+                labels.len() - 1
+            });
+            if let Some(&t) = group.tunnels.first() {
+                labels[net].get_or_insert_with(|| snapshot.tunnels[t].label.clone());
+            }
+            for &(comp, pin) in &group.pins {
+                match pin {
+                    PinId::In(InIdx(i)) => inputs[comp][i as usize] = Some(net),
+                    PinId::Out(OutIdx(i)) => outputs[comp][i as usize] = Some(net),
+                }
+            }
+        }
+        let outputs: Vec<Vec<usize>> = outputs
+            .into_iter()
+            .map(|pins| {
+                pins.into_iter()
+                    .map(|net| {
+                        net.unwrap_or_else(|| {
+                            labels.push(None);
+                            labels.len() - 1
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut widths = vec![1; labels.len()];
+        let mut pulls = vec![None; labels.len()];
+        for (i, c) in snapshot.components.iter().enumerate() {
+            let (in_widths, out_widths) = pin_widths(&c.spec);
+            let ins = inputs[i].iter().zip(in_widths);
+            let outs = outputs[i].iter().map(Some).zip(out_widths);
+            for (net, width) in ins.map(|(n, w)| (n.as_ref(), w)).chain(outs) {
+                if let (Some(&net), Some(width)) = (net, width) {
+                    widths[net] = widths[net].max(width);
+                }
+            }
+            if let ComponentSpec::PullResistor(pull) = &c.spec {
+                pulls[outputs[i][0]] = Some(pull.direction);
+            }
+        }
+        Self {
+            inputs,
+            outputs,
+            widths,
+            labels,
+            pulls,
+        }
+    }
+}
+
+// Each input and output pin's width; None where a pin takes any width.
+fn pin_widths(spec: &ComponentSpec) -> (Vec<Option<u8>>, Vec<Option<u8>>) {
+    if let ComponentSpec::Subcircuit {
+        input_widths,
+        output_widths,
+        ..
+    } = spec
+    {
+        let known = |widths: &[u8]| widths.iter().map(|&w| Some(w)).collect();
+        return (known(input_widths), known(output_widths));
+    }
+    let comp = spec.to_component();
+    (
+        (0..spec.n_inputs())
+            .map(|i| comp.input_width(InIdx(i as u8)))
+            .collect(),
+        (0..spec.n_outputs())
+            .map(|i| comp.output_width(OutIdx(i as u8)))
+            .collect(),
+    )
+}
+
+// The clock pin of a part that has one, and the edge it latches on.
+fn clock_pin(spec: &ComponentSpec) -> Option<(usize, ClockEdge)> {
+    match spec {
+        ComponentSpec::Reg(r) => Some((RegConf::CLOCK_PIN, r.clock_edge)),
+        ComponentSpec::ShiftReg(sr) => Some((sr.clock_pin(), sr.clock_edge)),
+        ComponentSpec::Ram(r) => Some((Ram::CLOCK_PIN, r.clock_edge)),
+        ComponentSpec::DFlipFlop(ff) => Some((DFlipFlopConf::CLOCK_PIN, ff.clock_edge)),
+        ComponentSpec::TFlipFlop(ff) => Some((TFlipFlopConf::CLOCK_PIN, ff.clock_edge)),
+        ComponentSpec::JKFlipFlop(ff) => Some((JKFlipFlopConf::CLOCK_PIN, ff.clock_edge)),
+        ComponentSpec::SRFlipFlop(ff) => Some((SRFlipFlopConf::CLOCK_PIN, ff.clock_edge)),
+        ComponentSpec::Counter(c) => Some((CounterConf::CLOCK_PIN, c.clock_edge)),
+        _ => None,
+    }
+}
+
+// One module port: the Input or Output record it stands for, and its name
+// and width.
+struct Port {
+    comp: usize,
+    name: String,
+    width: u8,
+}
+
+// A circuit's module name and ports, worked out for every circuit before any
+// module is written, so an instance can name its target's ports. `names`
+// holds the module's scope with the ports (and `clk`) already claimed.
+struct Interface {
+    module: String,
+    names: Names,
+    inputs: Vec<Port>,
+    outputs: Vec<Port>,
+    clocked: bool,
+}
+
+impl Interface {
+    fn of(file: &ProjectFile, index: usize, nets: &Nets, modules: &mut Names) -> Self {
+        let entry = &file.circuits[index];
+        let records = &entry.snapshot.components;
+        let (inputs, outputs) = boundary_pins(
+            records
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (&c.spec, c.grid_pos, i)),
+        );
+        let mut names = Names::default();
+        names.claim(CLOCK_PORT);
+        let mut port = |prefix: &str, i: usize, comp: usize, width: u8| {
+            let spec = &records[comp].spec;
+            let wanted = spec
+                .port_name()
+                .map_or_else(|| format!("{prefix}{i}"), str::to_string);
+            Port {
+                comp,
+                name: names.claim(&wanted),
+                width,
+            }
+        };
+        let inputs = inputs
+            .into_iter()
+            .enumerate()
+            .map(|(i, comp)| {
+                let width = nets.widths[nets.outputs[comp][0]];
+                port("in", i, comp, width)
+            })
+            .collect();
+        let outputs = outputs
+            .into_iter()
+            .enumerate()
+            .map(|(i, comp)| {
+                let width = nets.inputs[comp][0].map_or(1, |net| nets.widths[net]);
+                port("out", i, comp, width)
+            })
+            .collect();
+        Self {
+            module: modules.claim(&entry.name),
+            names,
+            inputs,
+            outputs,
+            clocked: false,
+        }
+    }
+}
+
+// The circuit each placed subcircuit of circuit `index` instantiates, by
+// component record, for those that can be: the reference must resolve, the
+// target mustn't (even indirectly) contain this circuit, and its ports must
+// still match the cached pin counts.
+fn instance_targets(
+    file: &ProjectFile,
+    index: usize,
+    interfaces: &[Interface],
+) -> HashMap<usize, usize> {
+    let entry = &file.circuits[index];
+    entry
+        .subcircuits
+        .iter()
+        .filter(|s| {
+            let ComponentSpec::Subcircuit {
+                input_widths,
+                output_widths,
+                ..
+            } = &entry.snapshot.components[s.component].spec
+            else {
+                return false;
+            };
+            let target = &interfaces[s.circuit];
+            !contains(file, s.circuit, index)
+                && target.inputs.len() == input_widths.len()
+                && target.outputs.len() == output_widths.len()
+        })
+        .map(|s| (s.component, s.circuit))
+        .collect()
+}
+
+// Whether circuit `outer` is, or places (at any depth), circuit `inner`.
+fn contains(file: &ProjectFile, outer: usize, inner: usize) -> bool {
+    let mut seen = vec![false; file.circuits.len()];
+    let mut stack = vec![outer];
+    while let Some(i) = stack.pop() {
+        if i == inner {
+            return true;
+        }
+        if !std::mem::replace(&mut seen[i], true) {
+            stack.extend(file.circuits[i].subcircuits.iter().map(|s| s.circuit));
+        }
+    }
+    false
+}
+
+// Whether circuit `index` follows the global clock, memoized in `clocked`.
+// Terminates because `targets` never closes a cycle.
+fn needs_clock(
+    file: &ProjectFile,
+    index: usize,
+    nets: &[Nets],
+    targets: &[HashMap<usize, usize>],
+    clocked: &mut [Option<bool>],
+) -> bool {
+    if let Some(known) = clocked[index] {
+        return known;
+    }
+    let records = &file.circuits[index].snapshot.components;
+    let own = records.iter().enumerate().any(|(i, c)| match &c.spec {
+        ComponentSpec::ClockSource(_) => true,
+        spec => clock_pin(spec).is_some_and(|(pin, _)| nets[index].inputs[i][pin].is_none()),
+    });
+    let needs = own
+        || targets[index]
+            .values()
+            .any(|&target| needs_clock(file, target, nets, targets, clocked));
+    clocked[index] = Some(needs);
+    needs
+}
+
+// `[w-1:0] ` for a vector, nothing for a single bit.
+fn range(width: u8) -> String {
+    if width > 1 {
+        format!("[{}:0] ", width - 1)
+    } else {
+        String::new()
+    }
+}
+
+fn hex(width: u8, bits: u64) -> String {
+    format!("{width}'h{:x}", bits & Value::mask(width))
+}
+
+fn float(width: u8) -> String {
+    format!("{width}'bz")
+}
+
+// One module being written.
+struct Module<'a> {
+    file: &'a ProjectFile,
+    index: usize,
+    nets: &'a Nets,
+    interfaces: &'a [Interface],
+    targets: &'a HashMap<usize, usize>,
+    names: Names,
+    net_names: Vec<String>,
+    // Ports that couldn't take over their net outright, assigned to it or
+    // from it in the body instead.
+    aliases: Vec<String>,
+    body: String,
+}
+
+impl<'a> Module<'a> {
+    // Names every net: an input port names the net its Input drives, an
+    // output port the net its Output reads if nothing else has, a tunnel label
+    // whatever it's on, and the rest are numbered.
+    fn new(
+        file: &'a ProjectFile,
+        index: usize,
+        nets: &'a Nets,
+        interfaces: &'a [Interface],
+        targets: &'a HashMap<usize, usize>,
+    ) -> Self {
+        let iface = &interfaces[index];
+        let mut names = iface.names.clone();
+        let mut net_names: Vec<Option<String>> = vec![None; nets.widths.len()];
+        let mut aliases = Vec::new();
+        for port in &iface.inputs {
+            let net = nets.outputs[port.comp][0];
+            if net_names[net].is_none() {
+                net_names[net] = Some(port.name.clone());
+            } else {
+                aliases.push((net, port.name.clone(), false));
+            }
+        }
+        let mut unwired_outputs = Vec::new();
+        for port in &iface.outputs {
+            match nets.inputs[port.comp][0] {
+                Some(net) if net_names[net].is_none() => net_names[net] = Some(port.name.clone()),
+                Some(net) => aliases.push((net, port.name.clone(), true)),
+                None => unwired_outputs.push(port),
+            }
+        }
+        let mut numbered = 0;
+        let net_names: Vec<String> = net_names
+            .into_iter()
+            .zip(&nets.labels)
+            .map(|(name, label)| {
+                name.unwrap_or_else(|| match label {
+                    Some(label) => names.claim(label),
+                    None => {
+                        numbered += 1;
+                        names.claim(&format!("n{}", numbered - 1))
+                    }
+                })
+            })
+            .collect();
+        let mut aliases: Vec<String> = aliases
+            .into_iter()
+            .map(|(net, port, is_output)| {
+                if is_output {
+                    format!("assign {port} = {};", net_names[net])
+                } else {
+                    format!("assign {} = {port};", net_names[net])
+                }
+            })
+            .collect();
+        for port in unwired_outputs {
+            aliases.push(format!("assign {} = {};", port.name, float(port.width)));
+        }
+        Self {
+            file,
+            index,
+            nets,
+            interfaces,
+            targets,
+            names,
+            net_names,
+            aliases,
+            body: String::new(),
+        }
+    }
+
+    fn render(mut self) -> String {
+        let (file, interfaces) = (self.file, self.interfaces);
+        let iface = &interfaces[self.index];
+        let records = &file.circuits[self.index].snapshot.components;
+        for (i, record) in records.iter().enumerate() {
+            self.component(i, &record.spec);
+        }
+
+        let net_kind = |net: usize| match self.nets.pulls[net] {
+            Some(PullDirection::Down) => "tri0",
+            Some(PullDirection::Up) => "tri1",
+            None => "wire",
+        };
+        let mut ports = Vec::new();
+        if iface.clocked {
+            ports.push(format!("input wire {CLOCK_PORT}"));
+        }
+        for port in &iface.inputs {
+            ports.push(format!("input wire {}{}", range(port.width), port.name));
+        }
+        let mut port_nets = HashSet::new();
+        for port in &iface.inputs {
+            port_nets.insert(self.nets.outputs[port.comp][0]);
+        }
+        for port in &iface.outputs {
+            let net = self.nets.inputs[port.comp][0];
+            let owned = net.filter(|&n| self.net_names[n] == port.name);
+            port_nets.extend(owned);
+            let kind = owned.map_or("wire", net_kind);
+            ports.push(format!("output {kind} {}{}", range(port.width), port.name));
+        }
+
+        let mut out = format!("module {} (", iface.module);
+        if !ports.is_empty() {
+            out.push('\n');
+            out.push_str(&format!("    {}\n", ports.join(",\n    ")));
+        }
+        out.push_str(");\n");
+        for (net, name) in self.net_names.iter().enumerate() {
+            if !port_nets.contains(&net) {
+                let width = range(self.nets.widths[net]);
+                out.push_str(&format!("    {} {width}{name};\n", net_kind(net)));
+            }
+        }
+        for alias in &self.aliases {
+            out.push_str(&format!("    {alias}\n"));
+        }
+        out.push_str(&self.body);
+        out.push_str("endmodule\n");
+        out
+    }
+
+    fn line(&mut self, text: &str) {
+        self.body.push_str("    ");
+        self.body.push_str(text);
+        self.body.push('\n');
+    }
+
+    fn input_net(&self, comp: usize, pin: usize) -> Option<&str> {
+        self.nets.inputs[comp][pin].map(|net| self.net_names[net].as_str())
+    }
+
+    // An input pin's net, or `unwired` in its place.
+    fn operand(&self, comp: usize, pin: usize, unwired: &str) -> String {
+        self.input_net(comp, pin).unwrap_or(unwired).to_string()
+    }
+
+    fn output(&self, comp: usize, pin: usize) -> String {
+        self.net_names[self.nets.outputs[comp][pin]].clone()
+    }
+
+    // Bit `bit` of an input pin's net, or `z` if it's unwired.
+    fn input_bit(&self, comp: usize, pin: usize, bit: u8) -> String {
+        match self.nets.inputs[comp][pin] {
+            Some(net) if self.nets.widths[net] > 1 => format!("{}[{bit}]", self.net_names[net]),
+            Some(net) => self.net_names[net].clone(),
+            None => "1'bz".to_string(),
+        }
+    }
+
+    // A register `u{comp}_{what}` of `width` bits, starting at zero.
+    fn state(&mut self, comp: usize, what: &str, width: u8) -> String {
+        let name = self.names.claim(&format!("u{comp}_{what}"));
+        self.line(&format!("reg {}{name} = {};", range(width), hex(width, 0)));
+        name
+    }
+
+    // The event a part latches on: its wired clock pin's edge, else a rising
+    // edge of the global `clk`.
+    fn clock_event(&self, comp: usize, spec: &ComponentSpec) -> String {
+        let (pin, edge) = clock_pin(spec).expect("a clocked part");
+        match (self.input_net(comp, pin), edge) {
+            (Some(net), ClockEdge::Rising) => format!("posedge {net}"),
+            (Some(net), ClockEdge::Falling) => format!("negedge {net}"),
+            (None, _) => format!("posedge {CLOCK_PORT}"),
+        }
+    }
+
+    // An always-block on the part's clock event, and on its async reset if
+    // that's wired. Held reset
+    // runs `clear`; otherwise the first `arms` whose condition holds runs (a
+    // None condition always does).
+    fn clocked(
+        &mut self,
+        comp: usize,
+        spec: &ComponentSpec,
+        reset_pin: Option<usize>,
+        clear: &[String],
+        arms: &[(Option<String>, Vec<String>)],
+    ) {
+        let clock = self.clock_event(comp, spec);
+        let reset = reset_pin.and_then(|pin| self.input_net(comp, pin).map(str::to_string));
+        let mut branches: Vec<(Option<String>, &[String])> = Vec::new();
+        let mut sensitivity = clock;
+        if let Some(reset) = reset {
+            sensitivity.push_str(&format!(" or posedge {reset}"));
+            branches.push((Some(reset), clear));
+        }
+        branches.extend(arms.iter().map(|(cond, stmts)| (cond.clone(), &stmts[..])));
+
+        self.line(&format!("always @({sensitivity}) begin"));
+        for (k, (cond, stmts)) in branches.into_iter().enumerate() {
+            let otherwise = if k == 0 { "" } else { "else " };
+            let head = match &cond {
+                Some(cond) => format!("{otherwise}if ({cond}) "),
+                None => otherwise.to_string(),
+            };
+            match stmts {
+                [one] => self.line(&format!("    {head}{one}")),
+                _ => {
+                    self.line(&format!("    {head}begin"));
+                    for stmt in stmts {
+                        self.line(&format!("        {stmt}"));
+                    }
+                    self.line("    end");
+                }
+            }
+            if cond.is_none() {
+                break;
+            }
+        }
+        self.line("end");
+    }
+
+    // `cond` joined with an optional enable pin: an unwired enable is on.
+    fn enabled(&self, comp: usize, enable_pin: usize, cond: Option<String>) -> Option<String> {
+        match (self.input_net(comp, enable_pin), cond) {
+            (Some(en), Some(cond)) => Some(format!("{en} && {cond}")),
+            (Some(en), None) => Some(en.to_string()),
+            (None, cond) => cond,
+        }
+    }
+
+    fn component(&mut self, i: usize, spec: &ComponentSpec) {
+        match spec {
+            // Ports, named when the module was.
+            ComponentSpec::Input(_) | ComponentSpec::Output(_) => {}
+            // Nothing to synthesize: a probe only watches, and a pull sets its
+            // net's type (tri0/tri1).
+            ComponentSpec::Probe(_) | ComponentSpec::PullResistor(_) => {}
+            ComponentSpec::Constant(c) => {
+                let y = self.output(i, 0);
+                self.line(&format!("assign {y} = {};", hex(c.width, c.bits)));
+            }
+            ComponentSpec::Gate(g) => {
+                let operands: Vec<String> = (0..g.n_inputs)
+                    .map(|pin| self.operand(i, pin, &float(g.width)))
+                    .collect();
+                let joined = |op: &str| operands.join(&format!(" {op} "));
+                let expr = match g.op {
+                    GateOp::And => joined("&"),
+                    GateOp::Or => joined("|"),
+                    GateOp::Xor => joined("^"),
+                    GateOp::Nand => format!("~({})", joined("&")),
+                    GateOp::Nor => format!("~({})", joined("|")),
+                    GateOp::Xnor => format!("~({})", joined("^")),
+                    GateOp::Not => format!("~{}", operands[0]),
+                };
+                let y = self.output(i, 0);
+                self.line(&format!("assign {y} = {expr};"));
+            }
+            ComponentSpec::TriState(t) => {
+                let d = self.operand(i, 0, &float(t.data_width));
+                let y = self.output(i, 0);
+                match self.input_net(i, 1) {
+                    Some(en) => {
+                        let z = float(t.data_width);
+                        self.line(&format!("assign {y} = {en} ? {d} : {z};"));
+                    }
+                    None => self.line(&format!("assign {y} = {d};")),
+                }
+            }
+            ComponentSpec::Mux(m) => {
+                let y = self.output(i, 0);
+                let Some(sel) = self.input_net(i, 0).map(str::to_string) else {
+                    self.line(&format!("assign {y} = {};", float(m.data_width)));
+                    return;
+                };
+                let q = self.names.claim(&format!("u{i}_y"));
+                self.line(&format!("reg {}{q};", range(m.data_width)));
+                self.line("always @* begin");
+                self.line(&format!("    case ({sel})"));
+                for arm in 0..1usize << m.sel_width {
+                    let d = self.operand(i, arm + 1, &float(m.data_width));
+                    self.line(&format!("        {}'d{arm}: {q} = {d};", m.sel_width));
+                }
+                self.line(&format!("        default: {q} = {}'bx;", m.data_width));
+                self.line("    endcase");
+                self.line("end");
+                self.line(&format!("assign {y} = {q};"));
+            }
+            ComponentSpec::Demux(d) => {
+                let data = self.operand(i, 0, &float(d.data_width));
+                let sel = self.input_net(i, 1).map(str::to_string);
+                for arm in 0..1usize << d.sel_width {
+                    let y = self.output(i, arm);
+                    let expr = match &sel {
+                        Some(sel) => format!(
+                            "{sel} == {}'d{arm} ? {data} : {}",
+                            d.sel_width,
+                            hex(d.data_width, 0)
+                        ),
+                        None => float(d.data_width),
+                    };
+                    self.line(&format!("assign {y} = {expr};"));
+                }
+            }
+            ComponentSpec::Encoder(e) => {
+                let en = self.operand(i, 0, "1'b1");
+                let arms: Vec<String> = (0..1usize << e.sel_width)
+                    .map(|arm| self.operand(i, arm + 1, "1'b0"))
+                    .collect();
+                let any = self.names.claim(&format!("u{i}_any"));
+                self.line(&format!("wire {any} = {};", arms.join(" | ")));
+                let (sel, en_out, group) =
+                    (self.output(i, 0), self.output(i, 1), self.output(i, 2));
+                self.line(&format!("assign {sel} ="));
+                self.line(&format!("    !({en} && {any}) ? {} :", float(e.sel_width)));
+                for (arm, a) in arms.iter().enumerate().skip(1).rev() {
+                    self.line(&format!("    {a} ? {}'d{arm} :", e.sel_width));
+                }
+                self.line(&format!("    {}'d0;", e.sel_width));
+                self.line(&format!("assign {en_out} = {en} && !{any};"));
+                self.line(&format!("assign {group} = {en} && {any};"));
+            }
+            ComponentSpec::Adder(a) => {
+                let w = a.data_width;
+                let (x, y) = (self.operand(i, 0, &float(w)), self.operand(i, 1, &float(w)));
+                let cin = self.operand(i, 2, "1'b0");
+                let (sum, cout) = (self.output(i, 0), self.output(i, 1));
+                self.line(&format!("assign {{{cout}, {sum}}} = {x} + {y} + {cin};"));
+            }
+            ComponentSpec::Subtractor(s) => {
+                let w = s.data_width;
+                let (x, y) = (self.operand(i, 0, &float(w)), self.operand(i, 1, &float(w)));
+                let bin = self.operand(i, 2, "1'b0");
+                let (diff, bout) = (self.output(i, 0), self.output(i, 1));
+                self.line(&format!("assign {{{bout}, {diff}}} = {x} - {y} - {bin};"));
+            }
+            ComponentSpec::Multiplier(m) => {
+                let w = m.data_width;
+                let (x, y) = (self.operand(i, 0, &float(w)), self.operand(i, 1, &float(w)));
+                let cin = self.operand(i, 2, &hex(w, 0));
+                let (product, carry) = (self.output(i, 0), self.output(i, 1));
+                self.line(&format!(
+                    "assign {{{carry}, {product}}} = {x} * {y} + {cin};"
+                ));
+            }
+            ComponentSpec::Divider(d) => {
+                let w = d.data_width;
+                let (x, y) = (self.operand(i, 0, &float(w)), self.operand(i, 1, &float(w)));
+                let cin = self.operand(i, 2, &hex(w, 0));
+                let (quotient, remainder) = (self.output(i, 0), self.output(i, 1));
+                // The carry-in is the dividend's upper half; a zero divisor
+                // divides by 1, as in the sim.
+                let divisor = self.names.claim(&format!("u{i}_divisor"));
+                self.line(&format!(
+                    "wire {}{divisor} = {y} == {} ? {} : {y};",
+                    range(w),
+                    hex(w, 0),
+                    hex(w, 1)
+                ));
+                self.line(&format!("assign {quotient} = {{{cin}, {x}}} / {divisor};"));
+                self.line(&format!("assign {remainder} = {{{cin}, {x}}} % {divisor};"));
+            }
+            ComponentSpec::Comparator(c) => {
+                let w = c.data_width;
+                let (x, y) = (self.operand(i, 0, &float(w)), self.operand(i, 1, &float(w)));
+                for (pin, op) in [(0, ">"), (1, "=="), (2, "<")] {
+                    let out = self.output(i, pin);
+                    self.line(&format!("assign {out} = {x} {op} {y};"));
+                }
+            }
+            ComponentSpec::Rom(r) => {
+                let y = self.output(i, 0);
+                let Some(addr) = self.input_net(i, 0).map(str::to_string) else {
+                    self.line(&format!("assign {y} = {};", float(r.data_width)));
+                    return;
+                };
+                let rom = self.names.claim(&format!("u{i}_rom"));
+                let words: Vec<(usize, u64)> = (0..r.len())
+                    .map(|a| (a, r.word(a)))
+                    .filter(|&(_, word)| word != 0)
+                    .collect();
+                self.memory(i, &rom, r.data_width, r.len(), &words);
+                self.line(&format!("assign {y} = {rom}[{addr}];"));
+            }
+            ComponentSpec::Ram(r) => {
+                let y = self.output(i, 0);
+                let w = r.data_width;
+                let mem = self.names.claim(&format!("u{i}_mem"));
+                let words: Vec<(usize, u64)> = (r.initial.iter().copied().enumerate())
+                    .filter(|&(_, word)| word != 0)
+                    .collect();
+                self.memory(i, &mem, w, r.len(), &words);
+                let q = self.state(i, "q", w);
+                let addr = self.operand(i, Ram::ADDR_PIN, &float(r.address_width));
+                let data_in = self.operand(i, Ram::DATA_IN_PIN, &float(w));
+                let write = format!("{mem}[{addr}] <= {data_in};");
+                let read = match (r.read_behavior, self.input_net(i, Ram::WE_PIN)) {
+                    (ReadBehavior::WriteAfterRead, _) => format!("{q} <= {mem}[{addr}];"),
+                    (ReadBehavior::ReadAfterWrite, Some(we)) => {
+                        format!("{q} <= {we} ? {data_in} : {mem}[{addr}];")
+                    }
+                    (ReadBehavior::ReadAfterWrite, None) => format!("{q} <= {data_in};"),
+                };
+                let clock = self.clock_event(i, spec);
+                let guarded = |pin: usize, stmt: String| match self.input_net(i, pin) {
+                    Some(en) => format!("if ({en}) {stmt}"),
+                    None => stmt,
+                };
+                let (write, read) = (guarded(Ram::WE_PIN, write), guarded(Ram::LE_PIN, read));
+                self.line(&format!("always @({clock}) begin"));
+                self.line(&format!("    {write}"));
+                self.line(&format!("    {read}"));
+                self.line("end");
+                self.line(&format!("assign {y} = {q};"));
+            }
+            ComponentSpec::Reg(r) => {
+                let w = r.data_width;
+                let q = self.state(i, "q", w);
+                let d = self.operand(i, RegConf::DATA_PIN, &float(w));
+                let arm = self.enabled(i, RegConf::WRITE_EN_PIN, None);
+                self.clocked(
+                    i,
+                    spec,
+                    Some(RegConf::RESET_PIN),
+                    &[format!("{q} <= {};", hex(w, 0))],
+                    &[(arm, vec![format!("{q} <= {d};")])],
+                );
+                let y = self.output(i, 0);
+                self.line(&format!("assign {y} = {q};"));
+            }
+            ComponentSpec::DFlipFlop(_) => {
+                let q = self.state(i, "q", 1);
+                let d = self.operand(i, DFlipFlopConf::DATA_PIN, "1'bz");
+                let arm = self.enabled(i, DFlipFlopConf::WRITE_EN_PIN, None);
+                self.clocked(
+                    i,
+                    spec,
+                    Some(DFlipFlopConf::RESET_PIN),
+                    &[format!("{q} <= 1'b0;")],
+                    &[(arm, vec![format!("{q} <= {d};")])],
+                );
+                let y = self.output(i, 0);
+                self.line(&format!("assign {y} = {q};"));
+            }
+            ComponentSpec::TFlipFlop(_) => {
+                let q = self.state(i, "q", 1);
+                let t = self.operand(i, TFlipFlopConf::TOGGLE_PIN, "1'b0");
+                let arm = self.enabled(i, TFlipFlopConf::WRITE_EN_PIN, Some(t));
+                self.clocked(
+                    i,
+                    spec,
+                    Some(TFlipFlopConf::RESET_PIN),
+                    &[format!("{q} <= 1'b0;")],
+                    &[(arm, vec![format!("{q} <= ~{q};")])],
+                );
+                let y = self.output(i, 0);
+                self.line(&format!("assign {y} = {q};"));
+            }
+            ComponentSpec::JKFlipFlop(_) => {
+                let q = self.state(i, "q", 1);
+                let j = self.operand(i, JKFlipFlopConf::J_PIN, "1'b0");
+                let k = self.operand(i, JKFlipFlopConf::K_PIN, "1'b0");
+                let en = JKFlipFlopConf::WRITE_EN_PIN;
+                let arms = [
+                    (format!("{j} && {k}"), format!("{q} <= ~{q};")),
+                    (j, format!("{q} <= 1'b1;")),
+                    (k, format!("{q} <= 1'b0;")),
+                ]
+                .map(|(cond, stmt)| (self.enabled(i, en, Some(cond)), vec![stmt]));
+                self.clocked(
+                    i,
+                    spec,
+                    Some(JKFlipFlopConf::RESET_PIN),
+                    &[format!("{q} <= 1'b0;")],
+                    &arms,
+                );
+                let y = self.output(i, 0);
+                self.line(&format!("assign {y} = {q};"));
+            }
+            ComponentSpec::SRFlipFlop(_) => {
+                let q = self.state(i, "q", 1);
+                let s = self.operand(i, SRFlipFlopConf::S_PIN, "1'b0");
+                let r = self.operand(i, SRFlipFlopConf::R_PIN, "1'b0");
+                let en = SRFlipFlopConf::WRITE_EN_PIN;
+                // S and R together is forbidden: the sim floats Q.
+                let arms = [
+                    (format!("{s} && {r}"), format!("{q} <= 1'bx;")),
+                    (s, format!("{q} <= 1'b1;")),
+                    (r, format!("{q} <= 1'b0;")),
+                ]
+                .map(|(cond, stmt)| (self.enabled(i, en, Some(cond)), vec![stmt]));
+                self.clocked(
+                    i,
+                    spec,
+                    Some(SRFlipFlopConf::RESET_PIN),
+                    &[format!("{q} <= 1'b0;")],
+                    &arms,
+                );
+                let y = self.output(i, 0);
+                self.line(&format!("assign {y} = {q};"));
+            }
+            ComponentSpec::ShiftReg(sr) => {
+                let w = sr.data_width;
+                let stages: Vec<String> = (0..sr.num_stages.max(1))
+                    .map(|k| self.state(i, &format!("s{k}"), w))
+                    .collect();
+                let d = self.operand(i, 0, &float(w));
+                let clear: Vec<String> = stages
+                    .iter()
+                    .map(|s| format!("{s} <= {};", hex(w, 0)))
+                    .collect();
+                let shift_in = std::iter::once(format!("{} <= {d};", stages[0]))
+                    .chain(stages.windows(2).map(|p| format!("{} <= {};", p[1], p[0])))
+                    .collect();
+                let mut arms = Vec::new();
+                if let Some(load) = sr.load_pin() {
+                    let load_all = stages
+                        .iter()
+                        .enumerate()
+                        .map(|(k, s)| {
+                            let p = self.operand(i, sr.stage_pin(k), &float(w));
+                            format!("{s} <= {p};")
+                        })
+                        .collect();
+                    arms.push((Some(self.operand(i, load, "1'b0")), load_all));
+                }
+                arms.push((Some(self.operand(i, sr.shift_pin(), "1'b0")), shift_in));
+                self.clocked(i, spec, Some(sr.reset_pin()), &clear, &arms);
+                if sr.parallel_load {
+                    for (k, s) in stages.iter().enumerate() {
+                        let y = self.output(i, k);
+                        self.line(&format!("assign {y} = {s};"));
+                    }
+                } else {
+                    let y = self.output(i, 0);
+                    self.line(&format!("assign {y} = {};", stages[stages.len() - 1]));
+                }
+            }
+            ComponentSpec::Counter(c) => {
+                let w = c.data_width;
+                let (q, carry) = (self.state(i, "q", w), self.state(i, "carry", 1));
+                let d = self.operand(i, CounterConf::DATA_PIN, &float(w));
+                let load = self.operand(i, CounterConf::LOAD_PIN, "1'b0");
+                let count = self.operand(i, CounterConf::COUNT_PIN, "1'b0");
+                let max = hex(w, c.max_value);
+                let (past_max, past_zero) = match c.overflow_action {
+                    OverflowAction::Wrap => (hex(w, 0), max.clone()),
+                    OverflowAction::StayMax => (max.clone(), hex(w, 0)),
+                    OverflowAction::PassMax => (format!("{q} + 1'b1"), format!("{q} - 1'b1")),
+                    OverflowAction::LoadNext => (d.clone(), d.clone()),
+                };
+                let zero = hex(w, 0);
+                // Load and count together count down.
+                let arms = [
+                    (
+                        Some(format!("{load} && {count}")),
+                        vec![
+                            format!("{carry} <= {q} == {zero};"),
+                            format!("{q} <= {q} == {zero} ? {past_zero} : {q} - 1'b1;"),
+                        ],
+                    ),
+                    (
+                        Some(load),
+                        vec![format!("{carry} <= 1'b0;"), format!("{q} <= {d};")],
+                    ),
+                    (
+                        Some(count),
+                        vec![
+                            format!("{carry} <= {q} >= {max};"),
+                            format!("{q} <= {q} >= {max} ? {past_max} : {q} + 1'b1;"),
+                        ],
+                    ),
+                    (None, vec![format!("{carry} <= 1'b0;")]),
+                ];
+                self.clocked(i, spec, None, &[], &arms);
+                let (y, carry_out) = (self.output(i, 0), self.output(i, 1));
+                self.line(&format!("assign {y} = {q};"));
+                self.line(&format!("assign {carry_out} = {carry};"));
+            }
+            ComponentSpec::ClockSource(conf) => {
+                // Low for low_ticks global ticks, then high for high_ticks.
+                let low = conf.low_ticks.max(1) as u64;
+                let period = low + conf.high_ticks.max(1) as u64;
+                let bits = (u64::BITS - (period - 1).leading_zeros()).max(1) as u8;
+                let phase = self.state(i, "phase", bits);
+                self.line(&format!("always @(posedge {CLOCK_PORT})"));
+                self.line(&format!(
+                    "    {phase} <= {phase} == {} ? {} : {phase} + 1'b1;",
+                    hex(bits, period - 1),
+                    hex(bits, 0)
+                ));
+                let y = self.output(i, 0);
+                self.line(&format!("assign {y} = {phase} >= {};", hex(bits, low)));
+            }
+            ComponentSpec::Splitter {
+                arm_bits,
+                direction,
+                ..
+            } => {
+                let splitter = Splitter::new(arm_bits.clone(), *direction);
+                let arms = splitter.arm_bits();
+                match direction {
+                    FanDirection::Right => {
+                        for (arm, bits) in arms.iter().enumerate() {
+                            if bits.is_empty() {
+                                continue;
+                            }
+                            let parts: Vec<String> = bits
+                                .iter()
+                                .rev()
+                                .map(|&b| self.input_bit(i, 0, b))
+                                .collect();
+                            let y = self.output(i, arm);
+                            self.line(&format!("assign {y} = {};", concat(&parts)));
+                        }
+                    }
+                    FanDirection::Left => {
+                        let mut owner = vec![None; splitter.data_width() as usize];
+                        for (arm, bits) in arms.iter().enumerate() {
+                            for (slot, &b) in bits.iter().enumerate() {
+                                owner[b as usize] = Some((arm, slot as u8));
+                            }
+                        }
+                        let parts: Vec<String> = owner
+                            .iter()
+                            .rev()
+                            .map(|o| match *o {
+                                Some((arm, slot)) => self.input_bit(i, arm, slot),
+                                None => "1'b0".to_string(),
+                            })
+                            .collect();
+                        let y = self.output(i, 0);
+                        self.line(&format!("assign {y} = {};", concat(&parts)));
+                    }
+                }
+            }
+            ComponentSpec::Subcircuit { name, .. } => {
+                let Some(&target) = self.targets.get(&i) else {
+                    self.line(&format!(
+                        "// {name}: left out, as it can't be instantiated here"
+                    ));
+                    return;
+                };
+                let target = &self.interfaces[target];
+                let mut connections = Vec::new();
+                if target.clocked {
+                    connections.push(format!(".{CLOCK_PORT}({CLOCK_PORT})"));
+                }
+                for (pin, port) in target.inputs.iter().enumerate() {
+                    let net = self.input_net(i, pin).unwrap_or("");
+                    connections.push(format!(".{}({net})", port.name));
+                }
+                for (pin, port) in target.outputs.iter().enumerate() {
+                    connections.push(format!(".{}({})", port.name, self.output(i, pin)));
+                }
+                let instance = self.names.claim(&format!("u{i}"));
+                self.line(&format!("{} {instance} (", target.module));
+                self.line(&format!("    {}", connections.join(",\n        ")));
+                self.line(");");
+            }
+        }
+    }
+
+    // A `len`-word memory array, zeroed by an initial block and then given
+    // `words` (address, value).
+    fn memory(&mut self, comp: usize, mem: &str, width: u8, len: usize, words: &[(usize, u64)]) {
+        let index = self.names.claim(&format!("u{comp}_i"));
+        self.line(&format!("reg {}{mem} [0:{}];", range(width), len - 1));
+        self.line(&format!("integer {index};"));
+        self.line("initial begin");
+        self.line(&format!(
+            "    for ({index} = 0; {index} < {len}; {index} = {index} + 1) {mem}[{index}] = {};",
+            hex(width, 0)
+        ));
+        for &(address, word) in words {
+            self.line(&format!("    {mem}[{address}] = {};", hex(width, word)));
+        }
+        self.line("end");
+    }
+}
+
+// Bits most significant first, braced if there's more than one.
+fn concat(parts: &[String]) -> String {
+    match parts {
+        [one] => one.clone(),
+        _ => format!("{{{}}}", parts.join(", ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::geometry::GridPos;
+    use crate::io::{
+        CircuitEntry, CircuitSnapshot, ComponentEntry, NodeAttachEntry, NodeEntry, SegEntry,
+        SubcircuitRef, TunnelEntry,
+    };
+    use crate::sim::circuit::TunnelRole;
+    use crate::sim::component::{Gate, Input, Output};
+
+    fn pin_node(comp: usize, is_input: bool, pin_index: u8) -> NodeEntry {
+        NodeEntry {
+            pos: GridPos::new(0, 0),
+            attach: NodeAttachEntry::Pin {
+                comp,
+                is_input,
+                pin_index,
+            },
+        }
+    }
+
+    // A wire from a (component, output pin) to a (component, input pin).
+    type Wire = ((usize, u8), (usize, u8));
+
+    // `specs` stacked top-down, joined by `wires`.
+    fn circuit(name: &str, specs: Vec<ComponentSpec>, wires: &[Wire]) -> CircuitEntry {
+        let mut nodes = Vec::new();
+        let mut segments = Vec::new();
+        for &((from, out_pin), (to, in_pin)) in wires {
+            segments.push(SegEntry {
+                a: nodes.len(),
+                b: nodes.len() + 1,
+            });
+            nodes.push(pin_node(from, false, out_pin));
+            nodes.push(pin_node(to, true, in_pin));
+        }
+        CircuitEntry {
+            name: name.to_string(),
+            snapshot: CircuitSnapshot {
+                components: specs
+                    .into_iter()
+                    .enumerate()
+                    .map(|(y, spec)| ComponentEntry {
+                        spec,
+                        grid_pos: GridPos::new(0, y as i32),
+                        delay: None,
+                    })
+                    .collect(),
+                tunnels: vec![],
+                nodes,
+                segments,
+            },
+            subcircuits: vec![],
+        }
+    }
+
+    fn input(label: &str, width: u8) -> ComponentSpec {
+        ComponentSpec::Input(Input {
+            label: label.to_string(),
+            ..Input::new(0, width)
+        })
+    }
+
+    fn output(label: &str) -> ComponentSpec {
+        ComponentSpec::Output(Output {
+            label: label.to_string(),
+        })
+    }
+
+    fn gate(op: GateOp, n_inputs: usize) -> ComponentSpec {
+        ComponentSpec::Gate(Gate {
+            op,
+            n_inputs,
+            width: 1,
+        })
+    }
+
+    #[test]
+    fn test_gates_become_assigns_between_ports() {
+        let top = circuit(
+            "top",
+            vec![
+                input("a", 1),
+                input("b", 1),
+                gate(GateOp::And, 2),
+                output("y"),
+            ],
+            &[((0, 0), (2, 0)), ((1, 0), (2, 1)), ((2, 0), (3, 0))],
+        );
+        let text = export(&ProjectFile::new(0, vec![top]));
+        let module = "\
+module top (
+    input wire a,
+    input wire b,
+    output wire y
+);
+    assign y = a & b;
+endmodule
+";
+        assert!(text.ends_with(module), "{text}");
+    }
+
+    #[test]
+    fn test_tunnels_name_and_join_their_nets() {
+        // a -> NOT -> tunnel "inv" ... tunnel "inv" -> NOT -> y
+        let mut top = circuit(
+            "top",
+            vec![
+                input("a", 1),
+                gate(GateOp::Not, 1),
+                gate(GateOp::Not, 1),
+                output("y"),
+            ],
+            &[((0, 0), (1, 0)), ((2, 0), (3, 0))],
+        );
+        let snapshot = &mut top.snapshot;
+        for (tunnel, (role, pin)) in [
+            (TunnelRole::Pull, pin_node(1, false, 0)),
+            (TunnelRole::Feed, pin_node(2, true, 0)),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            snapshot.tunnels.push(TunnelEntry {
+                label: "inv".to_string(),
+                role,
+                grid_pos: GridPos::new(4, tunnel as i32),
+            });
+            snapshot.segments.push(SegEntry {
+                a: snapshot.nodes.len(),
+                b: snapshot.nodes.len() + 1,
+            });
+            snapshot.nodes.push(pin);
+            snapshot.nodes.push(NodeEntry {
+                pos: GridPos::new(4, tunnel as i32),
+                attach: NodeAttachEntry::Tunnel { tunnel },
+            });
+        }
+        let text = export(&ProjectFile::new(0, vec![top]));
+        assert!(text.contains("    wire inv;\n"), "{text}");
+        assert!(text.contains("    assign inv = ~a;\n"), "{text}");
+        assert!(text.contains("    assign y = ~inv;\n"), "{text}");
+    }
+
+    #[test]
+    fn test_global_clock_becomes_a_clk_port_passed_to_instances() {
+        // "Reg Cell": d -> register (clock pin unwired) -> q. "top" places it.
+        let cell = circuit(
+            "Reg Cell",
+            vec![
+                input("d", 4),
+                ComponentSpec::Reg(RegConf {
+                    data_width: 4,
+                    clock_edge: ClockEdge::Falling,
+                }),
+                output("q"),
+            ],
+            &[((0, 0), (1, RegConf::DATA_PIN as u8)), ((1, 0), (2, 0))],
+        );
+        let sub = ComponentSpec::Subcircuit {
+            doc: Default::default(),
+            name: "Reg Cell".to_string(),
+            input_widths: vec![4],
+            output_widths: vec![4],
+        };
+        let mut top = circuit(
+            "top",
+            vec![input("", 4), sub, output("")],
+            &[((0, 0), (1, 0)), ((1, 0), (2, 0))],
+        );
+        top.subcircuits.push(SubcircuitRef {
+            component: 1,
+            circuit: 0,
+        });
+        let text = export(&ProjectFile::new(1, vec![cell, top]));
+
+        assert!(
+            text.contains("module Reg_Cell (\n    input wire clk,\n"),
+            "{text}"
+        );
+        // The global clock ticks on a rising edge whatever the part's edge.
+        assert!(text.contains("always @(posedge clk) begin\n"), "{text}");
+        assert!(text.contains("        u1_q <= d;\n"), "{text}");
+        assert!(text.contains("module top (\n    input wire clk,\n    input wire [3:0] in0,\n    output wire [3:0] out0\n);\n"), "{text}");
+        assert!(text.contains("    Reg_Cell u1 (\n        .clk(clk),\n        .d(in0),\n        .q(out0)\n    );\n"), "{text}");
+    }
+
+    #[test]
+    fn test_identifiers_are_legal_and_unique() {
+        assert_eq!(identifier("carry out"), "carry_out");
+        assert_eq!(identifier("4bit"), "_4bit");
+        assert_eq!(identifier(""), "_");
+        assert_eq!(identifier("module"), "module_");
+        assert_eq!(identifier("default"), "default_");
+        let mut names = Names::default();
+        assert_eq!(names.claim("a b"), "a_b");
+        assert_eq!(names.claim("a-b"), "a_b_2");
+        assert_eq!(names.claim("a b"), "a_b_3");
+    }
+}